@@ -0,0 +1,11 @@
+//! 生成 Codama 节点树
+//!
+//! 用法：`cargo run --example codama --features idl-build > idl/blueshift_escrow.codama.json`
+//!
+use blueshift_escrow::idl::write_codama_json;
+
+fn main() {
+    let mut json = String::new();
+    write_codama_json(&mut json).expect("IDL 中的种子取值必须与 PDA 的变量种子一一对应");
+    println!("{}", json);
+}
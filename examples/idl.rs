@@ -0,0 +1,11 @@
+//! 生成 JSON IDL
+//!
+//! 用法：`cargo run --example idl --features idl-build > idl/blueshift_escrow.json`
+//!
+use blueshift_escrow::idl::write_json;
+
+fn main() {
+    let mut json = String::new();
+    write_json(&mut json).expect("IDL 中的指令都必须有 sighash");
+    println!("{}", json);
+}
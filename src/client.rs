@@ -0,0 +1,211 @@
+//! 链下客户端（`client` feature）
+//!
+//! 启用 `client` feature 时本 crate 链接 std 且不导出 entrypoint，可以作为普通依赖在链下使用。
+//! 本模块按程序实际校验的顺序拼装 Make / Take / Refund 的账户列表与指令数据，并提供 PDA 派生函数，
+//! 集成方无需再手写账户顺序：
+//! - `make_instruction`：指令数据为 64 字节形式（seed + receive + amount + expiry_timestamp + taker）
+//! - `take_instruction`：可选的滑点保护（max_receive + min_token_a_out）
+//! - `refund_instruction`：可选的 WSOL 解包
+//!
+//! 返回的 `Instruction` 只包含固定账户；可选的尾部账户（Transfer Hook、白名单、统计 PDA 等，见各指令的文件头）
+//! 由调用方按顺序追加到 `accounts` 末尾。`AccountMeta` 与 `Instruction` 的字段与 `solana-instruction` 的同名类型一致，
+//! 可以直接逐字段转换。
+//!
+use crate::state::{derive_escrow_address, derive_vault_address};
+use crate::{Make, Refund, Take};
+use pinocchio::Address;
+use std::vec::Vec;
+
+/// 指令中的一个账户
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccountMeta {
+    pub pubkey: Address,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl AccountMeta {
+    /// 可写账户
+    pub fn new(pubkey: Address, is_signer: bool) -> Self {
+        Self {
+            pubkey,
+            is_signer,
+            is_writable: true,
+        }
+    }
+
+    /// 只读账户
+    pub fn new_readonly(pubkey: Address, is_signer: bool) -> Self {
+        Self {
+            pubkey,
+            is_signer,
+            is_writable: false,
+        }
+    }
+}
+
+/// 一条发往本程序的指令
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Instruction {
+    pub program_id: Address,
+    pub accounts: Vec<AccountMeta>,
+    pub data: Vec<u8>,
+}
+
+// ========== PDA 派生 ==========
+/// maker 以 `seed` 创建的 Escrow PDA 地址与 bump
+pub fn find_escrow_address(maker: &Address, seed: u64) -> (Address, u8) {
+    derive_escrow_address(maker, seed)
+}
+
+/// 全局配置 PDA 地址与 bump（种子 `["config"]`）
+pub fn find_config_address() -> (Address, u8) {
+    Address::find_program_address(&[b"config"], &crate::ID)
+}
+
+/// maker 的种子计数器 PDA 地址与 bump（种子 `["counter", maker]`，MakeAuto 使用）
+pub fn find_maker_counter_address(maker: &Address) -> (Address, u8) {
+    Address::find_program_address(&[b"counter", maker.as_ref()], &crate::ID)
+}
+
+/// `owner` 持有的 `mint` 的 ATA，`token_program` 为 mint 所属的 Token Program
+pub fn find_associated_token_address(owner: &Address, mint: &Address, token_program: &Address) -> Address {
+    derive_vault_address(owner, mint, token_program)
+}
+
+// ========== 指令构造 ==========
+/// Make 的参数
+pub struct MakeArgs {
+    pub seed: u64,
+    pub receive: u64,           // 期望接收的 Token B 数量
+    pub amount: u64,            // 托管的 Token A 数量
+    pub expiry_timestamp: i64,  // 过期的 Unix 时间戳，0 表示永不过期
+    pub taker: Option<Address>, // 指定的 taker，None 表示公开 Escrow
+}
+
+/// 构造 Make 指令
+///
+/// `token_program` 为 mint_a 所属的 Token Program；Escrow、vault 与 maker 的 ATA 由本函数派生。
+pub fn make_instruction(
+    maker: &Address,
+    mint_a: &Address,
+    mint_b: &Address,
+    token_program: &Address,
+    args: &MakeArgs,
+) -> Instruction {
+    let (escrow, _) = find_escrow_address(maker, args.seed);
+
+    let mut data = Vec::with_capacity(1 + 64);
+    data.push(*Make::DISCRIMINATOR);
+    data.extend_from_slice(&args.seed.to_le_bytes());
+    data.extend_from_slice(&args.receive.to_le_bytes());
+    data.extend_from_slice(&args.amount.to_le_bytes());
+    data.extend_from_slice(&args.expiry_timestamp.to_le_bytes());
+    // 全 0 的 taker 地址表示公开 Escrow
+    data.extend_from_slice(args.taker.clone().unwrap_or_default().as_ref());
+
+    Instruction {
+        program_id: crate::ID,
+        accounts: Vec::from([
+            AccountMeta::new(maker.clone(), true),
+            AccountMeta::new(escrow.clone(), false),
+            AccountMeta::new_readonly(mint_a.clone(), false),
+            AccountMeta::new_readonly(mint_b.clone(), false),
+            AccountMeta::new(find_associated_token_address(maker, mint_a, token_program), false),
+            AccountMeta::new(find_associated_token_address(&escrow, mint_a, token_program), false),
+            AccountMeta::new_readonly(pinocchio_system::ID, false),
+            AccountMeta::new_readonly(token_program.clone(), false),
+            AccountMeta::new_readonly(pinocchio_associated_token_account::ID, false),
+            AccountMeta::new(find_config_address().0, false),
+        ]),
+        data,
+    }
+}
+
+/// Take 的账户（Escrow 中记录的 maker 与两种 Mint，以及两者所属的 Token Program）
+pub struct TakeAccounts {
+    pub taker: Address,
+    pub maker: Address,
+    pub escrow: Address,
+    pub mint_a: Address,
+    pub mint_b: Address,          // 支付代币：mint_b 或备选支付代币之一
+    pub token_program: Address,   // mint_a 所属的 Token Program
+    pub token_program_b: Address, // mint_b 所属的 Token Program
+}
+
+/// 构造 Take 指令
+///
+/// `slippage` 为 `(max_receive, min_token_a_out)`，None 时不做滑点保护。
+/// 委托模式的 vault 是 maker 的 Token A ATA，需要调用方替换 `accounts[5]`。
+pub fn take_instruction(accounts: &TakeAccounts, slippage: Option<(u64, u64)>) -> Instruction {
+    let TakeAccounts {
+        taker,
+        maker,
+        escrow,
+        mint_a,
+        mint_b,
+        token_program,
+        token_program_b,
+    } = accounts;
+    let (config, _) = find_config_address();
+
+    let mut data = Vec::with_capacity(1 + 16);
+    data.push(*Take::DISCRIMINATOR);
+    if let Some((max_receive, min_token_a_out)) = slippage {
+        data.extend_from_slice(&max_receive.to_le_bytes());
+        data.extend_from_slice(&min_token_a_out.to_le_bytes());
+    }
+
+    Instruction {
+        program_id: crate::ID,
+        accounts: Vec::from([
+            AccountMeta::new(taker.clone(), true),
+            AccountMeta::new(maker.clone(), false),
+            AccountMeta::new(escrow.clone(), false),
+            AccountMeta::new_readonly(mint_a.clone(), false),
+            AccountMeta::new_readonly(mint_b.clone(), false),
+            AccountMeta::new(find_associated_token_address(escrow, mint_a, token_program), false),
+            AccountMeta::new(find_associated_token_address(taker, mint_a, token_program), false),
+            AccountMeta::new(find_associated_token_address(taker, mint_b, token_program_b), false),
+            AccountMeta::new(find_associated_token_address(maker, mint_b, token_program_b), false),
+            AccountMeta::new_readonly(pinocchio_system::ID, false),
+            AccountMeta::new_readonly(token_program.clone(), false),
+            AccountMeta::new_readonly(pinocchio_associated_token_account::ID, false),
+            AccountMeta::new(config.clone(), false),
+            AccountMeta::new(find_associated_token_address(&config, mint_b, token_program_b), false),
+            AccountMeta::new_readonly(token_program_b.clone(), false),
+        ]),
+        data,
+    }
+}
+
+/// 构造 Refund 指令
+///
+/// `unwrap` 为 true 时，Token A 为 WSOL 的托管在退款后关闭 maker 的 WSOL ATA，换回原生 SOL。
+pub fn refund_instruction(
+    maker: &Address,
+    escrow: &Address,
+    mint_a: &Address,
+    token_program: &Address,
+    unwrap: bool,
+) -> Instruction {
+    let mut data = Vec::from([*Refund::DISCRIMINATOR]);
+    if unwrap {
+        data.push(1);
+    }
+
+    Instruction {
+        program_id: crate::ID,
+        accounts: Vec::from([
+            AccountMeta::new(maker.clone(), true),
+            AccountMeta::new(escrow.clone(), false),
+            AccountMeta::new_readonly(mint_a.clone(), false),
+            AccountMeta::new(find_associated_token_address(escrow, mint_a, token_program), false),
+            AccountMeta::new(find_associated_token_address(maker, mint_a, token_program), false),
+            AccountMeta::new_readonly(pinocchio_system::ID, false),
+            AccountMeta::new_readonly(token_program.clone(), false),
+            AccountMeta::new_readonly(pinocchio_associated_token_account::ID, false),
+        ]),
+        data,
+    }
+}
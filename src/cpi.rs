@@ -0,0 +1,278 @@
+//! CPI 接口（`cpi` feature）
+//!
+//! 其他链上程序（pinocchio 或 Anchor）通过本模块以 CPI 调用 Make / Take / Refund，无需复制账户列表与指令数据布局。
+//! 用法与 pinocchio-token 的 CPI 结构体相同：填入账户与参数后调用 `invoke()`，由 PDA 签名时调用 `invoke_signed(signers)`。
+//! 例如市场程序以自己的 PDA 作为 maker 代用户挂单：`cpi::Make { maker: market_pda, .. }.invoke_signed(&[signer])`。
+//!
+//! 依赖本 crate 时启用 `cpi` feature：同时启用 `no-entrypoint`，不导出本程序的 entrypoint 与 panic handler，
+//! 仍为 `no_std`，可以链接进其他程序。
+//!
+//! 说明：
+//! - 只传入各指令的固定账户（顺序与程序解析的顺序一致，见 make.rs / take.rs / refund.rs），不支持需要尾部账户的场景
+//!   （mint 启用了 Transfer Hook、白名单、捆绑挂单、版税等），这些场景需要自行拼装完整的账户列表
+//! - 指令数据使用定长形式：Make 为 64 字节，Take 为 16 字节滑点保护，Refund 为 1 字节 unwrap
+//! - 调用方需要把本程序账户一并传入交易，`invoke` 只使用 `crate::ID` 作为目标程序
+//!
+use crate::instructions::{make::Make as MakeInstruction, refund::Refund as RefundInstruction, take::Take as TakeInstruction};
+use pinocchio::{
+    cpi::{invoke_signed, Signer},
+    instruction::{InstructionAccount, InstructionView},
+    AccountView, Address, ProgramResult,
+};
+
+/// 创建 Escrow
+///
+/// ### 账户
+///   0. `[WRITE, SIGNER]` maker
+///   1. `[WRITE]` Escrow PDA（`derive_escrow_address(maker, seed)`）
+///   2. `[]` mint_a
+///   3. `[]` mint_b
+///   4. `[WRITE]` maker 的 Token A ATA
+///   5. `[WRITE]` vault（Escrow 拥有的 Token A ATA）
+///   6. `[]` System Program
+///   7. `[]` mint_a 所属的 Token Program
+///   8. `[]` Associated Token Program
+///   9. `[WRITE]` 全局配置 PDA
+pub struct Make<'a> {
+    pub maker: &'a AccountView,
+    pub escrow: &'a AccountView,
+    pub mint_a: &'a AccountView,
+    pub mint_b: &'a AccountView,
+    pub maker_ata_a: &'a AccountView,
+    pub vault: &'a AccountView,
+    pub system_program: &'a AccountView,
+    pub token_program: &'a AccountView,
+    pub associated_token_program: &'a AccountView,
+    pub config: &'a AccountView,
+    pub seed: u64,
+    pub receive: u64,               // 期望接收的 Token B 数量
+    pub amount: u64,                // 托管的 Token A 数量
+    pub expiry_timestamp: i64,      // 过期的 Unix 时间戳，0 表示永不过期
+    pub taker: Option<&'a Address>, // 指定的 taker，None 表示公开 Escrow
+}
+
+impl Make<'_> {
+    /// 指令数据长度：判别器 + seed + receive + amount + expiry_timestamp + taker
+    const DATA_LEN: usize = 1 + 8 * 4 + 32;
+
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let instruction_accounts = [
+            InstructionAccount::writable_signer(self.maker.address()),
+            InstructionAccount::writable(self.escrow.address()),
+            InstructionAccount::readonly(self.mint_a.address()),
+            InstructionAccount::readonly(self.mint_b.address()),
+            InstructionAccount::writable(self.maker_ata_a.address()),
+            InstructionAccount::writable(self.vault.address()),
+            InstructionAccount::readonly(self.system_program.address()),
+            InstructionAccount::readonly(self.token_program.address()),
+            InstructionAccount::readonly(self.associated_token_program.address()),
+            InstructionAccount::writable(self.config.address()),
+        ];
+
+        let mut data = [0u8; Self::DATA_LEN];
+        data[0] = *MakeInstruction::DISCRIMINATOR;
+        data[1..9].copy_from_slice(&self.seed.to_le_bytes());
+        data[9..17].copy_from_slice(&self.receive.to_le_bytes());
+        data[17..25].copy_from_slice(&self.amount.to_le_bytes());
+        data[25..33].copy_from_slice(&self.expiry_timestamp.to_le_bytes());
+        // 全 0 的 taker 地址表示公开 Escrow
+        if let Some(taker) = self.taker {
+            data[33..65].copy_from_slice(taker.as_ref());
+        }
+
+        let instruction = InstructionView {
+            program_id: &crate::ID,
+            accounts: &instruction_accounts,
+            data: &data,
+        };
+
+        invoke_signed(
+            &instruction,
+            &[
+                self.maker,
+                self.escrow,
+                self.mint_a,
+                self.mint_b,
+                self.maker_ata_a,
+                self.vault,
+                self.system_program,
+                self.token_program,
+                self.associated_token_program,
+                self.config,
+            ],
+            signers,
+        )
+    }
+}
+
+/// 按当前报价成交
+///
+/// ### 账户
+///   0. `[WRITE, SIGNER]` taker
+///   1. `[WRITE]` maker
+///   2. `[WRITE]` Escrow PDA
+///   3. `[]` mint_a
+///   4. `[]` 支付代币的 Mint（mint_b 或备选支付代币之一）
+///   5. `[WRITE]` vault
+///   6. `[WRITE]` taker 的 Token A ATA
+///   7. `[WRITE]` taker 的 Token B ATA
+///   8. `[WRITE]` maker 的 Token B ATA
+///   9. `[]` System Program
+///  10. `[]` mint_a 所属的 Token Program
+///  11. `[]` Associated Token Program
+///  12. `[WRITE]` 全局配置 PDA
+///  13. `[WRITE]` fee vault（全局配置 PDA 拥有的 Token B ATA）
+///  14. `[]` mint_b 所属的 Token Program
+pub struct Take<'a> {
+    pub taker: &'a AccountView,
+    pub maker: &'a AccountView,
+    pub escrow: &'a AccountView,
+    pub mint_a: &'a AccountView,
+    pub mint_b: &'a AccountView,
+    pub vault: &'a AccountView,
+    pub taker_ata_a: &'a AccountView,
+    pub taker_ata_b: &'a AccountView,
+    pub maker_ata_b: &'a AccountView,
+    pub system_program: &'a AccountView,
+    pub token_program: &'a AccountView,
+    pub associated_token_program: &'a AccountView,
+    pub config: &'a AccountView,
+    pub fee_vault: &'a AccountView,
+    pub token_program_b: &'a AccountView,
+    pub max_receive: u64,     // 滑点保护：最多愿意支付的 Token B 数量（不限制时传入 u64::MAX）
+    pub min_token_a_out: u64, // 滑点保护：至少要收到的 Token A 数量（不限制时传入 0）
+}
+
+impl Take<'_> {
+    /// 指令数据长度：判别器 + max_receive + min_token_a_out
+    const DATA_LEN: usize = 1 + 8 * 2;
+
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let instruction_accounts = [
+            InstructionAccount::writable_signer(self.taker.address()),
+            InstructionAccount::writable(self.maker.address()),
+            InstructionAccount::writable(self.escrow.address()),
+            InstructionAccount::readonly(self.mint_a.address()),
+            InstructionAccount::readonly(self.mint_b.address()),
+            InstructionAccount::writable(self.vault.address()),
+            InstructionAccount::writable(self.taker_ata_a.address()),
+            InstructionAccount::writable(self.taker_ata_b.address()),
+            InstructionAccount::writable(self.maker_ata_b.address()),
+            InstructionAccount::readonly(self.system_program.address()),
+            InstructionAccount::readonly(self.token_program.address()),
+            InstructionAccount::readonly(self.associated_token_program.address()),
+            InstructionAccount::writable(self.config.address()),
+            InstructionAccount::writable(self.fee_vault.address()),
+            InstructionAccount::readonly(self.token_program_b.address()),
+        ];
+
+        let mut data = [0u8; Self::DATA_LEN];
+        data[0] = *TakeInstruction::DISCRIMINATOR;
+        data[1..9].copy_from_slice(&self.max_receive.to_le_bytes());
+        data[9..17].copy_from_slice(&self.min_token_a_out.to_le_bytes());
+
+        let instruction = InstructionView {
+            program_id: &crate::ID,
+            accounts: &instruction_accounts,
+            data: &data,
+        };
+
+        invoke_signed(
+            &instruction,
+            &[
+                self.taker,
+                self.maker,
+                self.escrow,
+                self.mint_a,
+                self.mint_b,
+                self.vault,
+                self.taker_ata_a,
+                self.taker_ata_b,
+                self.maker_ata_b,
+                self.system_program,
+                self.token_program,
+                self.associated_token_program,
+                self.config,
+                self.fee_vault,
+                self.token_program_b,
+            ],
+            signers,
+        )
+    }
+}
+
+/// maker 取消 Escrow
+///
+/// ### 账户
+///   0. `[WRITE, SIGNER]` maker
+///   1. `[WRITE]` Escrow PDA
+///   2. `[]` mint_a
+///   3. `[WRITE]` vault
+///   4. `[WRITE]` maker 的 Token A ATA
+///   5. `[]` System Program
+///   6. `[]` mint_a 所属的 Token Program
+///   7. `[]` Associated Token Program
+pub struct Refund<'a> {
+    pub maker: &'a AccountView,
+    pub escrow: &'a AccountView,
+    pub mint_a: &'a AccountView,
+    pub vault: &'a AccountView,
+    pub maker_ata_a: &'a AccountView,
+    pub system_program: &'a AccountView,
+    pub token_program: &'a AccountView,
+    pub associated_token_program: &'a AccountView,
+    pub unwrap: bool, // Token A 为 WSOL 时关闭 maker 的 WSOL ATA，换回原生 SOL
+}
+
+impl Refund<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let instruction_accounts = [
+            InstructionAccount::writable_signer(self.maker.address()),
+            InstructionAccount::writable(self.escrow.address()),
+            InstructionAccount::readonly(self.mint_a.address()),
+            InstructionAccount::writable(self.vault.address()),
+            InstructionAccount::writable(self.maker_ata_a.address()),
+            InstructionAccount::readonly(self.system_program.address()),
+            InstructionAccount::readonly(self.token_program.address()),
+            InstructionAccount::readonly(self.associated_token_program.address()),
+        ];
+
+        let data = [*RefundInstruction::DISCRIMINATOR, self.unwrap as u8];
+
+        let instruction = InstructionView {
+            program_id: &crate::ID,
+            accounts: &instruction_accounts,
+            data: &data,
+        };
+
+        invoke_signed(
+            &instruction,
+            &[
+                self.maker,
+                self.escrow,
+                self.mint_a,
+                self.vault,
+                self.maker_ata_a,
+                self.system_program,
+                self.token_program,
+                self.associated_token_program,
+            ],
+            signers,
+        )
+    }
+}
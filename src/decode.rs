@@ -0,0 +1,201 @@
+//! 账户解码（供索引器使用）
+//!
+//! Geyser 插件与 RPC 轮询的索引器拿到的是本程序拥有的任意账户数据，本模块给出统一入口
+//! `try_decode_account(data)`，返回 `EscrowAccountType`，不需要调用方按数据长度猜测账户类型。
+//!
+//! 本程序的每种账户都以 8 字节判别器开头（见各类型的 `ACCOUNT_DISCRIMINATOR`），按判别器识别：
+//! 1. 以 `Escrow::ACCOUNT_DISCRIMINATOR` 开头的是 Escrow：当前版本返回 `Escrow`，
+//!    旧版本（需要先 Migrate）返回 `OutdatedEscrow(version)`，高于当前版本的返回 None
+//! 2. 其他账户类型的判别器各不相同，判别器匹配且长度等于该类型的 `LEN` 时返回对应类型
+//!
+//! 说明：
+//! - 调用方需要先确认账户的 owner 是本程序，本模块不校验 owner
+//! - 零拷贝读取要求数据按 8 字节对齐（RPC 客户端返回的 `Vec<u8>` 满足），未对齐时返回 None
+//! - 已关闭的账户（数据为空，或第一个字节为关闭标记）返回 None
+//!
+use crate::state::{
+    Bid, Commitment, Config, Contribution, Escrow, FeeVault, MakerCounter, MakerStats, Offer, Pool, TradeReceipt,
+    Whitelist,
+};
+
+/// 本程序拥有的账户类型
+pub enum EscrowAccountType<'a> {
+    /// 当前版本的 Escrow
+    Escrow(&'a Escrow),
+    /// 旧版本的 Escrow（记录的布局版本号），需要先通过 Migrate 升级才能读取字段
+    OutdatedEscrow(u8),
+    /// 白名单，种子 `["whitelist", escrow]`
+    Whitelist(&'a Whitelist),
+    /// 拍卖出价，种子 `["bid", escrow]`
+    Bid(&'a Bid),
+    /// 还价，种子 `["offer", escrow, proposer]`
+    Offer(&'a Offer),
+    /// 全局配置，种子 `["config"]`
+    Config(&'a Config),
+    /// 众筹池，种子 `["pool", escrow]`
+    Pool(&'a Pool),
+    /// 出资记录，种子 `["contribution", pool, contributor]`
+    Contribution(&'a Contribution),
+    /// 成交承诺，种子 `["commit", taker, hash]`
+    Commitment(&'a Commitment),
+    /// maker 统计，种子 `["stats", maker]`
+    MakerStats(&'a MakerStats),
+    /// 成交回执，种子 `["receipt", escrow, taker]`
+    TradeReceipt(&'a TradeReceipt),
+    /// 手续费账本，种子 `["fee_vault", mint]`
+    FeeVault(&'a FeeVault),
+    /// maker 的种子计数器，种子 `["counter", maker]`
+    MakerCounter(&'a MakerCounter),
+}
+
+/// 根据账户数据开头的判别器识别账户类型
+pub fn try_decode_account(data: &[u8]) -> Option<EscrowAccountType<'_>> {
+    if let Some(version) = Escrow::stored_version(data) {
+        return decode_escrow(data, version);
+    }
+
+    let discriminator: [u8; 8] = data.get(..8)?.try_into().ok()?;
+    match discriminator {
+        Whitelist::ACCOUNT_DISCRIMINATOR => Whitelist::load(data).ok().map(EscrowAccountType::Whitelist),
+        Bid::ACCOUNT_DISCRIMINATOR => Bid::load(data).ok().map(EscrowAccountType::Bid),
+        Offer::ACCOUNT_DISCRIMINATOR => Offer::load(data).ok().map(EscrowAccountType::Offer),
+        Config::ACCOUNT_DISCRIMINATOR => Config::load(data).ok().map(EscrowAccountType::Config),
+        Pool::ACCOUNT_DISCRIMINATOR => Pool::load(data).ok().map(EscrowAccountType::Pool),
+        Contribution::ACCOUNT_DISCRIMINATOR => Contribution::load(data).ok().map(EscrowAccountType::Contribution),
+        Commitment::ACCOUNT_DISCRIMINATOR => Commitment::load(data).ok().map(EscrowAccountType::Commitment),
+        MakerStats::ACCOUNT_DISCRIMINATOR => MakerStats::load(data).ok().map(EscrowAccountType::MakerStats),
+        TradeReceipt::ACCOUNT_DISCRIMINATOR => TradeReceipt::load(data).ok().map(EscrowAccountType::TradeReceipt),
+        FeeVault::ACCOUNT_DISCRIMINATOR => FeeVault::load(data).ok().map(EscrowAccountType::FeeVault),
+        MakerCounter::ACCOUNT_DISCRIMINATOR => MakerCounter::load(data).ok().map(EscrowAccountType::MakerCounter),
+        _ => None,
+    }
+}
+
+/// 按记录的布局版本解码 Escrow
+fn decode_escrow(data: &[u8], version: u8) -> Option<EscrowAccountType<'_>> {
+    match version {
+        Escrow::CURRENT_VERSION => Escrow::load(data).ok().map(EscrowAccountType::Escrow),
+        version if version < Escrow::CURRENT_VERSION => Some(EscrowAccountType::OutdatedEscrow(version)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::mem::align_of;
+
+    /// 8 字节对齐的缓冲区（零拷贝读取要求对齐）
+    struct Buffer([u64; 128]);
+
+    impl Buffer {
+        fn new() -> Self {
+            Self([0; 128])
+        }
+
+        fn bytes(&mut self) -> &mut [u8] {
+            unsafe { core::slice::from_raw_parts_mut(self.0.as_mut_ptr().cast::<u8>(), self.0.len() * 8) }
+        }
+
+        /// 以 `discriminator` 开头、长度为 `len` 的账户数据
+        fn account(&mut self, discriminator: &[u8], len: usize) -> &[u8] {
+            let bytes = self.bytes();
+            bytes[..discriminator.len()].copy_from_slice(discriminator);
+            &bytes[..len]
+        }
+    }
+
+    fn kind(data: &[u8]) -> Option<&'static str> {
+        Some(match try_decode_account(data)? {
+            EscrowAccountType::Escrow(_) => "Escrow",
+            EscrowAccountType::OutdatedEscrow(_) => "OutdatedEscrow",
+            EscrowAccountType::Whitelist(_) => "Whitelist",
+            EscrowAccountType::Bid(_) => "Bid",
+            EscrowAccountType::Offer(_) => "Offer",
+            EscrowAccountType::Config(_) => "Config",
+            EscrowAccountType::Pool(_) => "Pool",
+            EscrowAccountType::Contribution(_) => "Contribution",
+            EscrowAccountType::Commitment(_) => "Commitment",
+            EscrowAccountType::MakerStats(_) => "MakerStats",
+            EscrowAccountType::TradeReceipt(_) => "TradeReceipt",
+            EscrowAccountType::FeeVault(_) => "FeeVault",
+            EscrowAccountType::MakerCounter(_) => "MakerCounter",
+        })
+    }
+
+    /// Escrow 之外的每种账户：判别器、长度、对齐要求与 `kind` 返回的名称
+    fn accounts() -> [([u8; 8], usize, usize, &'static str); 11] {
+        [
+            (Whitelist::ACCOUNT_DISCRIMINATOR, Whitelist::LEN, align_of::<Whitelist>(), "Whitelist"),
+            (Bid::ACCOUNT_DISCRIMINATOR, Bid::LEN, align_of::<Bid>(), "Bid"),
+            (Offer::ACCOUNT_DISCRIMINATOR, Offer::LEN, align_of::<Offer>(), "Offer"),
+            (Config::ACCOUNT_DISCRIMINATOR, Config::LEN, align_of::<Config>(), "Config"),
+            (Pool::ACCOUNT_DISCRIMINATOR, Pool::LEN, align_of::<Pool>(), "Pool"),
+            (Contribution::ACCOUNT_DISCRIMINATOR, Contribution::LEN, align_of::<Contribution>(), "Contribution"),
+            (Commitment::ACCOUNT_DISCRIMINATOR, Commitment::LEN, align_of::<Commitment>(), "Commitment"),
+            (MakerStats::ACCOUNT_DISCRIMINATOR, MakerStats::LEN, align_of::<MakerStats>(), "MakerStats"),
+            (TradeReceipt::ACCOUNT_DISCRIMINATOR, TradeReceipt::LEN, align_of::<TradeReceipt>(), "TradeReceipt"),
+            (FeeVault::ACCOUNT_DISCRIMINATOR, FeeVault::LEN, align_of::<FeeVault>(), "FeeVault"),
+            (MakerCounter::ACCOUNT_DISCRIMINATOR, MakerCounter::LEN, align_of::<MakerCounter>(), "MakerCounter"),
+        ]
+    }
+
+    #[test]
+    fn decodes_every_account_type_by_discriminator() {
+
+        for (discriminator, len, _, name) in accounts() {
+            let mut buffer = Buffer::new();
+            assert_eq!(kind(buffer.account(&discriminator, len)), Some(name));
+            // 判别器正确但长度不符时不解码
+            assert_eq!(kind(buffer.account(&discriminator, len + 1)), None, "{name}");
+        }
+
+        // Bid、Offer、Contribution、Commitment 长度相同，只靠判别器区分
+        assert_eq!(Bid::LEN, Contribution::LEN);
+        assert_eq!(Offer::LEN, Commitment::LEN);
+    }
+
+    #[test]
+    fn decodes_escrow_by_layout_version() {
+        let mut header = [0u8; Escrow::VERSION_OFFSET + 1];
+        header[..8].copy_from_slice(&Escrow::ACCOUNT_DISCRIMINATOR);
+        header[Escrow::VERSION_OFFSET] = Escrow::CURRENT_VERSION;
+        let mut buffer = Buffer::new();
+        assert_eq!(kind(buffer.account(&header, Escrow::LEN)), Some("Escrow"));
+
+        // 旧版本返回版本号（长度可以与当前布局不同），高于当前版本的不解码
+        header[Escrow::VERSION_OFFSET] = Escrow::CURRENT_VERSION - 1;
+        let data = buffer.account(&header, 100);
+        assert!(matches!(
+            try_decode_account(data),
+            Some(EscrowAccountType::OutdatedEscrow(version)) if version == Escrow::CURRENT_VERSION - 1
+        ));
+        header[Escrow::VERSION_OFFSET] = Escrow::CURRENT_VERSION + 1;
+        assert_eq!(kind(buffer.account(&header, Escrow::LEN)), None);
+    }
+
+    #[test]
+    fn ignores_closed_unknown_and_unaligned_data() {
+        assert_eq!(kind(&[]), None);
+        assert_eq!(kind(Buffer::new().account(&[], 7)), None);
+
+        for (discriminator, len, align, name) in accounts() {
+            let mut buffer = Buffer::new();
+            // 已关闭账户的第一个字节为关闭标记，其余全 0
+            assert_eq!(kind(buffer.account(&[0xff], len)), None, "{name}");
+            assert_eq!(kind(buffer.account(b"unknown!", len)), None, "{name}");
+
+            // 零拷贝读取要求按类型对齐（Whitelist 只包含字节数组，任意地址都对齐）
+            let bytes = buffer.bytes();
+            bytes[1..9].copy_from_slice(&discriminator);
+            let expected = (align == 1).then_some(name);
+            assert_eq!(kind(&bytes[1..1 + len]), expected, "{name}");
+        }
+
+        let mut buffer = Buffer::new();
+        let bytes = buffer.bytes();
+        bytes[1..9].copy_from_slice(&Escrow::ACCOUNT_DISCRIMINATOR);
+        bytes[1 + Escrow::VERSION_OFFSET] = Escrow::CURRENT_VERSION;
+        assert_eq!(kind(&bytes[1..1 + Escrow::LEN]), None);
+    }
+}
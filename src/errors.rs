@@ -3,7 +3,7 @@
 //! 本文件列举了程序中可能返回的自定义错误类型（映射为 ProgramError::Custom）。
 //! 每个错误带有简短中文说明，便于本地化调试与日志阅读。
 //!
-use pinocchio::error::ProgramError;
+use pinocchio::error::{ProgramError, ToStr};
 use core::fmt;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -31,8 +31,263 @@ pub enum EscrowError {
 
     /// 非法的地址
     /// 提供的地址不符合预期要求
-    /// 例如：PDA 派生失败、地址不匹配等
+    /// 已不再返回：各类地址不匹配都有具体的错误（如 `InvalidMint`、`InvalidBidPda`），保留以免错误码变化
     InvalidAddress = 4,
+
+    /// Escrow 已过期
+    /// 设置了过期时间的 Escrow 在到期后不能再被成交
+    /// 只能通过 CloseExpired 或 Refund 关闭
+    EscrowExpired = 5,
+
+    /// Escrow 尚未过期
+    /// CloseExpired 只能在过期时间之后调用
+    /// 未设置过期时间的 Escrow 永远不会过期
+    EscrowNotExpired = 6,
+
+    /// 未授权的 taker
+    /// 私有 Escrow（Make 时指定了 taker）只能由指定的 taker 成交
+    /// 用于双方已知的场外（OTC）交易
+    UnauthorizedTaker = 7,
+
+    /// 白名单已满
+    /// 每个 Escrow 的白名单最多容纳 Whitelist::MAX_ENTRIES 个地址
+    WhitelistFull = 8,
+
+    /// 对手方已存入 Token B
+    /// 双向托管在对手方 Deposit 之后双方都已锁定资产
+    /// 只能通过 Settle 原子结算，不能再成交、修改或退款
+    DepositPending = 9,
+
+    /// 拍卖模式的 Escrow
+    /// 拍卖只能通过 PlaceBid / AcceptBid / CancelAuction 操作
+    /// 不能直接 Take、修改报价或退款
+    AuctionMode = 10,
+
+    /// 出价过低
+    /// 出价必须不低于底价（Escrow 的 receive），且高于当前最高出价
+    BidTooLow = 11,
+
+    /// 尚无出价
+    /// AcceptBid 需要至少一个有效出价，无人出价的拍卖请使用 CancelAuction
+    NoBid = 12,
+
+    /// 已有出价
+    /// 有人出价后 maker 不能再取消拍卖，只能接受出价
+    BidPending = 13,
+
+    /// 荷兰拍最低价非法
+    /// 最低价必须大于 0 且不高于起拍价，否则降价结束后 Token A 会被白拿
+    InvalidFloorPrice = 14,
+
+    /// 推荐人非法
+    /// 推荐人不能是 taker 本人（否则 taker 可以给自己返佣），以 Token 支付时推荐人的账户必须是支付代币的 Token Account
+    InvalidReferrer = 15,
+
+    /// 托管已成交
+    /// 线性释放的 Escrow 在 Take 之后进入释放阶段
+    /// 只能由 taker 通过 Claim 领取，不能再成交、修改或退款
+    EscrowTaken = 16,
+
+    /// 三方托管的 Escrow
+    /// 对手方只能通过 Deposit 锁定 Token B，之后由 Resolve 结算
+    /// 不能直接 Take、还价或通过 Settle 结算
+    ArbiterMode = 17,
+
+    /// 无权裁决
+    /// 争议中只有仲裁人可以裁决；未发生争议时 maker 只能放行给对手方，对手方只能退回给 maker
+    NotArbiter = 18,
+
+    /// 众筹未凑齐
+    /// 众筹池中的 Token B 必须恰好等于 Escrow 的 receive 才能 Finalize
+    PoolNotFilled = 19,
+
+    /// 成交承诺不匹配
+    /// RevealTake 揭示的 (escrow, taker, salt) 与 CommitTake 提交的哈希不一致
+    InvalidCommitment = 20,
+
+    /// 揭示过早
+    /// 提交承诺之后至少要经过 `Commitment::MIN_REVEAL_SLOTS` 个 slot 才能 RevealTake
+    RevealTooEarly = 21,
+
+    /// 报价仍在锁定期内
+    /// Make 时设置了 refund_locked_until，在此之前 maker 不能撤回报价
+    RefundLocked = 22,
+
+    /// 超出滑点
+    /// 应付的 Token B 超过 taker 给出的 max_receive，或可得的 Token A 少于 min_token_a_out
+    SlippageExceeded = 23,
+
+    /// NFT 不属于要求的合集
+    /// Metaplex 元数据中没有已验证的合集，或合集与 MakeNft 指定的不一致
+    CollectionMismatch = 24,
+
+    /// Token Account 已冻结或未初始化
+    /// 转账前检查 vault 与收付款账户的状态，避免 Token Program CPI 以不透明的错误失败
+    AccountFrozen = 25,
+
+    /// Mint 设置了冻结权限
+    /// Make 启用严格模式时，mint_a / mint_b 不能带有冻结权限
+    MintFreezeAuthority = 26,
+
+    /// Escrow 布局版本过旧
+    /// 程序升级后旧版本的 Escrow 需要先通过 Migrate 升级到当前布局
+    OutdatedVersion = 27,
+
+    /// 程序已暂停
+    /// 全局配置的暂停开关打开时拒绝 Make 与 Take
+    ProgramPaused = 28,
+
+    /// 托管数量超出范围
+    /// Make 托管的 Token A 数量必须在全局配置的 min_amount 与 max_amount 之间
+    AmountOutOfRange = 29,
+
+    /// 代币对无效
+    /// Make 时 mint_a 与 mint_b 不能相同，否则 Escrow 只是把同一种代币换给自己
+    InvalidMintPair = 30,
+
+    /// 数量为 0
+    /// Make 时托管数量与期望接收的数量都必须大于 0
+    ZeroAmount = 31,
+
+    /// 自成交
+    /// taker 不能是 Escrow 的 maker
+    SelfTrade = 32,
+
+    /// 账户重复
+    /// 同一个地址被传入了多个含义不同的账户位置
+    DuplicateAccount = 33,
+
+    /// 数值溢出
+    /// 数量的加减乘除超出 u64 范围（或出现下溢），no_std 下的 panic 只会表现为不透明的中止，因此显式返回
+    MathOverflow = 34,
+
+    /// vault 权限异常
+    /// vault 设置了 delegate，或 close authority 不是 Escrow PDA，可能在成交前被转走或关闭
+    InvalidVaultAuthority = 35,
+
+    /// 修订号不匹配
+    /// Escrow 在客户端读取之后已被修改，需要重新读取后再提交
+    StaleRevision = 36,
+
+    /// 账户不可写
+    /// 指令需要修改的账户（Escrow、vault、ATA、maker 等）必须以可写方式传入
+    NotWritable = 37,
+
+    /// Escrow PDA 不匹配
+    /// 传入的 Escrow 地址不是由 maker 与 seed 派生的 PDA
+    InvalidEscrowPda = 38,
+
+    /// vault 不匹配
+    /// 传入的 vault 不是 Escrow 记录（或派生）的托管账户
+    InvalidVault = 39,
+
+    /// maker 不匹配
+    /// 传入的 maker 与 Escrow 中记录的创建者不一致
+    MakerMismatch = 40,
+
+    /// 数量不匹配
+    /// 指令数据中的数量与该类托管要求的数量不一致（如 NFT 托管数量必须为 1）
+    AmountMismatch = 41,
+
+    /// 费率过高
+    /// 手续费超过 100%，或推荐费（从手续费中分出）高于手续费
+    FeeTooHigh = 42,
+
+    /// Mint 不匹配
+    /// 传入的 Mint 与 Escrow（或捆绑代币）记录的 Mint 不一致，或不是 Escrow 接受的支付代币
+    InvalidMint = 43,
+
+    /// 白名单 PDA 不匹配
+    /// 传入的白名单账户不是该 Escrow 的白名单 PDA（种子 `["whitelist", escrow]`）
+    InvalidWhitelistPda = 44,
+
+    /// 出价 PDA 不匹配
+    /// 传入的出价账户不是该 Escrow 的出价 PDA（种子 `["bid", escrow]`）
+    InvalidBidPda = 45,
+
+    /// 出价人不匹配
+    /// 传入的出价人与出价 PDA 中记录的最高出价人不一致
+    InvalidBidder = 46,
+
+    /// 还价 PDA 不匹配
+    /// 传入的还价账户不是该 Escrow 与还价者的还价 PDA（种子 `["offer", escrow, proposer]`）
+    InvalidOfferPda = 47,
+
+    /// 众筹池 PDA 不匹配
+    /// 传入的众筹池账户不是该 Escrow 的众筹池 PDA（种子 `["pool", escrow]`）
+    InvalidPoolPda = 48,
+
+    /// 出资记录 PDA 不匹配
+    /// 传入的出资记录不是该众筹池与出资人的出资 PDA（种子 `["contribution", pool, contributor]`）
+    InvalidContributionPda = 49,
+
+    /// 不是众筹池的创建者
+    /// 租金返还账户与众筹池记录的创建者不一致
+    NotPoolCreator = 50,
+
+    /// 承诺 PDA 不匹配
+    /// 传入的承诺账户不是该 taker 的承诺 PDA（种子 `["commit", taker, hash]`）
+    InvalidCommitmentPda = 51,
+
+    /// 统计 PDA 不匹配
+    /// 传入的统计账户不是该 maker 的统计 PDA（种子 `["stats", maker]`）
+    InvalidStatsPda = 52,
+
+    /// 种子计数器 PDA 不匹配
+    /// 传入的计数器不是该 maker 的种子计数器 PDA（种子 `["counter", maker]`）
+    InvalidCounterPda = 53,
+
+    /// 手续费账本 PDA 不匹配
+    /// 传入的账本不是该手续费代币的账本 PDA（种子 `["fee_vault", mint]`）
+    InvalidFeeVaultPda = 54,
+
+    /// 全局配置 PDA 不匹配
+    /// 传入的配置账户不是全局配置 PDA（种子 `["config"]`）
+    InvalidConfigPda = 55,
+
+    /// 不是手续费管理员
+    /// 签名者与全局配置中记录的手续费管理员不一致
+    NotFeeAuthority = 56,
+
+    /// 成交回执 PDA 不匹配
+    /// 传入的回执账户不是该 Escrow 与 taker 的回执 PDA（种子 `["receipt", escrow, taker]`）
+    InvalidReceiptPda = 57,
+
+    /// 罚金接收者不匹配
+    /// 传入的罚金接收者与 Escrow 中记录的不一致
+    InvalidPenaltyRecipient = 58,
+
+    /// 新 maker 非法
+    /// 转让 Escrow 时新 maker 不能与当前 maker 相同
+    InvalidNewMaker = 59,
+
+    /// NFT 元数据不匹配
+    /// 元数据账户不是 Metaplex 为该 mint 派生的 PDA，或记录的 mint 不一致
+    InvalidMetadata = 60,
+
+    /// 版税接收者不匹配
+    /// 版税收款账户不属于元数据中对应的创作者，或不是支付代币的 Token Account
+    InvalidRoyaltyRecipient = 61,
+
+    /// ATA 地址不匹配
+    /// 传入的账户不是该 owner 与 mint 的关联代币账户
+    InvalidAssociatedTokenAccount = 62,
+
+    /// Transfer Hook 账户不匹配
+    /// hook 程序或 ExtraAccountMetaList 校验账户与 mint 登记的不一致
+    InvalidHookAccounts = 63,
+
+    /// 代币账户不匹配
+    /// 代币账户的 owner 或 mint 与指令要求的不一致
+    InvalidTokenAccount = 64,
+
+    /// 不是争议的当事方
+    /// 只有 maker 或指定的 taker 可以对三方托管发起争议
+    NotDisputeParty = 65,
+
+    /// 不支持的指令数据版本
+    /// 带版本前缀的指令数据使用了本程序不认识的版本号（见 instruction.rs）
+    UnsupportedPayloadVersion = 66,
 }
 
 impl From<EscrowError> for ProgramError {
@@ -41,6 +296,172 @@ impl From<EscrowError> for ProgramError {
     }
 }
 
+impl TryFrom<u32> for EscrowError {
+    type Error = ProgramError;
+
+    fn try_from(code: u32) -> Result<Self, Self::Error> {
+        match code {
+            0 => Ok(EscrowError::NotRentExempt),
+            1 => Ok(EscrowError::NotSigner),
+            2 => Ok(EscrowError::InvalidOwner),
+            3 => Ok(EscrowError::InvalidAccountData),
+            4 => Ok(EscrowError::InvalidAddress),
+            5 => Ok(EscrowError::EscrowExpired),
+            6 => Ok(EscrowError::EscrowNotExpired),
+            7 => Ok(EscrowError::UnauthorizedTaker),
+            8 => Ok(EscrowError::WhitelistFull),
+            9 => Ok(EscrowError::DepositPending),
+            10 => Ok(EscrowError::AuctionMode),
+            11 => Ok(EscrowError::BidTooLow),
+            12 => Ok(EscrowError::NoBid),
+            13 => Ok(EscrowError::BidPending),
+            14 => Ok(EscrowError::InvalidFloorPrice),
+            15 => Ok(EscrowError::InvalidReferrer),
+            16 => Ok(EscrowError::EscrowTaken),
+            17 => Ok(EscrowError::ArbiterMode),
+            18 => Ok(EscrowError::NotArbiter),
+            19 => Ok(EscrowError::PoolNotFilled),
+            20 => Ok(EscrowError::InvalidCommitment),
+            21 => Ok(EscrowError::RevealTooEarly),
+            22 => Ok(EscrowError::RefundLocked),
+            23 => Ok(EscrowError::SlippageExceeded),
+            24 => Ok(EscrowError::CollectionMismatch),
+            25 => Ok(EscrowError::AccountFrozen),
+            26 => Ok(EscrowError::MintFreezeAuthority),
+            27 => Ok(EscrowError::OutdatedVersion),
+            28 => Ok(EscrowError::ProgramPaused),
+            29 => Ok(EscrowError::AmountOutOfRange),
+            30 => Ok(EscrowError::InvalidMintPair),
+            31 => Ok(EscrowError::ZeroAmount),
+            32 => Ok(EscrowError::SelfTrade),
+            33 => Ok(EscrowError::DuplicateAccount),
+            34 => Ok(EscrowError::MathOverflow),
+            35 => Ok(EscrowError::InvalidVaultAuthority),
+            36 => Ok(EscrowError::StaleRevision),
+            37 => Ok(EscrowError::NotWritable),
+            38 => Ok(EscrowError::InvalidEscrowPda),
+            39 => Ok(EscrowError::InvalidVault),
+            40 => Ok(EscrowError::MakerMismatch),
+            41 => Ok(EscrowError::AmountMismatch),
+            42 => Ok(EscrowError::FeeTooHigh),
+            43 => Ok(EscrowError::InvalidMint),
+            44 => Ok(EscrowError::InvalidWhitelistPda),
+            45 => Ok(EscrowError::InvalidBidPda),
+            46 => Ok(EscrowError::InvalidBidder),
+            47 => Ok(EscrowError::InvalidOfferPda),
+            48 => Ok(EscrowError::InvalidPoolPda),
+            49 => Ok(EscrowError::InvalidContributionPda),
+            50 => Ok(EscrowError::NotPoolCreator),
+            51 => Ok(EscrowError::InvalidCommitmentPda),
+            52 => Ok(EscrowError::InvalidStatsPda),
+            53 => Ok(EscrowError::InvalidCounterPda),
+            54 => Ok(EscrowError::InvalidFeeVaultPda),
+            55 => Ok(EscrowError::InvalidConfigPda),
+            56 => Ok(EscrowError::NotFeeAuthority),
+            57 => Ok(EscrowError::InvalidReceiptPda),
+            58 => Ok(EscrowError::InvalidPenaltyRecipient),
+            59 => Ok(EscrowError::InvalidNewMaker),
+            60 => Ok(EscrowError::InvalidMetadata),
+            61 => Ok(EscrowError::InvalidRoyaltyRecipient),
+            62 => Ok(EscrowError::InvalidAssociatedTokenAccount),
+            63 => Ok(EscrowError::InvalidHookAccounts),
+            64 => Ok(EscrowError::InvalidTokenAccount),
+            65 => Ok(EscrowError::NotDisputeParty),
+            66 => Ok(EscrowError::UnsupportedPayloadVersion),
+            _ => Err(ProgramError::InvalidArgument),
+        }
+    }
+}
+
+/// 与 `ProgramError::to_str` 的内置错误保持相同的 `Error: 名称` 格式，日志中直接显示错误名而不是错误码
+impl ToStr for EscrowError {
+    fn to_str(&self) -> &'static str {
+        match self {
+            EscrowError::NotRentExempt => "Error: NotRentExempt",
+            EscrowError::NotSigner => "Error: NotSigner",
+            EscrowError::InvalidOwner => "Error: InvalidOwner",
+            EscrowError::InvalidAccountData => "Error: InvalidAccountData",
+            EscrowError::InvalidAddress => "Error: InvalidAddress",
+            EscrowError::EscrowExpired => "Error: EscrowExpired",
+            EscrowError::EscrowNotExpired => "Error: EscrowNotExpired",
+            EscrowError::UnauthorizedTaker => "Error: UnauthorizedTaker",
+            EscrowError::WhitelistFull => "Error: WhitelistFull",
+            EscrowError::DepositPending => "Error: DepositPending",
+            EscrowError::AuctionMode => "Error: AuctionMode",
+            EscrowError::BidTooLow => "Error: BidTooLow",
+            EscrowError::NoBid => "Error: NoBid",
+            EscrowError::BidPending => "Error: BidPending",
+            EscrowError::InvalidFloorPrice => "Error: InvalidFloorPrice",
+            EscrowError::InvalidReferrer => "Error: InvalidReferrer",
+            EscrowError::EscrowTaken => "Error: EscrowTaken",
+            EscrowError::ArbiterMode => "Error: ArbiterMode",
+            EscrowError::NotArbiter => "Error: NotArbiter",
+            EscrowError::PoolNotFilled => "Error: PoolNotFilled",
+            EscrowError::InvalidCommitment => "Error: InvalidCommitment",
+            EscrowError::RevealTooEarly => "Error: RevealTooEarly",
+            EscrowError::RefundLocked => "Error: RefundLocked",
+            EscrowError::SlippageExceeded => "Error: SlippageExceeded",
+            EscrowError::CollectionMismatch => "Error: CollectionMismatch",
+            EscrowError::AccountFrozen => "Error: AccountFrozen",
+            EscrowError::MintFreezeAuthority => "Error: MintFreezeAuthority",
+            EscrowError::OutdatedVersion => "Error: OutdatedVersion",
+            EscrowError::ProgramPaused => "Error: ProgramPaused",
+            EscrowError::AmountOutOfRange => "Error: AmountOutOfRange",
+            EscrowError::InvalidMintPair => "Error: InvalidMintPair",
+            EscrowError::ZeroAmount => "Error: ZeroAmount",
+            EscrowError::SelfTrade => "Error: SelfTrade",
+            EscrowError::DuplicateAccount => "Error: DuplicateAccount",
+            EscrowError::MathOverflow => "Error: MathOverflow",
+            EscrowError::InvalidVaultAuthority => "Error: InvalidVaultAuthority",
+            EscrowError::StaleRevision => "Error: StaleRevision",
+            EscrowError::NotWritable => "Error: NotWritable",
+            EscrowError::InvalidEscrowPda => "Error: InvalidEscrowPda",
+            EscrowError::InvalidVault => "Error: InvalidVault",
+            EscrowError::MakerMismatch => "Error: MakerMismatch",
+            EscrowError::AmountMismatch => "Error: AmountMismatch",
+            EscrowError::FeeTooHigh => "Error: FeeTooHigh",
+            EscrowError::InvalidMint => "Error: InvalidMint",
+            EscrowError::InvalidWhitelistPda => "Error: InvalidWhitelistPda",
+            EscrowError::InvalidBidPda => "Error: InvalidBidPda",
+            EscrowError::InvalidBidder => "Error: InvalidBidder",
+            EscrowError::InvalidOfferPda => "Error: InvalidOfferPda",
+            EscrowError::InvalidPoolPda => "Error: InvalidPoolPda",
+            EscrowError::InvalidContributionPda => "Error: InvalidContributionPda",
+            EscrowError::NotPoolCreator => "Error: NotPoolCreator",
+            EscrowError::InvalidCommitmentPda => "Error: InvalidCommitmentPda",
+            EscrowError::InvalidStatsPda => "Error: InvalidStatsPda",
+            EscrowError::InvalidCounterPda => "Error: InvalidCounterPda",
+            EscrowError::InvalidFeeVaultPda => "Error: InvalidFeeVaultPda",
+            EscrowError::InvalidConfigPda => "Error: InvalidConfigPda",
+            EscrowError::NotFeeAuthority => "Error: NotFeeAuthority",
+            EscrowError::InvalidReceiptPda => "Error: InvalidReceiptPda",
+            EscrowError::InvalidPenaltyRecipient => "Error: InvalidPenaltyRecipient",
+            EscrowError::InvalidNewMaker => "Error: InvalidNewMaker",
+            EscrowError::InvalidMetadata => "Error: InvalidMetadata",
+            EscrowError::InvalidRoyaltyRecipient => "Error: InvalidRoyaltyRecipient",
+            EscrowError::InvalidAssociatedTokenAccount => "Error: InvalidAssociatedTokenAccount",
+            EscrowError::InvalidHookAccounts => "Error: InvalidHookAccounts",
+            EscrowError::InvalidTokenAccount => "Error: InvalidTokenAccount",
+            EscrowError::NotDisputeParty => "Error: NotDisputeParty",
+            EscrowError::UnsupportedPayloadVersion => "Error: UnsupportedPayloadVersion",
+        }
+    }
+}
+
+/// 把指令失败的错误名写入程序日志
+///
+/// 浏览器只会显示 `custom program error: 0x..`，日志中的错误名便于直接定位失败原因。
+/// 只有 Solana 目标才有日志 syscall，其他目标（本地构建、测试）下什么也不做。
+pub fn log_error(error: &ProgramError) {
+    let message = error.to_str::<EscrowError>();
+    #[cfg(target_os = "solana")]
+    unsafe {
+        pinocchio::syscalls::sol_log_(message.as_ptr(), message.len() as u64);
+    }
+    #[cfg(not(target_os = "solana"))]
+    let _ = message;
+}
+
 impl fmt::Display for EscrowError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -49,6 +470,68 @@ impl fmt::Display for EscrowError {
             EscrowError::InvalidOwner => write!(f, "非法的所有者"),
             EscrowError::InvalidAccountData => write!(f, "非法的账户数据"),
             EscrowError::InvalidAddress => write!(f, "非法的地址"),
+            EscrowError::EscrowExpired => write!(f, "托管已过期"),
+            EscrowError::EscrowNotExpired => write!(f, "托管尚未过期"),
+            EscrowError::UnauthorizedTaker => write!(f, "未授权的接受者"),
+            EscrowError::WhitelistFull => write!(f, "白名单已满"),
+            EscrowError::DepositPending => write!(f, "对手方已存入代币，只能结算"),
+            EscrowError::AuctionMode => write!(f, "拍卖模式的托管只能通过出价成交"),
+            EscrowError::BidTooLow => write!(f, "出价过低"),
+            EscrowError::NoBid => write!(f, "尚无出价"),
+            EscrowError::BidPending => write!(f, "已有出价，不能取消拍卖"),
+            EscrowError::InvalidFloorPrice => write!(f, "荷兰拍最低价必须大于 0 且不高于起拍价"),
+            EscrowError::InvalidReferrer => write!(f, "推荐人不能是 taker，且必须持有支付代币"),
+            EscrowError::EscrowTaken => write!(f, "托管已成交，正在释放"),
+            EscrowError::ArbiterMode => write!(f, "三方托管只能通过 Resolve 结算"),
+            EscrowError::NotArbiter => write!(f, "无权裁决"),
+            EscrowError::PoolNotFilled => write!(f, "众筹未凑齐"),
+            EscrowError::InvalidCommitment => write!(f, "成交承诺不匹配"),
+            EscrowError::RevealTooEarly => write!(f, "揭示过早"),
+            EscrowError::RefundLocked => write!(f, "报价仍在锁定期内，不能撤回"),
+            EscrowError::SlippageExceeded => write!(f, "超出滑点"),
+            EscrowError::CollectionMismatch => write!(f, "NFT 不属于要求的合集"),
+            EscrowError::AccountFrozen => write!(f, "Token Account 已冻结或未初始化"),
+            EscrowError::MintFreezeAuthority => write!(f, "Mint 设置了冻结权限"),
+            EscrowError::OutdatedVersion => write!(f, "Escrow 布局版本过旧，需要先执行 Migrate"),
+            EscrowError::ProgramPaused => write!(f, "程序已暂停"),
+            EscrowError::AmountOutOfRange => write!(f, "托管数量超出允许范围"),
+            EscrowError::InvalidMintPair => write!(f, "mint_a 与 mint_b 不能相同"),
+            EscrowError::ZeroAmount => write!(f, "数量不能为 0"),
+            EscrowError::SelfTrade => write!(f, "taker 不能是 maker"),
+            EscrowError::DuplicateAccount => write!(f, "同一账户被用于多个不同的角色"),
+            EscrowError::MathOverflow => write!(f, "数值溢出"),
+            EscrowError::InvalidVaultAuthority => write!(f, "vault 设置了 delegate 或其他 close authority"),
+            EscrowError::StaleRevision => write!(f, "Escrow 已被修改，修订号不匹配"),
+            EscrowError::NotWritable => write!(f, "账户不可写"),
+            EscrowError::InvalidEscrowPda => write!(f, "Escrow PDA 不匹配"),
+            EscrowError::InvalidVault => write!(f, "vault 不匹配"),
+            EscrowError::MakerMismatch => write!(f, "maker 与 Escrow 记录的创建者不一致"),
+            EscrowError::AmountMismatch => write!(f, "数量与托管要求不一致"),
+            EscrowError::FeeTooHigh => write!(f, "手续费超过 100% 或推荐费高于手续费"),
+            EscrowError::InvalidMint => write!(f, "Mint 不匹配"),
+            EscrowError::InvalidWhitelistPda => write!(f, "白名单 PDA 不匹配"),
+            EscrowError::InvalidBidPda => write!(f, "出价 PDA 不匹配"),
+            EscrowError::InvalidBidder => write!(f, "出价人不匹配"),
+            EscrowError::InvalidOfferPda => write!(f, "还价 PDA 不匹配"),
+            EscrowError::InvalidPoolPda => write!(f, "众筹池 PDA 不匹配"),
+            EscrowError::InvalidContributionPda => write!(f, "出资记录 PDA 不匹配"),
+            EscrowError::NotPoolCreator => write!(f, "不是众筹池的创建者"),
+            EscrowError::InvalidCommitmentPda => write!(f, "承诺 PDA 不匹配"),
+            EscrowError::InvalidStatsPda => write!(f, "统计 PDA 不匹配"),
+            EscrowError::InvalidCounterPda => write!(f, "种子计数器 PDA 不匹配"),
+            EscrowError::InvalidFeeVaultPda => write!(f, "手续费账本 PDA 不匹配"),
+            EscrowError::InvalidConfigPda => write!(f, "全局配置 PDA 不匹配"),
+            EscrowError::NotFeeAuthority => write!(f, "不是手续费管理员"),
+            EscrowError::InvalidReceiptPda => write!(f, "成交回执 PDA 不匹配"),
+            EscrowError::InvalidPenaltyRecipient => write!(f, "罚金接收者不匹配"),
+            EscrowError::InvalidNewMaker => write!(f, "新 maker 非法"),
+            EscrowError::InvalidMetadata => write!(f, "NFT 元数据不匹配"),
+            EscrowError::InvalidRoyaltyRecipient => write!(f, "版税接收者不匹配"),
+            EscrowError::InvalidAssociatedTokenAccount => write!(f, "ATA 地址不匹配"),
+            EscrowError::InvalidHookAccounts => write!(f, "Transfer Hook 账户不匹配"),
+            EscrowError::InvalidTokenAccount => write!(f, "代币账户不匹配"),
+            EscrowError::NotDisputeParty => write!(f, "只有交易双方可以发起争议"),
+            EscrowError::UnsupportedPayloadVersion => write!(f, "不支持的指令数据版本"),
         }
     }
 }
\ No newline at end of file
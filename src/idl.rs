@@ -0,0 +1,806 @@
+//! IDL 描述（`idl-build` feature）
+//!
+//! 浏览器与客户端生成器需要 IDL 才能解析指令与账户，本模块用常量表标注 Make / Take / Refund 的账户列表、
+//! 指令数据、PDA 种子与 Escrow 的账户布局，并输出两种 JSON：
+//! - `write_json`：Anchor 格式（spec 0.1.0）的 IDL
+//! - `write_codama_json`：Codama 节点树（rootNode），包含 PDA 种子定义与账户默认值解析规则
+//!   （如 `vault = ATA(escrow, mint_a)`），JS / Rust 客户端可以完整生成，包括 PDA 自动派生
+//!
+//! 表中的判别器、sighash、种子、字段偏移与长度都直接引用程序中的常量（`DISCRIMINATOR`、`SIGHASHES`、
+//! `ESCROW_SEED`、`offset_of!`），程序布局变化时 IDL 随之更新，字段遗漏或顺序错误会在编译期断言失败。
+//!
+//! 生成 IDL：
+//! - `cargo run --example idl --features idl-build > idl/blueshift_escrow.json`
+//! - `cargo run --example codama --features idl-build > idl/blueshift_escrow.codama.json`
+//!
+//! 说明：
+//! - 本模块只使用 `core`，不引入 shank、codama 等依赖；程序构建不启用 `idl-build`，不会增加链上体积
+//! - Anchor IDL 的指令判别器为 8 字节 sighash（程序同时接受，见 sighash.rs），Anchor 客户端可直接使用；
+//!   Codama 节点树使用 1 字节判别器
+//! - 指令参数只描述定长的基本形式（Make 为 64 字节，Take 为 16 字节，Refund 为 1 字节），
+//!   选项字节及其参数、可选账户等扩展形式写在 `docs` 中
+//! - Anchor IDL 的每个字段额外输出 `offset` 与 `size`（字节），便于不使用 Borsh 的客户端按偏移直接读取
+//! - Take / Refund 的 Escrow 地址依赖 Make 时的 seed，无法从指令参数推导，由调用方传入
+//!
+use crate::errors::EscrowError;
+use crate::sighash::SIGHASHES;
+use crate::state::{BundleLeg, Escrow, PaymentOption, ESCROW_SEED};
+use crate::{Make, Refund, Take};
+use core::fmt::{self, Write};
+use core::mem::{offset_of, size_of};
+use pinocchio::{error::ToStr, Address};
+
+/// 字段类型
+pub enum FieldType {
+    U8,
+    U16,
+    U64,
+    I64,
+    Bool,
+    Pubkey,
+    Bytes(usize),               // 定长字节数组
+    Array(&'static str, usize), // 自定义类型（见 `TYPES`）的定长数组
+}
+
+/// PDA 种子
+pub enum IdlSeed {
+    Constant(&'static [u8]),           // 常量种子
+    Variable(&'static str, FieldType), // 变量种子：名称与类型
+}
+
+/// 变量种子的取值来源
+pub enum SeedValue {
+    Account(&'static str), // 同一指令中另一个账户的地址
+    Arg(&'static str),     // 指令参数
+}
+
+/// IDL 中的一个 PDA
+pub struct IdlPda {
+    pub name: &'static str,
+    pub program: Option<&'static Address>, // 派生所用的程序（None 表示本程序）
+    pub seeds: &'static [IdlSeed],
+    pub docs: &'static str,
+}
+
+/// 账户的默认值（客户端可以自动填入的账户）
+pub enum AccountDefault {
+    None,
+    Address(&'static Address),                  // 固定地址（程序账户）
+    Pda(&'static IdlPda, &'static [SeedValue]), // PDA：按顺序为每个变量种子给出取值来源
+}
+
+/// IDL 中的一个账户
+pub struct IdlAccount {
+    pub name: &'static str,
+    pub writable: bool,
+    pub signer: bool,
+    pub default: AccountDefault,
+    pub docs: &'static str,
+}
+
+/// IDL 中的一个字段（指令参数或账户字段）
+pub struct IdlField {
+    pub name: &'static str,
+    pub ty: FieldType,
+    pub offset: usize, // 在指令数据（判别器之后）或账户数据中的偏移
+    pub size: usize,   // 字节数
+    pub docs: &'static str,
+}
+
+/// IDL 中的一条指令
+pub struct IdlInstruction {
+    pub name: &'static str,
+    pub discriminator: &'static u8,
+    pub accounts: &'static [IdlAccount],
+    pub args: &'static [IdlField],
+    pub docs: &'static str,
+}
+
+/// IDL 中的一个自定义类型（账户布局或嵌套结构体）
+pub struct IdlType {
+    pub name: &'static str,
+    pub fields: &'static [IdlField],
+    pub docs: &'static str,
+}
+
+const fn account(
+    name: &'static str,
+    writable: bool,
+    signer: bool,
+    default: AccountDefault,
+    docs: &'static str,
+) -> IdlAccount {
+    IdlAccount {
+        name,
+        writable,
+        signer,
+        default,
+        docs,
+    }
+}
+
+const fn field(name: &'static str, ty: FieldType, offset: usize, size: usize, docs: &'static str) -> IdlField {
+    IdlField {
+        name,
+        ty,
+        offset,
+        size,
+        docs,
+    }
+}
+
+// ========== PDA ==========
+const ESCROW_PDA: IdlPda = IdlPda {
+    name: "escrow",
+    program: None,
+    seeds: &[
+        IdlSeed::Constant(ESCROW_SEED),
+        IdlSeed::Variable("maker", FieldType::Pubkey),
+        IdlSeed::Variable("seed", FieldType::U64),
+    ],
+    docs: "Escrow PDA",
+};
+
+const CONFIG_PDA: IdlPda = IdlPda {
+    name: "config",
+    program: None,
+    seeds: &[IdlSeed::Constant(b"config")],
+    docs: "全局配置 PDA",
+};
+
+const ATA_PDA: IdlPda = IdlPda {
+    name: "associated_token",
+    program: Some(&pinocchio_associated_token_account::ID),
+    seeds: &[
+        IdlSeed::Variable("owner", FieldType::Pubkey),
+        IdlSeed::Variable("token_program", FieldType::Pubkey),
+        IdlSeed::Variable("mint", FieldType::Pubkey),
+    ],
+    docs: "Associated Token Account",
+};
+
+/// IDL 描述的 PDA
+pub const PDAS: [&IdlPda; 3] = [&ESCROW_PDA, &CONFIG_PDA, &ATA_PDA];
+
+const SYSTEM_PROGRAM: AccountDefault = AccountDefault::Address(&pinocchio_system::ID);
+const TOKEN_PROGRAM: AccountDefault = AccountDefault::Address(&pinocchio_token::ID);
+const ATA_PROGRAM: AccountDefault = AccountDefault::Address(&pinocchio_associated_token_account::ID);
+const CONFIG: AccountDefault = AccountDefault::Pda(&CONFIG_PDA, &[]);
+
+/// `owner` 持有的 `mint` 的 ATA，`token_program` 为 mint 所属的 Token Program（均为同一指令中的账户名）
+macro_rules! ata {
+    ($owner:literal, $mint:literal, $token_program:literal) => {
+        AccountDefault::Pda(
+            &ATA_PDA,
+            &[
+                SeedValue::Account($owner),
+                SeedValue::Account($token_program),
+                SeedValue::Account($mint),
+            ],
+        )
+    };
+}
+
+// ========== 指令 ==========
+const MAKE_ACCOUNTS: [IdlAccount; 10] = [
+    account("maker", true, true, AccountDefault::None, "托管创建者，支付 Escrow 与 vault 的租金"),
+    account(
+        "escrow",
+        true,
+        false,
+        AccountDefault::Pda(&ESCROW_PDA, &[SeedValue::Account("maker"), SeedValue::Arg("seed")]),
+        "Escrow PDA，种子 [\"escrow\", maker, seed]",
+    ),
+    account("mint_a", false, false, AccountDefault::None, "托管的代币 Mint（原版 Token Program 或 Token-2022）"),
+    account("mint_b", false, false, AccountDefault::None, "期望获得的代币 Mint"),
+    account("maker_ata_a", true, false, ata!("maker", "mint_a", "token_program"), "maker 的 Token A ATA"),
+    account(
+        "vault",
+        true,
+        false,
+        ata!("escrow", "mint_a", "token_program"),
+        "Escrow PDA 拥有的 Token A ATA（由本指令创建）",
+    ),
+    account("system_program", false, false, SYSTEM_PROGRAM, "System Program"),
+    account("token_program", false, false, TOKEN_PROGRAM, "mint_a 所属的 Token Program"),
+    account("associated_token_program", false, false, ATA_PROGRAM, "Associated Token Program"),
+    account("config", true, false, CONFIG, "全局配置 PDA"),
+];
+
+const MAKE_ARGS: [IdlField; 5] = [
+    field("seed", FieldType::U64, 0, 8, "PDA 派生种子"),
+    field("receive", FieldType::U64, 8, 8, "期望接收的 Token B 数量"),
+    field("amount", FieldType::U64, 16, 8, "托管的 Token A 数量"),
+    field("expiry_timestamp", FieldType::I64, 24, 8, "过期的 Unix 时间戳，0 表示永不过期"),
+    field("taker", FieldType::Pubkey, 32, 32, "指定的 taker，全 0 表示公开 Escrow"),
+];
+
+const TAKE_ACCOUNTS: [IdlAccount; 15] = [
+    account("taker", true, true, AccountDefault::None, "成交者，支付 ATA 租金"),
+    account("maker", true, false, AccountDefault::None, "Escrow 的创建者，接收关闭账户返还的租金"),
+    account("escrow", true, false, AccountDefault::None, "Escrow PDA"),
+    account("mint_a", false, false, AccountDefault::None, "托管的代币 Mint"),
+    account("mint_b", false, false, AccountDefault::None, "支付代币的 Mint（mint_b 或备选支付代币之一）"),
+    account(
+        "vault",
+        true,
+        false,
+        ata!("escrow", "mint_a", "token_program"),
+        "托管 Token A 的 vault（委托模式为 maker 的 Token A ATA）",
+    ),
+    account(
+        "taker_ata_a",
+        true,
+        false,
+        ata!("taker", "mint_a", "token_program"),
+        "taker 的 Token A ATA（不存在时创建）",
+    ),
+    account("taker_ata_b", true, false, ata!("taker", "mint_b", "token_program_b"), "taker 的 Token B ATA"),
+    account(
+        "maker_ata_b",
+        true,
+        false,
+        ata!("maker", "mint_b", "token_program_b"),
+        "maker 的 Token B ATA（不存在时创建）",
+    ),
+    account("system_program", false, false, SYSTEM_PROGRAM, "System Program"),
+    account("token_program", false, false, TOKEN_PROGRAM, "mint_a 所属的 Token Program"),
+    account("associated_token_program", false, false, ATA_PROGRAM, "Associated Token Program"),
+    account("config", true, false, CONFIG, "全局配置 PDA（协议手续费）"),
+    account(
+        "fee_vault",
+        true,
+        false,
+        ata!("config", "mint_b", "token_program_b"),
+        "全局配置 PDA 拥有的 Token B ATA",
+    ),
+    account("token_program_b", false, false, TOKEN_PROGRAM, "mint_b 所属的 Token Program"),
+];
+
+const TAKE_ARGS: [IdlField; 2] = [
+    field("max_receive", FieldType::U64, 0, 8, "滑点保护：最多愿意支付的 Token B 数量"),
+    field("min_token_a_out", FieldType::U64, 8, 8, "滑点保护：至少要收到的 Token A 数量"),
+];
+
+const REFUND_ACCOUNTS: [IdlAccount; 8] = [
+    account("maker", true, true, AccountDefault::None, "托管创建者"),
+    account("escrow", true, false, AccountDefault::None, "Escrow PDA，关闭后租金返还给 maker"),
+    account("mint_a", false, false, AccountDefault::None, "托管的代币 Mint"),
+    account("vault", true, false, ata!("escrow", "mint_a", "token_program"), "托管 Token A 的 vault"),
+    account(
+        "maker_ata_a",
+        true,
+        false,
+        ata!("maker", "mint_a", "token_program"),
+        "maker 的 Token A ATA（接收返还的代币）",
+    ),
+    account("system_program", false, false, SYSTEM_PROGRAM, "System Program"),
+    account("token_program", false, false, TOKEN_PROGRAM, "mint_a 所属的 Token Program"),
+    account("associated_token_program", false, false, ATA_PROGRAM, "Associated Token Program"),
+];
+
+const REFUND_ARGS: [IdlField; 1] =
+    [field("unwrap", FieldType::Bool, 0, 1, "Token A 为 WSOL 时关闭 maker 的 WSOL ATA，换回原生 SOL")];
+
+/// IDL 描述的指令
+pub const INSTRUCTIONS: [IdlInstruction; 3] = [
+    IdlInstruction {
+        name: "make",
+        discriminator: Make::DISCRIMINATOR,
+        accounts: &MAKE_ACCOUNTS,
+        args: &MAKE_ARGS,
+        docs: "创建 Escrow 并把 Token A 转入 vault。指令数据可以只有前 24 字节（seed + receive + amount）或 32 字节；\
+               64 字节之后可选的选项字节启用拍卖、荷兰拍、线性释放等功能（见 make.rs）。\
+               固定账户之后依次可选传入 Transfer Hook 账户、拍卖/捆绑挂单账户与 maker 统计 PDA。",
+    },
+    IdlInstruction {
+        name: "take",
+        discriminator: Take::DISCRIMINATOR,
+        accounts: &TAKE_ACCOUNTS,
+        args: &TAKE_ARGS,
+        docs: "按当前报价成交：Token A 转给 taker，Token B 扣除手续费后转给 maker，关闭 vault 与 Escrow。\
+               指令数据可以为空（不做滑点保护）；16 字节之后可选的 mode、unwrap、receipt 字节见 take.rs。\
+               固定账户之后依次可选传入 hook、白名单、额外代币、版税、推荐人、手续费账本、统计与回执账户。",
+    },
+    IdlInstruction {
+        name: "refund",
+        discriminator: Refund::DISCRIMINATOR,
+        accounts: &REFUND_ACCOUNTS,
+        args: &REFUND_ARGS,
+        docs: "maker 取消 Escrow：Token A 返还给 maker，关闭 vault 与 Escrow。指令数据可以为空（不关闭 WSOL ATA）。\
+               固定账户之后依次可选传入 hook、白名单、额外代币、罚金接收者、全局配置与统计账户（见 refund.rs）。",
+    },
+];
+
+// ========== 账户布局 ==========
+macro_rules! escrow_field {
+    ($name:ident, $ty:expr, $docs:expr) => {
+        field(
+            stringify!($name),
+            $ty,
+            offset_of!(Escrow, $name),
+            {
+                const fn size<T>(_: fn(&Escrow) -> &T) -> usize {
+                    size_of::<T>()
+                }
+                size(|escrow| &escrow.$name)
+            },
+            $docs,
+        )
+    };
+}
+
+const ESCROW_FIELDS: [IdlField; 37] = [
+    escrow_field!(discriminator, FieldType::Bytes(8), "账户判别器 \"escrowv1\""),
+    escrow_field!(version, FieldType::U8, "布局版本号"),
+    escrow_field!(status, FieldType::U8, "生命周期状态"),
+    escrow_field!(has_taker, FieldType::U8, "是否指定了 taker"),
+    escrow_field!(reserved, FieldType::Bytes(5), "预留"),
+    escrow_field!(seed, FieldType::U64, "PDA 派生种子"),
+    escrow_field!(maker, FieldType::Pubkey, "托管创建者"),
+    escrow_field!(mint_a, FieldType::Pubkey, "托管的代币 Mint"),
+    escrow_field!(mint_b, FieldType::Pubkey, "期望获得的代币 Mint"),
+    escrow_field!(receive, FieldType::U64, "期望接收的 Token B 数量"),
+    escrow_field!(expiry_timestamp, FieldType::I64, "过期的 Unix 时间戳，0 表示永不过期"),
+    escrow_field!(taker, FieldType::Pubkey, "指定的 taker"),
+    escrow_field!(floor_price, FieldType::U64, "荷兰拍：最低价"),
+    escrow_field!(decay_start, FieldType::I64, "荷兰拍：开始降价的时间"),
+    escrow_field!(decay_end, FieldType::I64, "荷兰拍：降到最低价的时间"),
+    escrow_field!(vesting_duration, FieldType::I64, "线性释放：时长（秒）"),
+    escrow_field!(vesting_start, FieldType::I64, "线性释放：开始时间"),
+    escrow_field!(vesting_end, FieldType::I64, "线性释放：结束时间"),
+    escrow_field!(claimed, FieldType::U64, "线性释放：已领取的 Token A 数量"),
+    escrow_field!(arbiter, FieldType::Pubkey, "仲裁人，全 0 表示没有"),
+    escrow_field!(delegated_amount, FieldType::U64, "委托模式：Take 时拉取的数量"),
+    escrow_field!(refund_locked_until, FieldType::I64, "撤回锁定期"),
+    escrow_field!(penalty_recipient, FieldType::Pubkey, "取消罚金的接收者"),
+    escrow_field!(legs, FieldType::Array("BundleLeg", Escrow::MAX_LEGS), "捆绑挂单：额外代币"),
+    escrow_field!(
+        payment_options,
+        FieldType::Array("PaymentOption", Escrow::MAX_PAYMENT_OPTIONS),
+        "备选支付代币"
+    ),
+    escrow_field!(penalty_bps, FieldType::U16, "取消罚金比例（基点）"),
+    escrow_field!(flags, FieldType::U16, "标志位"),
+    escrow_field!(leg_count, FieldType::U8, "已使用的额外代币条目数"),
+    escrow_field!(payment_option_count, FieldType::U8, "已使用的备选支付代币条目数"),
+    escrow_field!(legacy_status, FieldType::U8, "版本 4 及之前的 status 位置"),
+    escrow_field!(bump, FieldType::U8, "PDA bump"),
+    escrow_field!(vault, FieldType::Pubkey, "托管 Token A 的账户"),
+    escrow_field!(amount, FieldType::U64, "累计托管的 Token A 数量"),
+    escrow_field!(filled, FieldType::U64, "已转给 taker 的 Token A 数量"),
+    escrow_field!(created_at, FieldType::I64, "创建时的 Unix 时间戳"),
+    escrow_field!(updated_slot, FieldType::U64, "最后一次修改的 slot"),
+    escrow_field!(revision, FieldType::U64, "修订号"),
+];
+
+const BUNDLE_LEG_FIELDS: [IdlField; 2] = [
+    field("mint", FieldType::Pubkey, offset_of!(BundleLeg, mint), size_of::<Address>(), "额外代币的 Mint"),
+    field("amount", FieldType::U64, offset_of!(BundleLeg, amount), size_of::<u64>(), "vault 实际收到的数量"),
+];
+
+const PAYMENT_OPTION_FIELDS: [IdlField; 2] = [
+    field("mint", FieldType::Pubkey, offset_of!(PaymentOption, mint), size_of::<Address>(), "备选支付代币的 Mint"),
+    field(
+        "receive",
+        FieldType::U64,
+        offset_of!(PaymentOption, receive),
+        size_of::<u64>(),
+        "以该代币支付时需要的数量",
+    ),
+];
+
+/// IDL 描述的类型（第一个为 Escrow 账户）
+pub const TYPES: [IdlType; 3] = [
+    IdlType {
+        name: "Escrow",
+        fields: &ESCROW_FIELDS,
+        docs: "Escrow PDA 的账户数据（当前布局版本见 version 字段），字段紧密排列，没有填充字节",
+    },
+    IdlType {
+        name: "BundleLeg",
+        fields: &BUNDLE_LEG_FIELDS,
+        docs: "捆绑挂单的一种额外代币",
+    },
+    IdlType {
+        name: "PaymentOption",
+        fields: &PAYMENT_OPTION_FIELDS,
+        docs: "一种备选支付代币",
+    },
+];
+
+/// 字段按顺序紧密排列且覆盖整个结构体：Borsh 解码结果与按偏移读取一致
+const fn is_contiguous(fields: &[IdlField], len: usize) -> bool {
+    let mut offset = 0;
+    let mut i = 0;
+    while i < fields.len() {
+        if fields[i].offset != offset {
+            return false;
+        }
+        offset += fields[i].size;
+        i += 1;
+    }
+    offset == len
+}
+
+const _: () = assert!(is_contiguous(&ESCROW_FIELDS, Escrow::LEN));
+const _: () = assert!(is_contiguous(&BUNDLE_LEG_FIELDS, size_of::<BundleLeg>()));
+const _: () = assert!(is_contiguous(&PAYMENT_OPTION_FIELDS, size_of::<PaymentOption>()));
+
+// ========== JSON 输出 ==========
+/// 转义 JSON 字符串内容（引号、反斜杠与控制字符）
+struct Escaped<'a, W: Write + ?Sized>(&'a mut W);
+
+impl<W: Write + ?Sized> Write for Escaped<'_, W> {
+    fn write_str(&mut self, value: &str) -> fmt::Result {
+        for c in value.chars() {
+            match c {
+                '"' | '\\' => write!(self.0, "\\{}", c)?,
+                c if (c as u32) < 0x20 => write!(self.0, "\\u{:04x}", c as u32)?,
+                _ => self.0.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 写入 JSON 字符串
+fn write_str(out: &mut dyn Write, value: &str) -> fmt::Result {
+    out.write_char('"')?;
+    Escaped(out).write_str(value)?;
+    out.write_char('"')
+}
+
+/// 以 camelCase 写入 JSON 字符串（Codama 的命名约定，snake_case 与 PascalCase 都会转换）
+fn write_camel(out: &mut dyn Write, name: &str) -> fmt::Result {
+    out.write_char('"')?;
+    let mut upper = false;
+    for (i, c) in name.chars().enumerate() {
+        match c {
+            '_' => upper = true,
+            _ if i == 0 => out.write_char(c.to_ascii_lowercase())?,
+            _ if upper => {
+                out.write_char(c.to_ascii_uppercase())?;
+                upper = false;
+            }
+            _ => out.write_char(c)?,
+        }
+    }
+    out.write_char('"')
+}
+
+/// 写入字节数组
+fn write_bytes(out: &mut dyn Write, bytes: &[u8]) -> fmt::Result {
+    write_list(out, bytes, |out, byte| write!(out, "{}", byte))
+}
+
+/// 依次写入列表中的每一项，以逗号分隔
+fn write_list<T>(
+    out: &mut dyn Write,
+    items: impl IntoIterator<Item = T>,
+    mut write_item: impl FnMut(&mut dyn Write, T) -> fmt::Result,
+) -> fmt::Result {
+    out.write_char('[')?;
+    for (i, item) in items.into_iter().enumerate() {
+        if i > 0 {
+            out.write_char(',')?;
+        }
+        write_item(out, item)?;
+    }
+    out.write_char(']')
+}
+
+/// 指令的 8 字节 sighash（见 sighash.rs）
+fn sighash(discriminator: &u8) -> Result<&'static [u8; 8], fmt::Error> {
+    SIGHASHES
+        .iter()
+        .find(|(known, _)| *known == discriminator)
+        .map(|(_, sighash)| sighash)
+        .ok_or(fmt::Error)
+}
+
+// ---------- Anchor ----------
+impl FieldType {
+    fn write_anchor(&self, out: &mut dyn Write) -> fmt::Result {
+        match self {
+            Self::U8 => out.write_str("\"u8\""),
+            Self::U16 => out.write_str("\"u16\""),
+            Self::U64 => out.write_str("\"u64\""),
+            Self::I64 => out.write_str("\"i64\""),
+            Self::Bool => out.write_str("\"bool\""),
+            Self::Pubkey => out.write_str("\"pubkey\""),
+            Self::Bytes(len) => write!(out, "{{\"array\":[\"u8\",{}]}}", len),
+            Self::Array(name, len) => write!(out, "{{\"array\":[{{\"defined\":{{\"name\":\"{}\"}}}},{}]}}", name, len),
+        }
+    }
+}
+
+fn write_anchor_fields(out: &mut dyn Write, fields: &[IdlField]) -> fmt::Result {
+    write_list(out, fields, |out, field| {
+        out.write_str("{\"name\":")?;
+        write_str(out, field.name)?;
+        out.write_str(",\"docs\":[")?;
+        write_str(out, field.docs)?;
+        out.write_str("],\"type\":")?;
+        field.ty.write_anchor(out)?;
+        write!(out, ",\"offset\":{},\"size\":{}}}", field.offset, field.size)
+    })
+}
+
+/// Anchor 的账户默认值：`address`（固定地址）或 `pda`（种子与派生程序）
+fn write_anchor_default(out: &mut dyn Write, default: &AccountDefault) -> fmt::Result {
+    match default {
+        AccountDefault::None => Ok(()),
+        AccountDefault::Address(address) => write!(out, ",\"address\":\"{}\"", address),
+        AccountDefault::Pda(pda, values) => {
+            let mut values = values.iter();
+            out.write_str(",\"pda\":{\"seeds\":")?;
+            write_list(out, pda.seeds, |out, seed| match seed {
+                IdlSeed::Constant(bytes) => {
+                    out.write_str("{\"kind\":\"const\",\"value\":")?;
+                    write_bytes(out, bytes)?;
+                    out.write_char('}')
+                }
+                IdlSeed::Variable(..) => match values.next().ok_or(fmt::Error)? {
+                    SeedValue::Account(name) => write!(out, "{{\"kind\":\"account\",\"path\":\"{}\"}}", name),
+                    SeedValue::Arg(name) => write!(out, "{{\"kind\":\"arg\",\"path\":\"{}\"}}", name),
+                },
+            })?;
+            if let Some(program) = pda.program {
+                out.write_str(",\"program\":{\"kind\":\"const\",\"value\":")?;
+                write_bytes(out, program.as_ref())?;
+                out.write_char('}')?;
+            }
+            out.write_char('}')
+        }
+    }
+}
+
+fn write_anchor_instruction(out: &mut dyn Write, instruction: &IdlInstruction) -> fmt::Result {
+    out.write_str("{\"name\":")?;
+    write_str(out, instruction.name)?;
+    out.write_str(",\"docs\":[")?;
+    write_str(out, instruction.docs)?;
+    out.write_str("],\"discriminator\":")?;
+    write_bytes(out, sighash(instruction.discriminator)?)?;
+    out.write_str(",\"accounts\":")?;
+    write_list(out, instruction.accounts, |out, account| {
+        out.write_str("{\"name\":")?;
+        write_str(out, account.name)?;
+        out.write_str(",\"docs\":[")?;
+        write_str(out, account.docs)?;
+        write!(out, "],\"writable\":{},\"signer\":{}", account.writable, account.signer)?;
+        write_anchor_default(out, &account.default)?;
+        out.write_char('}')
+    })?;
+    out.write_str(",\"args\":")?;
+    write_anchor_fields(out, instruction.args)?;
+    out.write_char('}')
+}
+
+/// 输出 Anchor 格式的 JSON IDL（单行）
+pub fn write_json(out: &mut dyn Write) -> fmt::Result {
+    write!(out, "{{\"address\":\"{}\",\"metadata\":{{\"name\":", crate::ID)?;
+    write_str(out, env!("CARGO_PKG_NAME"))?;
+    out.write_str(",\"version\":")?;
+    write_str(out, env!("CARGO_PKG_VERSION"))?;
+    out.write_str(",\"spec\":\"0.1.0\"},\"instructions\":")?;
+    write_list(out, &INSTRUCTIONS, write_anchor_instruction)?;
+    out.write_str(",\"accounts\":[{\"name\":\"Escrow\",\"discriminator\":")?;
+    write_bytes(out, &Escrow::ACCOUNT_DISCRIMINATOR)?;
+    out.write_str("}],\"types\":")?;
+    write_list(out, &TYPES, |out, ty| {
+        out.write_str("{\"name\":")?;
+        write_str(out, ty.name)?;
+        out.write_str(",\"docs\":[")?;
+        write_str(out, ty.docs)?;
+        out.write_str("],\"type\":{\"kind\":\"struct\",\"fields\":")?;
+        write_anchor_fields(out, ty.fields)?;
+        out.write_str("}}")
+    })?;
+    out.write_char('}')
+}
+
+// ---------- Codama ----------
+/// Codama 的小端整数类型节点
+fn write_codama_number(out: &mut dyn Write, format: &str) -> fmt::Result {
+    write!(out, "{{\"kind\":\"numberTypeNode\",\"format\":\"{}\",\"endian\":\"le\"}}", format)
+}
+
+impl FieldType {
+    fn write_codama(&self, out: &mut dyn Write) -> fmt::Result {
+        match self {
+            Self::U8 => write_codama_number(out, "u8"),
+            Self::U16 => write_codama_number(out, "u16"),
+            Self::U64 => write_codama_number(out, "u64"),
+            Self::I64 => write_codama_number(out, "i64"),
+            Self::Bool => {
+                out.write_str("{\"kind\":\"booleanTypeNode\",\"size\":")?;
+                write_codama_number(out, "u8")?;
+                out.write_char('}')
+            }
+            Self::Pubkey => out.write_str("{\"kind\":\"publicKeyTypeNode\"}"),
+            Self::Bytes(len) => write!(
+                out,
+                "{{\"kind\":\"fixedSizeTypeNode\",\"size\":{},\"type\":{{\"kind\":\"bytesTypeNode\"}}}}",
+                len
+            ),
+            Self::Array(name, len) => {
+                out.write_str("{\"kind\":\"arrayTypeNode\",\"item\":{\"kind\":\"definedTypeLinkNode\",\"name\":")?;
+                write_camel(out, name)?;
+                write!(out, "}},\"count\":{{\"kind\":\"fixedCountNode\",\"value\":{}}}}}", len)
+            }
+        }
+    }
+}
+
+fn write_codama_struct(out: &mut dyn Write, fields: &[IdlField]) -> fmt::Result {
+    out.write_str("{\"kind\":\"structTypeNode\",\"fields\":")?;
+    write_list(out, fields, |out, field| {
+        out.write_str("{\"kind\":\"structFieldTypeNode\",\"name\":")?;
+        write_camel(out, field.name)?;
+        out.write_str(",\"docs\":[")?;
+        write_str(out, field.docs)?;
+        out.write_str("],\"type\":")?;
+        field.ty.write_codama(out)?;
+        out.write_char('}')
+    })?;
+    out.write_char('}')
+}
+
+fn write_codama_pda(out: &mut dyn Write, pda: &IdlPda) -> fmt::Result {
+    out.write_str("{\"kind\":\"pdaNode\",\"name\":")?;
+    write_camel(out, pda.name)?;
+    out.write_str(",\"docs\":[")?;
+    write_str(out, pda.docs)?;
+    out.write_char(']')?;
+    if let Some(program) = pda.program {
+        write!(out, ",\"programId\":\"{}\"", program)?;
+    }
+    out.write_str(",\"seeds\":")?;
+    write_list(out, pda.seeds, |out, seed| match seed {
+        IdlSeed::Constant(bytes) => {
+            let value = core::str::from_utf8(bytes).map_err(|_| fmt::Error)?;
+            out.write_str(
+                "{\"kind\":\"constantPdaSeedNode\",\"type\":{\"kind\":\"bytesTypeNode\"},\
+                 \"value\":{\"kind\":\"bytesValueNode\",\"encoding\":\"utf8\",\"data\":",
+            )?;
+            write_str(out, value)?;
+            out.write_str("}}")
+        }
+        IdlSeed::Variable(name, ty) => {
+            out.write_str("{\"kind\":\"variablePdaSeedNode\",\"name\":")?;
+            write_camel(out, name)?;
+            out.write_str(",\"docs\":[],\"type\":")?;
+            ty.write_codama(out)?;
+            out.write_char('}')
+        }
+    })?;
+    out.write_char('}')
+}
+
+/// Codama 的账户默认值：`publicKeyValueNode` 或 `pdaValueNode`（按种子名称给出取值来源）
+fn write_codama_default(out: &mut dyn Write, default: &AccountDefault) -> fmt::Result {
+    match default {
+        AccountDefault::None => Ok(()),
+        AccountDefault::Address(address) => write!(
+            out,
+            ",\"defaultValue\":{{\"kind\":\"publicKeyValueNode\",\"publicKey\":\"{}\"}}",
+            address
+        ),
+        AccountDefault::Pda(pda, values) => {
+            out.write_str(",\"defaultValue\":{\"kind\":\"pdaValueNode\",\"pda\":{\"kind\":\"pdaLinkNode\",\"name\":")?;
+            write_camel(out, pda.name)?;
+            out.write_str("},\"seeds\":")?;
+            let names = pda.seeds.iter().filter_map(|seed| match seed {
+                IdlSeed::Variable(name, _) => Some(*name),
+                IdlSeed::Constant(_) => None,
+            });
+            write_list(out, names.zip(values.iter()), |out, (name, value)| {
+                out.write_str("{\"kind\":\"pdaSeedValueNode\",\"name\":")?;
+                write_camel(out, name)?;
+                let (kind, source) = match value {
+                    SeedValue::Account(source) => ("accountValueNode", source),
+                    SeedValue::Arg(source) => ("argumentValueNode", source),
+                };
+                write!(out, ",\"value\":{{\"kind\":\"{}\",\"name\":", kind)?;
+                write_camel(out, source)?;
+                out.write_str("}}")
+            })?;
+            out.write_char('}')
+        }
+    }
+}
+
+fn write_codama_instruction(out: &mut dyn Write, instruction: &IdlInstruction) -> fmt::Result {
+    out.write_str("{\"kind\":\"instructionNode\",\"name\":")?;
+    write_camel(out, instruction.name)?;
+    out.write_str(",\"docs\":[")?;
+    write_str(out, instruction.docs)?;
+    out.write_str("],\"optionalAccountStrategy\":\"programId\",\"accounts\":")?;
+    write_list(out, instruction.accounts, |out, account| {
+        out.write_str("{\"kind\":\"instructionAccountNode\",\"name\":")?;
+        write_camel(out, account.name)?;
+        out.write_str(",\"docs\":[")?;
+        write_str(out, account.docs)?;
+        write!(
+            out,
+            "],\"isWritable\":{},\"isSigner\":{},\"isOptional\":false",
+            account.writable, account.signer
+        )?;
+        write_codama_default(out, &account.default)?;
+        out.write_char('}')
+    })?;
+    // 1 字节判别器作为第一个参数，由生成的客户端自动填入
+    out.write_str(
+        ",\"arguments\":[{\"kind\":\"instructionArgumentNode\",\"name\":\"discriminator\",\"docs\":[],\"type\":",
+    )?;
+    write_codama_number(out, "u8")?;
+    write!(
+        out,
+        ",\"defaultValue\":{{\"kind\":\"numberValueNode\",\"number\":{}}},\"defaultValueStrategy\":\"omitted\"}}",
+        instruction.discriminator
+    )?;
+    for arg in instruction.args {
+        out.write_str(",{\"kind\":\"instructionArgumentNode\",\"name\":")?;
+        write_camel(out, arg.name)?;
+        out.write_str(",\"docs\":[")?;
+        write_str(out, arg.docs)?;
+        out.write_str("],\"type\":")?;
+        arg.ty.write_codama(out)?;
+        out.write_char('}')?;
+    }
+    out.write_str("],\"discriminators\":[{\"kind\":\"fieldDiscriminatorNode\",\"name\":\"discriminator\",\"offset\":0}]}")
+}
+
+/// 输出 Codama 节点树（rootNode，单行）
+pub fn write_codama_json(out: &mut dyn Write) -> fmt::Result {
+    let escrow = &TYPES[0];
+    out.write_str(
+        "{\"kind\":\"rootNode\",\"standard\":\"codama\",\"version\":\"1.0.0\",\
+         \"program\":{\"kind\":\"programNode\",\"name\":",
+    )?;
+    write_camel(out, env!("CARGO_PKG_NAME"))?;
+    write!(out, ",\"publicKey\":\"{}\",\"version\":", crate::ID)?;
+    write_str(out, env!("CARGO_PKG_VERSION"))?;
+    out.write_str(",\"docs\":[],\"accounts\":[{\"kind\":\"accountNode\",\"name\":")?;
+    write_camel(out, escrow.name)?;
+    out.write_str(",\"docs\":[")?;
+    write_str(out, escrow.docs)?;
+    write!(out, "],\"size\":{},\"data\":", Escrow::LEN)?;
+    write_codama_struct(out, escrow.fields)?;
+    out.write_str(
+        ",\"pda\":{\"kind\":\"pdaLinkNode\",\"name\":\"escrow\"},\"discriminators\":[{\"kind\":\"constantDiscriminatorNode\",\
+         \"offset\":0,\"constant\":{\"kind\":\"constantValueNode\",\"type\":{\"kind\":\"bytesTypeNode\"},\
+         \"value\":{\"kind\":\"bytesValueNode\",\"encoding\":\"base16\",\"data\":\"",
+    )?;
+    for byte in Escrow::ACCOUNT_DISCRIMINATOR {
+        write!(out, "{:02x}", byte)?;
+    }
+    out.write_str("\"}}}]}],\"instructions\":")?;
+    write_list(out, &INSTRUCTIONS, write_codama_instruction)?;
+    out.write_str(",\"definedTypes\":")?;
+    write_list(out, &TYPES[1..], |out, ty| {
+        out.write_str("{\"kind\":\"definedTypeNode\",\"name\":")?;
+        write_camel(out, ty.name)?;
+        out.write_str(",\"docs\":[")?;
+        write_str(out, ty.docs)?;
+        out.write_str("],\"type\":")?;
+        write_codama_struct(out, ty.fields)?;
+        out.write_char('}')
+    })?;
+    out.write_str(",\"pdas\":")?;
+    write_list(out, PDAS, write_codama_pda)?;
+    // 错误码从 0 开始连续编号，名称取自日志中的错误名（`ToStr` 去掉 "Error: " 前缀），消息取自 `Display`
+    out.write_str(",\"errors\":")?;
+    let errors = (0..).map_while(|code| EscrowError::try_from(code).ok().map(|error| (code, error)));
+    write_list(out, errors, |out, (code, error)| {
+        let name = error.to_str();
+        out.write_str("{\"kind\":\"errorNode\",\"name\":")?;
+        write_camel(out, name.strip_prefix("Error: ").unwrap_or(name))?;
+        write!(out, ",\"code\":{},\"docs\":[],\"message\":\"", code)?;
+        write!(Escaped(out), "{}", error)?;
+        out.write_str("\"}")
+    })?;
+    out.write_str("},\"additionalPrograms\":[]}")
+}
@@ -0,0 +1,327 @@
+//! 指令解析（`EscrowInstruction`）
+//!
+//! 本文件把指令数据解析为类型化的 `EscrowInstruction`，`process_instruction` 只负责解析与分发：
+//! - `unpack`：识别判别器（Anchor 的 8 字节判别器或 1 字节判别器，见 sighash.rs），
+//!   无需指令数据的指令在判别器之后还有多余字节时返回 `InvalidInstructionData`
+//! - 需要指令数据的指令保留判别器之后的数据，由对应的 `*InstructionData` 校验长度与内容；
+//!   所有解析器都只接受文档列出的长度（可选的尾部字段按位置逐个匹配），多余的字节一律返回 `InvalidInstructionData`，
+//!   新增参数时必须保持这一点，否则旧客户端附带的多余字节会被解释为新参数
+//! - `process`：按指令构造账户结构并执行
+//!
+//! 版本化的指令数据以 `VERSIONED_PREFIX` 与版本号开头，之后才是判别器与指令数据，`unpack` 按版本号选择解析器：
+//! - 版本 1（`PAYLOAD_VERSION`）：当前各指令的参数布局
+//! - 不带版本前缀的 1 字节判别器格式与 Anchor 的 8 字节判别器格式都按版本 1 解析，已有客户端不受影响
+//! - 未知的版本号返回 `UnsupportedPayloadVersion`，而不是按当前布局误读
+//!
+//! 以后修改某个指令的参数布局（如 Make 的过期时间、taker、手续费参数）时，新增版本号与对应的 `unpack_v*`，
+//! 新布局只接受带版本前缀的数据，旧客户端的数据仍按原来的版本解析。
+//!
+//! 判别器未知时先把收到的字节与可接受的判别器写入日志（`log_unknown_discriminator`），再返回 `InvalidInstructionData`。
+//!
+//! `unpack` 不读取任何账户，可以在链下直接测试指令数据的解析。
+//! 新增指令时需要同时添加枚举成员、`unpack` 与 `process` 中的分支、sighash.rs 中的 Anchor 判别器，并更新 `MAX_DISCRIMINATOR`。
+//!
+use crate::*;
+use pinocchio::{error::ProgramError, AccountView, ProgramResult};
+
+/// 版本化指令数据的前缀：`[VERSIONED_PREFIX, 版本号, 判别器, 指令数据...]`（不是任何指令的判别器）
+pub const VERSIONED_PREFIX: u8 = 0xFE;
+
+/// 当前的指令数据版本
+pub const PAYLOAD_VERSION: u8 = 1;
+
+/// 最大的 1 字节判别器（判别器从 0 开始连续分配，新增指令时更新）
+pub const MAX_DISCRIMINATOR: u8 = *GetVersion::DISCRIMINATOR;
+
+// 每个 1 字节判别器都有对应的 Anchor 判别器
+const _: () = assert!(SIGHASHES.len() == MAX_DISCRIMINATOR as usize + 1);
+
+/// 未知判别器：把收到的字节与可接受的判别器写入日志
+///
+/// 客户端与程序版本不一致时最常见的失败，日志依次为：收到的 1 字节判别器、从判别器开始的前 8 字节
+/// （按字节顺序，便于与 Anchor 判别器比对）、可接受的 1 字节判别器范围 `0..=MAX_DISCRIMINATOR`、
+/// 可接受的 Anchor 判别器数量（见 sighash.rs）。只有 Solana 目标才有日志 syscall。
+fn log_unknown_discriminator(discriminator: u8, data: &[u8]) {
+    let mut prefix = [0u8; SIGHASH_LEN];
+    prefix[0] = discriminator;
+    let len = data.len().min(SIGHASH_LEN - 1);
+    prefix[1..=len].copy_from_slice(&data[..len]);
+
+    #[cfg(target_os = "solana")]
+    unsafe {
+        const MESSAGE: &str = "Unknown discriminator: received, 8-byte prefix, expected 0..=max, sighash count";
+        pinocchio::syscalls::sol_log_(MESSAGE.as_ptr(), MESSAGE.len() as u64);
+        pinocchio::syscalls::sol_log_64_(
+            discriminator as u64,
+            u64::from_be_bytes(prefix),
+            0,
+            MAX_DISCRIMINATOR as u64,
+            SIGHASHES.len() as u64,
+        );
+    }
+    #[cfg(not(target_os = "solana"))]
+    let _ = prefix;
+}
+
+/// 解析后的指令：需要指令数据的成员保存判别器之后的数据
+pub enum EscrowInstruction<'a> {
+    Make(&'a [u8]),
+    Take(&'a [u8]),
+    Refund(&'a [u8]),
+    TakePartial(&'a [u8]),
+    CloseExpired,
+    UpdateEscrow(&'a [u8]),
+    MakeSol(&'a [u8]),
+    AddToWhitelist(&'a [u8]),
+    RemoveFromWhitelist(&'a [u8]),
+    Deposit,
+    Settle,
+    PlaceBid(&'a [u8]),
+    AcceptBid,
+    CancelAuction,
+    RefundBatch,
+    ProposeCounterOffer(&'a [u8]),
+    AcceptCounterOffer,
+    WithdrawCounterOffer,
+    InitializeConfig(&'a [u8]),
+    SetFee(&'a [u8]),
+    CollectFees,
+    Claim,
+    RaiseDispute,
+    Resolve(&'a [u8]),
+    TopUp(&'a [u8]),
+    WithdrawPartial(&'a [u8]),
+    TransferMaker,
+    Contribute(&'a [u8]),
+    Finalize,
+    WithdrawContribution,
+    MakeWithDelegate(&'a [u8]),
+    MakeAuto(&'a [u8]),
+    CommitTake(&'a [u8]),
+    RevealTake(&'a [u8]),
+    CancelCommit,
+    TakeSponsored(&'a [u8]),
+    Renew(&'a [u8]),
+    Sweep,
+    SkimLamports,
+    ViewEscrow,
+    MakeNft(&'a [u8]),
+    MakePnft(&'a [u8]),
+    TakePnft(&'a [u8]),
+    RefundPnft,
+    Migrate,
+    InitMakerStats,
+    InitFeeVault,
+    WithdrawFees(&'a [u8]),
+    Pause,
+    Unpause,
+    SetLimits(&'a [u8]),
+    GetVersion,
+}
+
+impl<'a> EscrowInstruction<'a> {
+    /// 解析指令数据
+    pub fn unpack(instruction_data: &'a [u8]) -> Result<Self, ProgramError> {
+        // Anchor 客户端以 8 字节判别器开头（见 sighash.rs），按版本 1 解析
+        if let Some((discriminator, data)) = split_sighash(instruction_data) {
+            return Self::unpack_v1(discriminator, data);
+        }
+
+        match instruction_data {
+            // 带版本前缀：按版本号选择解析器
+            [VERSIONED_PREFIX, version, discriminator, data @ ..] => match *version {
+                1 => Self::unpack_v1(discriminator, data),
+                _ => Err(EscrowError::UnsupportedPayloadVersion.into()),
+            },
+            // 不带版本前缀（旧客户端）：按版本 1 解析
+            [discriminator, data @ ..] => Self::unpack_v1(discriminator, data),
+            [] => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+
+    /// 版本 1 的指令数据（不带版本前缀的格式与之相同）
+    fn unpack_v1(discriminator: &u8, data: &'a [u8]) -> Result<Self, ProgramError> {
+        Ok(match (discriminator, data) {
+            (Make::DISCRIMINATOR, data) => Self::Make(data),
+            (Take::DISCRIMINATOR, data) => Self::Take(data),
+            (Refund::DISCRIMINATOR, data) => Self::Refund(data),
+            (TakePartial::DISCRIMINATOR, data) => Self::TakePartial(data),
+            (CloseExpired::DISCRIMINATOR, data) => Self::no_data(data, Self::CloseExpired)?,
+            (UpdateEscrow::DISCRIMINATOR, data) => Self::UpdateEscrow(data),
+            (MakeSol::DISCRIMINATOR, data) => Self::MakeSol(data),
+            (AddToWhitelist::DISCRIMINATOR, data) => Self::AddToWhitelist(data),
+            (RemoveFromWhitelist::DISCRIMINATOR, data) => Self::RemoveFromWhitelist(data),
+            (Deposit::DISCRIMINATOR, data) => Self::no_data(data, Self::Deposit)?,
+            (Settle::DISCRIMINATOR, data) => Self::no_data(data, Self::Settle)?,
+            (PlaceBid::DISCRIMINATOR, data) => Self::PlaceBid(data),
+            (AcceptBid::DISCRIMINATOR, data) => Self::no_data(data, Self::AcceptBid)?,
+            (CancelAuction::DISCRIMINATOR, data) => Self::no_data(data, Self::CancelAuction)?,
+            (RefundBatch::DISCRIMINATOR, data) => Self::no_data(data, Self::RefundBatch)?,
+            (ProposeCounterOffer::DISCRIMINATOR, data) => Self::ProposeCounterOffer(data),
+            (AcceptCounterOffer::DISCRIMINATOR, data) => Self::no_data(data, Self::AcceptCounterOffer)?,
+            (WithdrawCounterOffer::DISCRIMINATOR, data) => Self::no_data(data, Self::WithdrawCounterOffer)?,
+            (InitializeConfig::DISCRIMINATOR, data) => Self::InitializeConfig(data),
+            (SetFee::DISCRIMINATOR, data) => Self::SetFee(data),
+            (CollectFees::DISCRIMINATOR, data) => Self::no_data(data, Self::CollectFees)?,
+            (Claim::DISCRIMINATOR, data) => Self::no_data(data, Self::Claim)?,
+            (RaiseDispute::DISCRIMINATOR, data) => Self::no_data(data, Self::RaiseDispute)?,
+            (Resolve::DISCRIMINATOR, data) => Self::Resolve(data),
+            (TopUp::DISCRIMINATOR, data) => Self::TopUp(data),
+            (WithdrawPartial::DISCRIMINATOR, data) => Self::WithdrawPartial(data),
+            (TransferMaker::DISCRIMINATOR, data) => Self::no_data(data, Self::TransferMaker)?,
+            (Contribute::DISCRIMINATOR, data) => Self::Contribute(data),
+            (Finalize::DISCRIMINATOR, data) => Self::no_data(data, Self::Finalize)?,
+            (WithdrawContribution::DISCRIMINATOR, data) => Self::no_data(data, Self::WithdrawContribution)?,
+            (MakeWithDelegate::DISCRIMINATOR, data) => Self::MakeWithDelegate(data),
+            (MakeAuto::DISCRIMINATOR, data) => Self::MakeAuto(data),
+            (CommitTake::DISCRIMINATOR, data) => Self::CommitTake(data),
+            (RevealTake::DISCRIMINATOR, data) => Self::RevealTake(data),
+            (CancelCommit::DISCRIMINATOR, data) => Self::no_data(data, Self::CancelCommit)?,
+            (TakeSponsored::DISCRIMINATOR, data) => Self::TakeSponsored(data),
+            (Renew::DISCRIMINATOR, data) => Self::Renew(data),
+            (Sweep::DISCRIMINATOR, data) => Self::no_data(data, Self::Sweep)?,
+            (SkimLamports::DISCRIMINATOR, data) => Self::no_data(data, Self::SkimLamports)?,
+            (ViewEscrow::DISCRIMINATOR, data) => Self::no_data(data, Self::ViewEscrow)?,
+            (MakeNft::DISCRIMINATOR, data) => Self::MakeNft(data),
+            (MakePnft::DISCRIMINATOR, data) => Self::MakePnft(data),
+            (TakePnft::DISCRIMINATOR, data) => Self::TakePnft(data),
+            (RefundPnft::DISCRIMINATOR, data) => Self::no_data(data, Self::RefundPnft)?,
+            (Migrate::DISCRIMINATOR, data) => Self::no_data(data, Self::Migrate)?,
+            (InitMakerStats::DISCRIMINATOR, data) => Self::no_data(data, Self::InitMakerStats)?,
+            (InitFeeVault::DISCRIMINATOR, data) => Self::no_data(data, Self::InitFeeVault)?,
+            (WithdrawFees::DISCRIMINATOR, data) => Self::WithdrawFees(data),
+            (Pause::DISCRIMINATOR, data) => Self::no_data(data, Self::Pause)?,
+            (Unpause::DISCRIMINATOR, data) => Self::no_data(data, Self::Unpause)?,
+            (SetLimits::DISCRIMINATOR, data) => Self::SetLimits(data),
+            (GetVersion::DISCRIMINATOR, data) => Self::no_data(data, Self::GetVersion)?,
+            _ => {
+                log_unknown_discriminator(*discriminator, data);
+                return Err(ProgramError::InvalidInstructionData);
+            }
+        })
+    }
+
+    /// 无需指令数据的指令：判别器之后不能有多余字节
+    fn no_data(data: &[u8], instruction: Self) -> Result<Self, ProgramError> {
+        if !data.is_empty() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(instruction)
+    }
+
+    /// 构造账户结构并执行指令
+    pub fn process(self, accounts: &'a [AccountView]) -> ProgramResult {
+        match self {
+            Self::Make(data) => Make::try_from((data, accounts))?.process(),
+            Self::Take(data) => Take::try_from((data, accounts))?.process(),
+            Self::Refund(data) => Refund::try_from((data, accounts))?.process(),
+            Self::TakePartial(data) => TakePartial::try_from((data, accounts))?.process(),
+            Self::CloseExpired => CloseExpired::try_from(accounts)?.process(),
+            Self::UpdateEscrow(data) => UpdateEscrow::try_from((data, accounts))?.process(),
+            Self::MakeSol(data) => MakeSol::try_from((data, accounts))?.process(),
+            Self::AddToWhitelist(data) => AddToWhitelist::try_from((data, accounts))?.process(),
+            Self::RemoveFromWhitelist(data) => RemoveFromWhitelist::try_from((data, accounts))?.process(),
+            Self::Deposit => Deposit::try_from(accounts)?.process(),
+            Self::Settle => Settle::try_from(accounts)?.process(),
+            Self::PlaceBid(data) => PlaceBid::try_from((data, accounts))?.process(),
+            Self::AcceptBid => AcceptBid::try_from(accounts)?.process(),
+            Self::CancelAuction => CancelAuction::try_from(accounts)?.process(),
+            Self::RefundBatch => RefundBatch::try_from(accounts)?.process(),
+            Self::ProposeCounterOffer(data) => ProposeCounterOffer::try_from((data, accounts))?.process(),
+            Self::AcceptCounterOffer => AcceptCounterOffer::try_from(accounts)?.process(),
+            Self::WithdrawCounterOffer => WithdrawCounterOffer::try_from(accounts)?.process(),
+            Self::InitializeConfig(data) => InitializeConfig::try_from((data, accounts))?.process(),
+            Self::SetFee(data) => SetFee::try_from((data, accounts))?.process(),
+            Self::CollectFees => CollectFees::try_from(accounts)?.process(),
+            Self::Claim => Claim::try_from(accounts)?.process(),
+            Self::RaiseDispute => RaiseDispute::try_from(accounts)?.process(),
+            Self::Resolve(data) => Resolve::try_from((data, accounts))?.process(),
+            Self::TopUp(data) => TopUp::try_from((data, accounts))?.process(),
+            Self::WithdrawPartial(data) => WithdrawPartial::try_from((data, accounts))?.process(),
+            Self::TransferMaker => TransferMaker::try_from(accounts)?.process(),
+            Self::Contribute(data) => Contribute::try_from((data, accounts))?.process(),
+            Self::Finalize => Finalize::try_from(accounts)?.process(),
+            Self::WithdrawContribution => WithdrawContribution::try_from(accounts)?.process(),
+            Self::MakeWithDelegate(data) => MakeWithDelegate::try_from((data, accounts))?.process(),
+            Self::MakeAuto(data) => MakeAuto::try_from((data, accounts))?.process(),
+            Self::CommitTake(data) => CommitTake::try_from((data, accounts))?.process(),
+            Self::RevealTake(data) => RevealTake::try_from((data, accounts))?.process(),
+            Self::CancelCommit => CancelCommit::try_from(accounts)?.process(),
+            Self::TakeSponsored(data) => TakeSponsored::try_from((data, accounts))?.process(),
+            Self::Renew(data) => Renew::try_from((data, accounts))?.process(),
+            Self::Sweep => Sweep::try_from(accounts)?.process(),
+            Self::SkimLamports => SkimLamports::try_from(accounts)?.process(),
+            Self::ViewEscrow => ViewEscrow::try_from(accounts)?.process(),
+            Self::MakeNft(data) => MakeNft::try_from((data, accounts))?.process(),
+            Self::MakePnft(data) => MakePnft::try_from((data, accounts))?.process(),
+            Self::TakePnft(data) => TakePnft::try_from((data, accounts))?.process(),
+            Self::RefundPnft => RefundPnft::try_from(accounts)?.process(),
+            Self::Migrate => Migrate::try_from(accounts)?.process(),
+            Self::InitMakerStats => InitMakerStats::try_from(accounts)?.process(),
+            Self::InitFeeVault => InitFeeVault::try_from(accounts)?.process(),
+            Self::WithdrawFees(data) => WithdrawFees::try_from((data, accounts))?.process(),
+            Self::Pause => Pause::try_from(accounts)?.process(),
+            Self::Unpause => Unpause::try_from(accounts)?.process(),
+            Self::SetLimits(data) => SetLimits::try_from((data, accounts))?.process(),
+            Self::GetVersion => GetVersion.process(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sighash(discriminator: &u8) -> [u8; SIGHASH_LEN] {
+        SIGHASHES[*discriminator as usize].1
+    }
+
+    #[test]
+    fn one_byte_and_anchor_discriminators_parse_the_same() {
+        let data = [*Take::DISCRIMINATOR, 1, 2];
+        assert!(matches!(EscrowInstruction::unpack(&data), Ok(EscrowInstruction::Take(&[1, 2]))));
+
+        let data = [&sighash(Take::DISCRIMINATOR)[..], &[1, 2]].concat();
+        assert!(matches!(EscrowInstruction::unpack(&data), Ok(EscrowInstruction::Take(&[1, 2]))));
+
+        let data = sighash(GetVersion::DISCRIMINATOR);
+        assert!(matches!(EscrowInstruction::unpack(&data), Ok(EscrowInstruction::GetVersion)));
+        assert!(matches!(EscrowInstruction::unpack(&[*MakeAuto::DISCRIMINATOR]), Ok(EscrowInstruction::MakeAuto(&[]))));
+    }
+
+    #[test]
+    fn versioned_prefix_selects_the_parser() {
+        let data = [VERSIONED_PREFIX, PAYLOAD_VERSION, *Refund::DISCRIMINATOR, 1];
+        assert!(matches!(EscrowInstruction::unpack(&data), Ok(EscrowInstruction::Refund(&[1]))));
+
+        let data = [VERSIONED_PREFIX, PAYLOAD_VERSION, *Claim::DISCRIMINATOR];
+        assert!(matches!(EscrowInstruction::unpack(&data), Ok(EscrowInstruction::Claim)));
+
+        // 未知版本返回专门的错误，而不是按其他版本解析
+        let data = [VERSIONED_PREFIX, PAYLOAD_VERSION + 1, *Refund::DISCRIMINATOR, 1];
+        assert_eq!(
+            EscrowInstruction::unpack(&data).err(),
+            Some(ProgramError::from(EscrowError::UnsupportedPayloadVersion))
+        );
+
+        // 版本前缀不是判别器：缺少判别器时按未知判别器处理
+        assert!(EscrowInstruction::unpack(&[VERSIONED_PREFIX, PAYLOAD_VERSION]).is_err());
+        assert!(EscrowInstruction::unpack(&[VERSIONED_PREFIX]).is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_discriminators_and_trailing_bytes() {
+        assert_eq!(EscrowInstruction::unpack(&[]).err(), Some(ProgramError::InvalidInstructionData));
+        assert_eq!(
+            EscrowInstruction::unpack(&[MAX_DISCRIMINATOR + 1]).err(),
+            Some(ProgramError::InvalidInstructionData)
+        );
+
+        // 无需指令数据的指令不接受多余字节（两种判别器格式相同）
+        assert!(matches!(EscrowInstruction::unpack(&[*Claim::DISCRIMINATOR]), Ok(EscrowInstruction::Claim)));
+        assert!(EscrowInstruction::unpack(&[*Claim::DISCRIMINATOR, 0]).is_err());
+        let data = [&sighash(Claim::DISCRIMINATOR)[..], &[0]].concat();
+        assert!(EscrowInstruction::unpack(&data).is_err());
+    }
+}
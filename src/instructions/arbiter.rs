@@ -0,0 +1,332 @@
+//! RaiseDispute / Resolve 指令实现（三方托管：仲裁人裁决）
+//!
+//! Make 时通过 `OPTION_ARBITER` 指定仲裁人后，Escrow 成为三方托管：
+//! 1. maker 通过 Make 把 Token A 锁入 vault
+//! 2. 对手方通过 Deposit 把 Token B 锁入 vault_b（不能直接 Take，也不能 Settle）
+//! 3. 通过 Resolve 决定两个 vault 的去向：
+//!    - 放行给对手方：Token A 给对手方，Token B 给 maker（正常成交）
+//!    - 退回给 maker：Token A 退回 maker，Token B 退回对手方（交易撤销）
+//!
+//! 未发生争议时，双方都只能把资产让给对方：maker 可以放行给对手方，对手方可以退回给 maker。
+//! 任意一方调用 `RaiseDispute` 之后，只有仲裁人可以调用 Resolve；仲裁人在任何时候都可以裁决。
+//!
+use crate::errors::EscrowError;
+use crate::helper::{
+    AssociatedTokenAccount, MintInterface, ProgramAccount, SignerAccount, SystemProgramCheck, TokenTransfer,
+};
+use crate::state::Escrow;
+use crate::whitelist::close_whitelist;
+use crate::{AccountCheck, AccountClose, AssociatedTokenAccountCheck, AssociatedTokenAccountInit};
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    AccountView, Address, ProgramResult,
+};
+use pinocchio_token::{
+    instructions::CloseAccount,
+    state::TokenAccount,
+};
+
+/// 验证 Escrow PDA 与 maker 对应，且是已 Deposit 的三方托管
+fn verify_arbitrated(escrow: &Escrow, escrow_account: &AccountView, maker: &AccountView) -> ProgramResult {
+    if maker.address() != &escrow.maker {
+        return Err(EscrowError::MakerMismatch.into());
+    }
+
+    let escrow_key = Address::create_program_address(
+        &[
+            b"escrow",
+            maker.address().as_ref(),
+            &escrow.seed.to_le_bytes(),
+            &[escrow.bump],
+        ],
+        &crate::ID,
+    )?;
+    if &escrow_key != escrow_account.address() {
+        return Err(EscrowError::InvalidEscrowPda.into());
+    }
+
+    // 只有对手方已经 Deposit 的三方托管才有争议可言
+    if !escrow.has_arbiter() || !escrow.is_deposited() {
+        return Err(EscrowError::InvalidAccountData.into());
+    }
+
+    Ok(())
+}
+
+// ========== RaiseDispute ==========
+pub struct RaiseDisputeAccounts<'a> {
+    pub authority: &'a AccountView, // maker 或已 Deposit 的对手方（签名者）
+    pub maker: &'a AccountView,     // 托管创建者（用于验证 Escrow PDA）
+    pub escrow: &'a AccountView,    // Escrow PDA 账户
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for RaiseDisputeAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [authority, maker, escrow] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        SignerAccount::check(authority)?;
+        ProgramAccount::check(escrow)?;
+
+        Ok(Self {
+            authority,
+            maker,
+            escrow,
+        })
+    }
+}
+
+// ========== 指令数据结构（RaiseDispute 无需额外数据）==========
+pub struct RaiseDispute<'a> {
+    pub accounts: RaiseDisputeAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for RaiseDispute<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = RaiseDisputeAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> RaiseDispute<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &22;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let mut data = self.accounts.escrow.try_borrow_mut()?;
+        let escrow = Escrow::load_mut(data.as_mut())?;
+
+        verify_arbitrated(escrow, self.accounts.escrow, self.accounts.maker)?;
+
+        // 只有交易双方可以发起争议
+        let authority = self.accounts.authority.address();
+        if authority != &escrow.maker && escrow.designated_taker() != Some(authority) {
+            return Err(EscrowError::NotDisputeParty.into());
+        }
+
+        escrow.set_status(Escrow::STATUS_DISPUTED);
+        escrow.touch(Clock::get()?.slot);
+
+        Ok(())
+    }
+}
+
+// ========== Resolve ==========
+pub struct ResolveAccounts<'a> {
+    pub authority: &'a AccountView,     // 仲裁人，或让出资产的一方（签名者，支付可能需要创建的 ATA）
+    pub maker: &'a AccountView,         // 托管创建者
+    pub taker: &'a AccountView,         // 已存入 Token B 的对手方
+    pub escrow: &'a AccountView,        // Escrow PDA 账户
+    pub mint_a: &'a AccountView,        // Token A 的 Mint
+    pub mint_b: &'a AccountView,        // Token B 的 Mint
+    pub vault: &'a AccountView,         // Token A vault
+    pub vault_b: &'a AccountView,       // Token B vault
+    pub destination_a: &'a AccountView, // 接收 Token A 的 ATA（放行时为对手方的，退回时为 maker 的）
+    pub destination_b: &'a AccountView, // 接收 Token B 的 ATA（放行时为 maker 的，退回时为对手方的）
+    pub system_program: &'a AccountView,
+    pub token_program: &'a AccountView,
+    pub whitelist: Option<&'a AccountView>, // 可选：白名单 PDA（Escrow 启用白名单时必须传入）
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for ResolveAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [authority, maker, taker, escrow, mint_a, mint_b, vault, vault_b, destination_a, destination_b, system_program, token_program, _, rest @ ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        SystemProgramCheck::check(system_program)?;
+
+        // 账户基础校验
+        SignerAccount::check(authority)?;
+        ProgramAccount::check(escrow)?;
+        MintInterface::check(mint_a)?;
+        MintInterface::check(mint_b)?;
+        AssociatedTokenAccount::check(vault, escrow, mint_a, token_program)?;
+        AssociatedTokenAccount::check(vault_b, escrow, mint_b, token_program)?;
+
+        Ok(Self {
+            authority,
+            maker,
+            taker,
+            escrow,
+            mint_a,
+            mint_b,
+            vault,
+            vault_b,
+            destination_a,
+            destination_b,
+            system_program,
+            token_program,
+            whitelist: rest.first(),
+        })
+    }
+}
+
+pub struct ResolveInstructionData {
+    pub to_taker: bool, // true：放行给对手方；false：退回给 maker
+}
+
+impl ResolveInstructionData {
+    /// 裁决结果：Token A 给对手方，Token B 给 maker
+    pub const RESOLVE_TO_TAKER: u8 = 0;
+    /// 裁决结果：Token A 退回 maker，Token B 退回对手方
+    pub const RESOLVE_TO_MAKER: u8 = 1;
+}
+
+impl<'a> TryFrom<&'a [u8]> for ResolveInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        match data {
+            [Self::RESOLVE_TO_TAKER] => Ok(Self { to_taker: true }),
+            [Self::RESOLVE_TO_MAKER] => Ok(Self { to_taker: false }),
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+}
+
+pub struct Resolve<'a> {
+    pub accounts: ResolveAccounts<'a>,
+    pub instruction_data: ResolveInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for Resolve<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = ResolveAccounts::try_from(accounts)?;
+        let instruction_data = ResolveInstructionData::try_from(data)?;
+
+        // Token A 的接收方是对手方（放行）或 maker（退回），Token B 反之
+        let (owner_a, owner_b) = if instruction_data.to_taker {
+            (accounts.taker, accounts.maker)
+        } else {
+            (accounts.maker, accounts.taker)
+        };
+
+        AssociatedTokenAccount::init_if_needed(
+            accounts.destination_a,
+            accounts.mint_a,
+            accounts.authority,
+            owner_a,
+            accounts.system_program,
+            accounts.token_program,
+        )?;
+
+        AssociatedTokenAccount::init_if_needed(
+            accounts.destination_b,
+            accounts.mint_b,
+            accounts.authority,
+            owner_b,
+            accounts.system_program,
+            accounts.token_program,
+        )?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> Resolve<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &23;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let data = self.accounts.escrow.try_borrow()?;
+        let escrow = Escrow::load(&data)?;
+
+        verify_arbitrated(escrow, self.accounts.escrow, self.accounts.maker)?;
+
+        if self.accounts.taker.address() != &escrow.taker {
+            return Err(EscrowError::UnauthorizedTaker.into());
+        }
+        if self.accounts.mint_a.address() != &escrow.mint_a
+            || self.accounts.mint_b.address() != &escrow.mint_b
+        {
+            return Err(EscrowError::InvalidMint.into());
+        }
+
+        // 仲裁人可以任意裁决；未发生争议时，maker 只能放行给对手方，对手方只能退回给 maker
+        let authority = self.accounts.authority.address();
+        let to_taker = self.instruction_data.to_taker;
+        let allowed = authority == &escrow.arbiter
+            || (!escrow.is_disputed()
+                && ((to_taker && authority == &escrow.maker)
+                    || (!to_taker && escrow.designated_taker() == Some(authority))));
+        if !allowed {
+            return Err(EscrowError::NotArbiter.into());
+        }
+
+        let seed_binding = escrow.seed.to_le_bytes();
+        let bump_binding = [escrow.bump];
+        let escrow_seeds = [
+            Seed::from(b"escrow"),
+            Seed::from(self.accounts.maker.address().as_ref()),
+            Seed::from(&seed_binding),
+            Seed::from(&bump_binding),
+        ];
+        let signers = [Signer::from(&escrow_seeds)];
+
+        let amount_a = TokenAccount::from_account_view(self.accounts.vault)?.amount();
+        let amount_b = TokenAccount::from_account_view(self.accounts.vault_b)?.amount();
+
+        // Transfer Token A from the Vault to its recipient
+        TokenTransfer {
+            from: self.accounts.vault,
+            mint: self.accounts.mint_a,
+            to: self.accounts.destination_a,
+            authority: self.accounts.escrow,
+            amount: amount_a,
+            hook_accounts: &[],
+        }
+        .invoke_signed(&signers)?;
+
+        // Transfer Token B from the second Vault to its recipient
+        TokenTransfer {
+            from: self.accounts.vault_b,
+            mint: self.accounts.mint_b,
+            to: self.accounts.destination_b,
+            authority: self.accounts.escrow,
+            amount: amount_b,
+            hook_accounts: &[],
+        }
+        .invoke_signed(&signers)?;
+
+        // Close both Vaults（租金分别返还给各自的支付者）
+        CloseAccount {
+            account: self.accounts.vault,
+            destination: self.accounts.maker,
+            authority: self.accounts.escrow,
+        }
+        .invoke_signed(&signers)?;
+        CloseAccount {
+            account: self.accounts.vault_b,
+            destination: self.accounts.taker,
+            authority: self.accounts.escrow,
+        }
+        .invoke_signed(&signers)?;
+
+        // Close the Whitelist
+        if escrow.has_whitelist() {
+            close_whitelist(self.accounts.whitelist, self.accounts.escrow, self.accounts.maker)?;
+        }
+
+        // Close the Escrow
+        drop(data);
+        ProgramAccount::close(self.accounts.escrow, self.accounts.maker)?;
+
+        Ok(())
+    }
+}
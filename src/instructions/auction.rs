@@ -0,0 +1,627 @@
+//! PlaceBid / AcceptBid / CancelAuction 指令实现（英式拍卖）
+//!
+//! Make 时设置拍卖选项后，Escrow 进入拍卖模式，`receive` 作为底价：
+//! - `PlaceBid`：出价者把 Token B 锁入 vault_b，同时把上一个最高出价原路退回
+//! - `AcceptBid`：maker 随时接受当前最高出价；过期后任何人都可以调用完成结算
+//! - `CancelAuction`：无人出价时 maker 可以取消；过期且无人出价时任何人都可以关闭
+//!
+//! 出价记录在 PDA `["bid", escrow]` 中，出价的 Token B 存放在 Escrow 拥有的 vault_b（Token B ATA）。
+//! 出价 PDA 与 vault_b 的租金由 maker 在 Make 时支付，拍卖结束时一并返还给 maker。
+//!
+use crate::errors::EscrowError;
+use crate::helper::{
+    AssociatedTokenAccount, BidAccount, MintInterface, ProgramAccount, SignerAccount, SystemProgramCheck, TokenTransfer,
+};
+use crate::state::{Bid, Escrow};
+use crate::whitelist::{check_whitelisted, close_whitelist};
+use crate::{AccountCheck, AccountClose, AssociatedTokenAccountCheck, AssociatedTokenAccountInit};
+use core::mem::size_of;
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    AccountView, Address, ProgramResult,
+};
+use pinocchio_token::{
+    instructions::CloseAccount,
+    state::TokenAccount,
+};
+
+/// 验证出价账户属于 `escrow`（owner、长度、PDA 种子以及记录的 Escrow 地址）
+pub fn verify_bid(bid: &AccountView, escrow: &AccountView) -> ProgramResult {
+    BidAccount::check(bid)?;
+
+    let data = bid.try_borrow()?;
+    let state = Bid::load(&data)?;
+    if &state.escrow != escrow.address() {
+        return Err(EscrowError::InvalidBidPda.into());
+    }
+
+    let bid_key = Address::create_program_address(
+        &[b"bid", escrow.address().as_ref(), &state.bump],
+        &crate::ID,
+    )?;
+    if &bid_key != bid.address() {
+        return Err(EscrowError::InvalidBidPda.into());
+    }
+
+    Ok(())
+}
+
+/// 验证 Escrow PDA 由 `maker` 创建且处于拍卖模式
+fn verify_auction(escrow: &Escrow, escrow_account: &AccountView, maker: &AccountView) -> ProgramResult {
+    if maker.address() != &escrow.maker {
+        return Err(EscrowError::MakerMismatch.into());
+    }
+
+    let escrow_key = Address::create_program_address(
+        &[
+            b"escrow",
+            maker.address().as_ref(),
+            &escrow.seed.to_le_bytes(),
+            &[escrow.bump],
+        ],
+        &crate::ID,
+    )?;
+    if &escrow_key != escrow_account.address() {
+        return Err(EscrowError::InvalidEscrowPda.into());
+    }
+
+    if !escrow.is_auction() {
+        return Err(EscrowError::InvalidAccountData.into());
+    }
+
+    Ok(())
+}
+
+// ========== PlaceBid ==========
+pub struct PlaceBidAccounts<'a> {
+    pub bidder: &'a AccountView,                // 出价者（签名者）
+    pub maker: &'a AccountView,                 // 托管创建者（用于验证 Escrow PDA）
+    pub escrow: &'a AccountView,                // Escrow PDA 账户
+    pub mint_b: &'a AccountView,                // Token B 的 Mint
+    pub bidder_ata_b: &'a AccountView,          // 出价者的 Token B ATA
+    pub vault_b: &'a AccountView,               // 存放出价的 Token B vault
+    pub bid: &'a AccountView,                   // 出价 PDA
+    pub previous_bidder: &'a AccountView,       // 上一个最高出价者（首次出价时任意传入）
+    pub previous_bidder_ata_b: &'a AccountView, // 上一个最高出价者的 Token B ATA（接收退款）
+    pub system_program: &'a AccountView,
+    pub token_program: &'a AccountView,
+    pub whitelist: Option<&'a AccountView>, // 可选：白名单 PDA（Escrow 启用白名单时必须传入）
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for PlaceBidAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [bidder, maker, escrow, mint_b, bidder_ata_b, vault_b, bid, previous_bidder, previous_bidder_ata_b, system_program, token_program, _, rest @ ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        SystemProgramCheck::check(system_program)?;
+
+        // 账户基础校验
+        SignerAccount::check(bidder)?;
+        ProgramAccount::check(escrow)?;
+        MintInterface::check(mint_b)?;
+        AssociatedTokenAccount::check(bidder_ata_b, bidder, mint_b, token_program)?;
+        AssociatedTokenAccount::check(vault_b, escrow, mint_b, token_program)?;
+
+        Ok(Self {
+            bidder,
+            maker,
+            escrow,
+            mint_b,
+            bidder_ata_b,
+            vault_b,
+            bid,
+            previous_bidder,
+            previous_bidder_ata_b,
+            system_program,
+            token_program,
+            whitelist: rest.first(),
+        })
+    }
+}
+
+pub struct PlaceBidInstructionData {
+    pub amount: u64, // 出价的 Token B 数量
+}
+
+impl<'a> TryFrom<&'a [u8]> for PlaceBidInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len() != size_of::<u64>() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let amount = u64::from_le_bytes(data[0..8].try_into().unwrap());
+        if amount == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self { amount })
+    }
+}
+
+pub struct PlaceBid<'a> {
+    pub accounts: PlaceBidAccounts<'a>,
+    pub instruction_data: PlaceBidInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for PlaceBid<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = PlaceBidAccounts::try_from(accounts)?;
+        let instruction_data = PlaceBidInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> PlaceBid<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &11;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let data = self.accounts.escrow.try_borrow()?;
+        let escrow = Escrow::load(&data)?;
+        verify_auction(escrow, self.accounts.escrow, self.accounts.maker)?;
+        verify_bid(self.accounts.bid, self.accounts.escrow)?;
+
+        if self.accounts.mint_b.address() != &escrow.mint_b {
+            return Err(EscrowError::InvalidMint.into());
+        }
+
+        escrow.check_open()?;
+
+        // 已过期的拍卖不能再出价
+        if escrow.is_expired(Clock::get()?.unix_timestamp) {
+            return Err(EscrowError::EscrowExpired.into());
+        }
+
+        // 与 Take 相同的 taker 权限校验
+        if !escrow.is_taker_allowed(self.accounts.bidder.address()) {
+            return Err(EscrowError::UnauthorizedTaker.into());
+        }
+        if escrow.has_whitelist() {
+            check_whitelisted(
+                self.accounts.whitelist,
+                self.accounts.escrow,
+                self.accounts.bidder.address(),
+            )?;
+        }
+
+        let mut bid_data = self.accounts.bid.try_borrow_mut()?;
+        let bid = Bid::load_mut(bid_data.as_mut())?;
+
+        // 出价不能低于底价，且必须高于当前最高出价
+        let amount = self.instruction_data.amount;
+        if amount < escrow.receive || (bid.has_bid() && amount <= bid.amount) {
+            return Err(EscrowError::BidTooLow.into());
+        }
+
+        // 退回上一个最高出价
+        if bid.has_bid() {
+            if self.accounts.previous_bidder.address() != &bid.bidder {
+                return Err(EscrowError::InvalidBidder.into());
+            }
+
+            // 上一个出价者关闭了 ATA 时由新出价者重新创建，避免拍卖被卡住
+            AssociatedTokenAccount::init_if_needed(
+                self.accounts.previous_bidder_ata_b,
+                self.accounts.mint_b,
+                self.accounts.bidder,
+                self.accounts.previous_bidder,
+                self.accounts.system_program,
+                self.accounts.token_program,
+            )?;
+
+            let seed_binding = escrow.seed.to_le_bytes();
+            let bump_binding = [escrow.bump];
+            let escrow_seeds = [
+                Seed::from(b"escrow"),
+                Seed::from(self.accounts.maker.address().as_ref()),
+                Seed::from(&seed_binding),
+                Seed::from(&bump_binding),
+            ];
+            let signers = [Signer::from(&escrow_seeds)];
+
+            TokenTransfer {
+                from: self.accounts.vault_b,
+                mint: self.accounts.mint_b,
+                to: self.accounts.previous_bidder_ata_b,
+                authority: self.accounts.escrow,
+                amount: bid.amount,
+                hook_accounts: &[],
+            }
+            .invoke_signed(&signers)?;
+        }
+
+        // Transfer Token B from the Bidder to the vault
+        TokenTransfer {
+            from: self.accounts.bidder_ata_b,
+            mint: self.accounts.mint_b,
+            to: self.accounts.vault_b,
+            authority: self.accounts.bidder,
+            amount,
+            hook_accounts: &[],
+        }
+        .invoke()?;
+
+        bid.set_bid(self.accounts.bidder.address().clone(), amount);
+
+        Ok(())
+    }
+}
+
+// ========== AcceptBid ==========
+pub struct AcceptBidAccounts<'a> {
+    pub authority: &'a AccountView,   // 调用者（签名者）：maker，或过期后的任何人
+    pub maker: &'a AccountView,       // 托管创建者（接收 Token B 与租金）
+    pub bidder: &'a AccountView,      // 最高出价者（接收 Token A）
+    pub escrow: &'a AccountView,      // Escrow PDA 账户
+    pub mint_a: &'a AccountView,      // Token A 的 Mint
+    pub mint_b: &'a AccountView,      // Token B 的 Mint
+    pub vault: &'a AccountView,       // Token A vault
+    pub vault_b: &'a AccountView,     // 存放出价的 Token B vault
+    pub bid: &'a AccountView,         // 出价 PDA
+    pub bidder_ata_a: &'a AccountView, // 最高出价者的 Token A ATA
+    pub maker_ata_b: &'a AccountView, // maker 的 Token B ATA
+    pub system_program: &'a AccountView,
+    pub token_program: &'a AccountView,
+    pub whitelist: Option<&'a AccountView>, // 可选：白名单 PDA（Escrow 启用白名单时必须传入）
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for AcceptBidAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [authority, maker, bidder, escrow, mint_a, mint_b, vault, vault_b, bid, bidder_ata_a, maker_ata_b, system_program, token_program, _, rest @ ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        SystemProgramCheck::check(system_program)?;
+
+        // 账户基础校验
+        SignerAccount::check(authority)?;
+        ProgramAccount::check(escrow)?;
+        MintInterface::check(mint_a)?;
+        MintInterface::check(mint_b)?;
+        AssociatedTokenAccount::check(vault, escrow, mint_a, token_program)?;
+        AssociatedTokenAccount::check(vault_b, escrow, mint_b, token_program)?;
+
+        Ok(Self {
+            authority,
+            maker,
+            bidder,
+            escrow,
+            mint_a,
+            mint_b,
+            vault,
+            vault_b,
+            bid,
+            bidder_ata_a,
+            maker_ata_b,
+            system_program,
+            token_program,
+            whitelist: rest.first(),
+        })
+    }
+}
+
+pub struct AcceptBid<'a> {
+    pub accounts: AcceptBidAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for AcceptBid<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = AcceptBidAccounts::try_from(accounts)?;
+
+        // Initialize necessary accounts
+        AssociatedTokenAccount::init_if_needed(
+            accounts.bidder_ata_a,
+            accounts.mint_a,
+            accounts.authority,
+            accounts.bidder,
+            accounts.system_program,
+            accounts.token_program,
+        )?;
+
+        AssociatedTokenAccount::init_if_needed(
+            accounts.maker_ata_b,
+            accounts.mint_b,
+            accounts.authority,
+            accounts.maker,
+            accounts.system_program,
+            accounts.token_program,
+        )?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> AcceptBid<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &12;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let data = self.accounts.escrow.try_borrow()?;
+        let escrow = Escrow::load(&data)?;
+        verify_auction(escrow, self.accounts.escrow, self.accounts.maker)?;
+        verify_bid(self.accounts.bid, self.accounts.escrow)?;
+
+        if self.accounts.mint_a.address() != &escrow.mint_a
+            || self.accounts.mint_b.address() != &escrow.mint_b
+        {
+            return Err(EscrowError::InvalidMint.into());
+        }
+
+        // 拍卖结束前只有 maker 可以接受出价，过期后任何人都可以完成结算
+        if self.accounts.authority.address() != &escrow.maker
+            && !escrow.is_expired(Clock::get()?.unix_timestamp)
+        {
+            return Err(EscrowError::EscrowNotExpired.into());
+        }
+
+        {
+            let bid_data = self.accounts.bid.try_borrow()?;
+            let bid = Bid::load(&bid_data)?;
+            if !bid.has_bid() {
+                return Err(EscrowError::NoBid.into());
+            }
+            if self.accounts.bidder.address() != &bid.bidder {
+                return Err(EscrowError::InvalidBidder.into());
+            }
+        }
+
+        let seed_binding = escrow.seed.to_le_bytes();
+        let bump_binding = [escrow.bump];
+        let escrow_seeds = [
+            Seed::from(b"escrow"),
+            Seed::from(self.accounts.maker.address().as_ref()),
+            Seed::from(&seed_binding),
+            Seed::from(&bump_binding),
+        ];
+        let signers = [Signer::from(&escrow_seeds)];
+
+        // vault_b 中只有最高出价（以及可能被直接转入的代币），全部交给 maker 以便关闭
+        let amount_a = TokenAccount::from_account_view(self.accounts.vault)?.amount();
+        let amount_b = TokenAccount::from_account_view(self.accounts.vault_b)?.amount();
+
+        // Transfer Token A from the Vault to the Bidder
+        TokenTransfer {
+            from: self.accounts.vault,
+            mint: self.accounts.mint_a,
+            to: self.accounts.bidder_ata_a,
+            authority: self.accounts.escrow,
+            amount: amount_a,
+            hook_accounts: &[],
+        }
+        .invoke_signed(&signers)?;
+
+        // Transfer the winning bid from vault_b to the Maker
+        TokenTransfer {
+            from: self.accounts.vault_b,
+            mint: self.accounts.mint_b,
+            to: self.accounts.maker_ata_b,
+            authority: self.accounts.escrow,
+            amount: amount_b,
+            hook_accounts: &[],
+        }
+        .invoke_signed(&signers)?;
+
+        // Close both Vaults
+        CloseAccount {
+            account: self.accounts.vault,
+            destination: self.accounts.maker,
+            authority: self.accounts.escrow,
+        }
+        .invoke_signed(&signers)?;
+        CloseAccount {
+            account: self.accounts.vault_b,
+            destination: self.accounts.maker,
+            authority: self.accounts.escrow,
+        }
+        .invoke_signed(&signers)?;
+
+        // Close the Bid and Whitelist
+        ProgramAccount::close(self.accounts.bid, self.accounts.maker)?;
+        if escrow.has_whitelist() {
+            close_whitelist(self.accounts.whitelist, self.accounts.escrow, self.accounts.maker)?;
+        }
+
+        // Close the Escrow
+        drop(data);
+        ProgramAccount::close(self.accounts.escrow, self.accounts.maker)?;
+
+        Ok(())
+    }
+}
+
+// ========== CancelAuction ==========
+pub struct CancelAuctionAccounts<'a> {
+    pub authority: &'a AccountView,   // 调用者（签名者）：maker，或过期后的任何人
+    pub maker: &'a AccountView,       // 托管创建者（取回 Token A 与租金）
+    pub escrow: &'a AccountView,      // Escrow PDA 账户
+    pub mint_a: &'a AccountView,      // Token A 的 Mint
+    pub mint_b: &'a AccountView,      // Token B 的 Mint
+    pub maker_ata_a: &'a AccountView, // maker 的 Token A ATA
+    pub maker_ata_b: &'a AccountView, // maker 的 Token B ATA（仅 vault_b 中有被直接转入的代币时使用）
+    pub vault: &'a AccountView,       // Token A vault
+    pub vault_b: &'a AccountView,     // 存放出价的 Token B vault
+    pub bid: &'a AccountView,         // 出价 PDA
+    pub system_program: &'a AccountView,
+    pub token_program: &'a AccountView,
+    pub whitelist: Option<&'a AccountView>, // 可选：白名单 PDA（Escrow 启用白名单时必须传入）
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for CancelAuctionAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [authority, maker, escrow, mint_a, mint_b, maker_ata_a, maker_ata_b, vault, vault_b, bid, system_program, token_program, _, rest @ ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        SystemProgramCheck::check(system_program)?;
+
+        // 账户基础校验
+        SignerAccount::check(authority)?;
+        ProgramAccount::check(escrow)?;
+        MintInterface::check(mint_a)?;
+        MintInterface::check(mint_b)?;
+        AssociatedTokenAccount::check(vault, escrow, mint_a, token_program)?;
+        AssociatedTokenAccount::check(vault_b, escrow, mint_b, token_program)?;
+
+        Ok(Self {
+            authority,
+            maker,
+            escrow,
+            mint_a,
+            mint_b,
+            maker_ata_a,
+            maker_ata_b,
+            vault,
+            vault_b,
+            bid,
+            system_program,
+            token_program,
+            whitelist: rest.first(),
+        })
+    }
+}
+
+pub struct CancelAuction<'a> {
+    pub accounts: CancelAuctionAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for CancelAuction<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = CancelAuctionAccounts::try_from(accounts)?;
+
+        // Initialize necessary accounts
+        AssociatedTokenAccount::init_if_needed(
+            accounts.maker_ata_a,
+            accounts.mint_a,
+            accounts.authority,
+            accounts.maker,
+            accounts.system_program,
+            accounts.token_program,
+        )?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> CancelAuction<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &13;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let data = self.accounts.escrow.try_borrow()?;
+        let escrow = Escrow::load(&data)?;
+        verify_auction(escrow, self.accounts.escrow, self.accounts.maker)?;
+        verify_bid(self.accounts.bid, self.accounts.escrow)?;
+
+        // 有人出价后只能通过 AcceptBid 结算
+        if Bid::load(&self.accounts.bid.try_borrow()?)?.has_bid() {
+            return Err(EscrowError::BidPending.into());
+        }
+
+        // 拍卖结束前只有 maker 可以取消，过期后任何人都可以关闭
+        let now = Clock::get()?.unix_timestamp;
+        if self.accounts.authority.address() != &escrow.maker && !escrow.is_expired(now) {
+            return Err(EscrowError::EscrowNotExpired.into());
+        }
+
+        // maker 承诺在锁定期内保持拍卖有效
+        if escrow.is_refund_locked(now) {
+            return Err(EscrowError::RefundLocked.into());
+        }
+
+        let seed_binding = escrow.seed.to_le_bytes();
+        let bump_binding = [escrow.bump];
+        let escrow_seeds = [
+            Seed::from(b"escrow"),
+            Seed::from(self.accounts.maker.address().as_ref()),
+            Seed::from(&seed_binding),
+            Seed::from(&bump_binding),
+        ];
+        let signers = [Signer::from(&escrow_seeds)];
+
+        let amount_a = TokenAccount::from_account_view(self.accounts.vault)?.amount();
+
+        // Return Token A to the Maker
+        TokenTransfer {
+            from: self.accounts.vault,
+            mint: self.accounts.mint_a,
+            to: self.accounts.maker_ata_a,
+            authority: self.accounts.escrow,
+            amount: amount_a,
+            hook_accounts: &[],
+        }
+        .invoke_signed(&signers)?;
+
+        // 无人出价时 vault_b 通常为空；有人直接转入代币时转给 maker，避免 vault_b 无法关闭
+        let amount_b = TokenAccount::from_account_view(self.accounts.vault_b)?.amount();
+        if amount_b != 0 {
+            AssociatedTokenAccount::init_if_needed(
+                self.accounts.maker_ata_b,
+                self.accounts.mint_b,
+                self.accounts.authority,
+                self.accounts.maker,
+                self.accounts.system_program,
+                self.accounts.token_program,
+            )?;
+
+            TokenTransfer {
+                from: self.accounts.vault_b,
+                mint: self.accounts.mint_b,
+                to: self.accounts.maker_ata_b,
+                authority: self.accounts.escrow,
+                amount: amount_b,
+                hook_accounts: &[],
+            }
+            .invoke_signed(&signers)?;
+        }
+
+        // Close both Vaults
+        CloseAccount {
+            account: self.accounts.vault,
+            destination: self.accounts.maker,
+            authority: self.accounts.escrow,
+        }
+        .invoke_signed(&signers)?;
+        CloseAccount {
+            account: self.accounts.vault_b,
+            destination: self.accounts.maker,
+            authority: self.accounts.escrow,
+        }
+        .invoke_signed(&signers)?;
+
+        // Close the Bid and Whitelist
+        ProgramAccount::close(self.accounts.bid, self.accounts.maker)?;
+        if escrow.has_whitelist() {
+            close_whitelist(self.accounts.whitelist, self.accounts.escrow, self.accounts.maker)?;
+        }
+
+        // Close the Escrow
+        drop(data);
+        ProgramAccount::close(self.accounts.escrow, self.accounts.maker)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,119 @@
+//! 捆绑挂单（Bundle）辅助函数
+//!
+//! Make 时通过 `OPTION_BUNDLE` 可以在 Token A 之外再提供最多 `Escrow::MAX_LEGS` 种代币，
+//! 例如 "2 SOL(WSOL) + 100 USDC 换一个 NFT"。每种额外代币存放在 Escrow 拥有的该 Mint 的 ATA 中，
+//! Mint 与数量记录在 Escrow 的 `legs` 中。
+//!
+//! 每条额外代币在账户列表中占一组 `LEG_ACCOUNTS` 个账户：
+//! - Make：mint、maker 的 ATA（来源）、vault
+//! - Take / Refund / CloseExpired：mint、vault、接收方的 ATA（Take 为 taker，其余为 maker）
+//!
+//! 额外代币的 Mint 不能重复，也不能与 Token A 相同（对应的 vault 已存在，创建会失败）。
+//! 额外代币可以是 Token-2022 的 Mint，但必须与 Token A 属于同一个 Token Program。不支持启用了 Transfer Hook 的额外代币。
+//!
+use crate::errors::EscrowError;
+use crate::helper::{
+    check_token_program, token_account, AssociatedTokenAccount, MintInterface, TokenCloseAccount,
+    TokenTransfer,
+};
+use crate::state::Escrow;
+use crate::{AccountCheck, AssociatedTokenAccountCheck, AssociatedTokenAccountInit};
+use pinocchio::{cpi::Signer, error::ProgramError, AccountView, ProgramResult};
+
+/// 每条额外代币占用的账户数
+pub const LEG_ACCOUNTS: usize = 3;
+
+/// Make：为每条额外代币创建 vault，并把 `amounts` 中对应的数量从 maker 转入
+pub fn deposit_legs(
+    legs: &[AccountView],
+    amounts: &[u64],
+    maker: &AccountView,
+    escrow: &AccountView,
+    system_program: &AccountView,
+    token_program: &AccountView,
+) -> ProgramResult {
+    if legs.len() != amounts.len() * LEG_ACCOUNTS {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    for (group, amount) in legs.chunks_exact(LEG_ACCOUNTS).zip(amounts) {
+        let [mint, maker_ata, vault] = group else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        MintInterface::check(mint)?;
+        check_token_program(mint, token_program)?;
+        AssociatedTokenAccount::check(maker_ata, maker, mint, token_program)?;
+        AssociatedTokenAccount::init(vault, mint, maker, escrow, system_program, token_program)?;
+
+        TokenTransfer {
+            from: maker_ata,
+            mint,
+            to: vault,
+            authority: maker,
+            amount: *amount,
+            hook_accounts: &[],
+        }
+        .invoke()?;
+    }
+
+    Ok(())
+}
+
+/// 把每条额外代币的 vault 全部转给 `recipient` 的 ATA（不存在时由 `payer` 创建），
+/// 然后关闭 vault，租金返还给 `rent_destination`
+#[allow(clippy::too_many_arguments)]
+pub fn release_legs(
+    escrow: &Escrow,
+    legs: &[AccountView],
+    escrow_account: &AccountView,
+    recipient: &AccountView,
+    payer: &AccountView,
+    rent_destination: &AccountView,
+    system_program: &AccountView,
+    token_program: &AccountView,
+    signers: &[Signer],
+) -> ProgramResult {
+    if legs.len() < escrow.legs().len() * LEG_ACCOUNTS {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+
+    for (leg, group) in escrow.legs().iter().zip(legs.chunks_exact(LEG_ACCOUNTS)) {
+        let [mint, vault, destination] = group else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if mint.address() != &leg.mint {
+            return Err(EscrowError::InvalidMint.into());
+        }
+        AssociatedTokenAccount::check(vault, escrow_account, mint, token_program)?;
+        AssociatedTokenAccount::init_if_needed(
+            destination,
+            mint,
+            payer,
+            recipient,
+            system_program,
+            token_program,
+        )?;
+
+        let amount = token_account(vault)?.amount();
+        TokenTransfer {
+            from: vault,
+            mint,
+            to: destination,
+            authority: escrow_account,
+            amount,
+            hook_accounts: &[],
+        }
+        .invoke_signed(signers)?;
+
+        TokenCloseAccount {
+            account: vault,
+            destination: rent_destination,
+            authority: escrow_account,
+        }
+        .invoke_signed(signers)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,176 @@
+//! Claim 指令实现（线性释放的 Escrow 领取已解锁的 Token A）
+//!
+//! 线性释放的 Escrow 在 Take 之后，Token A 仍然留在 vault 中，
+//! 从 Take 时刻起经过 `vesting_duration` 秒线性解锁给 taker：
+//! - 已解锁数量 = 释放总量 × 已经过的时间 / 释放时长（释放总量 = vault 余额 + 已领取数量）
+//! - 每次 Claim 把"已解锁 − 已领取"的部分从 vault 转给 taker
+//! - 全部领取完毕后关闭 vault 与 Escrow PDA，租金返还给 maker
+//!
+use crate::errors::EscrowError;
+use crate::helper::{
+    AssociatedTokenAccount, MintInterface, ProgramAccount, SignerAccount, SystemProgramCheck, TokenTransfer,
+};
+use crate::state::Escrow;
+use crate::{AccountCheck, AccountClose, AssociatedTokenAccountCheck, AssociatedTokenAccountInit};
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    AccountView, Address, ProgramResult,
+};
+use pinocchio_token::{
+    instructions::CloseAccount,
+    state::TokenAccount,
+};
+
+// ========== 账户结构 ==========
+pub struct ClaimAccounts<'a> {
+    pub taker: &'a AccountView,       // 成交的 taker（必须是签名者）
+    pub maker: &'a AccountView,       // 托管创建者（用于验证 Escrow PDA，接收最终的租金）
+    pub escrow: &'a AccountView,      // Escrow PDA 账户
+    pub mint_a: &'a AccountView,      // Token A 的 Mint
+    pub vault: &'a AccountView,       // Vault（存储 Token A 的 ATA）
+    pub taker_ata_a: &'a AccountView, // taker 的 Token A ATA
+    pub system_program: &'a AccountView,
+    pub token_program: &'a AccountView,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for ClaimAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [taker, maker, escrow, mint_a, vault, taker_ata_a, system_program, token_program, _] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        SystemProgramCheck::check(system_program)?;
+
+        // 账户基础校验
+        SignerAccount::check(taker)?;
+        ProgramAccount::check(escrow)?;
+        MintInterface::check(mint_a)?;
+        AssociatedTokenAccount::check(vault, escrow, mint_a, token_program)?;
+
+        Ok(Self {
+            taker,
+            maker,
+            escrow,
+            mint_a,
+            vault,
+            taker_ata_a,
+            system_program,
+            token_program,
+        })
+    }
+}
+
+// ========== 指令数据结构（Claim 无需额外数据）==========
+pub struct Claim<'a> {
+    pub accounts: ClaimAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for Claim<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = ClaimAccounts::try_from(accounts)?;
+
+        AssociatedTokenAccount::init_if_needed(
+            accounts.taker_ata_a,
+            accounts.mint_a,
+            accounts.taker,
+            accounts.taker,
+            accounts.system_program,
+            accounts.token_program,
+        )?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> Claim<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &21;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let (seed, bump, claimable, fully_claimed) = {
+            let mut data = self.accounts.escrow.try_borrow_mut()?;
+            let escrow = Escrow::load_mut(data.as_mut())?;
+
+            // 验证 Escrow PDA 是否有效
+            let escrow_key = Address::create_program_address(
+                &[
+                    b"escrow",
+                    self.accounts.maker.address().as_ref(),
+                    &escrow.seed.to_le_bytes(),
+                    &[escrow.bump],
+                ],
+                &crate::ID,
+            )?;
+            if &escrow_key != self.accounts.escrow.address() {
+                return Err(EscrowError::InvalidEscrowPda.into());
+            }
+
+            // 只有已成交的线性释放 Escrow 可以领取，且只能由成交的 taker 领取
+            if !escrow.is_taken() {
+                return Err(EscrowError::InvalidAccountData.into());
+            }
+            if self.accounts.taker.address() != &escrow.taker {
+                return Err(EscrowError::UnauthorizedTaker.into());
+            }
+            if self.accounts.mint_a.address() != &escrow.mint_a {
+                return Err(EscrowError::InvalidMint.into());
+            }
+
+            let vault_amount = TokenAccount::from_account_view(self.accounts.vault)?.amount();
+            let claimable = escrow.claimable_at(vault_amount, Clock::get()?.unix_timestamp)?;
+
+            escrow.set_claimed(
+                escrow
+                    .claimed
+                    .checked_add(claimable)
+                    .ok_or(EscrowError::MathOverflow)?,
+            );
+            escrow.add_filled(claimable)?;
+            escrow.touch(Clock::get()?.slot);
+            (escrow.seed, [escrow.bump], claimable, claimable == vault_amount)
+        };
+
+        let seed_binding = seed.to_le_bytes();
+        let escrow_seeds = [
+            Seed::from(b"escrow"),
+            Seed::from(self.accounts.maker.address().as_ref()),
+            Seed::from(&seed_binding),
+            Seed::from(&bump),
+        ];
+        let signers = [Signer::from(&escrow_seeds)];
+
+        // Transfer the vested Token A to the Taker
+        if claimable != 0 {
+            TokenTransfer {
+                from: self.accounts.vault,
+                mint: self.accounts.mint_a,
+                to: self.accounts.taker_ata_a,
+                authority: self.accounts.escrow,
+                amount: claimable,
+                hook_accounts: &[],
+            }
+            .invoke_signed(&signers)?;
+        }
+
+        // 全部领取完毕：关闭 vault 与 Escrow，租金返还给 maker
+        if fully_claimed {
+            CloseAccount {
+                account: self.accounts.vault,
+                destination: self.accounts.maker,
+                authority: self.accounts.escrow,
+            }
+            .invoke_signed(&signers)?;
+
+            ProgramAccount::close(self.accounts.escrow, self.accounts.maker)?;
+        }
+
+        Ok(())
+    }
+}
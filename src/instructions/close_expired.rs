@@ -0,0 +1,236 @@
+//! CloseExpired 指令实现（关闭已过期的 Escrow）
+//!
+//! 本文件实现无需许可的 `CloseExpired` 指令（crank）：
+//! - 任何人都可以在 Escrow 过期后调用
+//! - 将 vault 中的 Token A 返还给 maker
+//! - 关闭 vault 并关闭 Escrow PDA，租金全部返还给 maker
+//!
+//! 调用者只需要签名并为 maker 的 Token A ATA 支付创建费用（如果它不存在）。
+//!
+use crate::bundle::{release_legs, LEG_ACCOUNTS};
+use crate::errors::EscrowError;
+use crate::helper::{
+    AssociatedTokenAccount, MintInterface, ProgramAccount, SignerAccount, SystemProgramCheck, TokenTransfer,
+};
+use crate::state::Escrow;
+use crate::whitelist::close_whitelist;
+use crate::{AccountCheck, AccountClose, AssociatedTokenAccountCheck, AssociatedTokenAccountInit};
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    AccountView, Address, ProgramResult,
+};
+use pinocchio_token::instructions::CloseAccount;
+use pinocchio_token::state::TokenAccount;
+
+// ========== 账户结构 ==========
+pub struct CloseExpiredAccounts<'a> {
+    pub payer: &'a AccountView,       // 调用者（签名者，支付 ATA 创建费用）
+    pub maker: &'a AccountView,       // 托管创建者（接收代币与租金，无需签名）
+    pub escrow: &'a AccountView,      // Escrow PDA 账户
+    pub mint_a: &'a AccountView,      // Token A 的 Mint
+    pub vault: &'a AccountView,       // Vault（存储 Token A 的 ATA）
+    pub maker_ata_a: &'a AccountView, // Maker 的 Token A ATA（接收返还的代币）
+    pub system_program: &'a AccountView,
+    pub token_program: &'a AccountView,
+    pub whitelist: Option<&'a AccountView>, // 可选：白名单 PDA（Escrow 启用白名单时必须传入）
+    pub legs: &'a [AccountView],            // 捆绑挂单：每种额外代币一组账户，排在白名单之后
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for CloseExpiredAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [payer, maker, escrow, mint_a, vault, maker_ata_a, system_program, token_program, _, rest @ ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        SystemProgramCheck::check(system_program)?;
+
+        // 账户基础校验
+        SignerAccount::check(payer)?;
+        ProgramAccount::check(escrow)?;
+        MintInterface::check(mint_a)?;
+
+        // 原生 SOL 托管没有 vault，lamports 直接存放在 Escrow PDA 中
+        // 委托模式没有 vault，Token A 一直留在 maker 的 ATA 中
+        {
+            let data = escrow.try_borrow()?;
+            let escrow_state = Escrow::load(&data)?;
+            if !escrow_state.is_native_a() && !escrow_state.is_delegated() {
+                AssociatedTokenAccount::check(vault, escrow, mint_a, token_program)?;
+            }
+        }
+
+        // 可选账户依次为：白名单（仅 Escrow 启用白名单时）、捆绑挂单的额外代币
+        let (has_whitelist, leg_count) = {
+            let data = escrow.try_borrow()?;
+            let escrow = Escrow::load(&data)?;
+            (escrow.has_whitelist(), escrow.legs().len())
+        };
+        let (whitelist, rest) = match rest.split_first() {
+            Some((whitelist, rest)) if has_whitelist => (Some(whitelist), rest),
+            _ => (None, rest),
+        };
+        let legs = rest
+            .get(..leg_count * LEG_ACCOUNTS)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+        // 返回账户
+        Ok(Self {
+            payer,
+            maker,
+            escrow,
+            mint_a,
+            vault,
+            maker_ata_a,
+            system_program,
+            token_program,
+            whitelist,
+            legs,
+        })
+    }
+}
+
+// ========== 指令数据结构（CloseExpired 无需额外数据）==========
+pub struct CloseExpired<'a> {
+    pub accounts: CloseExpiredAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for CloseExpired<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = CloseExpiredAccounts::try_from(accounts)?;
+
+        // 创建 maker 的 Token A ATA（如果不存在），由调用者支付
+        // 原生 SOL 托管直接把 lamports 还给 maker，委托模式的 Token A 本就在 maker 手中，都不需要 Token A ATA
+        let (native_a, delegated) = {
+            let data = accounts.escrow.try_borrow()?;
+            let escrow = Escrow::load(&data)?;
+            (escrow.is_native_a(), escrow.is_delegated())
+        };
+        if !native_a && !delegated {
+            AssociatedTokenAccount::init_if_needed(
+                accounts.maker_ata_a,    // 要创建/验证的账户
+                accounts.mint_a,         // mint 账户
+                accounts.payer,          // payer：调用者
+                accounts.maker,          // owner：maker
+                accounts.system_program, // System Program
+                accounts.token_program,  // Token Program
+            )?;
+        }
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> CloseExpired<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &4;
+
+    pub fn process(&mut self) -> ProgramResult {
+        // 1. 加载 Escrow 账户数据并验证 PDA
+        let data = self.accounts.escrow.try_borrow()?;
+        let escrow = Escrow::load(&data)?;
+
+        // maker 必须与 Escrow 中记录的创建者一致（maker 无需签名，但不能被替换）
+        if self.accounts.maker.address() != &escrow.maker {
+            return Err(EscrowError::MakerMismatch.into());
+        }
+
+        let escrow_key = Address::create_program_address(
+            &[
+                b"escrow",
+                self.accounts.maker.address().as_ref(),
+                &escrow.seed.to_le_bytes(),
+                &[escrow.bump],
+            ],
+            &crate::ID,
+        )?;
+        if &escrow_key != self.accounts.escrow.address() {
+            return Err(EscrowError::InvalidEscrowPda.into());
+        }
+
+        // 2. 只有过期后才允许任何人关闭
+        if !escrow.is_expired(Clock::get()?.unix_timestamp) {
+            return Err(EscrowError::EscrowNotExpired.into());
+        }
+
+        // 拍卖模式的 Escrow 只能通过出价成交
+        if escrow.is_auction() {
+            return Err(EscrowError::AuctionMode.into());
+        }
+
+        // 只有 Open 状态可以继续：线性释放成交后只能由 taker 领取，争议中只能由仲裁人裁决
+        escrow.check_open()?;
+
+        // 对手方已存入 Token B 的双向托管只能通过 Settle 结算
+        if escrow.is_deposited() {
+            return Err(EscrowError::DepositPending.into());
+        }
+
+        // 3. 构建 Escrow PDA 的签名种子
+        let seed_binding = escrow.seed.to_le_bytes();
+        let bump_binding = [escrow.bump];
+        let escrow_seeds = [
+            Seed::from(b"escrow"),
+            Seed::from(self.accounts.maker.address().as_ref()),
+            Seed::from(&seed_binding),
+            Seed::from(&bump_binding),
+        ];
+        let signers = [Signer::from(&escrow_seeds)];
+
+        // 原生 SOL 托管：存入的 lamports 会在第 6 步随租金一起返还给 maker
+        // 委托模式：Token A 仍在 maker 的 ATA 中；授权只能由 maker 撤销，关闭 Escrow 后授权无法再被使用
+        if !escrow.is_native_a() && !escrow.is_delegated() {
+            // 4. 将 Token A 从 Vault 全部返还给 Maker
+            let vault_amount = TokenAccount::from_account_view(self.accounts.vault)?.amount();
+            TokenTransfer {
+                from: self.accounts.vault,
+                mint: self.accounts.mint_a,
+                to: self.accounts.maker_ata_a,
+                authority: self.accounts.escrow,
+                amount: vault_amount,
+                hook_accounts: &[],
+            }
+            .invoke_signed(&signers)?;
+
+            // 5. 关闭 Vault ATA 账户（将租金返还给 maker）
+            CloseAccount {
+                account: self.accounts.vault,
+                destination: self.accounts.maker,
+                authority: self.accounts.escrow,
+            }
+            .invoke_signed(&signers)?;
+        }
+
+        // 捆绑挂单：额外代币全部返还给 maker
+        if escrow.is_bundle() {
+            release_legs(
+                escrow,
+                self.accounts.legs,
+                self.accounts.escrow,
+                self.accounts.maker,
+                self.accounts.payer,
+                self.accounts.maker,
+                self.accounts.system_program,
+                self.accounts.token_program,
+                &signers,
+            )?;
+        }
+
+        // 关闭白名单 PDA（将租金返还给 maker）
+        if escrow.has_whitelist() {
+            close_whitelist(self.accounts.whitelist, self.accounts.escrow, self.accounts.maker)?;
+        }
+
+        // 6. 关闭 Escrow PDA 账户（将租金返还给 maker）
+        drop(data);
+        ProgramAccount::close(self.accounts.escrow, self.accounts.maker)?;
+
+        Ok(())
+    }
+}
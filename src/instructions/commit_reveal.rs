@@ -0,0 +1,275 @@
+//! CommitTake / RevealTake / CancelCommit 指令实现（两步成交，防止抢跑）
+//!
+//! 直接 Take 一个报价明显偏离市场的 Escrow 时，交易在打包前就会被机器人看到并抢先成交。
+//! commit–reveal 把成交拆成两步：
+//! 1. `CommitTake`：taker 提交 sha256(escrow || taker || salt)，存入成交承诺 PDA
+//!    （种子 `["commit", taker, hash]`），此时链上看不出目标是哪个 Escrow
+//! 2. `RevealTake`：至少 `Commitment::MIN_REVEAL_SLOTS` 个 slot 之后，taker 揭示 salt，
+//!    程序验证哈希后按 Take 的规则完成成交，并关闭成交承诺（租金返还给 taker）
+//!
+//! 成交承诺不再需要时（例如 Escrow 已被其他人成交），taker 可以通过 `CancelCommit` 关闭并取回租金。
+//!
+use crate::errors::EscrowError;
+use crate::helper::{CommitmentAccount, ProgramAccount, SignerAccount, SystemProgramCheck};
+use crate::state::Commitment;
+use crate::{AccountCheck, AccountClose, ProgramAccountInit, Take};
+use pinocchio::{
+    cpi::Seed,
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    AccountView, Address, ProgramResult,
+};
+use solana_sha256_hasher::hashv;
+
+/// 验证成交承诺属于 `taker`，返回承诺的哈希与提交时的 slot
+fn verify_commitment(
+    commitment: &AccountView,
+    taker: &AccountView,
+) -> Result<([u8; 32], u64), ProgramError> {
+    CommitmentAccount::check(commitment)?;
+
+    let data = commitment.try_borrow()?;
+    let state = Commitment::load(&data)?;
+    if &state.taker != taker.address() {
+        return Err(EscrowError::InvalidCommitmentPda.into());
+    }
+
+    let commitment_key = Address::create_program_address(
+        &[b"commit", taker.address().as_ref(), &state.hash, &state.bump],
+        &crate::ID,
+    )?;
+    if &commitment_key != commitment.address() {
+        return Err(EscrowError::InvalidCommitmentPda.into());
+    }
+
+    Ok((state.hash, state.slot))
+}
+
+// ========== CommitTake ==========
+pub struct CommitTakeAccounts<'a> {
+    pub taker: &'a AccountView,      // 提交承诺的 taker（签名者，支付租金）
+    pub commitment: &'a AccountView, // 成交承诺 PDA（`["commit", taker, hash]`）
+    pub system_program: &'a AccountView,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for CommitTakeAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [taker, commitment, system_program] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        SystemProgramCheck::check(system_program)?;
+
+        SignerAccount::check(taker)?;
+
+        Ok(Self {
+            taker,
+            commitment,
+            system_program,
+        })
+    }
+}
+
+pub struct CommitTakeInstructionData {
+    pub hash: [u8; 32], // sha256(escrow || taker || salt)
+}
+
+impl<'a> TryFrom<&'a [u8]> for CommitTakeInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        let hash: [u8; 32] = data
+            .try_into()
+            .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+        Ok(Self { hash })
+    }
+}
+
+pub struct CommitTake<'a> {
+    pub accounts: CommitTakeAccounts<'a>,
+    pub instruction_data: CommitTakeInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for CommitTake<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = CommitTakeAccounts::try_from(accounts)?;
+        let instruction_data = CommitTakeInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> CommitTake<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &32;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let hash = self.instruction_data.hash;
+        let (commitment_key, bump) = Address::find_program_address(
+            &[b"commit", self.accounts.taker.address().as_ref(), &hash],
+            &crate::ID,
+        );
+        if &commitment_key != self.accounts.commitment.address() {
+            return Err(EscrowError::InvalidCommitmentPda.into());
+        }
+
+        let bump_binding = [bump];
+        let commitment_seeds = [
+            Seed::from(b"commit"),
+            Seed::from(self.accounts.taker.address().as_ref()),
+            Seed::from(&hash),
+            Seed::from(&bump_binding),
+        ];
+        ProgramAccount::init::<Commitment>(
+            self.accounts.taker,
+            self.accounts.commitment,
+            commitment_seeds.as_slice(),
+            Commitment::LEN,
+        )?;
+
+        let mut data = self.accounts.commitment.try_borrow_mut()?;
+        Commitment::init(data.as_mut())?.set_inner(
+            Clock::get()?.slot,
+            self.accounts.taker.address().clone(),
+            hash,
+            bump_binding,
+        );
+
+        Ok(())
+    }
+}
+
+// ========== RevealTake ==========
+pub struct RevealTakeInstructionData {
+    pub salt: [u8; 32], // CommitTake 时使用的随机数
+}
+
+impl<'a> TryFrom<&'a [u8]> for RevealTakeInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        let salt: [u8; 32] = data
+            .try_into()
+            .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+        Ok(Self { salt })
+    }
+}
+
+impl RevealTakeInstructionData {
+    /// 指令数据为 salt，之后是 Take 的指令数据（可选的滑点保护）
+    pub fn split(data: &[u8]) -> Result<(Self, &[u8]), ProgramError> {
+        let (salt, take_data) = data
+            .split_at_checked(32)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+
+        Ok((Self::try_from(salt)?, take_data))
+    }
+}
+
+/// 账户列表：成交承诺 PDA，之后与 Take 完全相同
+pub struct RevealTake<'a> {
+    pub commitment: &'a AccountView, // 成交承诺 PDA
+    pub take: Take<'a>,              // 揭示成功后执行的 Take
+    pub instruction_data: RevealTakeInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for RevealTake<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let [commitment, rest @ ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        let (instruction_data, take_data) = RevealTakeInstructionData::split(data)?;
+        let take = Take::try_from((take_data, rest))?;
+
+        Ok(Self {
+            commitment,
+            take,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> RevealTake<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &33;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let taker = self.take.accounts.taker;
+        let (hash, slot) = verify_commitment(self.commitment, taker)?;
+
+        // 揭示的内容必须与承诺一致
+        let revealed = hashv(&[
+            self.take.accounts.escrow.address().as_ref(),
+            taker.address().as_ref(),
+            &self.instruction_data.salt,
+        ])
+        .to_bytes();
+        if revealed != hash {
+            return Err(EscrowError::InvalidCommitment.into());
+        }
+
+        // 承诺与揭示不能在同一个 slot 中完成
+        if Clock::get()?.slot < slot.saturating_add(Commitment::MIN_REVEAL_SLOTS) {
+            return Err(EscrowError::RevealTooEarly.into());
+        }
+
+        self.take.process()?;
+
+        // Close the Commitment
+        ProgramAccount::close(self.commitment, taker)
+    }
+}
+
+// ========== CancelCommit ==========
+pub struct CancelCommitAccounts<'a> {
+    pub taker: &'a AccountView,      // 提交承诺的 taker（签名者，接收租金）
+    pub commitment: &'a AccountView, // 成交承诺 PDA
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for CancelCommitAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [taker, commitment] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        SignerAccount::check(taker)?;
+        verify_commitment(commitment, taker)?;
+
+        Ok(Self { taker, commitment })
+    }
+}
+
+// ========== 指令数据结构（CancelCommit 无需额外数据）==========
+pub struct CancelCommit<'a> {
+    pub accounts: CancelCommitAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for CancelCommit<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = CancelCommitAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> CancelCommit<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &34;
+
+    pub fn process(&mut self) -> ProgramResult {
+        ProgramAccount::close(self.accounts.commitment, self.accounts.taker)
+    }
+}
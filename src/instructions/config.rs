@@ -0,0 +1,485 @@
+//! InitializeConfig / SetFee / SetLimits / CollectFees / Pause / Unpause 指令实现（协议手续费、托管数量范围与暂停开关）
+//!
+//! 本文件实现全局配置 PDA（种子 `["config"]`）相关的管理指令：
+//! - `InitializeConfig`：创建全局配置，记录手续费费率、管理员以及可选的 Make 托管数量范围（只能执行一次）
+//! - `SetFee`：管理员修改手续费与推荐费费率，可选地移交管理员
+//! - `SetLimits`：管理员修改 Make 允许托管的 Token A 数量范围（防止粉尘挂单，或超出承保上限的大额挂单）
+//! - `CollectFees`：管理员提取某个 Token B 的累计手续费
+//! - `Pause` / `Unpause`：管理员打开 / 关闭暂停开关（紧急制动，怀疑存在漏洞时使用）
+//!
+//! Take / TakePartial 按 Token B 成交额收取手续费：
+//! - SPL Token：手续费转入 fee vault（全局配置 PDA 拥有的 Token B ATA）
+//! - 原生 SOL：手续费 lamports 直接转入全局配置 PDA
+//!
+//! Take 时额外传入推荐人账户的，按推荐费费率从手续费中分出一部分 Token B 转给推荐人（前端集成方），
+//! maker 的所得不受影响；推荐费费率不能高于手续费费率，推荐人不能是 taker 本人。
+//! 按 Mint 记账与按数量提取手续费见 fee_vault.rs（`FeeVault` 账本与 `WithdrawFees`）。
+//!
+//! Make 与 Take 都读取全局配置：暂停时两者都被拒绝，Make 托管的 Token A 数量必须在配置的范围内。
+//! Refund 不检查暂停开关，暂停期间 maker 仍然可以取回托管的代币（可选传入全局配置，只用于分配事件序号）。
+//! 全局配置还记录事件序号计数器 `event_seq`，Make / Take / Refund 写入事件时加 1（见 events.rs）。
+//!
+use crate::errors::EscrowError;
+use crate::helper::{
+    transfer_lamports, AssociatedTokenAccount, ConfigAccount, MintInterface, ProgramAccount,
+    SignerAccount, SystemProgramCheck, TokenTransfer, NATIVE_MINT,
+};
+use crate::state::Config;
+use crate::{AccountCheck, AssociatedTokenAccountCheck, ProgramAccountInit};
+use core::mem::size_of;
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    sysvars::{rent::Rent, Sysvar},
+    AccountView, Address, ProgramResult,
+};
+use pinocchio_token::state::TokenAccount;
+
+/// 验证调用者是全局配置记录的手续费管理员
+pub fn check_fee_authority(config: &AccountView, authority: &AccountView) -> ProgramResult {
+    ConfigAccount::check(config)?;
+
+    if &Config::load(&config.try_borrow()?)?.fee_authority != authority.address() {
+        return Err(EscrowError::NotFeeAuthority.into());
+    }
+
+    Ok(())
+}
+
+/// 拆出账户列表最后的全局配置 PDA（不是全局配置时原样返回）
+pub fn split_config(accounts: &[AccountView]) -> (Option<&AccountView>, &[AccountView]) {
+    match accounts.split_last() {
+        Some((config, rest)) if ConfigAccount::check(config).is_ok() => (Some(config), rest),
+        _ => (None, accounts),
+    }
+}
+
+// ========== InitializeConfig ==========
+pub struct InitializeConfigAccounts<'a> {
+    pub authority: &'a AccountView, // 初始的手续费管理员（签名者，支付租金）
+    pub config: &'a AccountView,    // 全局配置 PDA
+    pub system_program: &'a AccountView,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for InitializeConfigAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [authority, config, system_program] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        SystemProgramCheck::check(system_program)?;
+
+        SignerAccount::check(authority)?;
+
+        Ok(Self {
+            authority,
+            config,
+            system_program,
+        })
+    }
+}
+
+pub struct FeeInstructionData {
+    pub fee_bps: u16,                   // 手续费费率（万分比）
+    pub referral_bps: u16,              // 推荐费费率（万分比）
+    pub fee_authority: Option<Address>, // 可选：新的手续费管理员（仅 SetFee 使用）
+}
+
+impl<'a> TryFrom<&'a [u8]> for FeeInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        // - 4 字节：fee_bps + referral_bps
+        // - 36 字节：再加上新的手续费管理员
+        let fee_authority = match data.len() {
+            len if len == size_of::<u16>() * 2 => None,
+            len if len == size_of::<u16>() * 2 + size_of::<Address>() => {
+                Some(Address::new_from_array(data[4..36].try_into().unwrap()))
+            }
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+
+        let fee_bps = u16::from_le_bytes(data[0..2].try_into().unwrap());
+        let referral_bps = u16::from_le_bytes(data[2..4].try_into().unwrap());
+
+        // 手续费不能超过成交额；推荐费从手续费中分出，不能高于手续费
+        if fee_bps > Config::MAX_FEE_BPS || referral_bps > fee_bps {
+            return Err(EscrowError::FeeTooHigh.into());
+        }
+
+        Ok(Self {
+            fee_bps,
+            referral_bps,
+            fee_authority,
+        })
+    }
+}
+
+/// Make 托管数量范围（原始数量，0 表示不限制）
+pub struct LimitsInstructionData {
+    pub min_amount: u64, // 最小托管数量
+    pub max_amount: u64, // 最大托管数量
+}
+
+impl<'a> TryFrom<&'a [u8]> for LimitsInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len() != Self::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let min_amount = u64::from_le_bytes(data[0..8].try_into().unwrap());
+        let max_amount = u64::from_le_bytes(data[8..16].try_into().unwrap());
+        if max_amount != 0 && min_amount > max_amount {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self {
+            min_amount,
+            max_amount,
+        })
+    }
+}
+
+impl LimitsInstructionData {
+    /// 最小值 + 最大值的长度
+    pub const LEN: usize = size_of::<u64>() * 2;
+}
+
+pub struct InitializeConfig<'a> {
+    pub accounts: InitializeConfigAccounts<'a>,
+    pub instruction_data: FeeInstructionData,
+    pub limits: LimitsInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for InitializeConfig<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = InitializeConfigAccounts::try_from(accounts)?;
+
+        // 指令数据：fee_bps + referral_bps（4 字节），可选地再加上托管数量范围（16 字节）
+        let fee_len = size_of::<u16>() * 2;
+        let (fee_data, limits) = match data.len() {
+            len if len == fee_len => (data, LimitsInstructionData { min_amount: 0, max_amount: 0 }),
+            len if len == fee_len + LimitsInstructionData::LEN => {
+                let (fee_data, limits_data) = data.split_at(fee_len);
+                (fee_data, LimitsInstructionData::try_from(limits_data)?)
+            }
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+        // InitializeConfig 只接受费率，管理员就是签名者
+        let instruction_data = FeeInstructionData::try_from(fee_data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+            limits,
+        })
+    }
+}
+
+impl<'a> InitializeConfig<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &18;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let (config_key, bump) = Address::find_program_address(&[b"config"], &crate::ID);
+        if &config_key != self.accounts.config.address() {
+            return Err(EscrowError::InvalidConfigPda.into());
+        }
+
+        // 账户已存在时 CreateAccount 会失败，因此全局配置只能初始化一次
+        let bump_binding = [bump];
+        let config_seeds = [Seed::from(b"config"), Seed::from(&bump_binding)];
+        ProgramAccount::init::<Config>(
+            self.accounts.authority,
+            self.accounts.config,
+            config_seeds.as_slice(),
+            Config::LEN,
+        )?;
+
+        let mut data = self.accounts.config.try_borrow_mut()?;
+        let config = Config::init(data.as_mut())?;
+        config.set_inner(
+            self.accounts.authority.address().clone(),
+            self.instruction_data.fee_bps,
+            self.instruction_data.referral_bps,
+            bump_binding,
+        );
+        config.set_limits(self.limits.min_amount, self.limits.max_amount);
+
+        Ok(())
+    }
+}
+
+// ========== SetFee ==========
+pub struct SetFeeAccounts<'a> {
+    pub authority: &'a AccountView, // 当前手续费管理员（签名者）
+    pub config: &'a AccountView,    // 全局配置 PDA
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for SetFeeAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [authority, config] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        SignerAccount::check(authority)?;
+        check_fee_authority(config, authority)?;
+
+        Ok(Self { authority, config })
+    }
+}
+
+pub struct SetFee<'a> {
+    pub accounts: SetFeeAccounts<'a>,
+    pub instruction_data: FeeInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for SetFee<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = SetFeeAccounts::try_from(accounts)?;
+        let instruction_data = FeeInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> SetFee<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &19;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let mut data = self.accounts.config.try_borrow_mut()?;
+        let config = Config::load_mut(data.as_mut())?;
+
+        config.set_fee_bps(self.instruction_data.fee_bps);
+        config.set_referral_bps(self.instruction_data.referral_bps);
+        if let Some(fee_authority) = self.instruction_data.fee_authority.take() {
+            config.set_fee_authority(fee_authority);
+        }
+
+        Ok(())
+    }
+}
+
+// ========== SetLimits ==========
+/// 账户列表与 SetFee 相同
+pub struct SetLimits<'a> {
+    pub accounts: SetFeeAccounts<'a>,
+    pub instruction_data: LimitsInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for SetLimits<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = SetFeeAccounts::try_from(accounts)?;
+        let instruction_data = LimitsInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> SetLimits<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &50;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let mut data = self.accounts.config.try_borrow_mut()?;
+        Config::load_mut(data.as_mut())?
+            .set_limits(self.instruction_data.min_amount, self.instruction_data.max_amount);
+
+        Ok(())
+    }
+}
+
+// ========== CollectFees ==========
+pub struct CollectFeesAccounts<'a> {
+    pub authority: &'a AccountView,   // 手续费管理员（签名者）
+    pub config: &'a AccountView,      // 全局配置 PDA
+    pub mint: &'a AccountView,        // 要提取的 Token B 的 Mint（NATIVE_MINT 表示提取 lamports）
+    pub fee_vault: &'a AccountView,   // fee vault（全局配置 PDA 拥有的 ATA；提取 lamports 时不使用）
+    pub destination: &'a AccountView, // 接收手续费的 Token Account（提取 lamports 时为任意系统账户）
+    pub token_program: &'a AccountView,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for CollectFeesAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [authority, config, mint, fee_vault, destination, token_program] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        SignerAccount::check(authority)?;
+        check_fee_authority(config, authority)?;
+        if mint.address() != &NATIVE_MINT {
+            MintInterface::check(mint)?;
+            AssociatedTokenAccount::check(fee_vault, config, mint, token_program)?;
+        }
+
+        Ok(Self {
+            authority,
+            config,
+            mint,
+            fee_vault,
+            destination,
+            token_program,
+        })
+    }
+}
+
+pub struct CollectFees<'a> {
+    pub accounts: CollectFeesAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for CollectFees<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = CollectFeesAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> CollectFees<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &20;
+
+    pub fn process(&mut self) -> ProgramResult {
+        // 原生 SOL 手续费：提取全局配置 PDA 中超出租金豁免额的 lamports
+        if self.accounts.mint.address() == &NATIVE_MINT {
+            let rent_exempt = Rent::get()?.try_minimum_balance(Config::LEN)?;
+            let amount = self.accounts.config.lamports().saturating_sub(rent_exempt);
+            return transfer_lamports(self.accounts.config, self.accounts.destination, amount);
+        }
+
+        let bump_binding = Config::load(&self.accounts.config.try_borrow()?)?.bump;
+        let config_seeds = [Seed::from(b"config"), Seed::from(&bump_binding)];
+        let signers = [Signer::from(&config_seeds)];
+
+        let amount = TokenAccount::from_account_view(self.accounts.fee_vault)?.amount();
+        TokenTransfer {
+            from: self.accounts.fee_vault,
+            mint: self.accounts.mint,
+            to: self.accounts.destination,
+            authority: self.accounts.config,
+            amount,
+            hook_accounts: &[],
+        }
+        .invoke_signed(&signers)
+    }
+}
+
+// ========== Pause / Unpause ==========
+pub struct PauseAccounts<'a> {
+    pub authority: &'a AccountView, // 手续费管理员（签名者）
+    pub config: &'a AccountView,    // 全局配置 PDA
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for PauseAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [authority, config] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        SignerAccount::check(authority)?;
+        check_fee_authority(config, authority)?;
+
+        Ok(Self { authority, config })
+    }
+}
+
+impl PauseAccounts<'_> {
+    /// 写入暂停开关
+    fn set_paused(&self, paused: bool) -> ProgramResult {
+        let mut data = self.config.try_borrow_mut()?;
+        Config::load_mut(data.as_mut())?.set_paused(paused);
+
+        Ok(())
+    }
+}
+
+pub struct Pause<'a> {
+    pub accounts: PauseAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for Pause<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = PauseAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> Pause<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &48;
+
+    pub fn process(&mut self) -> ProgramResult {
+        self.accounts.set_paused(true)
+    }
+}
+
+/// 账户列表与 Pause 相同
+pub struct Unpause<'a> {
+    pub accounts: PauseAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for Unpause<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = PauseAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> Unpause<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &49;
+
+    pub fn process(&mut self) -> ProgramResult {
+        self.accounts.set_paused(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fee_data(fee_bps: u16, referral_bps: u16) -> [u8; 4] {
+        let mut data = [0u8; 4];
+        data[0..2].copy_from_slice(&fee_bps.to_le_bytes());
+        data[2..4].copy_from_slice(&referral_bps.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn referral_bps_cannot_exceed_fee_bps() {
+        let data = FeeInstructionData::try_from(&fee_data(100, 100)[..]).unwrap();
+        assert_eq!((data.fee_bps, data.referral_bps), (100, 100));
+        assert!(FeeInstructionData::try_from(&fee_data(0, 0)[..]).is_ok());
+
+        let fee_too_high = Some(EscrowError::FeeTooHigh.into());
+        assert_eq!(FeeInstructionData::try_from(&fee_data(100, 101)[..]).err(), fee_too_high);
+        assert_eq!(FeeInstructionData::try_from(&fee_data(0, 1)[..]).err(), fee_too_high);
+        assert_eq!(FeeInstructionData::try_from(&fee_data(Config::MAX_FEE_BPS + 1, 0)[..]).err(), fee_too_high);
+        assert!(FeeInstructionData::try_from(&fee_data(Config::MAX_FEE_BPS, Config::MAX_FEE_BPS)[..]).is_ok());
+    }
+}
@@ -0,0 +1,594 @@
+//! ProposeCounterOffer / AcceptCounterOffer / WithdrawCounterOffer 指令实现（还价）
+//!
+//! 本文件实现一个简单的议价流程：
+//! - `ProposeCounterOffer`：潜在 taker 提出替代的 Token B 数量，并把这些 Token B 锁入还价 PDA
+//!   （种子 `["offer", escrow, proposer]`）拥有的 ATA 中
+//! - `AcceptCounterOffer`：maker 接受还价，按还价成交（Token A 给还价者，锁定的 Token B 给 maker）
+//! - `WithdrawCounterOffer`：还价者撤回还价，取回 Token B（Escrow 已关闭时同样可以撤回）
+//!
+//! 还价 PDA 与其 Token B ATA 的租金由还价者支付，接受或撤回时返还给还价者。
+//!
+use crate::errors::EscrowError;
+use crate::helper::{
+    transfer_lamports, AssociatedTokenAccount, MintInterface, OfferAccount, ProgramAccount,
+    SignerAccount, SystemProgramCheck, TokenTransfer,
+};
+use crate::state::{Escrow, Offer};
+use crate::whitelist::{check_whitelisted, close_whitelist};
+use crate::{
+    AccountCheck, AccountClose, AssociatedTokenAccountCheck, AssociatedTokenAccountInit,
+    ProgramAccountInit,
+};
+use core::mem::size_of;
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
+    AccountView, Address, ProgramResult,
+};
+use pinocchio_token::{
+    instructions::CloseAccount,
+    state::TokenAccount,
+};
+
+/// 验证还价账户的 PDA 种子，返回记录的 Escrow 地址与还价者
+fn verify_offer(offer: &AccountView) -> Result<(Address, Address), ProgramError> {
+    OfferAccount::check(offer)?;
+
+    let data = offer.try_borrow()?;
+    let state = Offer::load(&data)?;
+    let offer_key = Address::create_program_address(
+        &[
+            b"offer",
+            state.escrow.as_ref(),
+            state.proposer.as_ref(),
+            &state.bump,
+        ],
+        &crate::ID,
+    )?;
+    if &offer_key != offer.address() {
+        return Err(EscrowError::InvalidOfferPda.into());
+    }
+
+    Ok((state.escrow.clone(), state.proposer.clone()))
+}
+
+/// 把还价 ATA 中的 Token B 全部转给 `to`，然后关闭还价 ATA 与还价 PDA，租金返还给还价者
+fn release_offer(
+    offer: &AccountView,
+    mint_b: &AccountView,
+    offer_vault: &AccountView,
+    to: &AccountView,
+    proposer: &AccountView,
+) -> ProgramResult {
+    let (escrow_binding, bump_binding) = {
+        let data = offer.try_borrow()?;
+        let state = Offer::load(&data)?;
+        (state.escrow.clone(), state.bump)
+    };
+    let offer_seeds = [
+        Seed::from(b"offer"),
+        Seed::from(escrow_binding.as_ref()),
+        Seed::from(proposer.address().as_ref()),
+        Seed::from(&bump_binding),
+    ];
+    let signers = [Signer::from(&offer_seeds)];
+
+    let amount = TokenAccount::from_account_view(offer_vault)?.amount();
+    TokenTransfer {
+        from: offer_vault,
+        mint: mint_b,
+        to,
+        authority: offer,
+        amount,
+        hook_accounts: &[],
+    }
+    .invoke_signed(&signers)?;
+
+    CloseAccount {
+        account: offer_vault,
+        destination: proposer,
+        authority: offer,
+    }
+    .invoke_signed(&signers)?;
+
+    ProgramAccount::close(offer, proposer)
+}
+
+// ========== ProposeCounterOffer ==========
+pub struct ProposeCounterOfferAccounts<'a> {
+    pub proposer: &'a AccountView,       // 还价者（签名者，支付还价 PDA 与 ATA 的租金）
+    pub maker: &'a AccountView,          // 托管创建者（用于验证 Escrow PDA）
+    pub escrow: &'a AccountView,         // Escrow PDA 账户
+    pub mint_b: &'a AccountView,         // Token B 的 Mint
+    pub proposer_ata_b: &'a AccountView, // 还价者的 Token B ATA
+    pub offer: &'a AccountView,          // 还价 PDA
+    pub offer_vault: &'a AccountView,    // 还价 PDA 拥有的 Token B ATA
+    pub system_program: &'a AccountView,
+    pub token_program: &'a AccountView,
+    pub whitelist: Option<&'a AccountView>, // 可选：白名单 PDA（Escrow 启用白名单时必须传入）
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for ProposeCounterOfferAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [proposer, maker, escrow, mint_b, proposer_ata_b, offer, offer_vault, system_program, token_program, _, rest @ ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        SystemProgramCheck::check(system_program)?;
+
+        // 账户基础校验
+        SignerAccount::check(proposer)?;
+        ProgramAccount::check(escrow)?;
+        MintInterface::check(mint_b)?;
+        AssociatedTokenAccount::check(proposer_ata_b, proposer, mint_b, token_program)?;
+
+        Ok(Self {
+            proposer,
+            maker,
+            escrow,
+            mint_b,
+            proposer_ata_b,
+            offer,
+            offer_vault,
+            system_program,
+            token_program,
+            whitelist: rest.first(),
+        })
+    }
+}
+
+pub struct ProposeCounterOfferInstructionData {
+    pub amount: u64, // 还价的 Token B 数量
+}
+
+impl<'a> TryFrom<&'a [u8]> for ProposeCounterOfferInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len() != size_of::<u64>() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let amount = u64::from_le_bytes(data[0..8].try_into().unwrap());
+        if amount == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self { amount })
+    }
+}
+
+pub struct ProposeCounterOffer<'a> {
+    pub accounts: ProposeCounterOfferAccounts<'a>,
+    pub instruction_data: ProposeCounterOfferInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for ProposeCounterOffer<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = ProposeCounterOfferAccounts::try_from(accounts)?;
+        let instruction_data = ProposeCounterOfferInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> ProposeCounterOffer<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &15;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let data = self.accounts.escrow.try_borrow()?;
+        let escrow = Escrow::load(&data)?;
+
+        // 验证 Escrow PDA 是否有效
+        let escrow_key = Address::create_program_address(
+            &[
+                b"escrow",
+                self.accounts.maker.address().as_ref(),
+                &escrow.seed.to_le_bytes(),
+                &[escrow.bump],
+            ],
+            &crate::ID,
+        )?;
+        if &escrow_key != self.accounts.escrow.address() {
+            return Err(EscrowError::InvalidEscrowPda.into());
+        }
+
+        // 还价只能针对可以直接成交的普通 Escrow（非拍卖、非线性释放、非三方托管、非捆绑挂单），且 Token B 必须是 SPL Token
+        if escrow.is_auction() {
+            return Err(EscrowError::AuctionMode.into());
+        }
+        escrow.check_open()?;
+        if escrow.is_deposited() {
+            return Err(EscrowError::DepositPending.into());
+        }
+        if escrow.is_vesting() || escrow.is_bundle() || escrow.is_delegated() {
+            return Err(EscrowError::InvalidAccountData.into());
+        }
+        if escrow.has_arbiter() {
+            return Err(EscrowError::ArbiterMode.into());
+        }
+        if escrow.is_native_b() || self.accounts.mint_b.address() != &escrow.mint_b {
+            return Err(EscrowError::InvalidMint.into());
+        }
+
+        // 已过期的 Escrow 不能再还价
+        if escrow.is_expired(Clock::get()?.unix_timestamp) {
+            return Err(EscrowError::EscrowExpired.into());
+        }
+
+        // 与 Take 相同的 taker 权限校验
+        if !escrow.is_taker_allowed(self.accounts.proposer.address()) {
+            return Err(EscrowError::UnauthorizedTaker.into());
+        }
+        if escrow.has_whitelist() {
+            check_whitelisted(
+                self.accounts.whitelist,
+                self.accounts.escrow,
+                self.accounts.proposer.address(),
+            )?;
+        }
+
+        // 创建还价 PDA（每个还价者对每个 Escrow 同时只能有一个还价）
+        let (offer_key, bump) = Address::find_program_address(
+            &[
+                b"offer",
+                self.accounts.escrow.address().as_ref(),
+                self.accounts.proposer.address().as_ref(),
+            ],
+            &crate::ID,
+        );
+        if &offer_key != self.accounts.offer.address() {
+            return Err(EscrowError::InvalidOfferPda.into());
+        }
+
+        let bump_binding = [bump];
+        let offer_seeds = [
+            Seed::from(b"offer"),
+            Seed::from(self.accounts.escrow.address().as_ref()),
+            Seed::from(self.accounts.proposer.address().as_ref()),
+            Seed::from(&bump_binding),
+        ];
+        ProgramAccount::init::<Offer>(
+            self.accounts.proposer,
+            self.accounts.offer,
+            offer_seeds.as_slice(),
+            Offer::LEN,
+        )?;
+
+        AssociatedTokenAccount::init(
+            self.accounts.offer_vault,
+            self.accounts.mint_b,
+            self.accounts.proposer,
+            self.accounts.offer,
+            self.accounts.system_program,
+            self.accounts.token_program,
+        )?;
+
+        {
+            let mut offer_data = self.accounts.offer.try_borrow_mut()?;
+            Offer::init(offer_data.as_mut())?.set_inner(
+                self.instruction_data.amount,
+                self.accounts.escrow.address().clone(),
+                self.accounts.proposer.address().clone(),
+                bump_binding,
+            );
+        }
+
+        // Lock the proposed Token B in the offer vault
+        TokenTransfer {
+            from: self.accounts.proposer_ata_b,
+            mint: self.accounts.mint_b,
+            to: self.accounts.offer_vault,
+            authority: self.accounts.proposer,
+            amount: self.instruction_data.amount,
+            hook_accounts: &[],
+        }
+        .invoke()
+    }
+}
+
+// ========== AcceptCounterOffer ==========
+pub struct AcceptCounterOfferAccounts<'a> {
+    pub maker: &'a AccountView,          // 托管创建者（必须是签名者）
+    pub proposer: &'a AccountView,       // 还价者（接收 Token A 与还价账户租金）
+    pub escrow: &'a AccountView,         // Escrow PDA 账户
+    pub mint_a: &'a AccountView,         // Token A 的 Mint
+    pub mint_b: &'a AccountView,         // Token B 的 Mint
+    pub vault: &'a AccountView,          // Token A vault（原生 SOL 托管时不使用）
+    pub offer: &'a AccountView,          // 还价 PDA
+    pub offer_vault: &'a AccountView,    // 还价 PDA 拥有的 Token B ATA
+    pub proposer_ata_a: &'a AccountView, // 还价者的 Token A ATA（原生 SOL 托管时不使用）
+    pub maker_ata_b: &'a AccountView,    // maker 的 Token B ATA
+    pub system_program: &'a AccountView,
+    pub token_program: &'a AccountView,
+    pub whitelist: Option<&'a AccountView>, // 可选：白名单 PDA（Escrow 启用白名单时必须传入）
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for AcceptCounterOfferAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [maker, proposer, escrow, mint_a, mint_b, vault, offer, offer_vault, proposer_ata_a, maker_ata_b, system_program, token_program, _, rest @ ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        SystemProgramCheck::check(system_program)?;
+
+        // 账户基础校验
+        SignerAccount::check(maker)?;
+        ProgramAccount::check(escrow)?;
+        MintInterface::check(mint_a)?;
+        MintInterface::check(mint_b)?;
+        AssociatedTokenAccount::check(offer_vault, offer, mint_b, token_program)?;
+
+        // 原生 SOL 托管没有 vault
+        if !Escrow::load(&escrow.try_borrow()?)?.is_native_a() {
+            AssociatedTokenAccount::check(vault, escrow, mint_a, token_program)?;
+        }
+
+        Ok(Self {
+            maker,
+            proposer,
+            escrow,
+            mint_a,
+            mint_b,
+            vault,
+            offer,
+            offer_vault,
+            proposer_ata_a,
+            maker_ata_b,
+            system_program,
+            token_program,
+            whitelist: rest.first(),
+        })
+    }
+}
+
+pub struct AcceptCounterOffer<'a> {
+    pub accounts: AcceptCounterOfferAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for AcceptCounterOffer<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = AcceptCounterOfferAccounts::try_from(accounts)?;
+
+        // Initialize necessary accounts（由 maker 支付）
+        if !Escrow::load(&accounts.escrow.try_borrow()?)?.is_native_a() {
+            AssociatedTokenAccount::init_if_needed(
+                accounts.proposer_ata_a,
+                accounts.mint_a,
+                accounts.maker,
+                accounts.proposer,
+                accounts.system_program,
+                accounts.token_program,
+            )?;
+        }
+
+        AssociatedTokenAccount::init_if_needed(
+            accounts.maker_ata_b,
+            accounts.mint_b,
+            accounts.maker,
+            accounts.maker,
+            accounts.system_program,
+            accounts.token_program,
+        )?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> AcceptCounterOffer<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &16;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let data = self.accounts.escrow.try_borrow()?;
+        let escrow = Escrow::load(&data)?;
+
+        // 只有 Escrow 的创建者可以接受还价
+        if self.accounts.maker.address() != &escrow.maker {
+            return Err(EscrowError::MakerMismatch.into());
+        }
+
+        // 验证 Escrow PDA 是否有效
+        let escrow_key = Address::create_program_address(
+            &[
+                b"escrow",
+                self.accounts.maker.address().as_ref(),
+                &escrow.seed.to_le_bytes(),
+                &[escrow.bump],
+            ],
+            &crate::ID,
+        )?;
+        if &escrow_key != self.accounts.escrow.address() {
+            return Err(EscrowError::InvalidEscrowPda.into());
+        }
+
+        // 还价必须属于该 Escrow 与传入的还价者
+        let (offer_escrow, offer_proposer) = verify_offer(self.accounts.offer)?;
+        if &offer_escrow != self.accounts.escrow.address()
+            || &offer_proposer != self.accounts.proposer.address()
+        {
+            return Err(EscrowError::InvalidOfferPda.into());
+        }
+        if self.accounts.mint_a.address() != &escrow.mint_a
+            || self.accounts.mint_b.address() != &escrow.mint_b
+        {
+            return Err(EscrowError::InvalidMint.into());
+        }
+
+        if escrow.is_auction() {
+            return Err(EscrowError::AuctionMode.into());
+        }
+        escrow.check_open()?;
+        if escrow.is_deposited() {
+            return Err(EscrowError::DepositPending.into());
+        }
+        if escrow.is_vesting() || escrow.is_bundle() || escrow.is_delegated() {
+            return Err(EscrowError::InvalidAccountData.into());
+        }
+        if escrow.has_arbiter() {
+            return Err(EscrowError::ArbiterMode.into());
+        }
+        if escrow.is_expired(Clock::get()?.unix_timestamp) {
+            return Err(EscrowError::EscrowExpired.into());
+        }
+
+        // Transfer the proposed Token B to the Maker, close the offer
+        release_offer(
+            self.accounts.offer,
+            self.accounts.mint_b,
+            self.accounts.offer_vault,
+            self.accounts.maker_ata_b,
+            self.accounts.proposer,
+        )?;
+
+        // Transfer Token A to the Proposer
+        if escrow.is_native_a() {
+            // 原生 SOL 托管：Escrow PDA 中超出租金豁免额的 lamports 即为 Token A
+            let rent_exempt = Rent::get()?.try_minimum_balance(Escrow::LEN)?;
+            let amount = self
+                .accounts
+                .escrow
+                .lamports()
+                .checked_sub(rent_exempt)
+                .ok_or(ProgramError::InsufficientFunds)?;
+            transfer_lamports(self.accounts.escrow, self.accounts.proposer, amount)?;
+        } else {
+            let seed_binding = escrow.seed.to_le_bytes();
+            let bump_binding = [escrow.bump];
+            let escrow_seeds = [
+                Seed::from(b"escrow"),
+                Seed::from(self.accounts.maker.address().as_ref()),
+                Seed::from(&seed_binding),
+                Seed::from(&bump_binding),
+            ];
+            let signers = [Signer::from(&escrow_seeds)];
+
+            let amount = TokenAccount::from_account_view(self.accounts.vault)?.amount();
+            TokenTransfer {
+                from: self.accounts.vault,
+                mint: self.accounts.mint_a,
+                to: self.accounts.proposer_ata_a,
+                authority: self.accounts.escrow,
+                amount,
+                hook_accounts: &[],
+            }
+            .invoke_signed(&signers)?;
+
+            CloseAccount {
+                account: self.accounts.vault,
+                destination: self.accounts.maker,
+                authority: self.accounts.escrow,
+            }
+            .invoke_signed(&signers)?;
+        }
+
+        // Close the Whitelist
+        if escrow.has_whitelist() {
+            close_whitelist(self.accounts.whitelist, self.accounts.escrow, self.accounts.maker)?;
+        }
+
+        // Close the Escrow
+        drop(data);
+        ProgramAccount::close(self.accounts.escrow, self.accounts.maker)?;
+
+        Ok(())
+    }
+}
+
+// ========== WithdrawCounterOffer ==========
+pub struct WithdrawCounterOfferAccounts<'a> {
+    pub proposer: &'a AccountView,       // 还价者（必须是签名者）
+    pub offer: &'a AccountView,          // 还价 PDA
+    pub mint_b: &'a AccountView,         // Token B 的 Mint
+    pub offer_vault: &'a AccountView,    // 还价 PDA 拥有的 Token B ATA
+    pub proposer_ata_b: &'a AccountView, // 还价者的 Token B ATA（接收退回的代币）
+    pub system_program: &'a AccountView,
+    pub token_program: &'a AccountView,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for WithdrawCounterOfferAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [proposer, offer, mint_b, offer_vault, proposer_ata_b, system_program, token_program, _] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        SystemProgramCheck::check(system_program)?;
+
+        // 账户基础校验
+        SignerAccount::check(proposer)?;
+        MintInterface::check(mint_b)?;
+        AssociatedTokenAccount::check(offer_vault, offer, mint_b, token_program)?;
+
+        Ok(Self {
+            proposer,
+            offer,
+            mint_b,
+            offer_vault,
+            proposer_ata_b,
+            system_program,
+            token_program,
+        })
+    }
+}
+
+pub struct WithdrawCounterOffer<'a> {
+    pub accounts: WithdrawCounterOfferAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for WithdrawCounterOffer<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = WithdrawCounterOfferAccounts::try_from(accounts)?;
+
+        AssociatedTokenAccount::init_if_needed(
+            accounts.proposer_ata_b,
+            accounts.mint_b,
+            accounts.proposer,
+            accounts.proposer,
+            accounts.system_program,
+            accounts.token_program,
+        )?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> WithdrawCounterOffer<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &17;
+
+    pub fn process(&mut self) -> ProgramResult {
+        // 只有还价者本人可以撤回（Escrow 是否仍然存在都不影响撤回）
+        let (_, proposer) = verify_offer(self.accounts.offer)?;
+        if &proposer != self.accounts.proposer.address() {
+            return Err(EscrowError::InvalidOfferPda.into());
+        }
+
+        release_offer(
+            self.accounts.offer,
+            self.accounts.mint_b,
+            self.accounts.offer_vault,
+            self.accounts.proposer_ata_b,
+            self.accounts.proposer,
+        )
+    }
+}
@@ -0,0 +1,764 @@
+//! Contribute / Finalize / WithdrawContribution 指令实现（众筹成交）
+//!
+//! 多个 taker 共同凑齐一个 Escrow 的 `receive`，成交后按出资比例分配 Token A：
+//! - `Contribute`：出资者把 Token B 锁入众筹池 PDA（种子 `["pool", escrow]`）拥有的 pool vault，
+//!   并记录在自己的出资 PDA（种子 `["contribution", pool, contributor]`）中；
+//!   第一个出资者创建众筹池并支付其租金，超出 receive 的部分不会被转入
+//! - `Finalize`：凑齐后任何人都可以调用，Token B 全部转给 maker，Token A 按出资比例分给所有出资者
+//!   （除不尽的零头归最后一个出资者），然后关闭所有相关账户
+//! - `WithdrawContribution`：Finalize 之前出资者可以随时撤回出资（Escrow 已关闭时同样可以撤回）
+//!
+//! 每个众筹池最多 `Pool::MAX_CONTRIBUTORS` 个出资者，以保证 Finalize 能在一笔交易中完成。
+//! 众筹只支持普通的 SPL Token Escrow（非拍卖、非荷兰拍、非线性释放、非三方托管、非捆绑挂单、非委托模式）。
+//!
+use crate::errors::EscrowError;
+use crate::helper::{
+    AssociatedTokenAccount, ContributionAccount, MintInterface, PoolAccount, ProgramAccount,
+    SignerAccount, SystemProgramCheck, TokenTransfer,
+};
+use crate::state::{Contribution, Escrow, Pool};
+use crate::whitelist::{check_whitelisted, close_whitelist};
+use crate::{
+    AccountCheck, AccountClose, AssociatedTokenAccountCheck, AssociatedTokenAccountInit,
+    ProgramAccountInit,
+};
+use core::mem::size_of;
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    AccountView, Address, ProgramResult,
+};
+use pinocchio_token::{
+    instructions::CloseAccount,
+    state::TokenAccount,
+};
+
+/// 众筹可以针对的 Escrow：普通的 SPL Token Escrow，且未过期
+fn check_crowdfillable(escrow: &Escrow, mint_b: &AccountView) -> ProgramResult {
+    if escrow.is_auction() {
+        return Err(EscrowError::AuctionMode.into());
+    }
+    if escrow.is_deposited() {
+        return Err(EscrowError::DepositPending.into());
+    }
+    escrow.check_open()?;
+    if escrow.has_arbiter() {
+        return Err(EscrowError::ArbiterMode.into());
+    }
+    if escrow.is_dutch()
+        || escrow.is_vesting()
+        || escrow.is_bundle()
+        || escrow.is_delegated()
+        || escrow.is_ui_receive()
+    {
+        return Err(EscrowError::InvalidAccountData.into());
+    }
+    if escrow.is_native_a() || escrow.is_native_b() || mint_b.address() != &escrow.mint_b {
+        return Err(EscrowError::InvalidMint.into());
+    }
+    if escrow.is_expired(Clock::get()?.unix_timestamp) {
+        return Err(EscrowError::EscrowExpired.into());
+    }
+
+    Ok(())
+}
+
+/// 验证众筹池账户的 PDA 种子，返回记录的 Escrow 地址与 bump
+fn verify_pool(pool: &AccountView) -> Result<(Address, [u8; 1]), ProgramError> {
+    PoolAccount::check(pool)?;
+
+    let data = pool.try_borrow()?;
+    let state = Pool::load(&data)?;
+    let pool_key =
+        Address::create_program_address(&[b"pool", state.escrow.as_ref(), &state.bump], &crate::ID)?;
+    if &pool_key != pool.address() {
+        return Err(EscrowError::InvalidPoolPda.into());
+    }
+
+    Ok((state.escrow.clone(), state.bump))
+}
+
+/// 验证出资记录属于 `pool` 且出资者为 `contributor`
+fn verify_contribution(
+    contribution: &AccountView,
+    pool: &AccountView,
+    contributor: &AccountView,
+) -> ProgramResult {
+    ContributionAccount::check(contribution)?;
+
+    let data = contribution.try_borrow()?;
+    let state = Contribution::load(&data)?;
+    if &state.pool != pool.address() || &state.contributor != contributor.address() {
+        return Err(EscrowError::InvalidContributionPda.into());
+    }
+
+    let contribution_key = Address::create_program_address(
+        &[
+            b"contribution",
+            pool.address().as_ref(),
+            contributor.address().as_ref(),
+            &state.bump,
+        ],
+        &crate::ID,
+    )?;
+    if &contribution_key != contribution.address() {
+        return Err(EscrowError::InvalidContributionPda.into());
+    }
+
+    Ok(())
+}
+
+// ========== Contribute ==========
+pub struct ContributeAccounts<'a> {
+    pub contributor: &'a AccountView,       // 出资者（签名者，支付出资 PDA 的租金）
+    pub maker: &'a AccountView,             // 托管创建者（用于验证 Escrow PDA）
+    pub escrow: &'a AccountView,            // Escrow PDA 账户
+    pub mint_b: &'a AccountView,            // Token B 的 Mint
+    pub contributor_ata_b: &'a AccountView, // 出资者的 Token B ATA
+    pub pool: &'a AccountView,              // 众筹池 PDA（不存在时创建）
+    pub pool_vault: &'a AccountView,        // 众筹池拥有的 Token B ATA
+    pub contribution: &'a AccountView,      // 出资 PDA（不存在时创建）
+    pub system_program: &'a AccountView,
+    pub token_program: &'a AccountView,
+    pub whitelist: Option<&'a AccountView>, // 可选：白名单 PDA（Escrow 启用白名单时必须传入）
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for ContributeAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [contributor, maker, escrow, mint_b, contributor_ata_b, pool, pool_vault, contribution, system_program, token_program, _, rest @ ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        SystemProgramCheck::check(system_program)?;
+
+        // 账户基础校验
+        SignerAccount::check(contributor)?;
+        ProgramAccount::check(escrow)?;
+        MintInterface::check(mint_b)?;
+        AssociatedTokenAccount::check(contributor_ata_b, contributor, mint_b, token_program)?;
+
+        Ok(Self {
+            contributor,
+            maker,
+            escrow,
+            mint_b,
+            contributor_ata_b,
+            pool,
+            pool_vault,
+            contribution,
+            system_program,
+            token_program,
+            whitelist: rest.first(),
+        })
+    }
+}
+
+pub struct ContributeInstructionData {
+    pub amount: u64, // 本次出资的 Token B 数量（超出 receive 的部分不会被转入）
+}
+
+impl<'a> TryFrom<&'a [u8]> for ContributeInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len() != size_of::<u64>() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let amount = u64::from_le_bytes(data[0..8].try_into().unwrap());
+        if amount == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self { amount })
+    }
+}
+
+pub struct Contribute<'a> {
+    pub accounts: ContributeAccounts<'a>,
+    pub instruction_data: ContributeInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for Contribute<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = ContributeAccounts::try_from(accounts)?;
+        let instruction_data = ContributeInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> Contribute<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &27;
+
+    /// 首次出资时创建众筹池 PDA 与 pool vault，之后验证已有的众筹池
+    fn init_pool_if_needed(&self) -> ProgramResult {
+        if self.accounts.pool.data_len() != 0 {
+            let (escrow, _) = verify_pool(self.accounts.pool)?;
+            if &escrow != self.accounts.escrow.address() {
+                return Err(EscrowError::InvalidPoolPda.into());
+            }
+            return AssociatedTokenAccount::check(
+                self.accounts.pool_vault,
+                self.accounts.pool,
+                self.accounts.mint_b,
+                self.accounts.token_program,
+            );
+        }
+
+        let (pool_key, bump) = Address::find_program_address(
+            &[b"pool", self.accounts.escrow.address().as_ref()],
+            &crate::ID,
+        );
+        if &pool_key != self.accounts.pool.address() {
+            return Err(EscrowError::InvalidPoolPda.into());
+        }
+
+        let bump_binding = [bump];
+        let pool_seeds = [
+            Seed::from(b"pool"),
+            Seed::from(self.accounts.escrow.address().as_ref()),
+            Seed::from(&bump_binding),
+        ];
+        ProgramAccount::init::<Pool>(
+            self.accounts.contributor,
+            self.accounts.pool,
+            pool_seeds.as_slice(),
+            Pool::LEN,
+        )?;
+
+        AssociatedTokenAccount::init(
+            self.accounts.pool_vault,
+            self.accounts.mint_b,
+            self.accounts.contributor,
+            self.accounts.pool,
+            self.accounts.system_program,
+            self.accounts.token_program,
+        )?;
+
+        let mut data = self.accounts.pool.try_borrow_mut()?;
+        Pool::init(data.as_mut())?.set_inner(
+            self.accounts.escrow.address().clone(),
+            self.accounts.contributor.address().clone(),
+            bump_binding,
+        );
+
+        Ok(())
+    }
+
+    /// 首次出资时创建出资 PDA 并增加出资人数，之后验证已有的出资记录
+    fn init_contribution_if_needed(&self) -> ProgramResult {
+        if self.accounts.contribution.data_len() != 0 {
+            return verify_contribution(
+                self.accounts.contribution,
+                self.accounts.pool,
+                self.accounts.contributor,
+            );
+        }
+
+        {
+            let mut data = self.accounts.pool.try_borrow_mut()?;
+            let pool = Pool::load_mut(data.as_mut())?;
+            if pool.contributors >= Pool::MAX_CONTRIBUTORS {
+                return Err(ProgramError::InvalidAccountData);
+            }
+            pool.set_contributors(pool.contributors + 1);
+        }
+
+        let (contribution_key, bump) = Address::find_program_address(
+            &[
+                b"contribution",
+                self.accounts.pool.address().as_ref(),
+                self.accounts.contributor.address().as_ref(),
+            ],
+            &crate::ID,
+        );
+        if &contribution_key != self.accounts.contribution.address() {
+            return Err(EscrowError::InvalidContributionPda.into());
+        }
+
+        let bump_binding = [bump];
+        let contribution_seeds = [
+            Seed::from(b"contribution"),
+            Seed::from(self.accounts.pool.address().as_ref()),
+            Seed::from(self.accounts.contributor.address().as_ref()),
+            Seed::from(&bump_binding),
+        ];
+        ProgramAccount::init::<Contribution>(
+            self.accounts.contributor,
+            self.accounts.contribution,
+            contribution_seeds.as_slice(),
+            Contribution::LEN,
+        )?;
+
+        let mut data = self.accounts.contribution.try_borrow_mut()?;
+        Contribution::init(data.as_mut())?.set_inner(
+            self.accounts.pool.address().clone(),
+            self.accounts.contributor.address().clone(),
+            bump_binding,
+        );
+
+        Ok(())
+    }
+
+    pub fn process(&mut self) -> ProgramResult {
+        let receive = {
+            let data = self.accounts.escrow.try_borrow()?;
+            let escrow = Escrow::load(&data)?;
+
+            // 验证 Escrow PDA 是否有效
+            let escrow_key = Address::create_program_address(
+                &[
+                    b"escrow",
+                    self.accounts.maker.address().as_ref(),
+                    &escrow.seed.to_le_bytes(),
+                    &[escrow.bump],
+                ],
+                &crate::ID,
+            )?;
+            if &escrow_key != self.accounts.escrow.address() {
+                return Err(EscrowError::InvalidEscrowPda.into());
+            }
+
+            check_crowdfillable(escrow, self.accounts.mint_b)?;
+
+            // 与 Take 相同的 taker 权限校验
+            if !escrow.is_taker_allowed(self.accounts.contributor.address()) {
+                return Err(EscrowError::UnauthorizedTaker.into());
+            }
+            if escrow.has_whitelist() {
+                check_whitelisted(
+                    self.accounts.whitelist,
+                    self.accounts.escrow,
+                    self.accounts.contributor.address(),
+                )?;
+            }
+
+            escrow.receive
+        };
+
+        self.init_pool_if_needed()?;
+        self.init_contribution_if_needed()?;
+
+        // 只接受凑齐 receive 所需的部分
+        let amount = {
+            let mut data = self.accounts.pool.try_borrow_mut()?;
+            let pool = Pool::load_mut(data.as_mut())?;
+            let amount = self
+                .instruction_data
+                .amount
+                .min(receive.saturating_sub(pool.total));
+
+            // 众筹池已经凑齐
+            if amount == 0 {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+            pool.set_total(pool.total.checked_add(amount).ok_or(EscrowError::MathOverflow)?);
+            amount
+        };
+
+        {
+            let mut data = self.accounts.contribution.try_borrow_mut()?;
+            let contribution = Contribution::load_mut(data.as_mut())?;
+            contribution.set_amount(
+                contribution
+                    .amount
+                    .checked_add(amount)
+                    .ok_or(EscrowError::MathOverflow)?,
+            );
+        }
+
+        // Lock the contributed Token B in the pool vault
+        TokenTransfer {
+            from: self.accounts.contributor_ata_b,
+            mint: self.accounts.mint_b,
+            to: self.accounts.pool_vault,
+            authority: self.accounts.contributor,
+            amount,
+            hook_accounts: &[],
+        }
+        .invoke()
+    }
+}
+
+// ========== Finalize ==========
+pub struct FinalizeAccounts<'a> {
+    pub payer: &'a AccountView,       // 调用者（签名者，支付可能需要创建的 ATA）
+    pub maker: &'a AccountView,       // 托管创建者（接收 Token B 与租金）
+    pub creator: &'a AccountView,     // 众筹池创建者（接收众筹池与 pool vault 的租金）
+    pub escrow: &'a AccountView,      // Escrow PDA 账户
+    pub mint_a: &'a AccountView,      // Token A 的 Mint
+    pub mint_b: &'a AccountView,      // Token B 的 Mint
+    pub vault: &'a AccountView,       // Token A vault
+    pub pool: &'a AccountView,        // 众筹池 PDA
+    pub pool_vault: &'a AccountView,  // 众筹池拥有的 Token B ATA
+    pub maker_ata_b: &'a AccountView, // maker 的 Token B ATA
+    pub system_program: &'a AccountView,
+    pub token_program: &'a AccountView,
+    pub whitelist: Option<&'a AccountView>, // 可选：白名单 PDA（仅 Escrow 启用白名单时传入）
+    pub contributions: &'a [AccountView],   // 每个出资者一组账户：出资 PDA、出资者、出资者的 Token A ATA
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for FinalizeAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [payer, maker, creator, escrow, mint_a, mint_b, vault, pool, pool_vault, maker_ata_b, system_program, token_program, _, rest @ ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        SystemProgramCheck::check(system_program)?;
+
+        // 账户基础校验
+        SignerAccount::check(payer)?;
+        ProgramAccount::check(escrow)?;
+        MintInterface::check(mint_a)?;
+        MintInterface::check(mint_b)?;
+        AssociatedTokenAccount::check(vault, escrow, mint_a, token_program)?;
+        AssociatedTokenAccount::check(pool_vault, pool, mint_b, token_program)?;
+
+        // 可选账户依次为：白名单（仅 Escrow 启用白名单时）、每个出资者的一组账户
+        let has_whitelist = Escrow::load(&escrow.try_borrow()?)?.has_whitelist();
+        let (whitelist, contributions) = match rest.split_first() {
+            Some((whitelist, rest)) if has_whitelist => (Some(whitelist), rest),
+            _ => (None, rest),
+        };
+        if contributions.len() % Finalize::GROUP_LEN != 0 {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+
+        Ok(Self {
+            payer,
+            maker,
+            creator,
+            escrow,
+            mint_a,
+            mint_b,
+            vault,
+            pool,
+            pool_vault,
+            maker_ata_b,
+            system_program,
+            token_program,
+            whitelist,
+            contributions,
+        })
+    }
+}
+
+// ========== 指令数据结构（Finalize 无需额外数据）==========
+pub struct Finalize<'a> {
+    pub accounts: FinalizeAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for Finalize<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = FinalizeAccounts::try_from(accounts)?;
+
+        AssociatedTokenAccount::init_if_needed(
+            accounts.maker_ata_b,
+            accounts.mint_b,
+            accounts.payer,
+            accounts.maker,
+            accounts.system_program,
+            accounts.token_program,
+        )?;
+
+        for group in accounts.contributions.chunks_exact(Finalize::GROUP_LEN) {
+            let [_, contributor, contributor_ata_a] = group else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            AssociatedTokenAccount::init_if_needed(
+                contributor_ata_a,
+                accounts.mint_a,
+                accounts.payer,
+                contributor,
+                accounts.system_program,
+                accounts.token_program,
+            )?;
+        }
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> Finalize<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &28;
+
+    /// 每个出资者占用的账户数：出资 PDA、出资者、出资者的 Token A ATA
+    pub const GROUP_LEN: usize = 3;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let data = self.accounts.escrow.try_borrow()?;
+        let escrow = Escrow::load(&data)?;
+
+        // maker 必须与 Escrow 中记录的创建者一致
+        if self.accounts.maker.address() != &escrow.maker {
+            return Err(EscrowError::MakerMismatch.into());
+        }
+
+        // Check if the escrow is valid
+        let escrow_key = Address::create_program_address(
+            &[
+                b"escrow",
+                self.accounts.maker.address().as_ref(),
+                &escrow.seed.to_le_bytes(),
+                &[escrow.bump],
+            ],
+            &crate::ID,
+        )?;
+        if escrow_key != *self.accounts.escrow.address() {
+            return Err(EscrowError::InvalidEscrowPda.into());
+        }
+
+        check_crowdfillable(escrow, self.accounts.mint_b)?;
+        if self.accounts.mint_a.address() != &escrow.mint_a {
+            return Err(EscrowError::InvalidMint.into());
+        }
+
+        // 验证众筹池属于该 Escrow，且已经凑齐、所有出资者都已传入
+        let (pool_escrow, pool_bump) = verify_pool(self.accounts.pool)?;
+        let (total, contributors) = {
+            let pool_data = self.accounts.pool.try_borrow()?;
+            let pool = Pool::load(&pool_data)?;
+            if &pool.creator != self.accounts.creator.address() {
+                return Err(EscrowError::NotPoolCreator.into());
+            }
+            (pool.total, pool.contributors as usize)
+        };
+        if &pool_escrow != self.accounts.escrow.address() {
+            return Err(EscrowError::InvalidPoolPda.into());
+        }
+        if total != escrow.receive {
+            return Err(EscrowError::PoolNotFilled.into());
+        }
+        if self.accounts.contributions.len() != contributors * Self::GROUP_LEN {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+
+        let pool_seeds = [
+            Seed::from(b"pool"),
+            Seed::from(pool_escrow.as_ref()),
+            Seed::from(&pool_bump),
+        ];
+        let pool_signers = [Signer::from(&pool_seeds)];
+
+        let seed_binding = escrow.seed.to_le_bytes();
+        let bump_binding = [escrow.bump];
+        let escrow_seeds = [
+            Seed::from(b"escrow"),
+            Seed::from(self.accounts.maker.address().as_ref()),
+            Seed::from(&seed_binding),
+            Seed::from(&bump_binding),
+        ];
+        let signers = [Signer::from(&escrow_seeds)];
+
+        // Transfer the pooled Token B to the Maker
+        TokenTransfer {
+            from: self.accounts.pool_vault,
+            mint: self.accounts.mint_b,
+            to: self.accounts.maker_ata_b,
+            authority: self.accounts.pool,
+            amount: total,
+            hook_accounts: &[],
+        }
+        .invoke_signed(&pool_signers)?;
+
+        // 按出资比例分配 Token A，零头归最后一个出资者
+        // 每个出资记录分配后立即关闭，因此同一个出资者重复传入会失败
+        let vault_amount = TokenAccount::from_account_view(self.accounts.vault)?.amount();
+        let mut distributed = 0u64;
+        for (i, group) in self
+            .accounts
+            .contributions
+            .chunks_exact(Self::GROUP_LEN)
+            .enumerate()
+        {
+            let [contribution, contributor, contributor_ata_a] = group else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            verify_contribution(contribution, self.accounts.pool, contributor)?;
+
+            let share = if i + 1 == contributors {
+                vault_amount.checked_sub(distributed).ok_or(EscrowError::MathOverflow)?
+            } else {
+                let amount = Contribution::load(&contribution.try_borrow()?)?.amount;
+                u64::try_from(vault_amount as u128 * amount as u128 / total as u128)
+                    .map_err(|_| EscrowError::MathOverflow)?
+            };
+            distributed = distributed.checked_add(share).ok_or(EscrowError::MathOverflow)?;
+
+            if share != 0 {
+                TokenTransfer {
+                    from: self.accounts.vault,
+                    mint: self.accounts.mint_a,
+                    to: contributor_ata_a,
+                    authority: self.accounts.escrow,
+                    amount: share,
+                    hook_accounts: &[],
+                }
+                .invoke_signed(&signers)?;
+            }
+
+            ProgramAccount::close(contribution, contributor)?;
+        }
+
+        // Close the Vault and the pool vault
+        CloseAccount {
+            account: self.accounts.vault,
+            destination: self.accounts.maker,
+            authority: self.accounts.escrow,
+        }
+        .invoke_signed(&signers)?;
+        CloseAccount {
+            account: self.accounts.pool_vault,
+            destination: self.accounts.creator,
+            authority: self.accounts.pool,
+        }
+        .invoke_signed(&pool_signers)?;
+
+        // Close the Pool
+        ProgramAccount::close(self.accounts.pool, self.accounts.creator)?;
+
+        // Close the Whitelist
+        if escrow.has_whitelist() {
+            close_whitelist(self.accounts.whitelist, self.accounts.escrow, self.accounts.maker)?;
+        }
+
+        // Close the Escrow
+        drop(data);
+        ProgramAccount::close(self.accounts.escrow, self.accounts.maker)?;
+
+        Ok(())
+    }
+}
+
+// ========== WithdrawContribution ==========
+pub struct WithdrawContributionAccounts<'a> {
+    pub contributor: &'a AccountView,       // 出资者（必须是签名者）
+    pub creator: &'a AccountView,           // 众筹池创建者（最后一个出资者撤回时接收众筹池的租金）
+    pub mint_b: &'a AccountView,            // Token B 的 Mint
+    pub contributor_ata_b: &'a AccountView, // 出资者的 Token B ATA（接收撤回的出资）
+    pub pool: &'a AccountView,              // 众筹池 PDA
+    pub pool_vault: &'a AccountView,        // 众筹池拥有的 Token B ATA
+    pub contribution: &'a AccountView,      // 出资 PDA
+    pub token_program: &'a AccountView,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for WithdrawContributionAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [contributor, creator, mint_b, contributor_ata_b, pool, pool_vault, contribution, token_program] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // 账户基础校验
+        SignerAccount::check(contributor)?;
+        MintInterface::check(mint_b)?;
+        AssociatedTokenAccount::check(contributor_ata_b, contributor, mint_b, token_program)?;
+        AssociatedTokenAccount::check(pool_vault, pool, mint_b, token_program)?;
+        verify_contribution(contribution, pool, contributor)?;
+
+        Ok(Self {
+            contributor,
+            creator,
+            mint_b,
+            contributor_ata_b,
+            pool,
+            pool_vault,
+            contribution,
+            token_program,
+        })
+    }
+}
+
+// ========== 指令数据结构（WithdrawContribution 无需额外数据）==========
+pub struct WithdrawContribution<'a> {
+    pub accounts: WithdrawContributionAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for WithdrawContribution<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = WithdrawContributionAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> WithdrawContribution<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &29;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let (pool_escrow, pool_bump) = verify_pool(self.accounts.pool)?;
+        let amount = Contribution::load(&self.accounts.contribution.try_borrow()?)?.amount;
+
+        // 更新众筹池：减少已凑集数量与出资人数
+        let remaining_contributors = {
+            let mut data = self.accounts.pool.try_borrow_mut()?;
+            let pool = Pool::load_mut(data.as_mut())?;
+            if &pool.creator != self.accounts.creator.address() {
+                return Err(EscrowError::NotPoolCreator.into());
+            }
+            pool.set_total(pool.total.checked_sub(amount).ok_or(EscrowError::MathOverflow)?);
+            pool.set_contributors(
+                pool.contributors
+                    .checked_sub(1)
+                    .ok_or(EscrowError::MathOverflow)?,
+            );
+            pool.contributors
+        };
+
+        let pool_seeds = [
+            Seed::from(b"pool"),
+            Seed::from(pool_escrow.as_ref()),
+            Seed::from(&pool_bump),
+        ];
+        let signers = [Signer::from(&pool_seeds)];
+
+        // Return the contributed Token B
+        TokenTransfer {
+            from: self.accounts.pool_vault,
+            mint: self.accounts.mint_b,
+            to: self.accounts.contributor_ata_b,
+            authority: self.accounts.pool,
+            amount,
+            hook_accounts: &[],
+        }
+        .invoke_signed(&signers)?;
+
+        ProgramAccount::close(self.accounts.contribution, self.accounts.contributor)?;
+
+        // 最后一个出资者撤回时关闭众筹池，租金返还给创建者
+        if remaining_contributors == 0 {
+            CloseAccount {
+                account: self.accounts.pool_vault,
+                destination: self.accounts.creator,
+                authority: self.accounts.pool,
+            }
+            .invoke_signed(&signers)?;
+
+            ProgramAccount::close(self.accounts.pool, self.accounts.creator)?;
+        }
+
+        Ok(())
+    }
+}
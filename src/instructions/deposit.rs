@@ -0,0 +1,167 @@
+//! Deposit 指令实现（双向托管：对手方锁定 Token B）
+//!
+//! 本文件实现 `Deposit` 指令，适用于双方都不愿在同一笔 Take 交易中先行完全承诺的场景：
+//! 1. maker 通过 Make 把 Token A 锁入 vault
+//! 2. 对手方调用 Deposit，把 `receive` 数量的 Token B 锁入第二个 vault（Escrow 拥有的 Token B ATA）
+//! 3. 任意一方调用 Settle，两个 vault 原子交换（三方托管则由 Resolve 决定两个 vault 的去向）
+//!
+//! Deposit 之后对手方成为 Escrow 的指定 taker，并记录 `FLAG_DEPOSITED`，
+//! 此后 Escrow 只能通过 Settle 结算（Take / Refund / UpdateEscrow 等都会被拒绝）。
+//!
+use crate::errors::EscrowError;
+use crate::helper::{
+    AssociatedTokenAccount, MintInterface, ProgramAccount, SignerAccount, SystemProgramCheck, TokenTransfer,
+};
+use crate::state::Escrow;
+use crate::whitelist::check_whitelisted;
+use crate::{AccountCheck, AssociatedTokenAccountCheck, AssociatedTokenAccountInit};
+use pinocchio::{
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    AccountView, Address, ProgramResult,
+};
+
+// ========== 账户结构 ==========
+pub struct DepositAccounts<'a> {
+    pub taker: &'a AccountView,       // 对手方（签名者，支付 vault_b 的创建费用）
+    pub maker: &'a AccountView,       // 托管创建者（用于验证 Escrow PDA）
+    pub escrow: &'a AccountView,      // Escrow PDA 账户
+    pub mint_b: &'a AccountView,      // Token B 的 Mint
+    pub taker_ata_b: &'a AccountView, // 对手方的 Token B ATA
+    pub vault_b: &'a AccountView,     // 第二个 vault（Escrow 拥有的 Token B ATA）
+    pub system_program: &'a AccountView,
+    pub token_program: &'a AccountView,
+    pub whitelist: Option<&'a AccountView>, // 可选：白名单 PDA（Escrow 启用白名单时必须传入）
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for DepositAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [taker, maker, escrow, mint_b, taker_ata_b, vault_b, system_program, token_program, _, rest @ ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        SystemProgramCheck::check(system_program)?;
+
+        // 账户基础校验
+        SignerAccount::check(taker)?;
+        ProgramAccount::check(escrow)?;
+        MintInterface::check(mint_b)?;
+        AssociatedTokenAccount::check(taker_ata_b, taker, mint_b, token_program)?;
+
+        Ok(Self {
+            taker,
+            maker,
+            escrow,
+            mint_b,
+            taker_ata_b,
+            vault_b,
+            system_program,
+            token_program,
+            whitelist: rest.first(),
+        })
+    }
+}
+
+// ========== 指令数据结构（Deposit 无需额外数据，存入数量即 Escrow 的 receive）==========
+pub struct Deposit<'a> {
+    pub accounts: DepositAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for Deposit<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = DepositAccounts::try_from(accounts)?;
+
+        // 创建第二个 vault：Escrow PDA 拥有的 Token B ATA，由对手方支付租金
+        AssociatedTokenAccount::init(
+            accounts.vault_b,
+            accounts.mint_b,
+            accounts.taker,
+            accounts.escrow,
+            accounts.system_program,
+            accounts.token_program,
+        )?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> Deposit<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &9;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let mut data = self.accounts.escrow.try_borrow_mut()?;
+        let escrow = Escrow::load_mut(data.as_mut())?;
+
+        // 验证 Escrow PDA 是否有效
+        let escrow_key = Address::create_program_address(
+            &[
+                b"escrow",
+                self.accounts.maker.address().as_ref(),
+                &escrow.seed.to_le_bytes(),
+                &[escrow.bump],
+            ],
+            &crate::ID,
+        )?;
+        if escrow_key != *self.accounts.escrow.address() {
+            return Err(EscrowError::InvalidEscrowPda.into());
+        }
+
+        // 双向托管只支持 SPL Token 两条腿，且每个 Escrow 只能有一个对手方存入
+        if escrow.is_native_a() || escrow.is_native_b() {
+            return Err(EscrowError::InvalidAccountData.into());
+        }
+        escrow.check_open()?;
+        if escrow.is_deposited() {
+            return Err(EscrowError::DepositPending.into());
+        }
+        if escrow.is_auction() {
+            return Err(EscrowError::AuctionMode.into());
+        }
+        // 荷兰拍的价格随时间变化，不能锁定固定数量的 Token B；线性释放、捆绑挂单与委托模式只能通过 Take 成交
+        if escrow.is_dutch() || escrow.is_vesting() || escrow.is_bundle() || escrow.is_delegated() {
+            return Err(EscrowError::InvalidAccountData.into());
+        }
+        if self.accounts.mint_b.address() != &escrow.mint_b {
+            return Err(EscrowError::InvalidMint.into());
+        }
+
+        // 已过期的 Escrow 不能再存入
+        if escrow.is_expired(Clock::get()?.unix_timestamp) {
+            return Err(EscrowError::EscrowExpired.into());
+        }
+
+        // 与 Take 相同的 taker 权限校验
+        if !escrow.is_taker_allowed(self.accounts.taker.address()) {
+            return Err(EscrowError::UnauthorizedTaker.into());
+        }
+        if escrow.has_whitelist() {
+            check_whitelisted(
+                self.accounts.whitelist,
+                self.accounts.escrow,
+                self.accounts.taker.address(),
+            )?;
+        }
+
+        // 锁定对手方：成为指定 taker，Settle 时 Token A 只会交付给它
+        escrow.set_taker(Some(self.accounts.taker.address().clone()));
+        escrow.set_flags(escrow.flags | Escrow::FLAG_DEPOSITED);
+        escrow.touch(Clock::get()?.slot);
+
+        // Transfer Token B from the Taker to the second vault
+        TokenTransfer {
+            from: self.accounts.taker_ata_b,
+            mint: self.accounts.mint_b,
+            to: self.accounts.vault_b,
+            authority: self.accounts.taker,
+            amount: escrow.receive,
+            hook_accounts: &[],
+        }
+        .invoke()
+    }
+}
@@ -0,0 +1,171 @@
+//! 结构化事件（程序数据日志）
+//!
+//! Make / Take / Refund 在指令末尾通过 `sol_log_data` 写入一条定长事件，交易日志中显示为 `Program data: <base64>`，
+//! 索引器直接解码即可，无需再从内部指令还原成交数量（手续费、部分成交会让内部指令与最终结果不一致）。
+//!
+//! 事件布局（共 `EscrowEvent::LEN` 字节，整数均为小端序）：
+//! `kind(u8) | escrow(32) | maker(32) | taker(32) | mint_a(32) | mint_b(32) | amount_a(u64) | amount_b(u64) | slot(u64) | seq(u64)`
+//!
+//! 各事件中字段的含义：
+//! - `EscrowCreated`：taker 为指定的 taker（公开 Escrow 为全 0），amount_a 为实际托管的 Token A 数量，amount_b 为 receive
+//! - `EscrowTaken`：taker 为成交者，amount_a 为 taker 获得的 Token A 数量，amount_b 为 taker 支付的 Token B 数量
+//! - `EscrowRefunded`：taker 为全 0，amount_a 为返还给 maker 的 Token A 数量（已扣除取消罚金），amount_b 为 0
+//!
+//! Take 的 exact-in 模式按部分成交处理，不写入事件。
+//!
+//! `seq` 为全局配置 PDA 中的事件序号（`Config::event_seq`），每写入一条事件加 1，从 1 开始连续递增，
+//! 索引器可以据此发现漏收或乱序的事件。Make 与 Take 总是传入全局配置；Refund 的全局配置是可选的
+//! （排在统计 PDA 之前，见 refund.rs），未传入时事件的 `seq` 为 0，不占用序号。
+//!
+//! 启用 `verbose-logs` feature 时，每条事件之前额外输出可读日志：事件名、Escrow 地址与本次划转的数量，
+//! 便于在 devnet 上直接阅读交易日志；主网构建不启用以节省 CU。
+//!
+//! 启用 `anchor-events` feature 时改用 Anchor 的事件格式：开头的 `kind` 换成 8 字节事件判别器
+//! （`sha256("event:EscrowCreated")` 等的前 8 字节，见 `ANCHOR_DISCRIMINATORS`），之后的字段顺序与类型
+//! 正好是同名 Anchor 事件结构体的 Borsh 序列化结果，现有的 Anchor 事件解析工具无需修改即可解码。
+//!
+use crate::state::{Config, Escrow};
+use core::mem::size_of;
+use pinocchio::{AccountView, Address, ProgramResult};
+
+/// Anchor 事件判别器：`sha256("event:<事件名>")` 的前 8 字节，按事件类型排列
+#[cfg(feature = "anchor-events")]
+const ANCHOR_DISCRIMINATORS: [[u8; 8]; 3] = [
+    [70, 127, 105, 102, 92, 97, 7, 173],    // EscrowCreated
+    [164, 79, 50, 26, 174, 149, 92, 158],   // EscrowTaken
+    [132, 209, 49, 109, 135, 138, 28, 81],  // EscrowRefunded
+];
+
+/// 一条 Escrow 事件
+pub struct EscrowEvent {
+    pub kind: u8,          // 事件类型（`EscrowEvent::CREATED` 等）
+    pub escrow: Address,   // Escrow PDA 地址
+    pub maker: Address,    // 创建者
+    pub taker: Address,    // 成交者（见文件头说明）
+    pub mint_a: Address,   // 托管的代币 Mint
+    pub mint_b: Address,   // 期望获得的代币 Mint
+    pub amount_a: u64,     // Token A 数量（见文件头说明）
+    pub amount_b: u64,     // Token B 数量（见文件头说明）
+    pub slot: u64,         // 事件发生的 slot
+    pub seq: u64,          // 事件序号（见文件头说明）
+}
+
+impl EscrowEvent {
+    /// 事件：创建 Escrow
+    pub const CREATED: u8 = 0;
+    /// 事件：Escrow 成交
+    pub const TAKEN: u8 = 1;
+    /// 事件：maker 取消 Escrow 并取回 Token A
+    pub const REFUNDED: u8 = 2;
+
+    /// 事件类型前缀长度：默认为 1 字节 `kind`，Anchor 格式为 8 字节判别器
+    #[cfg(not(feature = "anchor-events"))]
+    pub const PREFIX_LEN: usize = size_of::<u8>();
+    #[cfg(feature = "anchor-events")]
+    pub const PREFIX_LEN: usize = 8;
+
+    /// 事件数据长度
+    pub const LEN: usize = Self::PREFIX_LEN + size_of::<Address>() * 5 + size_of::<u64>() * 4;
+
+    /// 按 Escrow 中记录的 maker 与 mint 构造事件
+    pub fn new(
+        kind: u8,
+        escrow_key: &Address,
+        escrow: &Escrow,
+        taker: &Address,
+        amount_a: u64,
+        amount_b: u64,
+        slot: u64,
+    ) -> Self {
+        Self {
+            kind,
+            escrow: escrow_key.clone(),
+            maker: escrow.maker.clone(),
+            taker: taker.clone(),
+            mint_a: escrow.mint_a.clone(),
+            mint_b: escrow.mint_b.clone(),
+            amount_a,
+            amount_b,
+            slot,
+            seq: 0,
+        }
+    }
+
+    /// 事件类型前缀
+    #[cfg(not(feature = "anchor-events"))]
+    fn prefix(&self) -> [u8; Self::PREFIX_LEN] {
+        [self.kind]
+    }
+
+    /// 事件类型前缀（Anchor 事件判别器）
+    #[cfg(feature = "anchor-events")]
+    fn prefix(&self) -> [u8; Self::PREFIX_LEN] {
+        ANCHOR_DISCRIMINATORS[self.kind as usize]
+    }
+
+    /// 打包为事件布局
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut out = [0u8; Self::LEN];
+        let (prefix, body) = out.split_at_mut(Self::PREFIX_LEN);
+        prefix.copy_from_slice(&self.prefix());
+        body[0..32].copy_from_slice(self.escrow.as_ref());
+        body[32..64].copy_from_slice(self.maker.as_ref());
+        body[64..96].copy_from_slice(self.taker.as_ref());
+        body[96..128].copy_from_slice(self.mint_a.as_ref());
+        body[128..160].copy_from_slice(self.mint_b.as_ref());
+        body[160..168].copy_from_slice(&self.amount_a.to_le_bytes());
+        body[168..176].copy_from_slice(&self.amount_b.to_le_bytes());
+        body[176..184].copy_from_slice(&self.slot.to_le_bytes());
+        body[184..192].copy_from_slice(&self.seq.to_le_bytes());
+        out
+    }
+
+    /// 事件名（可读日志使用）
+    #[cfg(feature = "verbose-logs")]
+    fn name(&self) -> &'static str {
+        match self.kind {
+            Self::CREATED => "EscrowCreated",
+            Self::TAKEN => "EscrowTaken",
+            _ => "EscrowRefunded",
+        }
+    }
+
+    /// 可读的调试日志：事件名、Escrow 地址，以及 amount_a、amount_b、seq（`sol_log_64_` 以十六进制输出）
+    #[cfg(feature = "verbose-logs")]
+    fn log(&self) {
+        let name = self.name();
+        #[cfg(target_os = "solana")]
+        unsafe {
+            pinocchio::syscalls::sol_log_(name.as_ptr(), name.len() as u64);
+            pinocchio::syscalls::sol_log_pubkey(self.escrow.as_ref().as_ptr());
+            pinocchio::syscalls::sol_log_64_(self.amount_a, self.amount_b, self.seq, 0, 0);
+        }
+        #[cfg(not(target_os = "solana"))]
+        let _ = name;
+    }
+
+    /// 从全局配置分配事件序号（`config` 为 None 时序号为 0），并写入程序数据日志
+    ///
+    /// 只有 Solana 目标才有日志 syscall，其他目标（本地构建、测试）下只分配序号。
+    pub fn emit(mut self, config: Option<&AccountView>) -> ProgramResult {
+        if let Some(config) = config {
+            let mut data = config.try_borrow_mut()?;
+            self.seq = Config::load_mut(data.as_mut())?.next_event_seq();
+        }
+
+        // 可读日志每条约消耗数百 CU，只在启用 `verbose-logs` 的构建（如 devnet 调试）中输出
+        #[cfg(feature = "verbose-logs")]
+        self.log();
+
+        let data = self.to_bytes();
+        #[cfg(target_os = "solana")]
+        unsafe {
+            let fields: [&[u8]; 1] = [&data];
+            pinocchio::syscalls::sol_log_data(fields.as_ptr() as *const u8, fields.len() as u64);
+        }
+        #[cfg(not(target_os = "solana"))]
+        let _ = data;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,272 @@
+//! InitFeeVault / WithdrawFees 指令实现（按 Mint 记账的协议手续费）
+//!
+//! 本文件实现手续费账本（`FeeVault`，种子 `["fee_vault", mint]`）相关的管理指令：
+//! - `InitFeeVault`：手续费管理员为某种手续费代币创建账本
+//! - `WithdrawFees`：手续费管理员从 fee vault 提取指定数量的手续费，并记入账本
+//!
+//! 手续费本身仍按 config.rs 的规则存放在 fee vault（全局配置 PDA 拥有的 Token B ATA）或全局配置 PDA 中，
+//! 账本只负责记账：Take / TakePartial 在推荐人之后传入支付代币的账本时，把本次收取的手续费记入 `accumulated`。
+//! `CollectFees` 一次提取全部余额且不记账；需要按 Mint 对账时使用 `WithdrawFees`。
+//!
+use crate::config::check_fee_authority;
+use crate::errors::EscrowError;
+use crate::helper::{
+    transfer_lamports, AssociatedTokenAccount, FeeVaultAccount, MintInterface, ProgramAccount,
+    SignerAccount, SystemProgramCheck, TokenTransfer, NATIVE_MINT,
+};
+use crate::state::{Config, FeeVault};
+use crate::{AccountCheck, AssociatedTokenAccountCheck, ProgramAccountInit};
+use core::mem::size_of;
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    sysvars::{rent::Rent, Sysvar},
+    AccountView, Address, ProgramResult,
+};
+use pinocchio_token::state::TokenAccount;
+
+/// 验证账本账户记录的是 `mint`，并校验 PDA 种子
+pub fn verify_fee_vault(ledger: &AccountView, mint: &Address) -> ProgramResult {
+    FeeVaultAccount::check(ledger)?;
+
+    let data = ledger.try_borrow()?;
+    let state = FeeVault::load(&data)?;
+    if &state.mint != mint {
+        return Err(EscrowError::InvalidFeeVaultPda.into());
+    }
+
+    let ledger_key = Address::create_program_address(
+        &[b"fee_vault", mint.as_ref(), &state.bump],
+        &crate::ID,
+    )?;
+    if &ledger_key != ledger.address() {
+        return Err(EscrowError::InvalidFeeVaultPda.into());
+    }
+
+    Ok(())
+}
+
+/// 拆出账户列表最后的账本账户（不是账本账户时原样返回），并验证它记录的是 `mint`
+pub fn split_fee_vault<'a>(
+    mint: &Address,
+    accounts: &'a [AccountView],
+) -> Result<(Option<&'a AccountView>, &'a [AccountView]), ProgramError> {
+    match accounts.split_last() {
+        Some((ledger, rest)) if FeeVaultAccount::check(ledger).is_ok() => {
+            verify_fee_vault(ledger, mint)?;
+            Ok((Some(ledger), rest))
+        }
+        _ => Ok((None, accounts)),
+    }
+}
+
+/// 传入了账本时记录收取的手续费
+pub fn record_fee(ledger: Option<&AccountView>, amount: u64) -> ProgramResult {
+    if let Some(ledger) = ledger {
+        let mut data = ledger.try_borrow_mut()?;
+        FeeVault::load_mut(data.as_mut())?.record_fee(amount)?;
+    }
+
+    Ok(())
+}
+
+// ========== InitFeeVault ==========
+pub struct InitFeeVaultAccounts<'a> {
+    pub authority: &'a AccountView, // 手续费管理员（签名者，支付租金）
+    pub config: &'a AccountView,    // 全局配置 PDA
+    pub mint: &'a AccountView,      // 手续费代币的 Mint（NATIVE_MINT 表示原生 SOL 手续费）
+    pub ledger: &'a AccountView,    // 账本 PDA
+    pub system_program: &'a AccountView,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for InitFeeVaultAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [authority, config, mint, ledger, system_program] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        SystemProgramCheck::check(system_program)?;
+
+        SignerAccount::check(authority)?;
+        check_fee_authority(config, authority)?;
+        if mint.address() != &NATIVE_MINT {
+            MintInterface::check(mint)?;
+        }
+
+        Ok(Self {
+            authority,
+            config,
+            mint,
+            ledger,
+            system_program,
+        })
+    }
+}
+
+pub struct InitFeeVault<'a> {
+    pub accounts: InitFeeVaultAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for InitFeeVault<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = InitFeeVaultAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> InitFeeVault<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &46;
+
+    pub fn process(&mut self) -> ProgramResult {
+        // 对应 Anchor 的 init + seeds = [b"fee_vault", mint.key().as_ref()]
+        let (ledger_key, bump) = Address::find_program_address(
+            &[b"fee_vault", self.accounts.mint.address().as_ref()],
+            &crate::ID,
+        );
+        if &ledger_key != self.accounts.ledger.address() {
+            return Err(EscrowError::InvalidFeeVaultPda.into());
+        }
+
+        let bump_binding = [bump];
+        let ledger_seeds = [
+            Seed::from(b"fee_vault"),
+            Seed::from(self.accounts.mint.address().as_ref()),
+            Seed::from(&bump_binding),
+        ];
+
+        ProgramAccount::init::<FeeVault>(
+            self.accounts.authority,
+            self.accounts.ledger,
+            ledger_seeds.as_slice(),
+            FeeVault::LEN,
+        )?;
+
+        let mut data = self.accounts.ledger.try_borrow_mut()?;
+        FeeVault::init(data.as_mut())?
+            .set_inner(self.accounts.mint.address().clone(), bump_binding);
+
+        Ok(())
+    }
+}
+
+// ========== WithdrawFees ==========
+pub struct WithdrawFeesAccounts<'a> {
+    pub authority: &'a AccountView,   // 手续费管理员（签名者）
+    pub config: &'a AccountView,      // 全局配置 PDA
+    pub mint: &'a AccountView,        // 要提取的手续费代币的 Mint（NATIVE_MINT 表示提取 lamports）
+    pub fee_vault: &'a AccountView,   // fee vault（全局配置 PDA 拥有的 ATA；提取 lamports 时不使用）
+    pub destination: &'a AccountView, // 接收手续费的 Token Account（提取 lamports 时为任意系统账户）
+    pub token_program: &'a AccountView,
+    pub ledger: &'a AccountView,      // 该 Mint 的账本 PDA
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for WithdrawFeesAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [authority, config, mint, fee_vault, destination, token_program, ledger] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        SignerAccount::check(authority)?;
+        check_fee_authority(config, authority)?;
+        if mint.address() != &NATIVE_MINT {
+            MintInterface::check(mint)?;
+            AssociatedTokenAccount::check(fee_vault, config, mint, token_program)?;
+        }
+        verify_fee_vault(ledger, mint.address())?;
+
+        Ok(Self {
+            authority,
+            config,
+            mint,
+            fee_vault,
+            destination,
+            token_program,
+            ledger,
+        })
+    }
+}
+
+pub struct WithdrawFeesInstructionData {
+    pub amount: u64, // 提取的数量
+}
+
+impl<'a> TryFrom<&'a [u8]> for WithdrawFeesInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len() != size_of::<u64>() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let amount = u64::from_le_bytes(data.try_into().unwrap());
+        if amount == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self { amount })
+    }
+}
+
+pub struct WithdrawFees<'a> {
+    pub accounts: WithdrawFeesAccounts<'a>,
+    pub instruction_data: WithdrawFeesInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for WithdrawFees<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = WithdrawFeesAccounts::try_from(accounts)?;
+        let instruction_data = WithdrawFeesInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> WithdrawFees<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &47;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let amount = self.instruction_data.amount;
+
+        if self.accounts.mint.address() == &NATIVE_MINT {
+            // 原生 SOL 手续费：只能提取全局配置 PDA 中超出租金豁免额的 lamports
+            let rent_exempt = Rent::get()?.try_minimum_balance(Config::LEN)?;
+            if amount > self.accounts.config.lamports().saturating_sub(rent_exempt) {
+                return Err(ProgramError::InsufficientFunds);
+            }
+            transfer_lamports(self.accounts.config, self.accounts.destination, amount)?;
+        } else {
+            if amount > TokenAccount::from_account_view(self.accounts.fee_vault)?.amount() {
+                return Err(ProgramError::InsufficientFunds);
+            }
+
+            let bump_binding = Config::load(&self.accounts.config.try_borrow()?)?.bump;
+            let config_seeds = [Seed::from(b"config"), Seed::from(&bump_binding)];
+            let signers = [Signer::from(&config_seeds)];
+
+            TokenTransfer {
+                from: self.accounts.fee_vault,
+                mint: self.accounts.mint,
+                to: self.accounts.destination,
+                authority: self.accounts.config,
+                amount,
+                hook_accounts: &[],
+            }
+            .invoke_signed(&signers)?;
+        }
+
+        let mut data = self.accounts.ledger.try_borrow_mut()?;
+        FeeVault::load_mut(data.as_mut())?.record_withdrawal(amount)
+    }
+}
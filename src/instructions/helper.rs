@@ -10,13 +10,17 @@
 //
 // 本模块通过 Trait 和零大小类型（ZST）实现类型安全的账户验证
 
-use pinocchio::{AccountView, Address, ProgramResult};
-use pinocchio::cpi::{Seed, Signer};
+use pinocchio::{address::address, AccountView, Address, ProgramResult};
+use pinocchio::account::Ref;
+use pinocchio::cpi::{invoke_signed, invoke_signed_with_bounds, Seed, Signer};
+use pinocchio::instruction::{InstructionAccount, InstructionView};
 use pinocchio::error::ProgramError;
 use pinocchio::sysvars::rent::Rent;
 use pinocchio::sysvars::Sysvar;
 use pinocchio_associated_token_account::instructions::Create;
-use pinocchio_system::instructions::CreateAccount;
+use pinocchio_system::instructions::{CreateAccount, Transfer};
+use pinocchio_token::instructions::SyncNative;
+use pinocchio_token::state::{AccountState, Mint, TokenAccount};
 use crate::errors::EscrowError;
 
 // =============================================================================
@@ -150,6 +154,31 @@ impl AccountCheck for SystemAccount {
     }
 }
 
+// =============================================================================
+// SystemProgramCheck - System Program 账户验证
+// =============================================================================
+// 对应 Anchor 的约束：Program<'info, System>
+//
+// Anchor 版本：
+//   pub system_program: Program<'info, System>,
+//
+// 功能：
+// - 验证账户地址是 System Program
+//
+// 使用场景：
+// - 创建账户（PDA、ATA）或通过 System Program 转账的指令中的 system_program 账户
+pub struct SystemProgramCheck;
+
+impl AccountCheck for SystemProgramCheck {
+    fn check(account: &AccountView) -> Result<(), ProgramError> {
+        if account.address() != &pinocchio_system::ID {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        Ok(())
+    }
+}
+
 // =============================================================================
 // Token-2022 Program 常量
 // =============================================================================
@@ -173,6 +202,35 @@ pub const TOKEN_2022_MINT_DISCRIMINATOR: u8 = 0x01;
 // Token-2022 Token Account 的判别器值
 pub const TOKEN_2022_TOKEN_ACCOUNT_DISCRIMINATOR: u8 = 0x02;
 
+// Token-2022 Mint 扩展（TLV：type(u16) | length(u16) | value）紧跟在判别器之后
+const TOKEN_2022_EXTENSIONS_OFFSET: usize = TOKEN_2022_ACCOUNT_DISCRIMINATOR_OFFSET + 1;
+
+// TransferHook 扩展的类型，value 为 authority(32) + program_id(32)
+const TRANSFER_HOOK_EXTENSION: u16 = 14;
+
+// InterestBearingConfig 扩展的类型
+const INTEREST_BEARING_EXTENSION: u16 = 10;
+
+// 利率代币计息使用的一年秒数（与 Token-2022 一致，365.24 天）
+const SECONDS_PER_YEAR: f64 = 60.0 * 60.0 * 24.0 * 365.24;
+
+// 单次转账最多转发给 Token-2022 的 Transfer Hook 账户数量
+pub const MAX_HOOK_ACCOUNTS: usize = 16;
+
+// =============================================================================
+// 原生 SOL 常量
+// =============================================================================
+// Wrapped SOL 的 Mint 地址
+// 原生 SOL 托管（MakeSol）没有真正的 Token A mint，用它作为 mint_a 的占位记录
+pub const NATIVE_MINT: Address = address!("So11111111111111111111111111111111111111112");
+
+// =============================================================================
+// Metaplex 常量
+// =============================================================================
+// Metaplex Token Metadata Program ID
+// NFT 托管（MakeNft）通过它的元数据 PDA（种子 `["metadata", program_id, mint]`）校验合集
+pub const METADATA_PROGRAM_ID: Address = address!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
 // =============================================================================
 // MintInterface - Mint 账户验证（支持 Token Program 和 Token-2022）
 // =============================================================================
@@ -252,6 +310,22 @@ impl AccountCheck for MintInterface {
     }
 }
 
+impl MintInterface {
+    // 验证 Mint 没有冻结权限（Make 的严格模式）
+    // 冻结权限人可以在托管期间冻结 vault 或收款账户，使 taker 付款后拿不到代币
+    pub fn check_no_freeze_authority(account: &AccountView) -> Result<(), ProgramError> {
+        Self::check(account)?;
+
+        // Token-2022 的 Mint 前 82 字节与原版一致
+        let data = account.try_borrow()?;
+        if unsafe { Mint::from_bytes_unchecked(&data) }.has_freeze_authority() {
+            return Err(EscrowError::MintFreezeAuthority.into());
+        }
+
+        Ok(())
+    }
+}
+
 // =============================================================================
 // TokenAccountInterface - Token Account 验证
 // =============================================================================
@@ -350,7 +424,7 @@ impl AssociatedTokenAccountCheck for AssociatedTokenAccount {
         // 验证传入的账户地址，是否为该权威+代币对应的标准ATA
         // 确保账户是通过ATA程序正规派生的，而非手动创建的普通Token账户
         if ata_address.ne(account.address()) {
-            return Err(EscrowError::InvalidAddress.into());
+            return Err(EscrowError::InvalidAssociatedTokenAccount.into());
         }
 
 
@@ -428,6 +502,8 @@ impl AssociatedTokenAccountInit for AssociatedTokenAccount {
 //
 // 注意：
 // - PDA 验证（seeds、bump）需要在指令中单独进行
+// - `Escrow::load` 只校验数据（判别器、版本、长度），其他程序拥有的账户可以伪造出相同的数据，
+//   因此任何指令在读取 Escrow 账户数据之前都必须先调用本检查，owner 不符时返回 `EscrowError::InvalidOwner`
 pub struct ProgramAccount;
 
 impl AccountCheck for ProgramAccount {
@@ -447,6 +523,253 @@ impl AccountCheck for ProgramAccount {
     }
 }
 
+// =============================================================================
+// WhitelistAccount - 白名单账户验证
+// =============================================================================
+// 对应 Anchor 的约束：Account<'info, Whitelist>
+//
+// 功能：
+// - 验证账户由本程序拥有
+// - 验证账户数据长度与 Whitelist 结构体一致
+//
+// 注意：
+// - 白名单与 Escrow 的绑定关系（PDA 种子）需要在指令中单独验证
+pub struct WhitelistAccount;
+
+impl AccountCheck for WhitelistAccount {
+    fn check(account: &AccountView) -> Result<(), ProgramError> {
+        if !account.owned_by(&crate::ID) {
+            return Err(EscrowError::InvalidOwner.into());
+        }
+
+        if account.data_len().ne(&crate::state::Whitelist::LEN) {
+            return Err(EscrowError::InvalidAccountData.into());
+        }
+
+        Ok(())
+    }
+}
+
+// =============================================================================
+// BidAccount - 拍卖出价账户验证
+// =============================================================================
+// 对应 Anchor 的约束：Account<'info, Bid>
+//
+// 功能：
+// - 验证账户由本程序拥有
+// - 验证账户数据长度与 Bid 结构体一致
+//
+// 注意：
+// - 出价账户与 Escrow 的绑定关系（PDA 种子）需要在指令中单独验证
+pub struct BidAccount;
+
+impl AccountCheck for BidAccount {
+    fn check(account: &AccountView) -> Result<(), ProgramError> {
+        if !account.owned_by(&crate::ID) {
+            return Err(EscrowError::InvalidOwner.into());
+        }
+
+        if account.data_len().ne(&crate::state::Bid::LEN) {
+            return Err(EscrowError::InvalidAccountData.into());
+        }
+
+        Ok(())
+    }
+}
+
+// =============================================================================
+// OfferAccount - 还价账户验证
+// =============================================================================
+// 对应 Anchor 的约束：Account<'info, Offer>
+//
+// 功能：
+// - 验证账户由本程序拥有
+// - 验证账户数据长度与 Offer 结构体一致
+//
+// 注意：
+// - 还价账户的 PDA 种子需要在指令中单独验证
+pub struct OfferAccount;
+
+impl AccountCheck for OfferAccount {
+    fn check(account: &AccountView) -> Result<(), ProgramError> {
+        if !account.owned_by(&crate::ID) {
+            return Err(EscrowError::InvalidOwner.into());
+        }
+
+        if account.data_len().ne(&crate::state::Offer::LEN) {
+            return Err(EscrowError::InvalidAccountData.into());
+        }
+
+        Ok(())
+    }
+}
+
+// =============================================================================
+// PoolAccount - 众筹池账户验证
+// =============================================================================
+// 对应 Anchor 的约束：Account<'info, Pool>
+//
+// 功能：
+// - 验证账户由本程序拥有
+// - 验证账户数据长度与 Pool 结构体一致
+//
+// 注意：
+// - 众筹池的 PDA 种子需要在指令中单独验证
+pub struct PoolAccount;
+
+impl AccountCheck for PoolAccount {
+    fn check(account: &AccountView) -> Result<(), ProgramError> {
+        if !account.owned_by(&crate::ID) {
+            return Err(EscrowError::InvalidOwner.into());
+        }
+
+        if account.data_len().ne(&crate::state::Pool::LEN) {
+            return Err(EscrowError::InvalidAccountData.into());
+        }
+
+        Ok(())
+    }
+}
+
+// =============================================================================
+// ContributionAccount - 出资记录账户验证
+// =============================================================================
+// 对应 Anchor 的约束：Account<'info, Contribution>
+//
+// 功能：
+// - 验证账户由本程序拥有
+// - 验证账户数据长度与 Contribution 结构体一致
+//
+// 注意：
+// - 出资记录的 PDA 种子需要在指令中单独验证
+pub struct ContributionAccount;
+
+impl AccountCheck for ContributionAccount {
+    fn check(account: &AccountView) -> Result<(), ProgramError> {
+        if !account.owned_by(&crate::ID) {
+            return Err(EscrowError::InvalidOwner.into());
+        }
+
+        if account.data_len().ne(&crate::state::Contribution::LEN) {
+            return Err(EscrowError::InvalidAccountData.into());
+        }
+
+        Ok(())
+    }
+}
+
+// =============================================================================
+// CommitmentAccount - 成交承诺账户验证
+// =============================================================================
+// 对应 Anchor 的约束：Account<'info, Commitment>
+//
+// 功能：
+// - 验证账户由本程序拥有
+// - 验证账户数据长度与 Commitment 结构体一致
+//
+// 注意：
+// - 成交承诺的 PDA 种子需要在指令中单独验证
+pub struct CommitmentAccount;
+
+impl AccountCheck for CommitmentAccount {
+    fn check(account: &AccountView) -> Result<(), ProgramError> {
+        if !account.owned_by(&crate::ID) {
+            return Err(EscrowError::InvalidOwner.into());
+        }
+
+        if account.data_len().ne(&crate::state::Commitment::LEN) {
+            return Err(EscrowError::InvalidAccountData.into());
+        }
+
+        Ok(())
+    }
+}
+
+// =============================================================================
+// ConfigAccount - 全局配置账户验证
+// =============================================================================
+// 对应 Anchor 的约束：Account<'info, Config>
+//
+// 功能：
+// - 验证账户由本程序拥有
+// - 验证账户数据长度与 Config 结构体一致
+// - 验证账户地址为 `["config", bump]` 派生的 PDA
+pub struct ConfigAccount;
+
+impl AccountCheck for ConfigAccount {
+    fn check(account: &AccountView) -> Result<(), ProgramError> {
+        if !account.owned_by(&crate::ID) {
+            return Err(EscrowError::InvalidOwner.into());
+        }
+
+        if account.data_len().ne(&crate::state::Config::LEN) {
+            return Err(EscrowError::InvalidAccountData.into());
+        }
+
+        let bump = crate::state::Config::load(&account.try_borrow()?)?.bump;
+        let config_key = Address::create_program_address(&[b"config", &bump], &crate::ID)?;
+        if &config_key != account.address() {
+            return Err(EscrowError::InvalidConfigPda.into());
+        }
+
+        Ok(())
+    }
+}
+
+// =============================================================================
+// MakerStatsAccount - maker 统计账户验证
+// =============================================================================
+// 对应 Anchor 的约束：Account<'info, MakerStats>
+//
+// 功能：
+// - 验证账户由本程序拥有
+// - 验证账户数据长度与 MakerStats 结构体一致
+//
+// 注意：
+// - 统计账户与 maker 的绑定关系（PDA 种子）需要在指令中单独验证
+pub struct MakerStatsAccount;
+
+impl AccountCheck for MakerStatsAccount {
+    fn check(account: &AccountView) -> Result<(), ProgramError> {
+        if !account.owned_by(&crate::ID) {
+            return Err(EscrowError::InvalidOwner.into());
+        }
+
+        if account.data_len().ne(&crate::state::MakerStats::LEN) {
+            return Err(EscrowError::InvalidAccountData.into());
+        }
+
+        Ok(())
+    }
+}
+
+// =============================================================================
+// FeeVaultAccount - 手续费账本账户验证
+// =============================================================================
+// 对应 Anchor 的约束：Account<'info, FeeVault>
+//
+// 功能：
+// - 验证账户由本程序拥有
+// - 验证账户数据长度与 FeeVault 结构体一致
+//
+// 注意：
+// - 账本与手续费代币的绑定关系（PDA 种子）需要在指令中单独验证
+pub struct FeeVaultAccount;
+
+impl AccountCheck for FeeVaultAccount {
+    fn check(account: &AccountView) -> Result<(), ProgramError> {
+        if !account.owned_by(&crate::ID) {
+            return Err(EscrowError::InvalidOwner.into());
+        }
+
+        if account.data_len().ne(&crate::state::FeeVault::LEN) {
+            return Err(EscrowError::InvalidAccountData.into());
+        }
+
+        Ok(())
+    }
+}
+
 // =============================================================================
 // ProgramAccountInit Trait - 程序账户初始化
 // =============================================================================
@@ -466,6 +789,8 @@ impl AccountCheck for ProgramAccount {
 //       bump,
 //   )]
 //   pub escrow: Account<'info, Escrow>,
+//
+// 创建后按 Rent sysvar 校验账户的 lamports 满足租金豁免（`check_rent_exempt`），否则返回 NotRentExempt
 pub trait ProgramAccountInit {
     // 创建程序拥有的 PDA 账户
     fn init<'a, T: Sized>(
@@ -485,7 +810,8 @@ impl ProgramAccountInit for ProgramAccount {
     ) -> ProgramResult {
         // 获取租金豁免所需的 lamports 数量
         // 对应 Anchor 自动进行的租金计算
-        let lamports = Rent::get()?.try_minimum_balance(space)?;
+        let rent = Rent::get()?;
+        let lamports = rent.try_minimum_balance(space)?;
 
         // 使用种子创建 PDA 签名者
         // 对应 Anchor 的 bump 自动处理
@@ -502,10 +828,21 @@ impl ProgramAccountInit for ProgramAccount {
         }
             .invoke_signed(&signer)?;  // 使用 PDA 签名调用
 
-        Ok(())
+        // 按 Rent sysvar 校验创建后的账户满足租金豁免
+        check_rent_exempt(account, &rent)
     }
 }
 
+// 校验账户的 lamports 不低于当前数据长度的租金豁免额，否则返回 NotRentExempt
+// 对应 Anchor 在 init / realloc 之后自动进行的 rent-exempt 检查
+pub fn check_rent_exempt(account: &AccountView, rent: &Rent) -> ProgramResult {
+    if account.lamports() < rent.try_minimum_balance(account.data_len())? {
+        return Err(EscrowError::NotRentExempt.into());
+    }
+
+    Ok(())
+}
+
 // =============================================================================
 // AccountClose Trait - 关闭账户
 // =============================================================================
@@ -519,13 +856,18 @@ impl ProgramAccountInit for ProgramAccount {
 //   pub escrow: Account<'info, Escrow>,
 //
 // 功能：
+// - 将账户数据清零（包括账户判别器），第一个字节写入 `CLOSED_ACCOUNT_DISCRIMINATOR`
 // - 将账户的 lamports 转给指定账户
-// - 将账户数据清零
-// - 关闭账户（账户可以被重新分配）
+// - 关闭账户：owner 归还 System Program，数据长度清零（账户可以被重新分配）
 //
 // 注意：
 // - Anchor 在指令执行完毕后自动处理 close 约束
 // - Pinocchio 需要手动调用 close 方法
+// - 同一交易内即使有人重新向该地址转入 lamports，账户也已属于 System Program 且没有数据，
+//   不能再被当作 Escrow 等程序账户加载（`load` 同时校验 owner、长度与判别器）
+/// 已关闭账户的标记字节：关闭时写入数据的第一个字节（Anchor 的 CLOSED_ACCOUNT_DISCRIMINATOR 惯例）
+pub const CLOSED_ACCOUNT_DISCRIMINATOR: u8 = 0xff;
+
 pub trait AccountClose {
     fn close(account: &AccountView, destination: &AccountView) -> ProgramResult;
 }
@@ -533,21 +875,497 @@ pub trait AccountClose {
 impl AccountClose for ProgramAccount {
     fn close(account: &AccountView, destination: &AccountView) -> ProgramResult {
         {
-            // 将账户数据的第一个字节设置为 0xff
-            // 这是 Solana 的惯例，表示账户已关闭
+            // 清零账户数据（包括账户判别器），再在第一个字节写入关闭标记
+            // 这是 Solana 的惯例，表示账户已关闭，防止同一交易内被当作原类型重新使用
             let mut data = account.try_borrow_mut()?;
-            data[0] = 0xff;
+            data.fill(0);
+            data[0] = CLOSED_ACCOUNT_DISCRIMINATOR;
         }
 
         // 将账户的 lamports 转给目标账户
         // 对应 Anchor 的 close = destination 约束
-        destination.set_lamports(destination.lamports()+account.lamports());
+        let lamports = destination
+            .lamports()
+            .checked_add(account.lamports())
+            .ok_or(EscrowError::MathOverflow)?;
+        destination.set_lamports(lamports);
 
-        // 将账户大小缩减到 1 字节（只剩下 0xff 标记）
-        account.resize(1)?;
-
-        // 关闭账户
-        // 此时账户的 lamports 已被转移，数据被清零
+        // 关闭账户：owner 清零即归还 System Program，lamports 与数据长度清零
+        // 对应 Anchor close 约束中的 assign(system_program) + realloc(0)
         account.close()
     }
-}
\ No newline at end of file
+}
+
+// =============================================================================
+// AccountRealloc Trait - 程序账户扩容
+// =============================================================================
+// 对应 Anchor 的约束：realloc = new_len, realloc::payer = payer, realloc::zero = true
+//
+// Anchor 版本：
+//   #[account(
+//       mut,
+//       realloc = Escrow::LEN,
+//       realloc::payer = payer,
+//       realloc::zero = true,
+//   )]
+//   pub escrow: Account<'info, Escrow>,
+//
+// 功能：
+// - 由 payer 通过 System Program 补足新旧长度的租金豁免额之差
+// - 调整账户数据长度，新增部分为全 0
+//
+// 注意：
+// - 只补足租金豁免额之差，不以账户当前的 lamports 计算：原生 SOL 托管的 Token A
+//   存放在超出租金豁免额的部分中，不能用来抵扣扩容所需的租金
+// - 缩小账户时不退还多余的租金
+// - 调整长度后按 Rent sysvar 校验账户满足新长度的租金豁免（`check_rent_exempt`）
+pub trait AccountRealloc {
+    fn realloc(account: &AccountView, payer: &AccountView, new_len: usize) -> ProgramResult;
+}
+
+impl AccountRealloc for ProgramAccount {
+    fn realloc(account: &AccountView, payer: &AccountView, new_len: usize) -> ProgramResult {
+        let old_len = account.data_len();
+        let rent = Rent::get()?;
+        if new_len > old_len {
+            let shortfall = rent
+                .try_minimum_balance(new_len)?
+                .checked_sub(rent.try_minimum_balance(old_len)?)
+                .ok_or(EscrowError::MathOverflow)?;
+            if shortfall > 0 {
+                Transfer {
+                    from: payer,
+                    to: account,
+                    lamports: shortfall,
+                }
+                .invoke()?;
+            }
+        }
+
+        // 新增的数据由运行时清零
+        account.resize(new_len)?;
+
+        // 扩容后按新长度校验租金豁免
+        check_rent_exempt(account, &rent)
+    }
+}
+
+// =============================================================================
+// transfer_lamports - 直接划转 lamports
+// =============================================================================
+// 对应 Anchor 中直接修改 `**account.lamports.borrow_mut()` 的写法
+//
+// 注意：
+// - 只能从本程序拥有的账户（如 Escrow PDA）中扣除 lamports
+// - 无需 CPI 到 System Program，也就不需要 PDA 签名
+pub fn transfer_lamports(from: &AccountView, to: &AccountView, amount: u64) -> ProgramResult {
+    let from_lamports = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    let to_lamports = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(EscrowError::MathOverflow)?;
+
+    from.set_lamports(from_lamports);
+    to.set_lamports(to_lamports);
+
+    Ok(())
+}
+
+// =============================================================================
+// assert_no_duplicates - 账户别名校验
+// =============================================================================
+// Anchor 在反序列化时不会拒绝同一地址出现在多个位置，需要用 constraint 手动比较
+//
+// 同一个地址同时充当两个含义不同的角色（如 vault 与 taker_ata_a、escrow 与 maker）时，
+// 转账会变成自己转给自己、PDA 签名的授权假设也不再成立，因此这些角色的地址必须两两不同。
+// 只传入在当前模式下实际使用的角色：未使用的位置（如原生 SOL 托管的 vault）可以传入任意账户。
+pub fn assert_no_duplicates<'a, I>(accounts: I) -> ProgramResult
+where
+    I: IntoIterator<Item = &'a AccountView>,
+    I::IntoIter: Clone,
+{
+    let accounts = accounts.into_iter();
+    for (i, account) in accounts.clone().enumerate() {
+        if accounts
+            .clone()
+            .skip(i + 1)
+            .any(|other| other.address() == account.address())
+        {
+            return Err(EscrowError::DuplicateAccount.into());
+        }
+    }
+
+    Ok(())
+}
+
+// =============================================================================
+// assert_writable - 可写账户校验
+// =============================================================================
+// 对应 Anchor 的约束：#[account(mut)]
+//
+// 指令要修改的账户（lamports、数据或作为 CPI 的可写账户）在账户校验阶段统一检查，
+// 未以可写方式传入时直接返回 NotWritable，而不是在之后的 CPI 中以不透明的错误失败。
+pub fn assert_writable<'a>(accounts: impl IntoIterator<Item = &'a AccountView>) -> ProgramResult {
+    for account in accounts {
+        if !account.is_writable() {
+            return Err(EscrowError::NotWritable.into());
+        }
+    }
+
+    Ok(())
+}
+
+// =============================================================================
+// Token Program 选择 - 同时支持原版 Token Program 与 Token-2022
+// =============================================================================
+// 对应 Anchor 的 Interface<'info, TokenInterface> 与 token_interface::transfer_checked
+//
+// pinocchio_token 的指令固定调用原版 Token Program，Token-2022 的 Mint 无法使用。
+// 这里根据 Mint / Token Account 的 owner 选择 CPI 的 program id：
+// - 转账使用 TransferChecked（Token-2022 的转账手续费等扩展要求带上 mint 与 decimals）
+// - mint 启用了 Transfer Hook 时，由 `TokenTransfer::hook_accounts` 附带 hook 账户（见 `split_hook_accounts`）
+
+// TokenProgramCheck - Token Program 账户验证
+// 对应 Anchor 的 Interface<'info, TokenInterface>：
+// - 地址必须是原版 Token Program 或 Token-2022
+// - 账户必须可执行，防止把其他程序替换进 CPI
+pub struct TokenProgramCheck;
+
+impl AccountCheck for TokenProgramCheck {
+    fn check(account: &AccountView) -> Result<(), ProgramError> {
+        let program = account.address();
+        if program != &pinocchio_token::ID && program != &TOKEN_2022_PROGRAM_ID {
+            return Err(EscrowError::InvalidOwner.into());
+        }
+        if !account.executable() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        Ok(())
+    }
+}
+
+// 验证 token_program 是 mint 所属的 Token Program（同时通过 TokenProgramCheck）
+pub fn check_token_program(mint: &AccountView, token_program: &AccountView) -> ProgramResult {
+    TokenProgramCheck::check(token_program)?;
+    if !mint.owned_by(token_program.address()) {
+        return Err(EscrowError::InvalidOwner.into());
+    }
+
+    Ok(())
+}
+
+// 账户（Mint 或 Token Account）所属的 Token Program ID
+fn token_program_id(account: &AccountView) -> Result<&'static Address, ProgramError> {
+    if account.owned_by(&TOKEN_2022_PROGRAM_ID) {
+        Ok(&TOKEN_2022_PROGRAM_ID)
+    } else if account.owned_by(&pinocchio_token::ID) {
+        Ok(&pinocchio_token::ID)
+    } else {
+        Err(EscrowError::InvalidOwner.into())
+    }
+}
+
+// 读取 Token Account（原版或 Token-2022，扩展数据位于基础布局之后）
+pub fn token_account(account: &AccountView) -> Result<Ref<'_, TokenAccount>, ProgramError> {
+    TokenAccountInterface::check(account)?;
+
+    Ok(Ref::map(account.try_borrow()?, |data| unsafe {
+        TokenAccount::from_bytes_unchecked(data)
+    }))
+}
+
+// 读取 mint 的 Transfer Hook 程序，没有 hook（原版 Token 或未设置 hook 程序）时返回 None
+pub fn transfer_hook_program(mint: &AccountView) -> Result<Option<Address>, ProgramError> {
+    if !mint.owned_by(&TOKEN_2022_PROGRAM_ID) {
+        return Ok(None);
+    }
+
+    let data = mint.try_borrow()?;
+    let Some(value) = mint_extension(&data, TRANSFER_HOOK_EXTENSION)? else {
+        return Ok(None);
+    };
+    let program_id: [u8; 32] = value
+        .get(32..64)
+        .ok_or(ProgramError::InvalidAccountData)?
+        .try_into()
+        .unwrap();
+    let program_id = Address::new_from_array(program_id);
+
+    Ok((program_id != Address::default()).then_some(program_id))
+}
+
+// 在 Token-2022 Mint 的扩展数据中查找指定类型的扩展，返回其 value（没有该扩展时返回 None）
+fn mint_extension(data: &[u8], extension_type: u16) -> Result<Option<&[u8]>, ProgramError> {
+    let mut extensions = data.get(TOKEN_2022_EXTENSIONS_OFFSET..).unwrap_or(&[]);
+    while let [t0, t1, l0, l1, rest @ ..] = extensions {
+        let len = u16::from_le_bytes([*l0, *l1]) as usize;
+        let value = rest.get(..len).ok_or(ProgramError::InvalidAccountData)?;
+        if u16::from_le_bytes([*t0, *t1]) == extension_type {
+            return Ok(Some(value));
+        }
+        extensions = &rest[len..];
+    }
+
+    Ok(None)
+}
+
+// 利率代币（InterestBearingConfig 扩展）当前 UI 金额与原始金额之比，没有该扩展时返回 None
+//
+// 与 Token-2022 相同按连续复利计算：更新利率前按平均利率计息，之后按当前利率计息
+pub fn interest_scale(mint: &AccountView, now: i64) -> Result<Option<f64>, ProgramError> {
+    if !mint.owned_by(&TOKEN_2022_PROGRAM_ID) {
+        return Ok(None);
+    }
+
+    let data = mint.try_borrow()?;
+    let Some(value) = mint_extension(&data, INTEREST_BEARING_EXTENSION)? else {
+        return Ok(None);
+    };
+    // rate_authority(32) | initialization_timestamp(i64) | pre_update_average_rate(i16) |
+    // last_update_timestamp(i64) | current_rate(i16)
+    let config = value.get(32..52).ok_or(ProgramError::InvalidAccountData)?;
+    let initialized_at = i64::from_le_bytes(config[0..8].try_into().unwrap());
+    let pre_update_rate = i16::from_le_bytes(config[8..10].try_into().unwrap());
+    let last_update = i64::from_le_bytes(config[10..18].try_into().unwrap());
+    let current_rate = i16::from_le_bytes(config[18..20].try_into().unwrap());
+
+    // 利率单位为基点 / 年
+    let exponent = |rate: i16, seconds: i64| rate as f64 * seconds as f64 / SECONDS_PER_YEAR / 10_000.0;
+    let scale = exp(exponent(pre_update_rate, last_update - initialized_at)
+        + exponent(current_rate, now - last_update));
+
+    Ok(Some(scale))
+}
+
+// e^x：先把 x 缩小到 [-0.5, 0.5] 内用泰勒级数计算，再平方还原（no_std 下没有 f64::exp）
+fn exp(x: f64) -> f64 {
+    let mut reduced = x;
+    let mut halvings = 0;
+    while !(-0.5..=0.5).contains(&reduced) && halvings < 64 {
+        reduced /= 2.0;
+        halvings += 1;
+    }
+
+    let mut term = 1.0;
+    let mut sum = 1.0;
+    for n in 1..16 {
+        term *= reduced / n as f64;
+        sum += term;
+    }
+
+    for _ in 0..halvings {
+        sum *= sum;
+    }
+    sum
+}
+
+// 把以 UI 金额计价的 `receive`（按 decimals 放大后的整数）换算为当前需要转账的原始数量（向上取整，保护 maker）
+// mint 不是利率代币时原样返回
+pub fn ui_receive_to_amount(mint: &AccountView, receive: u64, now: i64) -> Result<u64, ProgramError> {
+    let Some(scale) = interest_scale(mint, now)? else {
+        return Ok(receive);
+    };
+    if !scale.is_finite() || scale <= 0.0 {
+        return Err(EscrowError::MathOverflow.into());
+    }
+
+    let amount = receive as f64 / scale;
+    if amount >= u64::MAX as f64 {
+        return Err(EscrowError::MathOverflow.into());
+    }
+    let truncated = amount as u64;
+    Ok(if (truncated as f64) < amount { truncated + 1 } else { truncated })
+}
+
+// 拆出 mint 的 Transfer Hook 所需的账户，返回 hook 账户与剩余账户
+//
+// hook 账户组依次为：ExtraAccountMetaList 校验账户（种子 `["extra-account-metas", mint]`）、
+// hook 程序、校验账户中登记的每个额外账户；mint 没有 hook 时为空，不占用账户位置
+pub fn split_hook_accounts<'a>(
+    mint: &AccountView,
+    accounts: &'a [AccountView],
+) -> Result<(&'a [AccountView], &'a [AccountView]), ProgramError> {
+    let Some(program_id) = transfer_hook_program(mint)? else {
+        return Ok((&[], accounts));
+    };
+
+    let [validation, program, ..] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let (validation_key, _) = Address::find_program_address(
+        &[b"extra-account-metas", mint.address().as_ref()],
+        &program_id,
+    );
+    if validation.address() != &validation_key || program.address() != &program_id {
+        return Err(EscrowError::InvalidHookAccounts.into());
+    }
+
+    // 校验账户布局：Execute 判别器(8) | length(u32) | count(u32) | ExtraAccountMeta(35) * count
+    let count = {
+        let data = validation.try_borrow()?;
+        let count = data.get(12..16).ok_or(ProgramError::InvalidAccountData)?;
+        u32::from_le_bytes(count.try_into().unwrap()) as usize
+    };
+    if 2 + count > MAX_HOOK_ACCOUNTS {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    accounts
+        .split_at_checked(2 + count)
+        .ok_or(ProgramError::NotEnoughAccountKeys)
+}
+
+// 校验 Token Account 处于 Initialized 状态：已冻结或未初始化时返回 AccountFrozen
+// 转账前调用，避免 Token Program 的 CPI 以不透明的错误失败
+pub fn check_token_account_state(account: &AccountView) -> ProgramResult {
+    if token_account(account)?.state() != AccountState::Initialized {
+        return Err(EscrowError::AccountFrozen.into());
+    }
+
+    Ok(())
+}
+
+// 校验 vault 没有 delegate，close authority 为空或为 Escrow PDA，否则返回 InvalidVaultAuthority
+// maker 可以抢先创建 Escrow 的 ATA 并设置 delegate，在成交过程中转走 vault 中的 Token A
+pub fn check_vault_authorities(vault: &AccountView, escrow: &AccountView) -> ProgramResult {
+    let vault = token_account(vault)?;
+    if vault.has_delegate()
+        || vault
+            .close_authority()
+            .is_some_and(|authority| authority != escrow.address())
+    {
+        return Err(EscrowError::InvalidVaultAuthority.into());
+    }
+
+    Ok(())
+}
+
+// 转账（TransferChecked），program id 取自 mint 的 owner
+// mint 启用了 Transfer Hook 时，hook 账户追加在 4 个基础账户之后（见 split_hook_accounts）
+pub struct TokenTransfer<'a> {
+    pub from: &'a AccountView,
+    pub mint: &'a AccountView,
+    pub to: &'a AccountView,
+    pub authority: &'a AccountView,
+    pub amount: u64,
+    pub hook_accounts: &'a [AccountView],
+}
+
+impl TokenTransfer<'_> {
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let decimals = {
+            let data = self.mint.try_borrow()?;
+            unsafe { Mint::from_bytes_unchecked(&data) }.decimals()
+        };
+
+        // [12 (TransferChecked), amount (u64), decimals (u8)]
+        let mut data = [0u8; 10];
+        data[0] = 12;
+        data[1..9].copy_from_slice(&self.amount.to_le_bytes());
+        data[9] = decimals;
+
+        let len = 4 + self.hook_accounts.len();
+        if self.hook_accounts.len() > MAX_HOOK_ACCOUNTS {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        // hook 账户保持调用方传入时的可写 / 签名属性
+        let instruction_accounts: [InstructionAccount; 4 + MAX_HOOK_ACCOUNTS] =
+            core::array::from_fn(|i| match i {
+                0 => InstructionAccount::writable(self.from.address()),
+                2 => InstructionAccount::writable(self.to.address()),
+                3 => InstructionAccount::readonly_signer(self.authority.address()),
+                _ if i >= 4 && i < len => {
+                    let account = &self.hook_accounts[i - 4];
+                    InstructionAccount::new(
+                        account.address(),
+                        account.is_writable(),
+                        account.is_signer(),
+                    )
+                }
+                _ => InstructionAccount::readonly(self.mint.address()),
+            });
+        let account_views: [&AccountView; 4 + MAX_HOOK_ACCOUNTS] =
+            core::array::from_fn(|i| match i {
+                0 => self.from,
+                2 => self.to,
+                3 => self.authority,
+                _ if i >= 4 && i < len => &self.hook_accounts[i - 4],
+                _ => self.mint,
+            });
+        let instruction = InstructionView {
+            program_id: token_program_id(self.mint)?,
+            data: &data,
+            accounts: &instruction_accounts[..len],
+        };
+
+        invoke_signed_with_bounds::<{ 4 + MAX_HOOK_ACCOUNTS }>(
+            &instruction,
+            &account_views[..len],
+            signers,
+        )
+    }
+}
+
+// 关闭 Token Account，program id 取自账户的 owner
+pub struct TokenCloseAccount<'a> {
+    pub account: &'a AccountView,
+    pub destination: &'a AccountView,
+    pub authority: &'a AccountView,
+}
+
+impl TokenCloseAccount<'_> {
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        // [9 (CloseAccount)]
+        let instruction_accounts = [
+            InstructionAccount::writable(self.account.address()),
+            InstructionAccount::writable(self.destination.address()),
+            InstructionAccount::readonly_signer(self.authority.address()),
+        ];
+        let instruction = InstructionView {
+            program_id: token_program_id(self.account)?,
+            data: &[9],
+            accounts: &instruction_accounts,
+        };
+
+        invoke_signed(
+            &instruction,
+            &[self.account, self.destination, self.authority],
+            signers,
+        )
+    }
+}
+
+// mint 为 NATIVE_MINT 时对 WSOL 账户执行 SyncNative，使直接转入账户的 lamports 计入代币余额
+pub fn sync_native(account: &AccountView, mint: &AccountView) -> ProgramResult {
+    if mint.address() != &NATIVE_MINT {
+        return Ok(());
+    }
+
+    SyncNative {
+        native_token: account,
+    }
+    .invoke()
+}
+
+// mint 为 NATIVE_MINT 时关闭 owner 的 WSOL 账户，余额与租金以原生 SOL 全部转给 owner（owner 必须签名）
+pub fn unwrap_native(account: &AccountView, mint: &AccountView, owner: &AccountView) -> ProgramResult {
+    if mint.address() != &NATIVE_MINT {
+        return Ok(());
+    }
+
+    TokenCloseAccount {
+        account,
+        destination: owner,
+        authority: owner,
+    }
+    .invoke_signed(&[])
+}
@@ -4,20 +4,40 @@
 //! - 创建 Escrow PDA
 //! - 初始化 vault（由 PDA 拥有的 ATA）
 //! - 将 Maker 的 Token A 转入 vault
+//! - 拍卖模式：额外创建出价 PDA（种子 `["bid", escrow]`）和存放出价的 vault_b
+//! - 三方托管：记录仲裁人，对手方必须通过 Deposit 锁定 Token B，之后由 Resolve 结算
+//! - 捆绑挂单：为每种额外代币创建 vault 并转入（见 bundle.rs）
+//! - 严格模式（扩展选项字节）：拒绝带有冻结权限的 mint_a / mint_b，防止托管期间被冻结
+//! - UI 报价（扩展选项字节）：mint_b 为 Token-2022 利率代币时，receive 按 UI 金额记录，
+//!   Take 时按当前利率换算为原始数量，长期挂单不会因利息累积而偏离报价
+//!
+//! 全局配置 PDA 紧跟在 ATA Program 之后：程序暂停时拒绝 Make，托管数量必须在配置的范围内。
+//! mint_a 与 mint_b 不能相同（`InvalidMintPair`），托管数量与 receive 都必须大于 0（`ZeroAmount`）；MakeSol 与 MakeWithDelegate 同样校验。
+//! 可选的 maker 统计 PDA 排在所有账户最后，传入时累加创建次数（见 maker_stats.rs）。
+//!
+//! Token A 可以是原版 Token Program 或 Token-2022 的 Mint，token_program 必须是 mint_a 所属的程序。
+//! mint_a 启用了 Transfer Hook 时，hook 账户紧跟在固定账户之后（见 helper.rs 的 `split_hook_accounts`）。
 //!
 //! 注意：这里遵循项目的约定，使用 helper 中的账户校验 trait 进行验证和初始化。
 //!
-use crate::helper::{AssociatedTokenAccount, MintInterface, ProgramAccount, SignerAccount};
-use crate::state::Escrow;
+use crate::errors::EscrowError;
+use crate::bundle::{deposit_legs, LEG_ACCOUNTS};
+use crate::maker_stats::{record_maker_stats, split_maker_stats};
+use crate::pnft::PnftAccounts;
+use crate::helper::{
+    assert_no_duplicates, assert_writable, check_token_program, interest_scale, split_hook_accounts, sync_native, token_account, AssociatedTokenAccount, ConfigAccount, MintInterface, ProgramAccount, SignerAccount, SystemProgramCheck,
+    TokenTransfer, NATIVE_MINT,
+};
+use crate::events::EscrowEvent;
+use crate::state::{derive_escrow_address, Bid, Config, Escrow, MakerStats, ESCROW_SEED};
 use crate::{AccountCheck, AssociatedTokenAccountCheck, AssociatedTokenAccountInit,ProgramAccountInit};
 use core::mem::size_of;
 use pinocchio::{
-    address::address,
-    cpi::{Seed, Signer},
+    cpi::Seed,
     error::ProgramError,
-    nostd_panic_handler, AccountView, Address, ProgramResult,
+    sysvars::{clock::Clock, Sysvar},
+    AccountView, Address, ProgramResult,
 };
-use pinocchio_token::instructions::Transfer;
 
 pub struct MakeAccounts<'a> {
     pub maker: &'a AccountView,
@@ -28,23 +48,40 @@ pub struct MakeAccounts<'a> {
     pub vault: &'a AccountView,
     pub system_program: &'a AccountView,
     pub token_program: &'a AccountView,
+    pub config: &'a AccountView,          // 全局配置 PDA（暂停开关与托管数量范围）
+    pub bid: Option<&'a AccountView>,     // 拍卖模式：出价 PDA
+    pub vault_b: Option<&'a AccountView>, // 拍卖模式：存放出价的 Token B vault
+    pub legs: &'a [AccountView],          // 捆绑挂单：每种额外代币一组账户（与拍卖模式互斥）
+    pub pnft: Option<PnftAccounts<'a>>,   // pNFT 账户组（仅 MakePnft 传入，见 pnft.rs）
+    pub hook_a: &'a [AccountView],        // mint_a 的 Transfer Hook 账户（未启用 hook 时为空）
+    pub stats: Option<&'a AccountView>,   // 可选：maker 的统计 PDA，排在最后（见 maker_stats.rs）
 }
 
 impl<'a> TryFrom<&'a [AccountView]> for MakeAccounts<'a> {
     type Error = ProgramError;
 
     fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
-        let [maker, escrow, mint_a, mint_b, maker_ata_a, vault, system_program, token_program, _] =
+        let [maker, escrow, mint_a, mint_b, maker_ata_a, vault, system_program, token_program, _, config, rest @ ..] =
             accounts
         else {
             return Err(ProgramError::NotEnoughAccountKeys);
         };
 
+        SystemProgramCheck::check(system_program)?;
+
         // Basic Accounts Checks
         SignerAccount::check(maker)?;
+        ConfigAccount::check(config)?;
         MintInterface::check(mint_a)?;
         MintInterface::check(mint_b)?;
+        check_token_program(mint_a, token_program)?;
         AssociatedTokenAccount::check(maker_ata_a, maker, mint_a, token_program)?;
+        assert_no_duplicates([maker, escrow, maker_ata_a, vault])?;
+        assert_writable([maker, escrow, maker_ata_a, vault, config])?;
+
+        // Transfer Hook 账户紧跟在固定账户之后，数量由 mint_a 与其校验账户决定
+        let (hook_a, rest) = split_hook_accounts(mint_a, rest)?;
+        let (stats, rest) = split_maker_stats(maker.address(), rest)?;
 
         // Return the accounts
         Ok(Self {
@@ -56,6 +93,13 @@ impl<'a> TryFrom<&'a [AccountView]> for MakeAccounts<'a> {
             vault,
             system_program,
             token_program,
+            config,
+            bid: rest.first(),
+            vault_b: rest.get(1),
+            legs: rest,
+            pnft: None,
+            hook_a,
+            stats,
         })
     }
 }
@@ -64,22 +108,363 @@ pub struct MakeInstructionData {
     pub seed: u64,
     pub receive: u64,
     pub amount: u64,
+    pub expiry_timestamp: i64, // 可选的过期时间，0 表示永不过期
+    pub taker: Option<Address>, // 可选的指定 taker，None 表示公开 Escrow
+    pub auction: bool,         // 可选：是否为拍卖模式（receive 作为底价）
+    pub dutch: bool,           // 可选：是否为荷兰拍（receive 作为起拍价）
+    pub floor_price: u64,      // 荷兰拍：最低价
+    pub decay_end: i64,        // 荷兰拍：降到最低价的 Unix 时间戳（从 Make 时开始降价）
+    pub vesting: bool,         // 可选：是否为线性释放的 Escrow（Take 后 Token A 逐步解锁给 taker）
+    pub vesting_duration: i64, // 线性释放：从 Take 开始到全部解锁的时长（秒）
+    pub arbiter: Address,      // 可选：仲裁人，全 0 表示没有仲裁人
+    pub leg_amounts: [u64; Escrow::MAX_LEGS], // 捆绑挂单：每种额外代币的数量
+    pub leg_count: u8,                        // 捆绑挂单：额外代币的种类数（0 表示普通 Escrow）
+    pub payment_options: [(Address, u64); Escrow::MAX_PAYMENT_OPTIONS], // 可选：备选支付代币（mint, receive）
+    pub payment_option_count: u8,                                       // 备选支付代币的种类数
+    pub refund_locked_until: i64, // 可选：在该时间之前 maker 不能撤回报价，0 表示不锁定
+    pub penalty_bps: u16,           // 可选：过期前 Refund 的罚金比例（基点），0 表示没有罚金
+    pub penalty_recipient: Address, // 罚金的接收者
+    pub strict: bool,               // 可选：严格模式，mint_a / mint_b 不能带有冻结权限
+    pub ui_receive: bool,           // 可选：receive 为 mint_b（利率代币）的 UI 金额，Take 时按当前利率换算
+}
+
+impl MakeInstructionData {
+    /// 与当前时间相关的校验：过期时间与荷兰拍的结束时间都必须在未来
+    pub fn check_timestamps(&self, now: i64) -> ProgramResult {
+        // 过期时间必须在未来，否则创建出来的 Escrow 立即就过期了
+        if self.expiry_timestamp != 0 && self.expiry_timestamp <= now {
+            return Err(EscrowError::EscrowExpired.into());
+        }
+        if self.dutch && self.decay_end <= now {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        if self.refund_locked_until != 0 && self.refund_locked_until <= now {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        Ok(())
+    }
+
+    /// 选项字节：拍卖模式
+    pub const OPTION_AUCTION: u8 = 1 << 0;
+    /// 选项字节：荷兰拍（选项字节后紧跟 floor_price: u64 + decay_end: i64）
+    pub const OPTION_DUTCH: u8 = 1 << 1;
+    /// 选项字节：线性释放（荷兰拍参数之后紧跟 vesting_duration: i64）
+    pub const OPTION_VESTING: u8 = 1 << 2;
+    /// 选项字节：三方托管（线性释放参数之后紧跟 arbiter: Address）
+    pub const OPTION_ARBITER: u8 = 1 << 3;
+    /// 选项字节：捆绑挂单（仲裁人之后紧跟 leg_count: u8 + leg_count 个 amount: u64）
+    pub const OPTION_BUNDLE: u8 = 1 << 4;
+    /// 选项字节：备选支付代币（捆绑挂单参数之后紧跟 count: u8 + count 组 mint: Address + receive: u64）
+    pub const OPTION_PAYMENT_OPTIONS: u8 = 1 << 5;
+    /// 选项字节：撤回锁定期（备选支付代币参数之后紧跟 refund_locked_until: i64）
+    pub const OPTION_REFUND_LOCK: u8 = 1 << 6;
+    /// 选项字节：取消罚金（撤回锁定期之后紧跟 penalty_bps: u16 + penalty_recipient: Address）
+    pub const OPTION_PENALTY: u8 = 1 << 7;
+
+    /// 启用全部选项参数时指令数据的最大长度（MakeAuto 在栈上拼接种子与指令数据时使用）
+    pub const MAX_LEN: usize = 64
+        + size_of::<u8>()
+        + size_of::<u64>() + size_of::<i64>()
+        + size_of::<i64>()
+        + size_of::<Address>()
+        + size_of::<u8>() + size_of::<u64>() * Escrow::MAX_LEGS
+        + size_of::<u8>() + (size_of::<Address>() + size_of::<u64>()) * Escrow::MAX_PAYMENT_OPTIONS
+        + size_of::<i64>()
+        + size_of::<u16>() + size_of::<Address>()
+        + size_of::<u8>();
+
+    /// 扩展选项字节：严格模式，拒绝带有冻结权限的 mint_a / mint_b
+    pub const EXT_OPTION_STRICT: u8 = 1 << 0;
+    /// 扩展选项字节：receive 为 mint_b 的 UI 金额（mint_b 必须是 Token-2022 利率代币）
+    pub const EXT_OPTION_UI_RECEIVE: u8 = 1 << 1;
+
+    /// mint_a 与 mint_b 不能相同（Make、MakeSol 与 MakeWithDelegate 共用，原生 SOL 为 NATIVE_MINT）
+    pub fn check_mint_pair(mint_a: &Address, mint_b: &Address) -> ProgramResult {
+        if mint_a == mint_b {
+            return Err(EscrowError::InvalidMintPair.into());
+        }
+        Ok(())
+    }
+
+    /// 严格模式：依次校验传入的 mint 都没有冻结权限（Make、MakeSol 与 MakeWithDelegate 共用）
+    pub fn check_freeze_authority(&self, mints: &[&AccountView]) -> ProgramResult {
+        if !self.strict {
+            return Ok(());
+        }
+        for mint in mints {
+            MintInterface::check_no_freeze_authority(mint)?;
+        }
+        Ok(())
+    }
+
+    /// UI 报价：mint_b 必须带有 InterestBearingConfig 扩展（Make、MakeSol 与 MakeWithDelegate 共用）
+    pub fn check_ui_receive(&self, mint_b: &AccountView, now: i64) -> ProgramResult {
+        if self.ui_receive && interest_scale(mint_b, now)?.is_none() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        Ok(())
+    }
+
+    /// Escrow 中需要额外记录的标志位（Make、MakeSol 与 MakeWithDelegate 共用）
+    pub fn extra_flags(&self) -> u16 {
+        if self.ui_receive {
+            Escrow::FLAG_UI_RECEIVE
+        } else {
+            0
+        }
+    }
+
+    /// 捆绑挂单：每种额外代币的数量
+    pub fn legs(&self) -> &[u64] {
+        &self.leg_amounts[..self.leg_count as usize]
+    }
+
+    /// 备选支付代币（mint, receive）
+    pub fn payment_options(&self) -> &[(Address, u64)] {
+        &self.payment_options[..self.payment_option_count as usize]
+    }
+
+    /// 把备选支付代币记录到 Escrow 中（Make 与 MakeSol 共用），备选代币不能与 mint_b 重复
+    pub fn set_payment_options(&self, escrow: &mut Escrow, mint_b: &Address) -> ProgramResult {
+        for (mint, receive) in self.payment_options() {
+            if mint == mint_b {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+            escrow.add_payment_option(mint.clone(), *receive)?;
+        }
+        Ok(())
+    }
+
+    /// 把撤回锁定期记录到 Escrow 中（Make、MakeSol 与 MakeWithDelegate 共用）
+    pub fn set_refund_lock(&self, escrow: &mut Escrow) {
+        escrow.set_refund_locked_until(self.refund_locked_until);
+    }
+
+    /// 是否设置了取消罚金
+    pub fn has_penalty(&self) -> bool {
+        self.penalty_bps != 0
+    }
 }
 
 impl<'a> TryFrom<&'a [u8]> for MakeInstructionData {
     type Error = ProgramError;
 
     fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
-        if data.len() != size_of::<u64>() * 3 {
+        // 过期时间和指定 taker 是可选的尾部字段：
+        // - 24 字节：seed + receive + amount
+        // - 32 字节：再加上过期时间
+        // - 64 字节：再加上指定的 taker 地址（不需要过期时间时填 0）
+        // - 65 字节：再加上选项字节（见 MakeInstructionData::OPTION_*）
+        // - 选项字节之后按顺序跟随已启用选项的参数：
+        //   荷兰拍 floor_price + decay_end（16 字节），线性释放 vesting_duration（8 字节），
+        //   仲裁人地址（32 字节），捆绑挂单 leg_count + 每种额外代币的数量（1 + 8 × leg_count 字节），
+        //   备选支付代币 count + 每种的 mint 与 receive（1 + 40 × count 字节），撤回锁定期（8 字节），
+        //   取消罚金 penalty_bps + 接收者（2 + 32 字节）
+        // - 所有选项参数之后可选的扩展选项字节（见 MakeInstructionData::EXT_OPTION_*）
+        let (data, options, mut params) = match data.split_at_checked(64) {
+            Some((head, [options, params @ ..])) => (head, *options, params),
+            _ => (data, 0, &[][..]),
+        };
+        let auction = options & Self::OPTION_AUCTION != 0;
+        let dutch = options & Self::OPTION_DUTCH != 0;
+        let vesting = options & Self::OPTION_VESTING != 0;
+        let has_arbiter = options & Self::OPTION_ARBITER != 0;
+        let bundle = options & Self::OPTION_BUNDLE != 0;
+        let has_payment_options = options & Self::OPTION_PAYMENT_OPTIONS != 0;
+        let refund_lock = options & Self::OPTION_REFUND_LOCK != 0;
+        let penalty = options & Self::OPTION_PENALTY != 0;
+        // 三方托管通过 Deposit 锁定 Token B，不能与拍卖、荷兰拍、线性释放组合
+        // 捆绑挂单只能通过 Take 一次性成交，不能与拍卖、线性释放、三方托管组合
+        // 备选支付代币只在 Take / TakePartial 时使用，不能与拍卖、荷兰拍、三方托管组合
+        // 取消罚金只在 Refund 时收取，拍卖通过 CancelAuction 取消，不能与拍卖组合
+        // （选项字节的 8 位已全部定义，不存在未知选项）
+        if (auction && (dutch || vesting))
+            || (has_arbiter && (auction || dutch || vesting))
+            || (bundle && (auction || vesting || has_arbiter))
+            || (has_payment_options && (auction || dutch || has_arbiter))
+            || (penalty && auction)
+        {
             return Err(ProgramError::InvalidInstructionData);
         }
 
+        let (floor_price, decay_end) = if dutch {
+            let (dutch_params, rest) = params
+                .split_at_checked(size_of::<u64>() + size_of::<i64>())
+                .ok_or(ProgramError::InvalidInstructionData)?;
+            params = rest;
+            (
+                u64::from_le_bytes(dutch_params[0..8].try_into().unwrap()),
+                i64::from_le_bytes(dutch_params[8..16].try_into().unwrap()),
+            )
+        } else {
+            (0, 0)
+        };
+
+        let vesting_duration = if vesting {
+            let (vesting_params, rest) = params
+                .split_at_checked(size_of::<i64>())
+                .ok_or(ProgramError::InvalidInstructionData)?;
+            params = rest;
+            i64::from_le_bytes(vesting_params.try_into().unwrap())
+        } else {
+            0
+        };
+
+        let arbiter = if has_arbiter {
+            let (arbiter_params, rest) = params
+                .split_at_checked(size_of::<Address>())
+                .ok_or(ProgramError::InvalidInstructionData)?;
+            params = rest;
+            Address::new_from_array(arbiter_params.try_into().unwrap())
+        } else {
+            Address::default()
+        };
+
+        let mut leg_amounts = [0u64; Escrow::MAX_LEGS];
+        let leg_count = if bundle {
+            let (&leg_count, rest) = params
+                .split_first()
+                .ok_or(ProgramError::InvalidInstructionData)?;
+            if leg_count == 0 || leg_count as usize > Escrow::MAX_LEGS {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+            let (leg_params, rest) = rest
+                .split_at_checked(size_of::<u64>() * leg_count as usize)
+                .ok_or(ProgramError::InvalidInstructionData)?;
+            params = rest;
+            for (amount, bytes) in leg_amounts.iter_mut().zip(leg_params.chunks_exact(size_of::<u64>())) {
+                *amount = u64::from_le_bytes(bytes.try_into().unwrap());
+                if *amount == 0 {
+                    return Err(ProgramError::InvalidInstructionData);
+                }
+            }
+            leg_count
+        } else {
+            0
+        };
+
+        let mut payment_options: [(Address, u64); Escrow::MAX_PAYMENT_OPTIONS] =
+            core::array::from_fn(|_| (Address::default(), 0));
+        let payment_option_count = if has_payment_options {
+            const OPTION_LEN: usize = size_of::<Address>() + size_of::<u64>();
+
+            let (&count, rest) = params
+                .split_first()
+                .ok_or(ProgramError::InvalidInstructionData)?;
+            if count == 0 || count as usize > Escrow::MAX_PAYMENT_OPTIONS {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+            let (option_params, rest) = rest
+                .split_at_checked(OPTION_LEN * count as usize)
+                .ok_or(ProgramError::InvalidInstructionData)?;
+            params = rest;
+            for (i, bytes) in option_params.chunks_exact(OPTION_LEN).enumerate() {
+                let mint = Address::new_from_array(bytes[0..32].try_into().unwrap());
+                let receive = u64::from_le_bytes(bytes[32..40].try_into().unwrap());
+                // 备选支付代币只支持 SPL Token，且不能重复
+                if receive == 0
+                    || mint == NATIVE_MINT
+                    || payment_options[..i].iter().any(|(other, _)| other == &mint)
+                {
+                    return Err(ProgramError::InvalidInstructionData);
+                }
+                payment_options[i] = (mint, receive);
+            }
+            count
+        } else {
+            0
+        };
+
+        let refund_locked_until = if refund_lock {
+            let (lock_params, rest) = params
+                .split_at_checked(size_of::<i64>())
+                .ok_or(ProgramError::InvalidInstructionData)?;
+            params = rest;
+            i64::from_le_bytes(lock_params.try_into().unwrap())
+        } else {
+            0
+        };
+
+        let (penalty_bps, penalty_recipient) = if penalty {
+            let (penalty_params, rest) = params
+                .split_at_checked(size_of::<u16>() + size_of::<Address>())
+                .ok_or(ProgramError::InvalidInstructionData)?;
+            params = rest;
+            (
+                u16::from_le_bytes(penalty_params[0..2].try_into().unwrap()),
+                Address::new_from_array(penalty_params[2..34].try_into().unwrap()),
+            )
+        } else {
+            (0, Address::default())
+        };
+
+        let ext_options = match params {
+            [] => 0,
+            [ext_options] => *ext_options,
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+        if ext_options & !(Self::EXT_OPTION_STRICT | Self::EXT_OPTION_UI_RECEIVE) != 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let strict = ext_options & Self::EXT_OPTION_STRICT != 0;
+        let ui_receive = ext_options & Self::EXT_OPTION_UI_RECEIVE != 0;
+        // UI 报价只在 Take 时换算，拍卖的出价与三方托管的 Deposit 都按原始数量计价
+        if ui_receive && (auction || has_arbiter) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        // 全 0 的 taker 地址表示公开 Escrow
+        let (expiry_timestamp, taker) = match data.len() {
+            len if len == size_of::<u64>() * 3 => (0, None),
+            len if len == size_of::<u64>() * 3 + size_of::<i64>() => (
+                i64::from_le_bytes(data[24..32].try_into().unwrap()),
+                None,
+            ),
+            len if len == size_of::<u64>() * 3 + size_of::<i64>() + size_of::<Address>() => (
+                i64::from_le_bytes(data[24..32].try_into().unwrap()),
+                Some(Address::new_from_array(data[32..64].try_into().unwrap()))
+                    .filter(|taker| taker != &Address::default()),
+            ),
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+
         let seed = u64::from_le_bytes(data[0..8].try_into().unwrap());
         let receive = u64::from_le_bytes(data[8..16].try_into().unwrap());
         let amount = u64::from_le_bytes(data[16..24].try_into().unwrap());
 
         // Instruction Checks
-        if amount == 0 {
+        // 托管数量或期望接收的数量为 0 的 Escrow 没有意义，只会浪费租金
+        if amount == 0 || receive == 0 {
+            return Err(EscrowError::ZeroAmount.into());
+        }
+        if expiry_timestamp < 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        // 荷兰拍的最低价必须大于 0 且不能高于起拍价：最低价为 0 时降价结束后 Token A 会被白拿
+        if dutch && (floor_price == 0 || floor_price > receive) {
+            return Err(EscrowError::InvalidFloorPrice.into());
+        }
+        // 线性释放的时长必须为正
+        if vesting && vesting_duration <= 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        // 启用三方托管时必须给出仲裁人
+        if has_arbiter && arbiter == Address::default() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        // 撤回锁定期不能晚于过期时间，否则过期后的报价仍无法收回
+        if refund_lock
+            && (refund_locked_until <= 0
+                || (expiry_timestamp != 0 && refund_locked_until > expiry_timestamp))
+        {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        // 启用取消罚金时比例必须在 (0, 100%] 之间，且必须给出接收者
+        if penalty
+            && (penalty_bps == 0
+                || penalty_bps > Escrow::MAX_PENALTY_BPS
+                || penalty_recipient == Address::default())
+        {
             return Err(ProgramError::InvalidInstructionData);
         }
 
@@ -87,6 +472,24 @@ impl<'a> TryFrom<&'a [u8]> for MakeInstructionData {
             seed,
             receive,
             amount,
+            expiry_timestamp,
+            taker,
+            auction,
+            dutch,
+            floor_price,
+            decay_end,
+            vesting,
+            vesting_duration,
+            arbiter,
+            leg_amounts,
+            leg_count,
+            payment_options,
+            payment_option_count,
+            refund_locked_until,
+            penalty_bps,
+            penalty_recipient,
+            strict,
+            ui_receive,
         })
     }
 }
@@ -95,6 +498,7 @@ pub struct Make<'a> {
     pub accounts: MakeAccounts<'a>,
     pub instruction_data: MakeInstructionData,
     pub bump: u8,
+    pub bid_bump: Option<u8>, // 拍卖模式：出价 PDA 的 bump
 }
 
 impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for Make<'a> {
@@ -105,20 +509,23 @@ impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for Make<'a> {
 
         let instruction_data = MakeInstructionData::try_from(data)?;
 
+        MakeInstructionData::check_mint_pair(accounts.mint_a.address(), accounts.mint_b.address())?;
+        Config::load(&accounts.config.try_borrow()?)?.check_make(instruction_data.amount)?;
+        instruction_data.check_timestamps(Clock::get()?.unix_timestamp)?;
+        instruction_data.check_freeze_authority(&[accounts.mint_a, accounts.mint_b])?;
+        instruction_data.check_ui_receive(accounts.mint_b, Clock::get()?.unix_timestamp)?;
+
         // Initialize the Accounts needed
-        let (_, bump) = Address::find_program_address(
-            &[
-                b"escrow",
-                accounts.maker.address().as_ref(),
-                &instruction_data.seed.to_le_bytes(),
-            ],
-            &crate::ID,
-        );
+        // 只接受 canonical bump 派生的地址，之后各指令用记录的 bump 校验 Escrow PDA
+        let (escrow_key, bump) = derive_escrow_address(accounts.maker.address(), instruction_data.seed);
+        if &escrow_key != accounts.escrow.address() {
+            return Err(EscrowError::InvalidEscrowPda.into());
+        }
 
         let seed_binding = instruction_data.seed.to_le_bytes();
         let bump_binding = [bump];
         let escrow_seeds = [
-            Seed::from(b"escrow"),
+            Seed::from(ESCROW_SEED),
             Seed::from(accounts.maker.address().as_ref()),
             Seed::from(&seed_binding),
             Seed::from(&bump_binding),
@@ -127,7 +534,7 @@ impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for Make<'a> {
         ProgramAccount::init::<Escrow>(
             accounts.maker,
             accounts.escrow,
-            &escrow_seeds.as_slice(),
+            escrow_seeds.as_slice(),
             Escrow::LEN,
         )?;
 
@@ -141,20 +548,72 @@ impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for Make<'a> {
             accounts.token_program,
         )?;
 
+        // 拍卖模式：创建出价 PDA 和 vault_b，租金由 maker 支付，成交或取消时返还
+        // 三方托管的 Token B 通过 Deposit 锁定在 vault_b 中，不支持原生 SOL
+        if instruction_data.arbiter != Address::default()
+            && accounts.mint_b.address() == &NATIVE_MINT
+        {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let bid_bump = if instruction_data.auction {
+            Some(Self::init_auction(&accounts)?)
+        } else {
+            None
+        };
+
         Ok(Self {
             accounts,
             instruction_data,
             bump,
+            bid_bump,
         })
     }
 }
 impl<'a> Make<'a> {
     pub const DISCRIMINATOR: &'a u8 = &0;
 
+    /// 创建出价 PDA（`["bid", escrow]`）与 vault_b，返回出价 PDA 的 bump
+    fn init_auction(accounts: &MakeAccounts<'a>) -> Result<u8, ProgramError> {
+        // 拍卖出价以 SPL Token 锁定在 vault_b 中，不支持原生 SOL
+        if accounts.mint_b.address() == &NATIVE_MINT {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let bid = accounts.bid.ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let vault_b = accounts.vault_b.ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+        let (bid_key, bid_bump) = Address::find_program_address(
+            &[b"bid", accounts.escrow.address().as_ref()],
+            &crate::ID,
+        );
+        if &bid_key != bid.address() {
+            return Err(EscrowError::InvalidBidPda.into());
+        }
+
+        let bump_binding = [bid_bump];
+        let bid_seeds = [
+            Seed::from(b"bid"),
+            Seed::from(accounts.escrow.address().as_ref()),
+            Seed::from(&bump_binding),
+        ];
+        ProgramAccount::init::<Bid>(accounts.maker, bid, bid_seeds.as_slice(), Bid::LEN)?;
+
+        AssociatedTokenAccount::init(
+            vault_b,
+            accounts.mint_b,
+            accounts.maker,
+            accounts.escrow,
+            accounts.system_program,
+            accounts.token_program,
+        )?;
+
+        Ok(bid_bump)
+    }
+
     pub fn process(&mut self) -> ProgramResult {
         // Populate the escrow account
         let mut data = self.accounts.escrow.try_borrow_mut()?;
-        let escrow = Escrow::load_mut(data.as_mut())?;
+        let escrow = Escrow::init(data.as_mut())?;
 
         escrow.set_inner(
             self.instruction_data.seed,
@@ -162,18 +621,299 @@ impl<'a> Make<'a> {
             self.accounts.mint_a.address().clone(),
             self.accounts.mint_b.address().clone(),
             self.instruction_data.receive,
-            [self.bump],
+            self.instruction_data.expiry_timestamp,
+            self.bump,
         );
+        escrow.set_taker(self.instruction_data.taker.clone());
+        escrow.set_created(&Clock::get()?);
+        escrow.set_vault(self.accounts.vault.address().clone());
+
+        // maker 希望收到原生 SOL 时记录标志位，Take 时由 taker 直接支付 lamports
+        if self.accounts.mint_b.address() == &NATIVE_MINT {
+            escrow.set_flags(Escrow::FLAG_NATIVE_B);
+        }
+        escrow.set_flags(escrow.flags | self.instruction_data.extra_flags());
+
+        // 荷兰拍：从现在开始降价
+        if self.instruction_data.dutch {
+            escrow.set_dutch(
+                self.instruction_data.floor_price,
+                Clock::get()?.unix_timestamp,
+                self.instruction_data.decay_end,
+            );
+            escrow.set_flags(escrow.flags | Escrow::FLAG_DUTCH);
+        }
+
+        // 线性释放：记录释放时长，Take 时才开始计时
+        if self.instruction_data.vesting {
+            escrow.set_vesting_duration(self.instruction_data.vesting_duration);
+            escrow.set_flags(escrow.flags | Escrow::FLAG_VESTING);
+        }
+
+        // 三方托管：记录仲裁人
+        escrow.set_arbiter(self.instruction_data.arbiter.clone());
+
+        // 备选支付代币
+        self.instruction_data
+            .set_payment_options(escrow, self.accounts.mint_b.address())?;
+
+        // 撤回锁定期
+        self.instruction_data.set_refund_lock(escrow);
+
+        // 取消罚金
+        escrow.set_penalty(
+            self.instruction_data.penalty_bps,
+            self.instruction_data.penalty_recipient.clone(),
+        );
+
+        // 拍卖模式：receive 作为底价，初始化出价 PDA
+        if let (Some(bid), Some(bid_bump)) = (self.accounts.bid, self.bid_bump) {
+            escrow.set_flags(escrow.flags | Escrow::FLAG_AUCTION);
+
+            let mut bid_data = bid.try_borrow_mut()?;
+            Bid::init(bid_data.as_mut())?
+                .set_inner(self.accounts.escrow.address().clone(), [bid_bump]);
+        }
+
+        drop(data);
 
         // Transfer tokens to vault
-        Transfer {
-            from: self.accounts.maker_ata_a,
-            to: self.accounts.vault,
-            authority: self.accounts.maker,
-            amount: self.instruction_data.amount,
+        // pNFT 的代币账户处于冻结状态，只能通过 Metaplex Transfer 转入
+        if let Some(pnft) = &self.accounts.pnft {
+            pnft.transfer(
+                self.accounts.maker_ata_a,
+                self.accounts.maker,
+                self.accounts.vault,
+                self.accounts.escrow,
+                self.accounts.mint_a,
+                self.accounts.maker,
+                self.accounts.maker,
+                self.accounts.system_program,
+                self.accounts.token_program,
+                &[],
+            )?;
+        } else {
+            // WSOL：先把直接转入 maker ATA 的 lamports 计入余额
+            sync_native(self.accounts.maker_ata_a, self.accounts.mint_a)?;
+            TokenTransfer {
+                from: self.accounts.maker_ata_a,
+                mint: self.accounts.mint_a,
+                to: self.accounts.vault,
+                authority: self.accounts.maker,
+                amount: self.instruction_data.amount,
+                hook_accounts: self.accounts.hook_a,
+            }
+            .invoke()?;
         }
-        .invoke();
 
-        Ok(())
+        // 捆绑挂单：创建额外代币的 vault 并转入
+        if self.instruction_data.leg_count != 0 {
+            deposit_legs(
+                self.accounts.legs,
+                self.instruction_data.legs(),
+                self.accounts.maker,
+                self.accounts.escrow,
+                self.accounts.system_program,
+                self.accounts.token_program,
+            )?;
+        }
+
+        // Token-2022 转账手续费会让 vault 实际收到的数量少于转出的数量：
+        // Take / Refund 始终以 vault 余额为准，这里只要求确实存入了代币，并按实际收到的数量记录
+        let deposited = token_account(self.accounts.vault)?.amount();
+        if deposited == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let mut data = self.accounts.escrow.try_borrow_mut()?;
+        let escrow = Escrow::load_mut(data.as_mut())?;
+        escrow.set_amount(deposited);
+
+        // 捆绑挂单：按 vault 实际收到的数量记录每种额外代币
+        if self.instruction_data.leg_count != 0 {
+            for leg in self.accounts.legs.chunks_exact(LEG_ACCOUNTS) {
+                escrow.add_leg(leg[0].address().clone(), token_account(&leg[2])?.amount())?;
+            }
+        }
+
+        EscrowEvent::new(
+            EscrowEvent::CREATED,
+            self.accounts.escrow.address(),
+            escrow,
+            &escrow.taker,
+            deposited,
+            escrow.receive,
+            Clock::get()?.slot,
+        )
+        .emit(Some(self.accounts.config))?;
+
+        record_maker_stats(self.accounts.stats, MakerStats::record_created)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 64 字节的基础指令数据：seed 1、receive 1000、amount 500，没有过期时间与指定 taker
+    fn base_data() -> [u8; 64] {
+        let mut data = [0u8; 64];
+        data[0..8].copy_from_slice(&1u64.to_le_bytes());
+        data[8..16].copy_from_slice(&1_000u64.to_le_bytes());
+        data[16..24].copy_from_slice(&500u64.to_le_bytes());
+        data
+    }
+
+    /// 基础指令数据 + 荷兰拍选项（floor_price + decay_end）
+    fn dutch_data(floor_price: u64) -> [u8; 81] {
+        let mut data = [0u8; 81];
+        data[..64].copy_from_slice(&base_data());
+        data[64] = MakeInstructionData::OPTION_DUTCH;
+        data[65..73].copy_from_slice(&floor_price.to_le_bytes());
+        data[73..81].copy_from_slice(&100i64.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn dutch_floor_price_must_be_positive_and_below_receive() {
+        let floor_error = Some(ProgramError::from(EscrowError::InvalidFloorPrice));
+        assert_eq!(MakeInstructionData::try_from(&dutch_data(0)[..]).err(), floor_error);
+        assert_eq!(MakeInstructionData::try_from(&dutch_data(1_001)[..]).err(), floor_error);
+
+        let data = MakeInstructionData::try_from(&dutch_data(1)[..]).unwrap();
+        assert!(data.dutch);
+        assert_eq!((data.floor_price, data.decay_end), (1, 100));
+        let data = MakeInstructionData::try_from(&dutch_data(1_000)[..]).unwrap();
+        assert_eq!(data.floor_price, 1_000);
+    }
+
+    /// 基础指令数据 + 选项字节 + 选项参数，返回缓冲区与有效长度
+    fn with_options(options: u8, params: &[u8]) -> ([u8; MakeInstructionData::MAX_LEN], usize) {
+        let mut data = [0u8; MakeInstructionData::MAX_LEN];
+        data[..64].copy_from_slice(&base_data());
+        data[64] = options;
+        data[65..65 + params.len()].copy_from_slice(params);
+        (data, 65 + params.len())
+    }
+
+    fn parse(options: u8, params: &[u8]) -> Result<MakeInstructionData, ProgramError> {
+        let (data, len) = with_options(options, params);
+        MakeInstructionData::try_from(&data[..len])
+    }
+
+    #[test]
+    fn accepts_only_documented_base_lengths() {
+        let mut data = base_data();
+        data[24..32].copy_from_slice(&7i64.to_le_bytes());
+
+        let parsed = MakeInstructionData::try_from(&data[..24]).unwrap();
+        assert_eq!((parsed.seed, parsed.receive, parsed.amount), (1, 1_000, 500));
+        assert_eq!((parsed.expiry_timestamp, parsed.taker), (0, None));
+
+        let parsed = MakeInstructionData::try_from(&data[..32]).unwrap();
+        assert_eq!((parsed.expiry_timestamp, parsed.taker), (7, None));
+
+        // 全 0 的 taker 表示公开 Escrow
+        assert_eq!(MakeInstructionData::try_from(&data[..]).unwrap().taker, None);
+        data[32..64].copy_from_slice(&[9; 32]);
+        let parsed = MakeInstructionData::try_from(&data[..]).unwrap();
+        assert_eq!(parsed.taker, Some(Address::new_from_array([9; 32])));
+        assert!(!parsed.auction && !parsed.dutch && !parsed.strict);
+
+        for len in [0, 8, 23, 25, 40, 63] {
+            assert!(MakeInstructionData::try_from(&data[..len]).is_err(), "len {len}");
+        }
+    }
+
+    #[test]
+    fn rejects_conflicting_options() {
+        use MakeInstructionData as D;
+
+        for options in [
+            D::OPTION_AUCTION | D::OPTION_DUTCH,
+            D::OPTION_AUCTION | D::OPTION_VESTING,
+            D::OPTION_ARBITER | D::OPTION_AUCTION,
+            D::OPTION_ARBITER | D::OPTION_DUTCH,
+            D::OPTION_ARBITER | D::OPTION_VESTING,
+            D::OPTION_BUNDLE | D::OPTION_AUCTION,
+            D::OPTION_BUNDLE | D::OPTION_VESTING,
+            D::OPTION_BUNDLE | D::OPTION_ARBITER,
+            D::OPTION_PAYMENT_OPTIONS | D::OPTION_AUCTION,
+            D::OPTION_PAYMENT_OPTIONS | D::OPTION_DUTCH,
+            D::OPTION_PAYMENT_OPTIONS | D::OPTION_ARBITER,
+            D::OPTION_PENALTY | D::OPTION_AUCTION,
+        ] {
+            assert_eq!(parse(options, &[]).err(), Some(ProgramError::InvalidInstructionData), "options {options:#010b}");
+        }
+
+        // UI 报价不能用于拍卖与三方托管
+        assert!(parse(D::OPTION_AUCTION, &[]).unwrap().auction);
+        assert!(parse(D::OPTION_AUCTION, &[D::EXT_OPTION_UI_RECEIVE]).is_err());
+    }
+
+    #[test]
+    fn parses_params_in_option_bit_order() {
+        use MakeInstructionData as D;
+
+        // 荷兰拍、线性释放、撤回锁定期、取消罚金按选项位顺序排列，最后是扩展选项字节
+        let mut params = [0u8; 16 + 8 + 8 + 34 + 1];
+        params[0..8].copy_from_slice(&100u64.to_le_bytes());
+        params[8..16].copy_from_slice(&50i64.to_le_bytes());
+        params[16..24].copy_from_slice(&30i64.to_le_bytes());
+        params[24..32].copy_from_slice(&10i64.to_le_bytes());
+        params[32..34].copy_from_slice(&250u16.to_le_bytes());
+        params[34..66].copy_from_slice(&[7; 32]);
+        params[66] = D::EXT_OPTION_STRICT;
+
+        let options = D::OPTION_DUTCH | D::OPTION_VESTING | D::OPTION_REFUND_LOCK | D::OPTION_PENALTY;
+        let parsed = parse(options, &params).unwrap();
+        assert!(parsed.dutch && parsed.vesting && parsed.has_penalty() && parsed.strict);
+        assert!(!parsed.auction && !parsed.ui_receive);
+        assert_eq!((parsed.floor_price, parsed.decay_end), (100, 50));
+        assert_eq!(parsed.vesting_duration, 30);
+        assert_eq!(parsed.refund_locked_until, 10);
+        assert_eq!(parsed.penalty_bps, 250);
+        assert_eq!(parsed.penalty_recipient, Address::new_from_array([7; 32]));
+
+        // 参数缺少字节、扩展选项字节之后还有数据、未知的扩展选项位都被拒绝
+        assert!(parse(options, &params[..65]).is_err());
+        assert!(parse(options, &[&params[..], &[0]].concat()).is_err());
+        params[66] = 1 << 2;
+        assert!(parse(options, &params).is_err());
+    }
+
+    #[test]
+    fn bundle_and_payment_options_are_bounded() {
+        use MakeInstructionData as D;
+
+        let mut legs = [0u8; 1 + 8 * 2];
+        legs[0] = 2;
+        legs[1..9].copy_from_slice(&3u64.to_le_bytes());
+        legs[9..17].copy_from_slice(&4u64.to_le_bytes());
+        assert_eq!(parse(D::OPTION_BUNDLE, &legs).unwrap().legs(), &[3, 4]);
+
+        // 种类数为 0、超过上限，或数量为 0 的额外代币都被拒绝
+        assert!(parse(D::OPTION_BUNDLE, &[0]).is_err());
+        legs[0] = Escrow::MAX_LEGS as u8 + 1;
+        assert!(parse(D::OPTION_BUNDLE, &legs).is_err());
+        legs[0] = 2;
+        legs[9..17].copy_from_slice(&0u64.to_le_bytes());
+        assert!(parse(D::OPTION_BUNDLE, &legs).is_err());
+
+        let mut payment = [0u8; 1 + 40 * 2];
+        payment[0] = 2;
+        payment[1..33].copy_from_slice(&[5; 32]);
+        payment[33..41].copy_from_slice(&10u64.to_le_bytes());
+        payment[41..73].copy_from_slice(&[6; 32]);
+        payment[73..81].copy_from_slice(&20u64.to_le_bytes());
+        let parsed = parse(D::OPTION_PAYMENT_OPTIONS, &payment).unwrap();
+        assert_eq!(parsed.payment_options().len(), 2);
+        assert_eq!(parsed.payment_options()[1].1, 20);
+
+        // 备选支付代币不能重复，也不能是原生 SOL
+        payment[41..73].copy_from_slice(&[5; 32]);
+        assert!(parse(D::OPTION_PAYMENT_OPTIONS, &payment).is_err());
+        payment[41..73].copy_from_slice(NATIVE_MINT.as_ref());
+        assert!(parse(D::OPTION_PAYMENT_OPTIONS, &payment).is_err());
     }
 }
@@ -0,0 +1,108 @@
+//! MakeAuto 指令实现（由计数器生成种子的 Make）
+//!
+//! 本文件实现 `MakeAuto` 指令：
+//! - 读取 maker 的种子计数器 PDA（种子 `["counter", maker]`），以 `next_seed` 作为新 Escrow 的种子并递增
+//! - 计数器不存在时先创建（canonical bump，租金由 maker 支付），种子从 `MakerCounter::SEED_BASE` 开始
+//! - 之后与 `Make` 完全相同
+//!
+//! 账户列表：计数器 PDA，之后与 Make 完全相同。Escrow 地址必须是 `["escrow", maker, next_seed]` 派生的 PDA，
+//! 客户端需要先读取计数器（不存在时使用 `SEED_BASE`）再派生；两笔 MakeAuto 并发时后执行的一笔因地址不匹配失败。
+//! 指令数据：Make 的指令数据去掉开头 8 字节的 seed。
+//!
+use crate::errors::EscrowError;
+use crate::helper::ProgramAccount;
+use crate::make::{Make, MakeInstructionData};
+use crate::state::MakerCounter;
+use crate::ProgramAccountInit;
+use core::mem::size_of;
+use pinocchio::{cpi::Seed, error::ProgramError, AccountView, Address, ProgramResult};
+
+/// 取出计数器中的下一个种子，计数器不存在时先创建
+fn next_seed(maker: &AccountView, counter: &AccountView) -> Result<u64, ProgramError> {
+    if !counter.owned_by(&crate::ID) {
+        // 对应 Anchor 的 init_if_needed + seeds = [b"counter", maker.key().as_ref()]
+        let (counter_key, bump) = Address::find_program_address(
+            &[b"counter", maker.address().as_ref()],
+            &crate::ID,
+        );
+        if &counter_key != counter.address() {
+            return Err(EscrowError::InvalidCounterPda.into());
+        }
+
+        let bump_binding = [bump];
+        let counter_seeds = [
+            Seed::from(b"counter"),
+            Seed::from(maker.address().as_ref()),
+            Seed::from(&bump_binding),
+        ];
+        ProgramAccount::init::<MakerCounter>(maker, counter, counter_seeds.as_slice(), MakerCounter::LEN)?;
+
+        let mut data = counter.try_borrow_mut()?;
+        let state = MakerCounter::init(data.as_mut())?;
+        state.set_inner(maker.address().clone(), bump_binding);
+        return state.take_seed();
+    }
+
+    let mut data = counter.try_borrow_mut()?;
+    let state = MakerCounter::load_mut(data.as_mut())?;
+    if &state.maker != maker.address() {
+        return Err(EscrowError::InvalidCounterPda.into());
+    }
+    let counter_key = Address::create_program_address(
+        &[b"counter", maker.address().as_ref(), &state.bump],
+        &crate::ID,
+    )?;
+    if &counter_key != counter.address() {
+        return Err(EscrowError::InvalidCounterPda.into());
+    }
+
+    state.take_seed()
+}
+
+pub struct MakeAuto<'a> {
+    pub maker: &'a AccountView,          // maker（Make 账户列表的第一个账户）
+    pub counter: &'a AccountView,        // maker 的种子计数器 PDA
+    pub make_accounts: &'a [AccountView], // 交给 Make 的账户列表
+    pub data: &'a [u8],                  // 去掉 seed 的 Make 指令数据
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for MakeAuto<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let [counter, make_accounts @ ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        let [maker, ..] = make_accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // 拼接 seed 之后不能超过 Make 指令数据的最大长度
+        if size_of::<u64>() + data.len() > MakeInstructionData::MAX_LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self {
+            maker,
+            counter,
+            make_accounts,
+            data,
+        })
+    }
+}
+
+impl<'a> MakeAuto<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &31;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let seed = next_seed(self.maker, self.counter)?;
+
+        // 在栈上拼接 seed 与 Make 的其余指令数据，按 Make 的格式解析并执行
+        let len = size_of::<u64>() + self.data.len();
+        let mut buffer = [0u8; MakeInstructionData::MAX_LEN];
+        buffer[..size_of::<u64>()].copy_from_slice(&seed.to_le_bytes());
+        buffer[size_of::<u64>()..len].copy_from_slice(self.data);
+
+        Make::try_from((&buffer[..len], self.make_accounts))?.process()
+    }
+}
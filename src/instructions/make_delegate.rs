@@ -0,0 +1,208 @@
+//! MakeWithDelegate 指令实现（以委托授权代替存入 vault 创建 Escrow）
+//!
+//! 本文件实现 `MakeWithDelegate` 指令：
+//! - 创建 Escrow PDA（没有 vault）
+//! - 通过 Token Program 的 Approve 把 Escrow PDA 设为 maker Token A ATA 的 delegate，额度为 amount
+//! - 在 Escrow 中记录 `FLAG_DELEGATED` 与委托数量
+//!
+//! Token A 在成交前一直留在 maker 的 ATA 中，更接近限价单：
+//! - Take 时由 Escrow PDA 以 delegate 身份从 maker 的 ATA 拉取（Take 的 vault 账户位置传入 maker 的 Token A ATA）
+//! - maker 如果在成交前转走代币或撤销授权，Take 会失败，maker 可以随时 Refund
+//! - Refund 会撤销仍指向该 Escrow 的授权；CloseExpired 由第三方调用，只能关闭 Escrow，授权需要 maker 自行撤销
+//!
+//! 与 Make 相同，需要传入全局配置 PDA（最后一个账户），暂停时拒绝，委托额度必须在配置的范围内。
+//!
+//! 拍卖、线性释放、三方托管、捆绑挂单以及依赖 vault 的指令（TakePartial、Deposit、还价、TopUp 等）不支持委托模式。
+//!
+use crate::errors::EscrowError;
+use crate::helper::{
+    assert_writable, AssociatedTokenAccount, ConfigAccount, MintInterface, ProgramAccount, SignerAccount, SystemProgramCheck, NATIVE_MINT,
+};
+use crate::events::EscrowEvent;
+use crate::state::{derive_escrow_address, Config, Escrow, ESCROW_SEED};
+use crate::{AccountCheck, AssociatedTokenAccountCheck, MakeInstructionData, ProgramAccountInit};
+use pinocchio::{
+    cpi::Seed,
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    AccountView, Address, ProgramResult,
+};
+use pinocchio_token::instructions::Approve;
+
+pub struct MakeWithDelegateAccounts<'a> {
+    pub maker: &'a AccountView,
+    pub escrow: &'a AccountView,
+    pub mint_a: &'a AccountView,
+    pub mint_b: &'a AccountView,
+    pub maker_ata_a: &'a AccountView, // Maker 的 Token A ATA（Escrow PDA 成为它的 delegate）
+    pub system_program: &'a AccountView,
+    pub token_program: &'a AccountView,
+    pub config: &'a AccountView, // 全局配置 PDA（暂停开关与托管数量范围）
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for MakeWithDelegateAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [maker, escrow, mint_a, mint_b, maker_ata_a, system_program, token_program, config] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        SystemProgramCheck::check(system_program)?;
+
+        // Basic Accounts Checks
+        SignerAccount::check(maker)?;
+        ConfigAccount::check(config)?;
+        MintInterface::check(mint_a)?;
+        MintInterface::check(mint_b)?;
+        AssociatedTokenAccount::check(maker_ata_a, maker, mint_a, token_program)?;
+        assert_writable([maker, escrow, maker_ata_a, config])?;
+
+        // Return the accounts
+        Ok(Self {
+            maker,
+            escrow,
+            mint_a,
+            mint_b,
+            maker_ata_a,
+            system_program,
+            token_program,
+            config,
+        })
+    }
+}
+
+pub struct MakeWithDelegate<'a> {
+    pub accounts: MakeWithDelegateAccounts<'a>,
+    pub instruction_data: MakeInstructionData, // 与 Make 相同的指令数据，amount 为委托额度
+    pub bump: u8,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for MakeWithDelegate<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = MakeWithDelegateAccounts::try_from(accounts)?;
+
+        let instruction_data = MakeInstructionData::try_from(data)?;
+        MakeInstructionData::check_mint_pair(accounts.mint_a.address(), accounts.mint_b.address())?;
+        Config::load(&accounts.config.try_borrow()?)?.check_make(instruction_data.amount)?;
+
+        // 拍卖、线性释放、三方托管、捆绑挂单与取消罚金都需要 vault，委托模式不支持
+        if instruction_data.auction
+            || instruction_data.vesting
+            || instruction_data.arbiter != Address::default()
+            || instruction_data.leg_count != 0
+            || instruction_data.has_penalty()
+        {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        instruction_data.check_timestamps(Clock::get()?.unix_timestamp)?;
+        instruction_data.check_ui_receive(accounts.mint_b, Clock::get()?.unix_timestamp)?;
+        instruction_data.check_freeze_authority(&[accounts.mint_a, accounts.mint_b])?;
+
+        // Initialize the Escrow account
+        // 只接受 canonical bump 派生的地址，之后各指令用记录的 bump 校验 Escrow PDA
+        let (escrow_key, bump) = derive_escrow_address(accounts.maker.address(), instruction_data.seed);
+        if &escrow_key != accounts.escrow.address() {
+            return Err(EscrowError::InvalidEscrowPda.into());
+        }
+
+        let seed_binding = instruction_data.seed.to_le_bytes();
+        let bump_binding = [bump];
+        let escrow_seeds = [
+            Seed::from(ESCROW_SEED),
+            Seed::from(accounts.maker.address().as_ref()),
+            Seed::from(&seed_binding),
+            Seed::from(&bump_binding),
+        ];
+
+        ProgramAccount::init::<Escrow>(
+            accounts.maker,
+            accounts.escrow,
+            escrow_seeds.as_slice(),
+            Escrow::LEN,
+        )?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+            bump,
+        })
+    }
+}
+
+impl<'a> MakeWithDelegate<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &30;
+
+    pub fn process(&mut self) -> ProgramResult {
+        // Populate the escrow account
+        {
+            let mut data = self.accounts.escrow.try_borrow_mut()?;
+            let escrow = Escrow::init(data.as_mut())?;
+
+            escrow.set_inner(
+                self.instruction_data.seed,
+                self.accounts.maker.address().clone(),
+                self.accounts.mint_a.address().clone(),
+                self.accounts.mint_b.address().clone(),
+                self.instruction_data.receive,
+                self.instruction_data.expiry_timestamp,
+                self.bump,
+            );
+            escrow.set_taker(self.instruction_data.taker.clone());
+            escrow.set_vault(self.accounts.maker_ata_a.address().clone());
+            escrow.set_delegated_amount(self.instruction_data.amount);
+            escrow.set_amount(self.instruction_data.amount);
+            escrow.set_created(&Clock::get()?);
+            let mut flags = Escrow::FLAG_DELEGATED | self.instruction_data.extra_flags();
+            if self.accounts.mint_b.address() == &NATIVE_MINT {
+                flags |= Escrow::FLAG_NATIVE_B;
+            }
+            if self.instruction_data.dutch {
+                escrow.set_dutch(
+                    self.instruction_data.floor_price,
+                    Clock::get()?.unix_timestamp,
+                    self.instruction_data.decay_end,
+                );
+                flags |= Escrow::FLAG_DUTCH;
+            }
+            escrow.set_flags(flags);
+
+            // 备选支付代币
+            self.instruction_data
+                .set_payment_options(escrow, self.accounts.mint_b.address())?;
+
+            // 撤回锁定期
+            self.instruction_data.set_refund_lock(escrow);
+        }
+
+        // Approve the Escrow PDA as delegate of the Maker's Token A ATA
+        // 同一个 ATA 只能有一个 delegate，新的授权会覆盖之前的授权
+        Approve {
+            source: self.accounts.maker_ata_a,
+            delegate: self.accounts.escrow,
+            authority: self.accounts.maker,
+            amount: self.instruction_data.amount,
+        }
+        .invoke()?;
+
+        let data = self.accounts.escrow.try_borrow()?;
+        let escrow = Escrow::load(&data)?;
+        EscrowEvent::new(
+            EscrowEvent::CREATED,
+            self.accounts.escrow.address(),
+            escrow,
+            &escrow.taker,
+            self.instruction_data.amount,
+            escrow.receive,
+            Clock::get()?.slot,
+        )
+        .emit(Some(self.accounts.config))?;
+
+        Ok(())
+    }
+}
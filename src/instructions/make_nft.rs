@@ -0,0 +1,291 @@
+//! MakeNft 指令实现（以 NFT 作为 Token A 创建 Escrow）
+//!
+//! 本文件实现 `MakeNft` 指令：
+//! - 校验 mint_a 是 NFT：supply 为 1、decimals 为 0
+//! - 可选：校验 Metaplex 元数据 PDA（种子 `["metadata", METADATA_PROGRAM_ID, mint_a]`）中
+//!   记录了已验证的指定合集，防止用同名的仿冒 NFT 挂单
+//! - 可选：启用版税，Take 时从 maker 所得中按元数据的 `seller_fee_basis_points` 与创作者分成比例支付版税
+//! - 之后与 `Make` 完全相同（amount 必须为 1），并在 Escrow 中记录 `FLAG_NFT`
+//!
+//! 账户列表：元数据 PDA，之后与 Make 完全相同（不校验合集、不启用版税时元数据位置可以传入任意账户）。
+//! 指令数据：合集地址（32 字节，全 0 表示不校验）+ 版税开关（1 字节），之后与 Make 完全相同。
+//!
+//! Take 对 NFT 托管按数量 1 转出；NFT 不可分割，TakePartial、TopUp、WithdrawPartial 与线性释放都不支持。
+//! Programmable NFT（pNFT）需要通过 Metaplex Transfer 转移，使用 `MakePnft`（见 pnft.rs）。
+//!
+use crate::errors::EscrowError;
+use crate::helper::METADATA_PROGRAM_ID;
+use crate::state::Escrow;
+use crate::Make;
+use core::mem::size_of;
+use pinocchio::{error::ProgramError, AccountView, Address, ProgramResult};
+use pinocchio_token::state::Mint;
+
+/// Metaplex 元数据账户的 key（MetadataV1）
+const METADATA_V1_KEY: u8 = 4;
+
+/// 按 Borsh 布局顺序读取 Metaplex 元数据
+struct MetadataReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> MetadataReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], ProgramError> {
+        let (head, rest) = self
+            .data
+            .split_at_checked(len)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        self.data = rest;
+        Ok(head)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, ProgramError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn read_u32(&mut self) -> Result<u32, ProgramError> {
+        Ok(u32::from_le_bytes(self.take(size_of::<u32>())?.try_into().unwrap()))
+    }
+
+    /// 跳过 Borsh 字符串（u32 长度 + 内容）
+    fn skip_string(&mut self) -> Result<(), ProgramError> {
+        let len = self.read_u32()? as usize;
+        self.take(len).map(|_| ())
+    }
+
+    /// 跳过 Option<T>（1 字节标签，Some 时跟随 `len` 字节）
+    fn skip_option(&mut self, len: usize) -> Result<(), ProgramError> {
+        if self.read_u8()? != 0 {
+            self.take(len)?;
+        }
+        Ok(())
+    }
+}
+
+/// 元数据中与托管相关的字段
+pub struct Metadata<'a> {
+    pub seller_fee_basis_points: u16, // 版税比例
+    creators: &'a [u8],               // 创作者列表，每个为 address(32) + verified(1) + share(1)
+    pub collection: Option<Address>,  // 已验证的合集（没有合集或合集未验证时为 None）
+}
+
+impl Metadata<'_> {
+    /// 每个创作者一条记录的长度
+    const CREATOR_LEN: usize = size_of::<Address>() + 2;
+
+    /// 创作者数量
+    pub fn creator_count(&self) -> usize {
+        self.creators.len() / Self::CREATOR_LEN
+    }
+
+    /// 依次返回每个创作者的地址与分成比例（百分比）
+    pub fn creators(&self) -> impl Iterator<Item = (&[u8], u8)> {
+        self.creators
+            .chunks_exact(Self::CREATOR_LEN)
+            .map(|creator| (&creator[..32], creator[33]))
+    }
+}
+
+/// 读取 mint 的 Metaplex 元数据
+///
+/// 布局：key | update_authority | mint | name | symbol | uri | seller_fee_basis_points |
+/// creators | primary_sale_happened | is_mutable | edition_nonce | token_standard | collection
+pub fn parse_metadata<'a>(data: &'a [u8], mint: &Address) -> Result<Metadata<'a>, ProgramError> {
+    let mut reader = MetadataReader { data };
+
+    if reader.read_u8()? != METADATA_V1_KEY {
+        return Err(EscrowError::InvalidAccountData.into());
+    }
+    reader.take(size_of::<Address>())?; // update_authority
+    if reader.take(size_of::<Address>())? != mint.as_ref() {
+        return Err(EscrowError::InvalidMetadata.into());
+    }
+    reader.skip_string()?; // name
+    reader.skip_string()?; // symbol
+    reader.skip_string()?; // uri
+    let seller_fee_basis_points =
+        u16::from_le_bytes(reader.take(size_of::<u16>())?.try_into().unwrap());
+
+    // creators: Option<Vec<Creator>>
+    let creators = if reader.read_u8()? != 0 {
+        let count = reader.read_u32()? as usize;
+        let len = count
+            .checked_mul(Metadata::CREATOR_LEN)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        reader.take(len)?
+    } else {
+        &[]
+    };
+
+    reader.take(2)?; // primary_sale_happened + is_mutable
+    reader.skip_option(1)?; // edition_nonce
+    reader.skip_option(1)?; // token_standard
+
+    // collection: Option<Collection>，Collection 为 verified(1) + key(32)
+    let collection = if reader.read_u8()? != 0 {
+        let verified = reader.read_u8()? != 0;
+        let key: [u8; 32] = reader.take(size_of::<Address>())?.try_into().unwrap();
+        verified.then(|| Address::new_from_array(key))
+    } else {
+        None
+    };
+
+    Ok(Metadata {
+        seller_fee_basis_points,
+        creators,
+        collection,
+    })
+}
+
+/// 校验 `metadata` 是 Metaplex 为 `mint` 派生的元数据 PDA
+pub fn check_metadata(metadata: &AccountView, mint: &AccountView) -> ProgramResult {
+    let (metadata_key, _) = Address::find_program_address(
+        &[
+            b"metadata",
+            METADATA_PROGRAM_ID.as_ref(),
+            mint.address().as_ref(),
+        ],
+        &METADATA_PROGRAM_ID,
+    );
+    if &metadata_key != metadata.address() || !metadata.owned_by(&METADATA_PROGRAM_ID) {
+        return Err(EscrowError::InvalidMetadata.into());
+    }
+
+    Ok(())
+}
+
+// ========== 指令数据结构 ==========
+pub struct MakeNftInstructionData {
+    pub collection: Address, // 要求的合集（全 0 表示不校验）
+    pub royalties: bool,     // 成交时是否按元数据向创作者支付版税
+}
+
+impl<'a> TryFrom<&'a [u8]> for MakeNftInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len() != Self::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let royalties = match data[32] {
+            0 => false,
+            1 => true,
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+
+        Ok(Self {
+            collection: Address::new_from_array(data[0..32].try_into().unwrap()),
+            royalties,
+        })
+    }
+}
+
+impl MakeNftInstructionData {
+    /// 合集地址 + 版税开关的长度
+    const LEN: usize = size_of::<Address>() + size_of::<u8>();
+
+    /// 指令数据为合集地址与版税开关，之后是 Make 的指令数据
+    pub fn split(data: &[u8]) -> Result<(Self, &[u8]), ProgramError> {
+        let (nft_data, make_data) = data
+            .split_at_checked(Self::LEN)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+
+        Ok((Self::try_from(nft_data)?, make_data))
+    }
+}
+
+/// 账户列表：元数据 PDA，之后与 Make 完全相同
+pub struct MakeNft<'a> {
+    pub metadata: &'a AccountView, // Metaplex 元数据 PDA（不校验合集时不使用）
+    pub make: Make<'a>,            // 校验通过后执行的 Make
+    pub royalties: bool,           // 成交时是否支付版税
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for MakeNft<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let [metadata, rest @ ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Self::with_metadata(metadata, data, rest)
+    }
+}
+
+impl<'a> MakeNft<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &40;
+
+    /// 元数据账户与 Make 的账户分开传入（MakePnft 的元数据位于 pNFT 账户组中）
+    pub fn with_metadata(
+        metadata: &'a AccountView,
+        data: &'a [u8],
+        accounts: &'a [AccountView],
+    ) -> Result<Self, ProgramError> {
+        let (instruction_data, make_data) = MakeNftInstructionData::split(data)?;
+        let make = Make::try_from((make_data, accounts))?;
+
+        // NFT 不可分割：只能整体托管 1 个，不支持线性释放
+        if make.instruction_data.amount != 1 {
+            return Err(EscrowError::AmountMismatch.into());
+        }
+        if make.instruction_data.vesting {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Self::check_nft(make.accounts.mint_a, metadata, &instruction_data)?;
+
+        Ok(Self {
+            metadata,
+            make,
+            royalties: instruction_data.royalties,
+        })
+    }
+
+    /// 校验 mint_a 为 NFT，并按需校验合集
+    fn check_nft(
+        mint_a: &AccountView,
+        metadata: &AccountView,
+        instruction_data: &MakeNftInstructionData,
+    ) -> ProgramResult {
+        // supply 为 1、decimals 为 0（Token-2022 的 Mint 前 82 字节与原版一致）
+        {
+            let data = mint_a.try_borrow()?;
+            let mint = unsafe { Mint::from_bytes_unchecked(&data) };
+            if mint.supply() != 1 || mint.decimals() != 0 {
+                return Err(EscrowError::InvalidAccountData.into());
+            }
+        }
+
+        let check_collection = instruction_data.collection != Address::default();
+        if !check_collection && !instruction_data.royalties {
+            return Ok(());
+        }
+
+        // 元数据必须是 Metaplex 为该 mint 派生的 PDA
+        check_metadata(metadata, mint_a)?;
+
+        let data = metadata.try_borrow()?;
+        let metadata = parse_metadata(&data, mint_a.address())?;
+        if check_collection && metadata.collection.as_ref() != Some(&instruction_data.collection) {
+            return Err(EscrowError::CollectionMismatch.into());
+        }
+
+        Ok(())
+    }
+
+    pub fn process(&mut self) -> ProgramResult {
+        self.make.process()?;
+
+        let mut data = self.make.accounts.escrow.try_borrow_mut()?;
+        let escrow = Escrow::load_mut(data.as_mut())?;
+        let mut flags = escrow.flags | Escrow::FLAG_NFT;
+        if self.royalties {
+            flags |= Escrow::FLAG_ROYALTIES;
+        }
+        escrow.set_flags(flags);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,187 @@
+//! MakeSol 指令实现（以原生 SOL 作为 Token A 创建 Escrow）
+//!
+//! 本文件实现 `MakeSol` 指令：
+//! - 创建 Escrow PDA
+//! - 将 Maker 的 lamports 直接转入 Escrow PDA（没有 vault，也无需包装成 WSOL）
+//! - 在 Escrow 中记录 `FLAG_NATIVE_A`，mint_a 记录为 `NATIVE_MINT`
+//!
+//! Take / Refund / CloseExpired 会根据该标志位直接划转 lamports，而不是调用 Token Program。
+//! 与 Make 相同，需要传入全局配置 PDA（最后一个账户），暂停时拒绝，lamports 数量必须在配置的范围内。
+//!
+use crate::errors::EscrowError;
+use crate::helper::{assert_writable, ConfigAccount, MintInterface, ProgramAccount, SignerAccount, SystemProgramCheck, NATIVE_MINT};
+use crate::events::EscrowEvent;
+use crate::state::{derive_escrow_address, Config, Escrow, ESCROW_SEED};
+use crate::{AccountCheck, MakeInstructionData, ProgramAccountInit};
+use pinocchio::{
+    cpi::Seed,
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    AccountView, Address, ProgramResult,
+};
+use pinocchio_system::instructions::Transfer;
+
+pub struct MakeSolAccounts<'a> {
+    pub maker: &'a AccountView,
+    pub escrow: &'a AccountView,
+    pub mint_b: &'a AccountView,
+    pub system_program: &'a AccountView,
+    pub config: &'a AccountView, // 全局配置 PDA（暂停开关与托管数量范围）
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for MakeSolAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [maker, escrow, mint_b, system_program, config] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        SystemProgramCheck::check(system_program)?;
+
+        // Basic Accounts Checks
+        SignerAccount::check(maker)?;
+        ConfigAccount::check(config)?;
+        MintInterface::check(mint_b)?;
+        assert_writable([maker, escrow, config])?;
+
+        // Return the accounts
+        Ok(Self {
+            maker,
+            escrow,
+            mint_b,
+            system_program,
+            config,
+        })
+    }
+}
+
+pub struct MakeSol<'a> {
+    pub accounts: MakeSolAccounts<'a>,
+    pub instruction_data: MakeInstructionData, // 与 Make 相同的指令数据，amount 单位为 lamports
+    pub bump: u8,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for MakeSol<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = MakeSolAccounts::try_from(accounts)?;
+
+        let instruction_data = MakeInstructionData::try_from(data)?;
+        MakeInstructionData::check_mint_pair(&NATIVE_MINT, accounts.mint_b.address())?;
+        Config::load(&accounts.config.try_borrow()?)?.check_make(instruction_data.amount)?;
+
+        // 拍卖、线性释放、三方托管、捆绑挂单与取消罚金都需要 Token A vault，原生 SOL Escrow 不支持
+        if instruction_data.auction
+            || instruction_data.vesting
+            || instruction_data.arbiter != Address::default()
+            || instruction_data.leg_count != 0
+            || instruction_data.has_penalty()
+        {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        instruction_data.check_timestamps(Clock::get()?.unix_timestamp)?;
+        instruction_data.check_ui_receive(accounts.mint_b, Clock::get()?.unix_timestamp)?;
+        instruction_data.check_freeze_authority(&[accounts.mint_b])?;
+
+        // Initialize the Escrow account
+        // 只接受 canonical bump 派生的地址，之后各指令用记录的 bump 校验 Escrow PDA
+        let (escrow_key, bump) = derive_escrow_address(accounts.maker.address(), instruction_data.seed);
+        if &escrow_key != accounts.escrow.address() {
+            return Err(EscrowError::InvalidEscrowPda.into());
+        }
+
+        let seed_binding = instruction_data.seed.to_le_bytes();
+        let bump_binding = [bump];
+        let escrow_seeds = [
+            Seed::from(ESCROW_SEED),
+            Seed::from(accounts.maker.address().as_ref()),
+            Seed::from(&seed_binding),
+            Seed::from(&bump_binding),
+        ];
+
+        ProgramAccount::init::<Escrow>(
+            accounts.maker,
+            accounts.escrow,
+            escrow_seeds.as_slice(),
+            Escrow::LEN,
+        )?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+            bump,
+        })
+    }
+}
+
+impl<'a> MakeSol<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &6;
+
+    pub fn process(&mut self) -> ProgramResult {
+        // Populate the escrow account
+        {
+            let mut data = self.accounts.escrow.try_borrow_mut()?;
+            let escrow = Escrow::init(data.as_mut())?;
+
+            escrow.set_inner(
+                self.instruction_data.seed,
+                self.accounts.maker.address().clone(),
+                NATIVE_MINT,
+                self.accounts.mint_b.address().clone(),
+                self.instruction_data.receive,
+                self.instruction_data.expiry_timestamp,
+                self.bump,
+            );
+            escrow.set_taker(self.instruction_data.taker.clone());
+            escrow.set_amount(self.instruction_data.amount);
+            escrow.set_created(&Clock::get()?);
+            let mut flags = Escrow::FLAG_NATIVE_A | self.instruction_data.extra_flags();
+            if self.accounts.mint_b.address() == &NATIVE_MINT {
+                flags |= Escrow::FLAG_NATIVE_B;
+            }
+            if self.instruction_data.dutch {
+                escrow.set_dutch(
+                    self.instruction_data.floor_price,
+                    Clock::get()?.unix_timestamp,
+                    self.instruction_data.decay_end,
+                );
+                flags |= Escrow::FLAG_DUTCH;
+            }
+            escrow.set_flags(flags);
+
+            // 备选支付代币
+            self.instruction_data
+                .set_payment_options(escrow, self.accounts.mint_b.address())?;
+
+            // 撤回锁定期
+            self.instruction_data.set_refund_lock(escrow);
+        }
+
+        // Deposit lamports into the escrow PDA
+        // Escrow 的余额 = 租金豁免额 + 存入的 SOL
+        Transfer {
+            from: self.accounts.maker,
+            to: self.accounts.escrow,
+            lamports: self.instruction_data.amount,
+        }
+        .invoke()?;
+
+        let data = self.accounts.escrow.try_borrow()?;
+        let escrow = Escrow::load(&data)?;
+        EscrowEvent::new(
+            EscrowEvent::CREATED,
+            self.accounts.escrow.address(),
+            escrow,
+            &escrow.taker,
+            self.instruction_data.amount,
+            escrow.receive,
+            Clock::get()?.slot,
+        )
+        .emit(Some(self.accounts.config))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,136 @@
+//! InitMakerStats 指令实现（maker 统计 PDA）
+//!
+//! 本文件实现 `InitMakerStats` 指令以及 Make / Take / Refund 共用的统计辅助函数：
+//! - `InitMakerStats`：maker 创建自己的统计 PDA（种子 `["stats", maker]`），记录 maker 地址与 bump
+//! - Make / Take / Refund 在账户列表最后传入 maker 的统计 PDA 时，分别累加创建、成交、取消次数，
+//!   Take 还按 mint_a 累加成交的 Token A 数量（最多 `MakerStats::MAX_MINTS` 种 Mint）
+//!
+//! 统计账户是可选的：不传入时指令行为不变。最后一个账户由本程序拥有且长度为 `MakerStats::LEN` 时视为统计账户，
+//! 此时必须是该 Escrow 的 maker 的统计 PDA，否则指令失败。
+//! Take 的 exact-in 模式按部分成交处理，不更新统计。
+//!
+use crate::errors::EscrowError;
+use crate::helper::{MakerStatsAccount, ProgramAccount, SignerAccount, SystemProgramCheck};
+use crate::state::MakerStats;
+use crate::{AccountCheck, ProgramAccountInit};
+use pinocchio::{cpi::Seed, error::ProgramError, AccountView, Address, ProgramResult};
+
+/// 拆出账户列表最后的统计 PDA（不是统计账户时原样返回），并验证它属于 `maker`
+pub fn split_maker_stats<'a>(
+    maker: &Address,
+    accounts: &'a [AccountView],
+) -> Result<(Option<&'a AccountView>, &'a [AccountView]), ProgramError> {
+    let Some((stats, rest)) = accounts.split_last() else {
+        return Ok((None, accounts));
+    };
+    if MakerStatsAccount::check(stats).is_err() {
+        return Ok((None, accounts));
+    }
+
+    let data = stats.try_borrow()?;
+    let state = MakerStats::load(&data)?;
+    if &state.maker != maker {
+        return Err(EscrowError::InvalidStatsPda.into());
+    }
+
+    let stats_key = Address::create_program_address(
+        &[b"stats", maker.as_ref(), &state.bump],
+        &crate::ID,
+    )?;
+    if &stats_key != stats.address() {
+        return Err(EscrowError::InvalidStatsPda.into());
+    }
+
+    Ok((Some(stats), rest))
+}
+
+/// 传入了统计 PDA 时更新统计
+pub fn record_maker_stats(
+    stats: Option<&AccountView>,
+    record: impl FnOnce(&mut MakerStats),
+) -> ProgramResult {
+    if let Some(stats) = stats {
+        let mut data = stats.try_borrow_mut()?;
+        record(MakerStats::load_mut(data.as_mut())?);
+    }
+
+    Ok(())
+}
+
+// ========== 账户结构 ==========
+pub struct InitMakerStatsAccounts<'a> {
+    pub maker: &'a AccountView,          // 统计所属的 maker（必须是签名者，支付租金）
+    pub stats: &'a AccountView,          // 统计 PDA 账户
+    pub system_program: &'a AccountView, // System Program
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for InitMakerStatsAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [maker, stats, system_program] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        SystemProgramCheck::check(system_program)?;
+
+        // 账户基础校验
+        SignerAccount::check(maker)?;
+
+        Ok(Self {
+            maker,
+            stats,
+            system_program,
+        })
+    }
+}
+
+// ========== 指令数据结构（InitMakerStats 无需额外数据）==========
+pub struct InitMakerStats<'a> {
+    pub accounts: InitMakerStatsAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for InitMakerStats<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = InitMakerStatsAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> InitMakerStats<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &45;
+
+    pub fn process(&mut self) -> ProgramResult {
+        // 对应 Anchor 的 init + seeds = [b"stats", maker.key().as_ref()]
+        let (stats_key, bump) = Address::find_program_address(
+            &[b"stats", self.accounts.maker.address().as_ref()],
+            &crate::ID,
+        );
+        if &stats_key != self.accounts.stats.address() {
+            return Err(EscrowError::InvalidStatsPda.into());
+        }
+
+        let bump_binding = [bump];
+        let stats_seeds = [
+            Seed::from(b"stats"),
+            Seed::from(self.accounts.maker.address().as_ref()),
+            Seed::from(&bump_binding),
+        ];
+
+        ProgramAccount::init::<MakerStats>(
+            self.accounts.maker,
+            self.accounts.stats,
+            stats_seeds.as_slice(),
+            MakerStats::LEN,
+        )?;
+
+        let mut data = self.accounts.stats.try_borrow_mut()?;
+        MakerStats::init(data.as_mut())?
+            .set_inner(self.accounts.maker.address().clone(), bump_binding);
+
+        Ok(())
+    }
+}
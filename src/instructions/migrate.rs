@@ -0,0 +1,187 @@
+//! Migrate 指令实现（把旧版本布局的 Escrow 升级到当前布局）
+//!
+//! 本文件实现 `Migrate` 指令：
+//! - Escrow 必须由本程序拥有且带有账户判别器，版本号低于 `Escrow::CURRENT_VERSION`
+//! - 账户数据短于当前布局时，通过 `ProgramAccount::realloc` 由 payer 补足租金后扩容到 `Escrow::LEN`（新增字段为全 0）
+//! - 按版本号逐级执行升级步骤，最后写入当前版本号
+//!
+//! 各版本的升级步骤：
+//! - 1 → 2：记录 vault 地址。非原生 SOL 托管需要在 System Program 之后依次传入 vault
+//!   （委托模式为 maker 的 Token A ATA）、mint_a 与 mint_a 所属的 Token Program
+//! - 2 → 3：按当前托管的 Token A 记录 `amount`，已领取的线性释放数量计入 `filled`。
+//!   非原生 SOL、非委托模式的托管需要传入 vault，位置与 1 → 2 相同
+//! - 3 → 4：创建时间无法追溯，`created_at` 保持 0
+//! - 4 → 5：status 移到版本号之后的固定偏移，原位置清零
+//! - 5 → 6：按 taker 是否为全 0 记录 `has_taker`
+//! - 6 → 7：新增 `revision`，从 0 开始
+//!
+//! 升级完成后记录当前 slot 为 `updated_slot`。
+//!
+//! 版本号位于判别器之后的固定偏移，各版本间保持不变；`Escrow::load*` 遇到旧版本返回 `OutdatedVersion`，
+//! 因此程序升级后，未成交的旧 Escrow 需要先执行 Migrate 才能继续使用。任何人都可以支付租金为 Escrow 升级，
+//! 升级不会改变交易条款。
+//!
+use crate::errors::EscrowError;
+use crate::helper::{check_token_program, token_account, ProgramAccount, SignerAccount, SystemProgramCheck};
+use crate::state::{derive_vault_address, Escrow};
+use crate::{AccountCheck, AccountRealloc};
+use pinocchio::{
+    error::ProgramError,
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
+    AccountView, Address, ProgramResult,
+};
+
+// ========== 账户结构 ==========
+pub struct MigrateAccounts<'a> {
+    pub payer: &'a AccountView,          // 支付扩容所需租金的账户（必须是签名者）
+    pub escrow: &'a AccountView,         // 待升级的 Escrow PDA 账户
+    pub system_program: &'a AccountView, // System Program
+    pub rest: &'a [AccountView],         // 升级步骤需要的额外账户（见文件头说明）
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for MigrateAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [payer, escrow, system_program, rest @ ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        SystemProgramCheck::check(system_program)?;
+
+        // 账户基础校验（旧版本的长度与当前布局不同，只校验 owner）
+        SignerAccount::check(payer)?;
+        if !escrow.owned_by(&crate::ID) {
+            return Err(EscrowError::InvalidOwner.into());
+        }
+
+        Ok(Self {
+            payer,
+            escrow,
+            system_program,
+            rest,
+        })
+    }
+}
+
+// ========== 指令数据结构（Migrate 无需额外数据）==========
+pub struct Migrate<'a> {
+    pub accounts: MigrateAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for Migrate<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = MigrateAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> Migrate<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &44;
+
+    /// 把 `version` 版本的数据升级到下一个版本
+    ///
+    /// 调用前账户已扩容到当前长度，新增字段为全 0；需要填充或转换字段的版本在这里添加对应的分支。
+    fn upgrade(&self, data: &mut [u8], version: u8) -> Result<u8, ProgramError> {
+        let escrow = Escrow::load_mut_unchecked(data)?;
+        match version {
+            1 => {
+                if !escrow.is_native_a() {
+                    escrow.set_vault(self.vault_address(escrow)?);
+                }
+                Ok(2)
+            }
+            2 => {
+                // 版本 5 之前 status 存放在 legacy_status 中
+                let taken = escrow.legacy_status == Escrow::STATUS_FILLED;
+                let filled = if taken { escrow.claimed } else { 0 };
+                let held = self.held_amount(escrow)?;
+                escrow.set_amount(held.checked_add(filled).ok_or(EscrowError::MathOverflow)?);
+                escrow.set_filled(filled);
+                Ok(3)
+            }
+            3 => Ok(4),
+            4 => {
+                escrow.set_status(escrow.legacy_status);
+                escrow.legacy_status = 0;
+                Ok(5)
+            }
+            5 => {
+                let taker = (escrow.taker != Address::default()).then(|| escrow.taker.clone());
+                escrow.set_taker(taker);
+                Ok(6)
+            }
+            6 => Ok(7),
+            _ => Err(EscrowError::InvalidAccountData.into()),
+        }
+    }
+
+    /// 1 → 2：校验传入的 vault 是 Escrow 托管 Token A 的 ATA（委托模式为 maker 的 ATA）
+    fn vault_address(&self, escrow: &Escrow) -> Result<Address, ProgramError> {
+        let [vault, mint_a, token_program, ..] = self.accounts.rest else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        if mint_a.address() != &escrow.mint_a {
+            return Err(EscrowError::InvalidMint.into());
+        }
+        check_token_program(mint_a, token_program)?;
+
+        let authority = if escrow.is_delegated() {
+            &escrow.maker
+        } else {
+            self.accounts.escrow.address()
+        };
+        let ata = derive_vault_address(authority, mint_a.address(), token_program.address());
+        if &ata != vault.address() {
+            return Err(EscrowError::InvalidVault.into());
+        }
+
+        Ok(ata)
+    }
+
+    /// 2 → 3：当前托管中的 Token A 数量
+    fn held_amount(&self, escrow: &Escrow) -> Result<u64, ProgramError> {
+        if escrow.is_delegated() {
+            return Ok(escrow.delegated_amount);
+        }
+        if escrow.is_native_a() {
+            // 扩容时已补足当前长度的租金豁免额
+            let rent_exempt = Rent::get()?.try_minimum_balance(Escrow::LEN)?;
+            return Ok(self.accounts.escrow.lamports().saturating_sub(rent_exempt));
+        }
+
+        let vault = self.accounts.rest.first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        if vault.address() != &escrow.vault {
+            return Err(EscrowError::InvalidVault.into());
+        }
+        Ok(token_account(vault)?.amount())
+    }
+
+    pub fn process(&mut self) -> ProgramResult {
+        let version = {
+            let data = self.accounts.escrow.try_borrow()?;
+            Escrow::stored_version(&data).ok_or(EscrowError::InvalidAccountData)?
+        };
+        if version >= Escrow::CURRENT_VERSION {
+            return Err(EscrowError::InvalidAccountData.into());
+        }
+
+        // 扩容到当前布局，并由 payer 补足两种长度的租金豁免额之差
+        if self.accounts.escrow.data_len() < Escrow::LEN {
+            ProgramAccount::realloc(self.accounts.escrow, self.accounts.payer, Escrow::LEN)?;
+        }
+
+        let mut data = self.accounts.escrow.try_borrow_mut()?;
+        let mut version = version;
+        while version < Escrow::CURRENT_VERSION {
+            version = self.upgrade(&mut data, version)?;
+        }
+        data[Escrow::VERSION_OFFSET] = version;
+        Escrow::load_mut(data.as_mut())?.touch(Clock::get()?.slot);
+
+        Ok(())
+    }
+}
@@ -1,14 +1,80 @@
 //! instructions 模块汇总
 //!
-//! 本文件用于声明并导出子模块：`make`、`take`、`refund`、`helper`。
+//! 本文件用于声明并导出子模块：`make`、`take`、`take_partial`、`refund`、`close_expired`、`update`、`make_sol`、`whitelist`、`deposit`、`settle`、`auction`、`refund_batch`、`counter_offer`、`config`、`claim`、`arbiter`、`top_up`、`withdraw_partial`、`transfer_maker`、`bundle`、`crowdfill`、`make_delegate`、`make_auto`、`commit_reveal`、`renew`、`sweep`、`skim`、`view`、`make_nft`、`pnft`、`migrate`、`maker_stats`、`fee_vault`、`version`、`events`、`sighash`、`helper`。
 //! 这样顶层 `lib.rs` 可以通过 `instructions::*` 直接访问各指令实现。
 // 1. 声明子模块
 pub mod make;
 pub mod take;
+pub mod take_partial;
 pub mod refund;
+pub mod close_expired;
+pub mod update;
+pub mod make_sol;
+pub mod whitelist;
+pub mod deposit;
+pub mod settle;
+pub mod auction;
+pub mod refund_batch;
+pub mod counter_offer;
+pub mod config;
+pub mod claim;
+pub mod arbiter;
+pub mod top_up;
+pub mod withdraw_partial;
+pub mod transfer_maker;
+pub mod bundle;
+pub mod crowdfill;
+pub mod make_delegate;
+pub mod make_auto;
+pub mod commit_reveal;
+pub mod renew;
+pub mod sweep;
+pub mod skim;
+pub mod view;
+pub mod make_nft;
+pub mod pnft;
+pub mod migrate;
+pub mod maker_stats;
+pub mod fee_vault;
+pub mod version;
+pub mod events;
+pub mod sighash;
 pub mod helper;
 // 2. 导出子模块内容，方便外部调用
 pub use make::*;
 pub use take::*;
+pub use take_partial::*;
 pub use refund::*;
+pub use close_expired::*;
+pub use update::*;
+pub use make_sol::*;
+pub use whitelist::*;
+pub use deposit::*;
+pub use settle::*;
+pub use auction::*;
+pub use refund_batch::*;
+pub use counter_offer::*;
+pub use config::*;
+pub use claim::*;
+pub use arbiter::*;
+pub use top_up::*;
+pub use withdraw_partial::*;
+pub use transfer_maker::*;
+pub use bundle::*;
+pub use crowdfill::*;
+pub use make_delegate::*;
+pub use make_auto::*;
+pub use commit_reveal::*;
+pub use renew::*;
+pub use sweep::*;
+pub use skim::*;
+pub use view::*;
+pub use make_nft::*;
+pub use pnft::*;
+pub use migrate::*;
+pub use maker_stats::*;
+pub use fee_vault::*;
+pub use version::*;
+pub use events::*;
+pub use sighash::*;
 pub use helper::*;
\ No newline at end of file
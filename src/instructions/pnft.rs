@@ -0,0 +1,243 @@
+//! MakePnft / TakePnft / RefundPnft 指令实现（Programmable NFT 托管）
+//!
+//! pNFT 的代币账户始终处于冻结状态，转账受 Metaplex 授权规则（auth rules）约束，
+//! Token Program 的 Transfer 会直接失败，必须通过 Metaplex Token Metadata 的 Transfer CPI 转移。
+//!
+//! 三个指令的账户列表都是 pNFT 账户组（`PNFT_ACCOUNTS` 个，见 `PnftAccounts`），之后分别与
+//! MakeNft（去掉元数据账户）、Take、Refund 完全相同；指令数据也分别与它们相同：
+//! - `MakePnft`：按 MakeNft 的规则校验后创建 Escrow，NFT 通过 Metaplex 转入 vault，并记录 `FLAG_PNFT`
+//! - `TakePnft`：按 Take 的规则成交，NFT 通过 Metaplex 从 vault 转给 taker（代币记录租金由 payer 支付）
+//! - `RefundPnft`：按 Refund 的规则退款，NFT 通过 Metaplex 从 vault 返还给 maker
+//!
+//! pNFT 托管不支持拍卖、三方托管、捆绑挂单与取消罚金。vault 一直处于冻结状态，
+//! 其他直接调用 Token Program 转出 vault 的指令（CloseExpired、RefundBatch、还价等）都会失败，
+//! 过期后由 maker 通过 RefundPnft 取回。
+//!
+use crate::helper::METADATA_PROGRAM_ID;
+use crate::state::Escrow;
+use crate::{MakeNft, Refund, Take};
+use core::mem::size_of;
+use pinocchio::{
+    cpi::{invoke_signed, Signer},
+    error::ProgramError,
+    instruction::{InstructionAccount, InstructionView},
+    AccountView, Address, ProgramResult,
+};
+
+/// pNFT 账户组的账户数量
+pub const PNFT_ACCOUNTS: usize = 9;
+
+/// Metaplex Token Metadata 的 Transfer 指令判别器
+const TRANSFER_DISCRIMINATOR: u8 = 49;
+
+/// pNFT 账户组：Metaplex Transfer 所需、Escrow 指令本身不包含的账户
+pub struct PnftAccounts<'a> {
+    pub metadata: &'a AccountView,                 // Metaplex 元数据 PDA
+    pub metadata_program: &'a AccountView,         // Metaplex Token Metadata Program
+    pub edition: &'a AccountView,                  // Master Edition PDA
+    pub owner_token_record: &'a AccountView,       // 转出方代币账户的代币记录 PDA
+    pub destination_token_record: &'a AccountView, // 转入方代币账户的代币记录 PDA（不存在时由 Metaplex 创建）
+    pub sysvar_instructions: &'a AccountView,      // Instructions sysvar
+    pub ata_program: &'a AccountView,              // Associated Token Program
+    pub auth_rules_program: &'a AccountView,       // 授权规则程序（没有规则时传入 Metaplex Program）
+    pub auth_rules: &'a AccountView,               // 授权规则集（没有规则时传入 Metaplex Program）
+}
+
+impl<'a> PnftAccounts<'a> {
+    /// 拆出账户列表开头的 pNFT 账户组，返回剩余的账户
+    pub fn split(
+        accounts: &'a [AccountView],
+    ) -> Result<(Self, &'a [AccountView]), ProgramError> {
+        let [metadata, metadata_program, edition, owner_token_record, destination_token_record, sysvar_instructions, ata_program, auth_rules_program, auth_rules, rest @ ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if metadata_program.address() != &METADATA_PROGRAM_ID {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        Ok((
+            Self {
+                metadata,
+                metadata_program,
+                edition,
+                owner_token_record,
+                destination_token_record,
+                sysvar_instructions,
+                ata_program,
+                auth_rules_program,
+                auth_rules,
+            },
+            rest,
+        ))
+    }
+
+    /// 通过 Metaplex Transfer 转移 1 个 pNFT
+    ///
+    /// 元数据、代币记录与授权规则由 Metaplex 自行校验。
+    #[allow(clippy::too_many_arguments)]
+    pub fn transfer(
+        &self,
+        token: &AccountView,
+        token_owner: &AccountView,
+        destination: &AccountView,
+        destination_owner: &AccountView,
+        mint: &AccountView,
+        authority: &AccountView,
+        payer: &AccountView,
+        system_program: &AccountView,
+        token_program: &AccountView,
+        signers: &[Signer],
+    ) -> ProgramResult {
+        // TransferArgs::V1 { amount: 1, authorization_data: None }
+        let mut data = [0u8; 2 + size_of::<u64>() + 1];
+        data[0] = TRANSFER_DISCRIMINATOR;
+        data[2..10].copy_from_slice(&1u64.to_le_bytes());
+
+        let instruction_accounts = [
+            InstructionAccount::writable(token.address()),
+            InstructionAccount::readonly(token_owner.address()),
+            InstructionAccount::writable(destination.address()),
+            InstructionAccount::readonly(destination_owner.address()),
+            InstructionAccount::readonly(mint.address()),
+            InstructionAccount::writable(self.metadata.address()),
+            InstructionAccount::readonly(self.edition.address()),
+            InstructionAccount::writable(self.owner_token_record.address()),
+            InstructionAccount::writable(self.destination_token_record.address()),
+            InstructionAccount::readonly_signer(authority.address()),
+            InstructionAccount::writable_signer(payer.address()),
+            InstructionAccount::readonly(system_program.address()),
+            InstructionAccount::readonly(self.sysvar_instructions.address()),
+            InstructionAccount::readonly(token_program.address()),
+            InstructionAccount::readonly(self.ata_program.address()),
+            InstructionAccount::readonly(self.auth_rules_program.address()),
+            InstructionAccount::readonly(self.auth_rules.address()),
+        ];
+        let instruction = InstructionView {
+            program_id: &METADATA_PROGRAM_ID,
+            data: &data,
+            accounts: &instruction_accounts,
+        };
+
+        invoke_signed(
+            &instruction,
+            &[
+                token,
+                token_owner,
+                destination,
+                destination_owner,
+                mint,
+                self.metadata,
+                self.edition,
+                self.owner_token_record,
+                self.destination_token_record,
+                authority,
+                payer,
+                system_program,
+                self.sysvar_instructions,
+                token_program,
+                self.ata_program,
+                self.auth_rules_program,
+                self.auth_rules,
+            ],
+            signers,
+        )
+    }
+}
+
+// ========== MakePnft ==========
+/// 账户列表：pNFT 账户组，之后与 Make 完全相同；指令数据与 MakeNft 相同
+pub struct MakePnft<'a> {
+    pub make_nft: MakeNft<'a>, // 校验通过后执行的 MakeNft
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for MakePnft<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let (pnft, rest) = PnftAccounts::split(accounts)?;
+        let mut make_nft = MakeNft::with_metadata(pnft.metadata, data, rest)?;
+
+        // 拍卖、三方托管、捆绑挂单与取消罚金的结算都直接调用 Token Program，不支持 pNFT
+        let instruction_data = &make_nft.make.instruction_data;
+        if instruction_data.auction
+            || instruction_data.arbiter != Address::default()
+            || instruction_data.leg_count != 0
+            || instruction_data.has_penalty()
+        {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        make_nft.make.accounts.pnft = Some(pnft);
+
+        Ok(Self { make_nft })
+    }
+}
+
+impl<'a> MakePnft<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &41;
+
+    pub fn process(&mut self) -> ProgramResult {
+        self.make_nft.process()?;
+
+        let mut data = self.make_nft.make.accounts.escrow.try_borrow_mut()?;
+        let escrow = Escrow::load_mut(data.as_mut())?;
+        escrow.set_flags(escrow.flags | Escrow::FLAG_PNFT);
+
+        Ok(())
+    }
+}
+
+// ========== TakePnft ==========
+/// 账户列表：pNFT 账户组，之后与 Take 完全相同；指令数据与 Take 相同
+pub struct TakePnft<'a> {
+    pub take: Take<'a>, // 附带 pNFT 账户组的 Take
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for TakePnft<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let (pnft, rest) = PnftAccounts::split(accounts)?;
+        let mut take = Take::try_from((data, rest))?;
+        take.accounts.pnft = Some(pnft);
+
+        Ok(Self { take })
+    }
+}
+
+impl<'a> TakePnft<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &42;
+
+    pub fn process(&mut self) -> ProgramResult {
+        self.take.process()
+    }
+}
+
+// ========== RefundPnft ==========
+/// 账户列表：pNFT 账户组，之后与 Refund 完全相同
+pub struct RefundPnft<'a> {
+    pub refund: Refund<'a>, // 附带 pNFT 账户组的 Refund
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for RefundPnft<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let (pnft, rest) = PnftAccounts::split(accounts)?;
+        let mut refund = Refund::try_from(rest)?;
+        refund.accounts.pnft = Some(pnft);
+
+        Ok(Self { refund })
+    }
+}
+
+impl<'a> RefundPnft<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &43;
+
+    pub fn process(&mut self) -> ProgramResult {
+        self.refund.process()
+    }
+}
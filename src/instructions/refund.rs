@@ -4,22 +4,41 @@
 //! - 验证调用者为 Escrow 的创建者
 //! - 将 vault 中的 Token A 返还给 maker
 //! - 关闭 vault 并关闭 Escrow PDA，将租金返还给 maker
+//! - Make 时设置了撤回锁定期的 Escrow，在锁定期结束前不能退款
+//! - Make 时设置了取消罚金的 Escrow，过期前退款需要把罚金部分转给罚金接收者
+//!   （罚金接收者及其 Token A ATA 排在捆绑挂单的额外代币之后）
+//! - 委托模式没有 vault：撤销 Escrow PDA 在 maker Token A ATA 上的授权
+//! - pNFT 托管必须通过 `RefundPnft` 退款（见 pnft.rs）
+//! - Token A 可以是 Token-2022 的 Mint，token_program 必须是 mint_a 所属的程序
+//! - mint_a 启用了 Transfer Hook 时，hook 账户紧跟在固定账户之后（见 helper.rs 的 `split_hook_accounts`）
+//! - 转账前校验 vault 与接收方的 Token Account 未冻结，否则以 `AccountFrozen` 失败
+//! - 可选的 maker 统计 PDA 排在所有账户最后，传入时累加取消次数（见 maker_stats.rs）
+//! - 可选的全局配置 PDA 排在统计 PDA 之前，传入时为退款事件分配序号（见 events.rs）
+//! - 可选的指令数据 `unwrap`（1 字节）为 1 时，Token A 为 WSOL 的托管在退款后关闭 maker 的 WSOL ATA，换回原生 SOL
 //!
-use crate::helper::{AssociatedTokenAccount, MintInterface, ProgramAccount, SignerAccount};
-use crate::state::Escrow;
+use crate::bundle::{release_legs, LEG_ACCOUNTS};
+use crate::errors::EscrowError;
+use crate::pnft::PnftAccounts;
+use crate::helper::{
+    assert_no_duplicates, assert_writable, check_token_account_state, check_token_program, check_vault_authorities, split_hook_accounts, token_account,
+    unwrap_native, AssociatedTokenAccount, MintInterface, ProgramAccount, SignerAccount, SystemProgramCheck,
+    TokenCloseAccount, TokenTransfer,
+};
+use crate::config::split_config;
+use crate::maker_stats::{record_maker_stats, split_maker_stats};
+use crate::events::EscrowEvent;
+use crate::state::{Escrow, MakerStats};
+use crate::whitelist::close_whitelist;
 use crate::{
     AccountCheck, AccountClose, AssociatedTokenAccountCheck, AssociatedTokenAccountInit,
-    ProgramAccountInit,
 };
-use core::mem::size_of;
 use pinocchio::{
-    address::address,
     cpi::{Seed, Signer},
     error::ProgramError,
-    nostd_panic_handler, AccountView, Address, ProgramResult,
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
+    AccountView, Address, ProgramResult,
 };
-use pinocchio_token::instructions::{CloseAccount, Transfer};
-use pinocchio_token::state::TokenAccount;
+use pinocchio_token::instructions::Revoke;
 
 // ========== 账户结构 ==========
 pub struct RefundAccounts<'a> {
@@ -30,6 +49,14 @@ pub struct RefundAccounts<'a> {
     pub maker_ata_a: &'a AccountView, // Maker 的 Token A ATA（接收返还的代币）
     pub system_program: &'a AccountView,
     pub token_program: &'a AccountView,
+    pub whitelist: Option<&'a AccountView>, // 可选：白名单 PDA（Escrow 启用白名单时必须传入）
+    pub legs: &'a [AccountView],            // 捆绑挂单：每种额外代币一组账户，排在白名单之后
+    pub penalty_recipient: Option<&'a AccountView>, // 可选：罚金接收者（过期前退款且设置了罚金时必须传入），排在额外代币之后
+    pub penalty_ata: Option<&'a AccountView>,       // 可选：罚金接收者的 Token A ATA（不存在时由 maker 创建）
+    pub pnft: Option<PnftAccounts<'a>>,             // pNFT 账户组（仅 RefundPnft 传入，见 pnft.rs）
+    pub hook_a: &'a [AccountView],                  // mint_a 的 Transfer Hook 账户（未启用 hook 时为空）
+    pub stats: Option<&'a AccountView>,             // 可选：maker 的统计 PDA，排在最后（见 maker_stats.rs）
+    pub config: Option<&'a AccountView>,            // 可选：全局配置 PDA（为事件分配序号），排在统计 PDA 之前
 }
 
 pub struct RefundAccounts1 {
@@ -46,21 +73,37 @@ impl<'a> TryFrom<&'a [AccountView]> for RefundAccounts<'a> {
     type Error = ProgramError;
 
     fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
-        let [maker, escrow, mint_a, vault, maker_ata_a, system_program, token_program, _] =
+        let [maker, escrow, mint_a, vault, maker_ata_a, system_program, token_program, _, rest @ ..] =
             accounts
         else {
             return Err(ProgramError::NotEnoughAccountKeys);
         };
 
-        // 账户基础校验
-        SignerAccount::check(maker)?;
+        SystemProgramCheck::check(system_program)?;
+
+        // 读取 Escrow 之前先校验 owner：其他程序拥有的账户可以伪造出相同的数据
         ProgramAccount::check(escrow)?;
-        MintInterface::check(mint_a)?;
-         AssociatedTokenAccount::check(vault, escrow, mint_a, token_program)?;
-         AssociatedTokenAccount::check(maker_ata_a, maker, mint_a, token_program)?;
 
-        // 返回账户
-        Ok(Self {
+        // Transfer Hook 账户紧跟在固定账户之后，数量由 mint_a 与其校验账户决定
+        let (hook_a, rest) = split_hook_accounts(mint_a, rest)?;
+        let (stats, rest) = split_maker_stats(maker.address(), rest)?;
+        let (config, rest) = split_config(rest);
+
+        // 可选账户依次为：白名单（仅 Escrow 启用白名单时）、捆绑挂单的额外代币
+        let (has_whitelist, leg_count) = {
+            let data = escrow.try_borrow()?;
+            let escrow = Escrow::load(&data)?;
+            (escrow.has_whitelist(), escrow.legs().len())
+        };
+        let (whitelist, rest) = match rest.split_first() {
+            Some((whitelist, rest)) if has_whitelist => (Some(whitelist), rest),
+            _ => (None, rest),
+        };
+        let (legs, rest) = rest
+            .split_at_checked(leg_count * LEG_ACCOUNTS)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+        let accounts = Self {
             maker,
             escrow,
             mint_a,
@@ -68,37 +111,149 @@ impl<'a> TryFrom<&'a [AccountView]> for RefundAccounts<'a> {
             maker_ata_a,
             system_program,
             token_program,
-        })
+            whitelist,
+            legs,
+            penalty_recipient: rest.first(),
+            penalty_ata: rest.get(1),
+            pnft: None,
+            hook_a,
+            stats,
+            config,
+        };
+        accounts.validate()?;
+
+        // 返回账户
+        Ok(accounts)
+    }
+}
+
+impl<'a> RefundAccounts<'a> {
+    /// 账户基础校验（Refund 与 RefundBatch 共用）
+    pub fn validate(&self) -> ProgramResult {
+        SignerAccount::check(self.maker)?;
+        ProgramAccount::check(self.escrow)?;
+        MintInterface::check(self.mint_a)?;
+
+        // 原生 SOL 托管没有 vault，lamports 直接存放在 Escrow PDA 中
+        // 委托模式没有 vault，Token A 一直留在 maker 的 ATA 中
+        let (native_a, delegated, escrow_vault) = {
+            let data = self.escrow.try_borrow()?;
+            let escrow = Escrow::load(&data)?;
+            (escrow.is_native_a(), escrow.is_delegated(), escrow.vault.clone())
+        };
+        if !native_a {
+            check_token_program(self.mint_a, self.token_program)?;
+            if !delegated {
+                if self.vault.address() != &escrow_vault {
+                    return Err(EscrowError::InvalidVault.into());
+                }
+                check_vault_authorities(self.vault, self.escrow)?;
+            }
+            AssociatedTokenAccount::check(self.maker_ata_a, self.maker, self.mint_a, self.token_program)?;
+        }
+
+        // 委托模式的 vault 位置不使用，原生 SOL 托管的 vault 与 maker_ata_a 位置都不使用
+        // 这些角色两两不同，且都会被修改
+        let roles = [
+            Some(self.maker),
+            Some(self.escrow),
+            (!native_a && !delegated).then_some(self.vault),
+            (!native_a).then_some(self.maker_ata_a),
+            self.config,
+        ];
+        assert_no_duplicates(roles.into_iter().flatten())?;
+        assert_writable(roles.into_iter().flatten())?;
+
+        Ok(())
+    }
+}
+
+// ========== 指令数据结构 ==========
+pub struct RefundInstructionData {
+    pub unwrap: bool, // Token A 为 WSOL 时，退款后关闭 maker 的 WSOL ATA，换回原生 SOL
+}
+
+impl<'a> TryFrom<&'a [u8]> for RefundInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        // 可选的 1 字节 unwrap（0 或 1），不传时不关闭
+        let unwrap = match data {
+            [] | [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+
+        Ok(Self { unwrap })
     }
 }
 
-// ========== 指令数据结构（Refund 无需额外数据）==========
 pub struct Refund<'a> {
     pub accounts: RefundAccounts<'a>,
+    pub instruction_data: RefundInstructionData,
 }
 
 impl<'a> TryFrom<&'a [AccountView]> for Refund<'a> {
     type Error = ProgramError;
 
     fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
-        let accounts = RefundAccounts::try_from(accounts)?;
+        Self::try_from(RefundAccounts::try_from(accounts)?)
+    }
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for Refund<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let instruction_data = RefundInstructionData::try_from(data)?;
+        let mut refund = Self::try_from(accounts)?;
+        refund.instruction_data = instruction_data;
 
+        Ok(refund)
+    }
+}
+
+impl<'a> TryFrom<RefundAccounts<'a>> for Refund<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: RefundAccounts<'a>) -> Result<Self, Self::Error> {
         // 创建创建者的代币 A ATA（如果不存在）
         // 对应 Anchor: pub maker_ata_a 的 init_if_needed 约束
         // helpers.rs 中的 init_if_needed 实现：
         // - 先尝试验证账户（check）
         // - 如果验证失败，说明账户不存在，调用 init 创建
-        AssociatedTokenAccount::init_if_needed(
-            accounts.maker_ata_a,    // 要创建/验证的账户
-            accounts.mint_a,         // mint 账户
-            accounts.maker,          // payer：对应 Anchor 的 payer = maker
-            accounts.maker,          // owner：对应 Anchor 的 authority = maker
-            accounts.system_program, // System Program
-            accounts.token_program,  // Token Program
-        )?;
+        // 原生 SOL 托管直接把 lamports 还给 maker，不需要 Token A ATA
+        if !Escrow::load(&accounts.escrow.try_borrow()?)?.is_native_a() {
+            AssociatedTokenAccount::init_if_needed(
+                accounts.maker_ata_a,    // 要创建/验证的账户
+                accounts.mint_a,         // mint 账户
+                accounts.maker,          // payer：对应 Anchor 的 payer = maker
+                accounts.maker,          // owner：对应 Anchor 的 authority = maker
+                accounts.system_program, // System Program
+                accounts.token_program,  // Token Program
+            )?;
+        }
+
+        // 过期前退款需要支付罚金：创建罚金接收者的 Token A ATA（如果不存在），由 maker 支付
+        let has_penalty = Escrow::load(&accounts.escrow.try_borrow()?)?.has_penalty();
+        if let (true, Some(penalty_recipient), Some(penalty_ata)) =
+            (has_penalty, accounts.penalty_recipient, accounts.penalty_ata)
+        {
+            AssociatedTokenAccount::init_if_needed(
+                penalty_ata,
+                accounts.mint_a,
+                accounts.maker,
+                penalty_recipient,
+                accounts.system_program,
+                accounts.token_program,
+            )?;
+        }
 
         // 返回完整的指令结构
-        Ok(Self { accounts })
+        Ok(Self {
+            accounts,
+            instruction_data: RefundInstructionData { unwrap: false },
+        })
     }
 }
 
@@ -111,59 +266,203 @@ impl<'a> Refund<'a> {
         let escrow = Escrow::load(&data)?;
 
         // 验证 Escrow PDA 是否有效（使用 create_program_address 检验）
-        let escrow_key = Address::create_program_address(
-            &[
-                b"escrow",
-                self.accounts.maker.address().as_ref(),
-                &escrow.seed.to_le_bytes(),
-                &escrow.bump,
-            ],
-            &crate::ID,
-        )?;
-        if &escrow_key != self.accounts.escrow.address() {
-            return Err(ProgramError::InvalidAccountOwner);
-        }
+        escrow.check_address(self.accounts.maker.address(), self.accounts.escrow.address())?;
 
         // 验证调用者是 Escrow 的创建者（maker）
         if self.accounts.maker.address() != &escrow.maker {
-            return Err(ProgramError::MissingRequiredSignature);
+            return Err(EscrowError::MakerMismatch.into());
+        }
+
+        // 拍卖模式的 Escrow 只能通过出价成交
+        if escrow.is_auction() {
+            return Err(EscrowError::AuctionMode.into());
+        }
+
+        // 只有 Open 状态可以继续：线性释放成交后只能由 taker 领取，争议中只能由仲裁人裁决
+        escrow.check_open()?;
+
+        // 对手方已存入 Token B 的双向托管只能通过 Settle 结算
+        if escrow.is_deposited() {
+            return Err(EscrowError::DepositPending.into());
+        }
+
+        // maker 承诺在锁定期内保持报价有效
+        let now = Clock::get()?.unix_timestamp;
+        if escrow.is_refund_locked(now) {
+            return Err(EscrowError::RefundLocked.into());
         }
 
         // 2. 构建 Escrow PDA 的签名种子（用于带签名调用）
         let seed_binding = escrow.seed.to_le_bytes();
-        let bump_binding = escrow.bump;
+        let bump_binding = [escrow.bump];
         let escrow_seeds = [
             Seed::from(b"escrow"),
             Seed::from(self.accounts.maker.address().as_ref()),
             Seed::from(&seed_binding),
             Seed::from(&bump_binding),
         ];
-        let signer = Signer::from(&escrow_seeds);
+        let signers = [Signer::from(&escrow_seeds)];
 
-        // 3. 从 Vault 中提取 Token A 的余额
-        let vault_amount = TokenAccount::from_account_view(self.accounts.vault)?.amount();
+        // 返还给 maker 的 Token A 数量（写入事件）
+        let mut refunded = 0;
 
-        // 4. 将 Token A 从 Vault 转账回 Maker
-        Transfer {
-            from: self.accounts.vault,
-            to: self.accounts.maker_ata_a,
-            authority: self.accounts.escrow, // Escrow PDA 作为 vault 的所有者
-            amount: vault_amount,
+        // 委托模式：撤销仍指向该 Escrow 的授权（maker 可能已经自行撤销或改授权给别人）
+        // 原生 SOL 托管：存入的 lamports 会在第 6 步随租金一起返还给 maker
+        if escrow.is_delegated() {
+            refunded = escrow.delegated_amount;
+            let delegate = token_account(self.accounts.maker_ata_a)?
+                .delegate()
+                .cloned();
+            if delegate.as_ref() == Some(self.accounts.escrow.address()) {
+                Revoke {
+                    source: self.accounts.maker_ata_a,
+                    authority: self.accounts.maker,
+                }
+                .invoke()?;
+            }
+        } else if !escrow.is_native_a() {
+            // vault 与 maker 的 ATA 必须未冻结（pNFT 的 vault 始终冻结，由 Metaplex Transfer 负责解冻）
+            if !escrow.is_pnft() {
+                check_token_account_state(self.accounts.vault)?;
+                check_token_account_state(self.accounts.maker_ata_a)?;
+            }
+
+            // 3. 从 Vault 中提取 Token A 的余额
+            let vault_amount = token_account(self.accounts.vault)?.amount();
+
+            // 过期前撤回报价：按比例把罚金转给罚金接收者
+            let penalty = escrow.penalty_for(vault_amount, now);
+            if penalty != 0 {
+                let penalty_recipient = self
+                    .accounts
+                    .penalty_recipient
+                    .ok_or(ProgramError::NotEnoughAccountKeys)?;
+                let penalty_ata = self
+                    .accounts
+                    .penalty_ata
+                    .ok_or(ProgramError::NotEnoughAccountKeys)?;
+                if penalty_recipient.address() != &escrow.penalty_recipient {
+                    return Err(EscrowError::InvalidPenaltyRecipient.into());
+                }
+                AssociatedTokenAccount::check(
+                    penalty_ata,
+                    penalty_recipient,
+                    self.accounts.mint_a,
+                    self.accounts.token_program,
+                )?;
+                check_token_account_state(penalty_ata)?;
+
+                // 将罚金从 vault 转给罚金接收者
+                TokenTransfer {
+                    from: self.accounts.vault,
+                    mint: self.accounts.mint_a,
+                    to: penalty_ata,
+                    authority: self.accounts.escrow,
+                    amount: penalty,
+                    hook_accounts: self.accounts.hook_a,
+                }
+                .invoke_signed(&signers)?;
+            }
+
+            refunded = vault_amount.checked_sub(penalty).ok_or(EscrowError::MathOverflow)?;
+
+            // 4. 将 Token A 从 Vault 转账回 Maker
+            // pNFT 的 vault 处于冻结状态，只能通过 Metaplex Transfer 转出（pNFT 托管没有罚金）
+            if escrow.is_pnft() {
+                let pnft = self.accounts.pnft.as_ref().ok_or(ProgramError::NotEnoughAccountKeys)?;
+                pnft.transfer(
+                    self.accounts.vault,
+                    self.accounts.escrow,
+                    self.accounts.maker_ata_a,
+                    self.accounts.maker,
+                    self.accounts.mint_a,
+                    self.accounts.escrow,
+                    self.accounts.maker,
+                    self.accounts.system_program,
+                    self.accounts.token_program,
+                    &signers,
+                )?;
+            } else {
+                TokenTransfer {
+                    from: self.accounts.vault,
+                    mint: self.accounts.mint_a,
+                    to: self.accounts.maker_ata_a,
+                    authority: self.accounts.escrow, // Escrow PDA 作为 vault 的所有者
+                    amount: refunded,
+                    hook_accounts: self.accounts.hook_a,
+                }
+                .invoke_signed(&signers)?;
+            }
+
+            // 5. 关闭 Vault ATA 账户（将租金返还给 maker）
+            TokenCloseAccount {
+                account: self.accounts.vault,
+                destination: self.accounts.maker,
+                authority: self.accounts.escrow,
+            }
+            .invoke_signed(&signers)?;
+
+            // WSOL：按需关闭 maker 的 WSOL ATA，换回原生 SOL
+            if self.instruction_data.unwrap {
+                unwrap_native(self.accounts.maker_ata_a, self.accounts.mint_a, self.accounts.maker)?;
+            }
+        }
+
+        // 捆绑挂单：额外代币全部返还给 maker
+        if escrow.is_bundle() {
+            release_legs(
+                escrow,
+                self.accounts.legs,
+                self.accounts.escrow,
+                self.accounts.maker,
+                self.accounts.maker,
+                self.accounts.maker,
+                self.accounts.system_program,
+                self.accounts.token_program,
+                &signers,
+            )?;
         }
-        .invoke_signed(&[signer.clone()])?;
 
-        // 5. 关闭 Vault ATA 账户（将租金返还给 maker）
-        CloseAccount {
-            account: self.accounts.vault,
-            destination: self.accounts.maker,
-            authority: self.accounts.escrow,
+        // 关闭白名单 PDA（将租金返还给 maker）
+        if escrow.has_whitelist() {
+            close_whitelist(self.accounts.whitelist, self.accounts.escrow, self.accounts.maker)?;
         }
-        .invoke_signed(&[signer.clone()])?;
+
+        if escrow.is_native_a() {
+            let rent_exempt = Rent::get()?.try_minimum_balance(Escrow::LEN)?;
+            refunded = self.accounts.escrow.lamports().saturating_sub(rent_exempt);
+        }
+        EscrowEvent::new(
+            EscrowEvent::REFUNDED,
+            self.accounts.escrow.address(),
+            escrow,
+            &Address::default(),
+            refunded,
+            0,
+            Clock::get()?.slot,
+        )
+        .emit(self.accounts.config)?;
 
         // 6. 关闭 Escrow PDA 账户（将租金返还给 maker）
         drop(data); // 释放借用的数据
         ProgramAccount::close(self.accounts.escrow, self.accounts.maker)?;
 
-        Ok(())
+        record_maker_stats(self.accounts.stats, MakerStats::record_cancelled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_optional_unwrap_byte() {
+        assert!(!RefundInstructionData::try_from(&[][..]).unwrap().unwrap);
+        assert!(!RefundInstructionData::try_from(&[0][..]).unwrap().unwrap);
+        assert!(RefundInstructionData::try_from(&[1][..]).unwrap().unwrap);
+
+        let invalid = Some(ProgramError::InvalidInstructionData);
+        assert_eq!(RefundInstructionData::try_from(&[2][..]).err(), invalid);
+        assert_eq!(RefundInstructionData::try_from(&[1, 0][..]).err(), invalid);
     }
 }
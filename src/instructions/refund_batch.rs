@@ -0,0 +1,90 @@
+//! RefundBatch 指令实现（批量取消同一 maker 的多个 Escrow）
+//!
+//! 本文件实现 `RefundBatch` 指令，一次交易中退款多个 Escrow：
+//! - 前 4 个账户为公共账户：maker、System Program、Token Program、ATA Program
+//! - 之后每个 Escrow 占一组固定的 5 个账户：escrow、mint_a、vault、maker_ata_a、whitelist
+//! - 每组账户都按 Refund 的规则校验并处理（返还 Token A，关闭 vault、白名单与 Escrow）
+//!
+//! Escrow 未启用白名单时，whitelist 位置可以传入任意账户（不会被读取）。
+//! 捆绑挂单需要额外代币的账户，过期前需要支付取消罚金的 Escrow 需要罚金接收者的账户，
+//! mint_a 启用了 Transfer Hook 的 Escrow 需要 hook 账户，都只能通过 Refund 单独退款。
+//! 任何一组校验失败都会使整笔交易回滚。
+//! 可选的全局配置 PDA 排在所有组之后，传入时为每条退款事件分配序号（见 events.rs）。
+//!
+use crate::config::split_config;
+use crate::helper::SystemProgramCheck;
+use crate::{AccountCheck, Refund, RefundAccounts};
+use pinocchio::{error::ProgramError, AccountView, ProgramResult};
+
+pub struct RefundBatch<'a> {
+    pub maker: &'a AccountView,          // 托管创建者（必须是签名者）
+    pub system_program: &'a AccountView,
+    pub token_program: &'a AccountView,
+    pub groups: &'a [AccountView],       // 每个 Escrow 一组账户（见 RefundBatch::GROUP_LEN）
+    pub config: Option<&'a AccountView>, // 可选：全局配置 PDA（为事件分配序号），排在所有组之后
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for RefundBatch<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [maker, system_program, token_program, _, groups @ ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        SystemProgramCheck::check(system_program)?;
+
+        let (config, groups) = split_config(groups);
+
+        // 至少一组，且账户数量必须是整组
+        if groups.is_empty() || groups.len() % Self::GROUP_LEN != 0 {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+
+        Ok(Self {
+            maker,
+            system_program,
+            token_program,
+            groups,
+            config,
+        })
+    }
+}
+
+impl<'a> RefundBatch<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &14;
+
+    /// 每个 Escrow 占用的账户数：escrow、mint_a、vault、maker_ata_a、whitelist
+    pub const GROUP_LEN: usize = 5;
+
+    pub fn process(&mut self) -> ProgramResult {
+        for group in self.groups.chunks_exact(Self::GROUP_LEN) {
+            let [escrow, mint_a, vault, maker_ata_a, whitelist] = group else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+
+            let accounts = RefundAccounts {
+                maker: self.maker,
+                escrow,
+                mint_a,
+                vault,
+                maker_ata_a,
+                system_program: self.system_program,
+                token_program: self.token_program,
+                whitelist: Some(whitelist),
+                legs: &[],
+                penalty_recipient: None,
+                penalty_ata: None,
+                pnft: None,
+                hook_a: &[],
+                stats: None,
+                config: self.config,
+            };
+            accounts.validate()?;
+
+            Refund::try_from(accounts)?.process()?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+//! Renew 指令实现（续期已过期的挂单）
+//!
+//! 本文件实现 `Renew` 指令：
+//! - 验证调用者为 Escrow 的创建者（必须签名）
+//! - 只能续期已经过期的 Escrow
+//! - 把过期时间改为新的时间戳（必须在未来，0 表示永不过期）
+//!
+//! vault 中的 Token A 原地保留，maker 无需 Refund 再 Make（两次代币转账 + 租金往返）即可让挂单重新生效。
+//!
+use crate::errors::EscrowError;
+use crate::helper::{ProgramAccount, SignerAccount};
+use crate::state::Escrow;
+use crate::update::split_revision;
+use crate::AccountCheck;
+use core::mem::size_of;
+use pinocchio::{
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    AccountView, Address, ProgramResult,
+};
+
+// ========== 账户结构 ==========
+pub struct RenewAccounts<'a> {
+    pub maker: &'a AccountView,  // 托管创建者（必须是签名者）
+    pub escrow: &'a AccountView, // Escrow PDA 账户
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for RenewAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [maker, escrow] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // 账户基础校验
+        SignerAccount::check(maker)?;
+        ProgramAccount::check(escrow)?;
+
+        Ok(Self { maker, escrow })
+    }
+}
+
+// ========== 指令数据结构 ==========
+pub struct RenewInstructionData {
+    pub expiry_timestamp: i64,  // 新的过期时间（0 表示永不过期）
+    pub expected_revision: u64, // 期望的 Escrow 修订号
+}
+
+impl<'a> TryFrom<&'a [u8]> for RenewInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        let (data, expected_revision) = split_revision(data)?;
+        if data.len() != size_of::<i64>() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let expiry_timestamp = i64::from_le_bytes(data.try_into().unwrap());
+        if expiry_timestamp < 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self {
+            expiry_timestamp,
+            expected_revision,
+        })
+    }
+}
+
+pub struct Renew<'a> {
+    pub accounts: RenewAccounts<'a>,
+    pub instruction_data: RenewInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for Renew<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = RenewAccounts::try_from(accounts)?;
+        let instruction_data = RenewInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> Renew<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &36;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let mut data = self.accounts.escrow.try_borrow_mut()?;
+        let escrow = Escrow::load_mut(data.as_mut())?;
+
+        // 只有 Escrow 的创建者可以续期
+        if self.accounts.maker.address() != &escrow.maker {
+            return Err(EscrowError::MakerMismatch.into());
+        }
+
+        // 验证 Escrow PDA 是否有效
+        let escrow_key = Address::create_program_address(
+            &[
+                b"escrow",
+                self.accounts.maker.address().as_ref(),
+                &escrow.seed.to_le_bytes(),
+                &[escrow.bump],
+            ],
+            &crate::ID,
+        )?;
+        if &escrow_key != self.accounts.escrow.address() {
+            return Err(EscrowError::InvalidEscrowPda.into());
+        }
+
+        // 拍卖模式：延长拍卖会改变出价者的预期，只能取消后重新创建
+        if escrow.is_auction() {
+            return Err(EscrowError::AuctionMode.into());
+        }
+
+        // 只有 Open 状态可以继续：线性释放成交后只能由 taker 领取，争议中只能由仲裁人裁决
+        escrow.check_open()?;
+        escrow.check_revision(self.instruction_data.expected_revision)?;
+
+        // 对手方已存入 Token B 的双向托管只能通过 Settle / Resolve 结算
+        if escrow.is_deposited() {
+            return Err(EscrowError::DepositPending.into());
+        }
+
+        // 只能续期已经过期的 Escrow
+        let now = Clock::get()?.unix_timestamp;
+        if !escrow.is_expired(now) {
+            return Err(EscrowError::EscrowNotExpired.into());
+        }
+
+        // 新的过期时间必须在未来，否则续期后立即就过期了
+        let expiry_timestamp = self.instruction_data.expiry_timestamp;
+        if expiry_timestamp != 0 && expiry_timestamp <= now {
+            return Err(EscrowError::EscrowExpired.into());
+        }
+
+        escrow.set_expiry_timestamp(expiry_timestamp);
+        escrow.touch(Clock::get()?.slot);
+
+        Ok(())
+    }
+}
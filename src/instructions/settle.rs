@@ -0,0 +1,219 @@
+//! Settle 指令实现（双向托管：两个 vault 原子交换）
+//!
+//! 本文件实现 `Settle` 指令，在对手方 Deposit 之后由任意一方（或任何人）调用：
+//! - 将 vault（Token A）全部转给对手方
+//! - 将 vault_b（Token B）全部转给 maker
+//! - 关闭两个 vault、白名单与 Escrow PDA，租金返还给各自的支付者
+//!
+//! 双方资产都已锁定，因此 Settle 不检查过期时间：过期后仍然可以结算，避免资产被卡住。
+//!
+use crate::errors::EscrowError;
+use crate::helper::{
+    AssociatedTokenAccount, MintInterface, ProgramAccount, SignerAccount, SystemProgramCheck, TokenTransfer,
+};
+use crate::state::Escrow;
+use crate::whitelist::close_whitelist;
+use crate::{AccountCheck, AccountClose, AssociatedTokenAccountCheck, AssociatedTokenAccountInit};
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    AccountView, Address, ProgramResult,
+};
+use pinocchio_token::{
+    instructions::CloseAccount,
+    state::TokenAccount,
+};
+
+// ========== 账户结构 ==========
+pub struct SettleAccounts<'a> {
+    pub payer: &'a AccountView,       // 调用者（签名者，支付可能需要创建的 ATA）
+    pub maker: &'a AccountView,       // 托管创建者（接收 Token B 与租金）
+    pub taker: &'a AccountView,       // 已存入 Token B 的对手方（接收 Token A）
+    pub escrow: &'a AccountView,      // Escrow PDA 账户
+    pub mint_a: &'a AccountView,      // Token A 的 Mint
+    pub mint_b: &'a AccountView,      // Token B 的 Mint
+    pub vault: &'a AccountView,       // Token A vault
+    pub vault_b: &'a AccountView,     // Token B vault
+    pub taker_ata_a: &'a AccountView, // 对手方的 Token A ATA
+    pub maker_ata_b: &'a AccountView, // maker 的 Token B ATA
+    pub system_program: &'a AccountView,
+    pub token_program: &'a AccountView,
+    pub whitelist: Option<&'a AccountView>, // 可选：白名单 PDA（Escrow 启用白名单时必须传入）
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for SettleAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [payer, maker, taker, escrow, mint_a, mint_b, vault, vault_b, taker_ata_a, maker_ata_b, system_program, token_program, _, rest @ ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        SystemProgramCheck::check(system_program)?;
+
+        // 账户基础校验
+        SignerAccount::check(payer)?;
+        ProgramAccount::check(escrow)?;
+        MintInterface::check(mint_a)?;
+        MintInterface::check(mint_b)?;
+        AssociatedTokenAccount::check(vault, escrow, mint_a, token_program)?;
+        AssociatedTokenAccount::check(vault_b, escrow, mint_b, token_program)?;
+
+        Ok(Self {
+            payer,
+            maker,
+            taker,
+            escrow,
+            mint_a,
+            mint_b,
+            vault,
+            vault_b,
+            taker_ata_a,
+            maker_ata_b,
+            system_program,
+            token_program,
+            whitelist: rest.first(),
+        })
+    }
+}
+
+// ========== 指令数据结构（Settle 无需额外数据）==========
+pub struct Settle<'a> {
+    pub accounts: SettleAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for Settle<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = SettleAccounts::try_from(accounts)?;
+
+        // Initialize necessary accounts
+        AssociatedTokenAccount::init_if_needed(
+            accounts.taker_ata_a,
+            accounts.mint_a,
+            accounts.payer,
+            accounts.taker,
+            accounts.system_program,
+            accounts.token_program,
+        )?;
+
+        AssociatedTokenAccount::init_if_needed(
+            accounts.maker_ata_b,
+            accounts.mint_b,
+            accounts.payer,
+            accounts.maker,
+            accounts.system_program,
+            accounts.token_program,
+        )?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> Settle<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &10;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let data = self.accounts.escrow.try_borrow()?;
+        let escrow = Escrow::load(&data)?;
+
+        // maker 必须与 Escrow 中记录的创建者一致
+        if self.accounts.maker.address() != &escrow.maker {
+            return Err(EscrowError::MakerMismatch.into());
+        }
+
+        // Check if the escrow is valid
+        let escrow_key = Address::create_program_address(
+            &[
+                b"escrow",
+                self.accounts.maker.address().as_ref(),
+                &escrow.seed.to_le_bytes(),
+                &[escrow.bump],
+            ],
+            &crate::ID,
+        )?;
+        if escrow_key != *self.accounts.escrow.address() {
+            return Err(EscrowError::InvalidEscrowPda.into());
+        }
+
+        // 只有对手方已经 Deposit 的 Escrow 才能结算，且 Token A 只能交付给该对手方
+        if !escrow.is_deposited() {
+            return Err(EscrowError::InvalidAccountData.into());
+        }
+        // 三方托管需要 maker 放行或仲裁人裁决，只能通过 Resolve 结算
+        if escrow.has_arbiter() {
+            return Err(EscrowError::ArbiterMode.into());
+        }
+        if self.accounts.taker.address() != &escrow.taker {
+            return Err(EscrowError::UnauthorizedTaker.into());
+        }
+        if self.accounts.mint_a.address() != &escrow.mint_a
+            || self.accounts.mint_b.address() != &escrow.mint_b
+        {
+            return Err(EscrowError::InvalidMint.into());
+        }
+
+        let seed_binding = escrow.seed.to_le_bytes();
+        let bump_binding = [escrow.bump];
+        let escrow_seeds = [
+            Seed::from(b"escrow"),
+            Seed::from(self.accounts.maker.address().as_ref()),
+            Seed::from(&seed_binding),
+            Seed::from(&bump_binding),
+        ];
+        let signers = [Signer::from(&escrow_seeds)];
+
+        let amount_a = TokenAccount::from_account_view(self.accounts.vault)?.amount();
+        let amount_b = TokenAccount::from_account_view(self.accounts.vault_b)?.amount();
+
+        // Transfer Token A from the Vault to the Taker
+        TokenTransfer {
+            from: self.accounts.vault,
+            mint: self.accounts.mint_a,
+            to: self.accounts.taker_ata_a,
+            authority: self.accounts.escrow,
+            amount: amount_a,
+            hook_accounts: &[],
+        }
+        .invoke_signed(&signers)?;
+
+        // Transfer Token B from the second Vault to the Maker
+        TokenTransfer {
+            from: self.accounts.vault_b,
+            mint: self.accounts.mint_b,
+            to: self.accounts.maker_ata_b,
+            authority: self.accounts.escrow,
+            amount: amount_b,
+            hook_accounts: &[],
+        }
+        .invoke_signed(&signers)?;
+
+        // Close both Vaults（租金分别返还给各自的支付者）
+        CloseAccount {
+            account: self.accounts.vault,
+            destination: self.accounts.maker,
+            authority: self.accounts.escrow,
+        }
+        .invoke_signed(&signers)?;
+        CloseAccount {
+            account: self.accounts.vault_b,
+            destination: self.accounts.taker,
+            authority: self.accounts.escrow,
+        }
+        .invoke_signed(&signers)?;
+
+        // Close the Whitelist
+        if escrow.has_whitelist() {
+            close_whitelist(self.accounts.whitelist, self.accounts.escrow, self.accounts.maker)?;
+        }
+
+        // Close the Escrow
+        drop(data);
+        ProgramAccount::close(self.accounts.escrow, self.accounts.maker)?;
+
+        Ok(())
+    }
+}
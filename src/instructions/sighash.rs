@@ -0,0 +1,161 @@
+//! Anchor 指令判别器兼容（sighash）
+//!
+//! Anchor 生成的客户端与按 IDL 构造交易的工具用 8 字节判别器标识指令：`sha256("global:<指令名>")` 的前 8 字节，
+//! 指令名为 snake_case（如 `make`、`take_partial`、`make_with_delegate`）。
+//! 本文件把这些判别器映射到本程序的 1 字节判别器，`process_instruction` 先按 8 字节判别器匹配，
+//! 匹配不到时再按原来的 1 字节判别器分发，两种格式可以同时使用。
+//!
+//! 判别器之后的指令数据与 1 字节格式完全相同（整数均为小端序，与 Borsh 的编码一致）。
+//! 1 字节格式的指令数据前 8 字节恰好等于某个 sighash 的概率约为 2^-64，可以忽略。
+//!
+use crate::*;
+
+/// Anchor 判别器的长度
+pub const SIGHASH_LEN: usize = 8;
+
+/// 1 字节判别器与对应的 Anchor 判别器（`sha256("global:<指令名>")[..8]`）
+pub const SIGHASHES: [(&u8, [u8; SIGHASH_LEN]); 52] = [
+    (Make::DISCRIMINATOR, [138, 227, 232, 77, 223, 166, 96, 197]), // make
+    (Take::DISCRIMINATOR, [149, 226, 52, 104, 6, 142, 230, 39]), // take
+    (Refund::DISCRIMINATOR, [2, 96, 183, 251, 63, 208, 46, 46]), // refund
+    (TakePartial::DISCRIMINATOR, [234, 93, 209, 76, 4, 56, 12, 13]), // take_partial
+    (CloseExpired::DISCRIMINATOR, [138, 186, 164, 245, 32, 116, 162, 62]), // close_expired
+    (UpdateEscrow::DISCRIMINATOR, [252, 228, 127, 1, 60, 43, 54, 28]), // update_escrow
+    (MakeSol::DISCRIMINATOR, [133, 154, 47, 191, 126, 237, 180, 74]), // make_sol
+    (AddToWhitelist::DISCRIMINATOR, [157, 211, 52, 54, 144, 81, 5, 55]), // add_to_whitelist
+    (RemoveFromWhitelist::DISCRIMINATOR, [7, 144, 216, 239, 243, 236, 193, 235]), // remove_from_whitelist
+    (Deposit::DISCRIMINATOR, [242, 35, 198, 137, 82, 225, 242, 182]), // deposit
+    (Settle::DISCRIMINATOR, [175, 42, 185, 87, 144, 131, 102, 212]), // settle
+    (PlaceBid::DISCRIMINATOR, [238, 77, 148, 91, 200, 151, 92, 146]), // place_bid
+    (AcceptBid::DISCRIMINATOR, [196, 191, 1, 229, 144, 172, 122, 227]), // accept_bid
+    (CancelAuction::DISCRIMINATOR, [156, 43, 197, 110, 218, 105, 143, 182]), // cancel_auction
+    (RefundBatch::DISCRIMINATOR, [227, 54, 194, 2, 78, 8, 104, 29]), // refund_batch
+    (ProposeCounterOffer::DISCRIMINATOR, [61, 70, 107, 218, 131, 161, 227, 247]), // propose_counter_offer
+    (AcceptCounterOffer::DISCRIMINATOR, [94, 106, 245, 253, 116, 96, 27, 165]), // accept_counter_offer
+    (WithdrawCounterOffer::DISCRIMINATOR, [245, 87, 188, 147, 68, 208, 253, 212]), // withdraw_counter_offer
+    (InitializeConfig::DISCRIMINATOR, [208, 127, 21, 1, 194, 190, 196, 70]), // initialize_config
+    (SetFee::DISCRIMINATOR, [18, 154, 24, 18, 237, 214, 19, 80]), // set_fee
+    (CollectFees::DISCRIMINATOR, [164, 152, 207, 99, 30, 186, 19, 182]), // collect_fees
+    (Claim::DISCRIMINATOR, [62, 198, 214, 193, 213, 159, 108, 210]), // claim
+    (RaiseDispute::DISCRIMINATOR, [41, 243, 1, 51, 150, 95, 246, 73]), // raise_dispute
+    (Resolve::DISCRIMINATOR, [246, 150, 236, 206, 108, 63, 58, 10]), // resolve
+    (TopUp::DISCRIMINATOR, [236, 225, 96, 9, 60, 106, 77, 208]), // top_up
+    (WithdrawPartial::DISCRIMINATOR, [142, 181, 230, 69, 132, 105, 19, 229]), // withdraw_partial
+    (TransferMaker::DISCRIMINATOR, [185, 143, 78, 24, 168, 160, 182, 59]), // transfer_maker
+    (Contribute::DISCRIMINATOR, [82, 33, 68, 131, 32, 0, 205, 95]), // contribute
+    (Finalize::DISCRIMINATOR, [171, 61, 218, 56, 127, 115, 12, 217]), // finalize
+    (WithdrawContribution::DISCRIMINATOR, [228, 248, 41, 205, 208, 250, 5, 65]), // withdraw_contribution
+    (MakeWithDelegate::DISCRIMINATOR, [149, 181, 153, 211, 38, 141, 183, 36]), // make_with_delegate
+    (MakeAuto::DISCRIMINATOR, [236, 195, 93, 155, 127, 209, 235, 146]), // make_auto
+    (CommitTake::DISCRIMINATOR, [33, 184, 250, 68, 255, 12, 26, 224]), // commit_take
+    (RevealTake::DISCRIMINATOR, [201, 254, 100, 46, 10, 137, 76, 174]), // reveal_take
+    (CancelCommit::DISCRIMINATOR, [158, 121, 111, 125, 253, 235, 148, 78]), // cancel_commit
+    (TakeSponsored::DISCRIMINATOR, [164, 215, 250, 158, 139, 108, 171, 250]), // take_sponsored
+    (Renew::DISCRIMINATOR, [43, 239, 15, 46, 27, 7, 163, 73]), // renew
+    (Sweep::DISCRIMINATOR, [40, 23, 234, 175, 14, 61, 154, 177]), // sweep
+    (SkimLamports::DISCRIMINATOR, [255, 46, 186, 195, 206, 171, 111, 49]), // skim_lamports
+    (ViewEscrow::DISCRIMINATOR, [50, 15, 115, 85, 79, 164, 198, 161]), // view_escrow
+    (MakeNft::DISCRIMINATOR, [222, 166, 93, 153, 179, 114, 17, 160]), // make_nft
+    (MakePnft::DISCRIMINATOR, [100, 54, 8, 63, 143, 97, 242, 182]), // make_pnft
+    (TakePnft::DISCRIMINATOR, [198, 164, 165, 136, 240, 240, 11, 73]), // take_pnft
+    (RefundPnft::DISCRIMINATOR, [182, 102, 157, 102, 155, 88, 68, 89]), // refund_pnft
+    (Migrate::DISCRIMINATOR, [155, 234, 231, 146, 236, 158, 162, 30]), // migrate
+    (InitMakerStats::DISCRIMINATOR, [160, 240, 77, 151, 150, 120, 196, 253]), // init_maker_stats
+    (InitFeeVault::DISCRIMINATOR, [141, 17, 88, 209, 137, 84, 89, 235]), // init_fee_vault
+    (WithdrawFees::DISCRIMINATOR, [198, 212, 171, 109, 144, 215, 174, 89]), // withdraw_fees
+    (Pause::DISCRIMINATOR, [211, 22, 221, 251, 74, 121, 193, 47]), // pause
+    (Unpause::DISCRIMINATOR, [169, 144, 4, 38, 10, 141, 188, 255]), // unpause
+    (SetLimits::DISCRIMINATOR, [207, 50, 250, 67, 211, 33, 70, 91]), // set_limits
+    (GetVersion::DISCRIMINATOR, [168, 85, 244, 45, 81, 56, 130, 50]), // get_version
+];
+
+/// 指令数据以 Anchor 判别器开头时，返回对应的 1 字节判别器与之后的指令数据
+pub fn split_sighash(data: &[u8]) -> Option<(&'static u8, &[u8])> {
+    let (sighash, rest) = data.split_first_chunk::<SIGHASH_LEN>()?;
+    SIGHASHES
+        .iter()
+        .find(|(_, known)| known == sighash)
+        .map(|(discriminator, _)| (*discriminator, rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sha256_hasher::hashv;
+
+    /// 每个 1 字节判别器对应的 Anchor 指令名（按判别器顺序）
+    const NAMES: [&str; SIGHASHES.len()] = [
+        "make",
+        "take",
+        "refund",
+        "take_partial",
+        "close_expired",
+        "update_escrow",
+        "make_sol",
+        "add_to_whitelist",
+        "remove_from_whitelist",
+        "deposit",
+        "settle",
+        "place_bid",
+        "accept_bid",
+        "cancel_auction",
+        "refund_batch",
+        "propose_counter_offer",
+        "accept_counter_offer",
+        "withdraw_counter_offer",
+        "initialize_config",
+        "set_fee",
+        "collect_fees",
+        "claim",
+        "raise_dispute",
+        "resolve",
+        "top_up",
+        "withdraw_partial",
+        "transfer_maker",
+        "contribute",
+        "finalize",
+        "withdraw_contribution",
+        "make_with_delegate",
+        "make_auto",
+        "commit_take",
+        "reveal_take",
+        "cancel_commit",
+        "take_sponsored",
+        "renew",
+        "sweep",
+        "skim_lamports",
+        "view_escrow",
+        "make_nft",
+        "make_pnft",
+        "take_pnft",
+        "refund_pnft",
+        "migrate",
+        "init_maker_stats",
+        "init_fee_vault",
+        "withdraw_fees",
+        "pause",
+        "unpause",
+        "set_limits",
+        "get_version",
+    ];
+
+    #[test]
+    fn sighashes_are_anchor_discriminators_in_order() {
+        for (index, ((discriminator, sighash), name)) in SIGHASHES.iter().zip(NAMES).enumerate() {
+            assert_eq!(**discriminator as usize, index, "{name}");
+            let hash = hashv(&[b"global:", name.as_bytes()]).to_bytes();
+            assert_eq!(sighash[..], hash[..SIGHASH_LEN], "{name}");
+        }
+    }
+
+    #[test]
+    fn split_sighash_maps_to_one_byte_discriminator() {
+        let (_, take) = SIGHASHES[*Take::DISCRIMINATOR as usize];
+        let data = [&take[..], &[1, 2, 3]].concat();
+        assert_eq!(split_sighash(&data), Some((Take::DISCRIMINATOR, &[1u8, 2, 3][..])));
+
+        // 不足 8 字节或不是已知的判别器时不匹配
+        assert_eq!(split_sighash(&take[..7]), None);
+        assert_eq!(split_sighash(&[0; 16]), None);
+    }
+}
@@ -0,0 +1,100 @@
+//! SkimLamports 指令实现（取回误转入 Escrow PDA 的 lamports）
+//!
+//! 本文件实现 `SkimLamports` 指令：
+//! - 验证调用者为 Escrow 的创建者（必须签名）
+//! - 通过 Rent sysvar 计算 Escrow 账户的租金豁免额
+//! - 把超出租金豁免额的 lamports 全部转给 maker
+//!
+//! 有人直接向 Escrow PDA 转入 lamports 时，这些 lamports 在 Escrow 关闭前无法取出。
+//! 原生 SOL 托管（MakeSol）的 Token A 本身就存放在超出租金豁免额的部分中，无法区分，因此不支持。
+//!
+use crate::errors::EscrowError;
+use crate::helper::{transfer_lamports, ProgramAccount, SignerAccount};
+use crate::state::Escrow;
+use crate::AccountCheck;
+use pinocchio::{
+    error::ProgramError,
+    sysvars::{rent::Rent, Sysvar},
+    AccountView, Address, ProgramResult,
+};
+
+// ========== 账户结构 ==========
+pub struct SkimLamportsAccounts<'a> {
+    pub maker: &'a AccountView,  // 托管创建者（必须是签名者，接收多余的 lamports）
+    pub escrow: &'a AccountView, // Escrow PDA 账户
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for SkimLamportsAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [maker, escrow] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // 账户基础校验
+        SignerAccount::check(maker)?;
+        ProgramAccount::check(escrow)?;
+
+        Ok(Self { maker, escrow })
+    }
+}
+
+// ========== 指令数据结构（SkimLamports 无需额外数据）==========
+pub struct SkimLamports<'a> {
+    pub accounts: SkimLamportsAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for SkimLamports<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = SkimLamportsAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> SkimLamports<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &38;
+
+    pub fn process(&mut self) -> ProgramResult {
+        {
+            let data = self.accounts.escrow.try_borrow()?;
+            let escrow = Escrow::load(&data)?;
+
+            // 只有 Escrow 的创建者可以取回
+            if self.accounts.maker.address() != &escrow.maker {
+                return Err(EscrowError::MakerMismatch.into());
+            }
+
+            // 验证 Escrow PDA 是否有效
+            let escrow_key = Address::create_program_address(
+                &[
+                    b"escrow",
+                    self.accounts.maker.address().as_ref(),
+                    &escrow.seed.to_le_bytes(),
+                    &[escrow.bump],
+                ],
+                &crate::ID,
+            )?;
+            if &escrow_key != self.accounts.escrow.address() {
+                return Err(EscrowError::InvalidEscrowPda.into());
+            }
+
+            // 原生 SOL 托管的 Token A 与误转入的 lamports 无法区分
+            if escrow.is_native_a() {
+                return Err(EscrowError::InvalidAccountData.into());
+            }
+        }
+
+        // 超出租金豁免额的部分都是误转入的 lamports
+        let rent_exempt = Rent::get()?.try_minimum_balance(Escrow::LEN)?;
+        let excess = self.accounts.escrow.lamports().saturating_sub(rent_exempt);
+        if excess == 0 {
+            return Ok(());
+        }
+
+        transfer_lamports(self.accounts.escrow, self.accounts.maker, excess)
+    }
+}
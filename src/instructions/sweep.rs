@@ -0,0 +1,176 @@
+//! Sweep 指令实现（取回误转入 Escrow 的代币）
+//!
+//! 本文件实现 `Sweep` 指令：
+//! - 验证调用者为 Escrow 的创建者（必须签名）
+//! - 把 Escrow PDA 拥有的任意代币账户中的余额全部转给 maker，然后关闭该账户（租金返还给 maker）
+//!
+//! 有人会向 Escrow 的 ATA 空投或误转与挂单无关的代币，这些代币没有其他指令能取出。
+//! 正在使用的 vault 不能被清扫：Token A vault（原生 SOL 托管与委托模式没有）、
+//! 拍卖与双向托管的 Token B vault、捆绑挂单的额外代币 vault。
+//!
+use crate::errors::EscrowError;
+use crate::helper::{
+    AssociatedTokenAccount, MintInterface, ProgramAccount, SignerAccount, SystemProgramCheck, TokenAccountInterface,
+    TokenTransfer,
+};
+use crate::state::Escrow;
+use crate::{AccountCheck, AssociatedTokenAccountInit};
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    AccountView, Address, ProgramResult,
+};
+use pinocchio_token::{
+    instructions::CloseAccount,
+    state::TokenAccount,
+};
+
+// ========== 账户结构 ==========
+pub struct SweepAccounts<'a> {
+    pub maker: &'a AccountView,         // 托管创建者（必须是签名者）
+    pub escrow: &'a AccountView,        // Escrow PDA 账户
+    pub mint: &'a AccountView,          // 误转入代币的 Mint
+    pub stray_account: &'a AccountView, // Escrow PDA 拥有的代币账户
+    pub maker_ata: &'a AccountView,     // maker 的该代币 ATA（不存在时由 maker 创建）
+    pub system_program: &'a AccountView,
+    pub token_program: &'a AccountView,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for SweepAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [maker, escrow, mint, stray_account, maker_ata, system_program, token_program, _] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        SystemProgramCheck::check(system_program)?;
+
+        // 账户基础校验
+        SignerAccount::check(maker)?;
+        ProgramAccount::check(escrow)?;
+        MintInterface::check(mint)?;
+        TokenAccountInterface::check(stray_account)?;
+
+        Ok(Self {
+            maker,
+            escrow,
+            mint,
+            stray_account,
+            maker_ata,
+            system_program,
+            token_program,
+        })
+    }
+}
+
+// ========== 指令数据结构（Sweep 无需额外数据）==========
+pub struct Sweep<'a> {
+    pub accounts: SweepAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for Sweep<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = SweepAccounts::try_from(accounts)?;
+
+        AssociatedTokenAccount::init_if_needed(
+            accounts.maker_ata,
+            accounts.mint,
+            accounts.maker,
+            accounts.maker,
+            accounts.system_program,
+            accounts.token_program,
+        )?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> Sweep<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &37;
+
+    /// `mint` 是否为 Escrow 正在使用的 vault 的代币
+    fn is_active_mint(escrow: &Escrow, mint: &Address) -> bool {
+        let vault_a = !escrow.is_native_a() && !escrow.is_delegated();
+        let vault_b = escrow.is_auction() || escrow.is_deposited();
+
+        (vault_a && mint == &escrow.mint_a)
+            || (vault_b && mint == &escrow.mint_b)
+            || escrow.legs().iter().any(|leg| &leg.mint == mint)
+    }
+
+    pub fn process(&mut self) -> ProgramResult {
+        let data = self.accounts.escrow.try_borrow()?;
+        let escrow = Escrow::load(&data)?;
+
+        // 只有 Escrow 的创建者可以清扫
+        if self.accounts.maker.address() != &escrow.maker {
+            return Err(EscrowError::MakerMismatch.into());
+        }
+
+        // 验证 Escrow PDA 是否有效
+        let escrow_key = Address::create_program_address(
+            &[
+                b"escrow",
+                self.accounts.maker.address().as_ref(),
+                &escrow.seed.to_le_bytes(),
+                &[escrow.bump],
+            ],
+            &crate::ID,
+        )?;
+        if &escrow_key != self.accounts.escrow.address() {
+            return Err(EscrowError::InvalidEscrowPda.into());
+        }
+
+        // 代币账户必须由 Escrow PDA 拥有，且不是正在使用的 vault
+        let amount = {
+            let stray = TokenAccount::from_account_view(self.accounts.stray_account)?;
+            if stray.owner() != self.accounts.escrow.address()
+                || stray.mint() != self.accounts.mint.address()
+            {
+                return Err(EscrowError::InvalidTokenAccount.into());
+            }
+            stray.amount()
+        };
+        if Self::is_active_mint(escrow, self.accounts.mint.address()) {
+            return Err(EscrowError::InvalidAccountData.into());
+        }
+
+        let seed_binding = escrow.seed.to_le_bytes();
+        let bump_binding = [escrow.bump];
+        let escrow_seeds = [
+            Seed::from(b"escrow"),
+            Seed::from(self.accounts.maker.address().as_ref()),
+            Seed::from(&seed_binding),
+            Seed::from(&bump_binding),
+        ];
+        let signers = [Signer::from(&escrow_seeds)];
+
+        // Transfer the stray tokens to the Maker
+        if amount != 0 {
+            TokenTransfer {
+                from: self.accounts.stray_account,
+                mint: self.accounts.mint,
+                to: self.accounts.maker_ata,
+                authority: self.accounts.escrow,
+                amount,
+                hook_accounts: &[],
+            }
+            .invoke_signed(&signers)?;
+        }
+
+        // Close the stray token account
+        CloseAccount {
+            account: self.accounts.stray_account,
+            destination: self.accounts.maker,
+            authority: self.accounts.escrow,
+        }
+        .invoke_signed(&signers)?;
+
+        Ok(())
+    }
+}
@@ -3,28 +3,67 @@
 //! 本文件实现 `Take` 指令，流程概览：
 //! 1. 校验并初始化所需 ATA
 //! 2. 将 vault 中的 Token A 转给 taker
-//! 3. 将 taker 提供的 Token B 转给 maker
-//! 4. 关闭 vault 和 Escrow PDA，返还租金给 maker
+//! 3. 将 taker 提供的 Token B 转给 maker（扣除协议手续费，见全局配置 Config）
+//! 4. 关闭 vault（租金返还给 maker）和 Escrow PDA（租金转给 taker，原生 SOL 托管的 lamports 一并转出）
+//!
+//! 可选的推荐人从协议手续费中分得推荐费（maker 的所得不受影响，见 config.rs），推荐人不能是 taker 本人，否则以 `InvalidReferrer` 失败。
+//!
+//! taker 可以用 mint_b 或 Make 时指定的备选支付代币之一支付（传入对应的 mint_b 账户）。
+//! 可选的指令数据 `max_receive + min_token_a_out` 用于滑点保护，超出时以 `SlippageExceeded` 失败。
+//! 委托模式（MakeWithDelegate）没有 vault：vault 位置传入 maker 的 Token A ATA，Escrow PDA 以 delegate 身份拉取。
+//! `TakeSponsored` 允许 relayer 代付：relayer 作为交易手续费支付者并支付 ATA 租金，taker 只需签名授权代币转账。
+//! 捆绑挂单还会把每种额外代币的 vault 转给 taker（账户排在白名单之后、推荐人之前，见 bundle.rs）。
+//! pNFT 托管必须通过 `TakePnft` 成交（见 pnft.rs）。
+//! mint_a 与 mint_b 可以分别属于原版 Token Program 或 Token-2022：token_program 对应 mint_a，
+//! fee vault 之后的 token_program_b 对应 mint_b（两者相同时重复传入）。
+//! 启用版税的 NFT 托管还需传入元数据与每个创作者的收款账户（排在额外代币之后、推荐人之前，见 make_nft.rs）。
+//! mint_a / mint_b 启用了 Token-2022 Transfer Hook 时，token_program_b 之后依次传入两者的 hook 账户
+//! （见 helper.rs 的 `split_hook_accounts`），转账时原样转发给 Token-2022。
+//! 推荐人之后可选传入支付代币的手续费账本 PDA，传入时记入本次收取的手续费（见 fee_vault.rs）。
+//! 可选的 maker 统计 PDA 排在手续费账本之后（回执 PDA 之前），传入时累加成交次数与 mint_a 的成交量（见 maker_stats.rs）。
+//! 转账前校验 vault 与各收付款 Token Account 未冻结，否则以 `AccountFrozen` 失败。
+//! vault 不能设置 delegate，close authority 只能为空或 Escrow PDA，否则以 `InvalidVaultAuthority` 失败（委托模式除外）。
+//! taker 不能是 Escrow 的 maker，自成交以 `SelfTrade` 失败（TakePartial 共用账户校验，同样拒绝）。
+//!
+//! 滑点保护数据之后可选的 `mode` 字节选择成交方式：
+//! - exact-out（默认）：taker 买下整个 vault，支付当前报价
+//! - exact-in：taker 最多支付 `max_receive` 数量的 Token B，按比例获得 Token A（向下取整，见 take_partial.rs）
+//!
+//! mode 之后可选的 `unwrap` 字节为 1 时，Token A 为 WSOL 的托管在成交后关闭 taker 的 WSOL ATA，
+//! taker 直接得到原生 SOL；Token B 为 WSOL 时付款前自动对 taker 的 ATA 执行 SyncNative。
+//!
+//! unwrap 之后可选的 `receipt` 字节为 1 时，在账户列表最后传入成交回执 PDA（种子 `["receipt", escrow, taker]`），
+//! 成交时由 payer 创建并记录双方、两种 Mint、成交数量与 slot（见 state.rs 的 `TradeReceipt`）。
+//! exact-in 模式按部分成交处理，不支持回执。
 //!
 use pinocchio::{
-    address::address,
     cpi::{Seed, Signer},
     error::ProgramError,
-    nostd_panic_handler, AccountView, Address, ProgramResult,
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
+    AccountView, Address, ProgramResult,
 };
 
 // 导入系统合约的转账指令（存款本质是用户转Lamports到金库）
-use crate::helper::{AssociatedTokenAccount, MintInterface, ProgramAccount, SignerAccount};
-use crate::state::Escrow;
+use crate::bundle::{release_legs, LEG_ACCOUNTS};
+use crate::fee_vault::{record_fee, split_fee_vault};
+use crate::make_nft::{check_metadata, parse_metadata};
+use crate::maker_stats::{record_maker_stats, split_maker_stats};
+use crate::pnft::PnftAccounts;
+use crate::errors::EscrowError;
+use crate::helper::{
+    assert_no_duplicates, assert_writable, check_token_account_state, check_vault_authorities, check_token_program, split_hook_accounts, sync_native, token_account,
+    ui_receive_to_amount, unwrap_native, AssociatedTokenAccount, ConfigAccount, MintInterface,
+    ProgramAccount, SignerAccount, SystemProgramCheck, TokenCloseAccount, TokenTransfer,
+};
+use crate::events::EscrowEvent;
+use crate::state::{Config, Escrow, TradeReceipt};
+use crate::whitelist::{check_whitelisted, close_whitelist};
 use crate::{
     AccountCheck, AccountClose, AssociatedTokenAccountCheck, AssociatedTokenAccountInit,
-    ProgramAccountInit,
-};
-//use pinocchio_system::instructions::Transfer;
-use pinocchio_token::{
-    instructions::{CloseAccount, Transfer},
-    state::TokenAccount,
+    ProgramAccountInit, TakePartial, TakePartialInstructionData,
 };
+use core::mem::size_of;
+use pinocchio_system::instructions::Transfer as SystemTransfer;
 
 pub struct TakeAccounts<'a> {
     pub taker: &'a AccountView,
@@ -38,6 +77,22 @@ pub struct TakeAccounts<'a> {
     pub maker_ata_b: &'a AccountView,
     pub system_program: &'a AccountView,
     pub token_program: &'a AccountView,
+    pub config: &'a AccountView,    // 全局配置 PDA（协议手续费）
+    pub fee_vault: &'a AccountView, // fee vault（全局配置 PDA 拥有的 Token B ATA，原生 SOL 支付时不使用）
+    pub token_program_b: &'a AccountView, // mint_b 所属的 Token Program（与 token_program 相同时重复传入）
+    pub whitelist: Option<&'a AccountView>, // 可选：白名单 PDA（Escrow 启用白名单时必须传入）
+    pub legs: &'a [AccountView],            // 捆绑挂单：每种额外代币一组账户，排在白名单之后
+    pub metadata: Option<&'a AccountView>,  // 版税：NFT 的 Metaplex 元数据 PDA（Escrow 启用版税时必须传入），排在额外代币之后
+    pub creators: &'a [AccountView],        // 版税：按元数据顺序每个创作者的 Token B 账户（原生 SOL 支付时为创作者钱包），排在元数据之后
+    pub referrer: Option<&'a AccountView>,  // 可选：推荐人的 Token B 账户（原生 SOL 支付时为推荐人钱包），排在最后
+    pub native_b: bool,                     // 是否以原生 SOL 支付（mint_b 为 NATIVE_MINT 且未选择备选支付代币）
+    pub payer: &'a AccountView,             // 支付 ATA 租金的账户（默认为 taker，代付成交时为 relayer）
+    pub pnft: Option<PnftAccounts<'a>>,     // pNFT 账户组（仅 TakePnft 传入，见 pnft.rs）
+    pub hook_a: &'a [AccountView],          // mint_a 的 Transfer Hook 账户（未启用 hook 时为空）
+    pub hook_b: &'a [AccountView],          // mint_b 的 Transfer Hook 账户（未启用 hook 时为空），排在 hook_a 之后
+    pub fee_ledger: Option<&'a AccountView>, // 可选：支付代币的手续费账本 PDA，排在推荐人之后（见 fee_vault.rs）
+    pub stats: Option<&'a AccountView>,     // 可选：maker 的统计 PDA，排在手续费账本之后（见 maker_stats.rs）
+    pub receipt: Option<&'a AccountView>,   // 可选：成交回执 PDA（指令数据要求创建回执时传入），排在最后
 }
 
 
@@ -85,19 +140,110 @@ impl<'a> TryFrom<&'a [AccountView]> for TakeAccounts<'a> {
     type Error = ProgramError;
 
     fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
-        let [taker, maker, escrow, mint_a, mint_b, vault, taker_ata_a, taker_ata_b, maker_ata_b, system_program, token_program, _] =
+        let [taker, maker, escrow, mint_a, mint_b, vault, taker_ata_a, taker_ata_b, maker_ata_b, system_program, token_program, _, config, fee_vault, token_program_b, rest @ ..] =
             accounts
         else {
             return Err(ProgramError::NotEnoughAccountKeys);
         };
 
+        SystemProgramCheck::check(system_program)?;
+
         // Basic Accounts Checks
         SignerAccount::check(taker)?;
         ProgramAccount::check(escrow)?;
         MintInterface::check(mint_a)?;
         MintInterface::check(mint_b)?;
-        AssociatedTokenAccount::check(taker_ata_b, taker, mint_b, token_program)?;
-        AssociatedTokenAccount::check(vault, escrow, mint_a, token_program)?;
+        ConfigAccount::check(config)?;
+        Config::load(&config.try_borrow()?)?.check_not_paused()?;
+        check_token_program(mint_a, token_program)?;
+        check_token_program(mint_b, token_program_b)?;
+
+        let (native_a, delegated, native_b, has_whitelist, leg_count, royalties, escrow_vault) = {
+            let data = escrow.try_borrow()?;
+            let escrow = Escrow::load(&data)?;
+
+            // 自成交只会白白支付手续费并污染成交统计
+            if &escrow.maker == taker.address() {
+                return Err(EscrowError::SelfTrade.into());
+            }
+
+            // 支付代币必须是 mint_b 或备选支付代币之一
+            if !escrow.accepts_payment(mint_b.address()) {
+                return Err(EscrowError::InvalidMint.into());
+            }
+
+            (
+                escrow.is_native_a(),
+                escrow.is_delegated(),
+                escrow.is_native_b() && mint_b.address() == &escrow.mint_b,
+                escrow.has_whitelist(),
+                escrow.legs().len(),
+                escrow.has_royalties(),
+                escrow.vault.clone(),
+            )
+        };
+
+        // Transfer Hook 账户紧跟在固定账户之后，数量由 mint 与其校验账户决定
+        let (hook_a, rest) = split_hook_accounts(mint_a, rest)?;
+        let (hook_b, rest) = split_hook_accounts(mint_b, rest)?;
+        let (stats, rest) = split_maker_stats(maker.address(), rest)?;
+        let (fee_ledger, rest) = split_fee_vault(mint_b.address(), rest)?;
+
+        // 可选账户依次为：白名单（仅 Escrow 启用白名单时）、捆绑挂单的额外代币、
+        // 版税的元数据与创作者（仅 Escrow 启用版税时）、推荐人
+        let (whitelist, rest) = match rest.split_first() {
+            Some((whitelist, rest)) if has_whitelist => (Some(whitelist), rest),
+            _ => (None, rest),
+        };
+        let (legs, rest) = rest
+            .split_at_checked(leg_count * LEG_ACCOUNTS)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let (metadata, creators, rest) = if royalties {
+            let (metadata, rest) = rest.split_first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+            check_metadata(metadata, mint_a)?;
+            let creator_count = parse_metadata(&metadata.try_borrow()?, mint_a.address())?.creator_count();
+            let (creators, rest) = rest
+                .split_at_checked(creator_count)
+                .ok_or(ProgramError::NotEnoughAccountKeys)?;
+            (Some(metadata), creators, rest)
+        } else {
+            (None, &[][..], rest)
+        };
+        let referrer = rest.first();
+        if let Some(referrer) = referrer {
+            check_referrer(referrer, taker, mint_b, native_b)?;
+        }
+
+        // 以原生 SOL 支付时 taker 直接转 lamports，没有 Token B ATA
+        if !native_b {
+            AssociatedTokenAccount::check(taker_ata_b, taker, mint_b, token_program_b)?;
+        }
+
+        // 原生 SOL 托管没有 vault，Token A 以 lamports 形式直接存放在 Escrow PDA 中
+        // 委托模式没有 vault，vault 位置传入 maker 的 Token A ATA（Make 时记录在 Escrow 中）
+        if !native_a && vault.address() != &escrow_vault {
+            return Err(EscrowError::InvalidVault.into());
+        }
+        // 委托模式的 vault 是 maker 的 ATA，delegate 本来就是 Escrow PDA
+        if !native_a && !delegated {
+            check_vault_authorities(vault, escrow)?;
+        }
+
+        // 原生 SOL 托管不使用 vault 与 taker_ata_a，以原生 SOL 支付时不使用 Token B 的三个账户
+        // 这些角色两两不同，且都会被修改
+        let roles = [
+            Some(taker),
+            Some(maker),
+            Some(escrow),
+            Some(config),
+            (!native_a).then_some(vault),
+            (!native_a).then_some(taker_ata_a),
+            (!native_b).then_some(taker_ata_b),
+            (!native_b).then_some(maker_ata_b),
+            (!native_b).then_some(fee_vault),
+        ];
+        assert_no_duplicates(roles.into_iter().flatten())?;
+        assert_writable(roles.into_iter().flatten())?;
 
         // Return the accounts
         Ok(Self {
@@ -112,99 +258,772 @@ impl<'a> TryFrom<&'a [AccountView]> for TakeAccounts<'a> {
             vault,
             system_program,
             token_program,
+            config,
+            fee_vault,
+            token_program_b,
+            whitelist,
+            legs,
+            metadata,
+            creators,
+            referrer,
+            native_b,
+            payer: taker,
+            pnft: None,
+            hook_a,
+            hook_b,
+            fee_ledger,
+            stats,
+            receipt: None,
+        })
+    }
+}
+
+impl<'a> TakeAccounts<'a> {
+    /// taker 支付 `amount` 的 Token B：扣除手续费后转给 maker，
+    /// 传入推荐人时从手续费中分出推荐费转给推荐人，其余手续费转入 fee vault
+    /// 启用版税的 NFT 托管再从 maker 所得中扣除版税，按分成比例转给各创作者（向下取整，余数归 maker）
+    /// 原生 SOL 支付时直接转 lamports，手续费转入全局配置 PDA
+    pub fn pay_maker(&self, amount: u64) -> ProgramResult {
+        // WSOL：先把直接转入 taker ATA 的 lamports 计入余额
+        if !self.native_b {
+            sync_native(self.taker_ata_b, self.mint_b)?;
+        }
+
+        // 推荐费从协议手续费中分出，不影响 maker 的所得
+        let (fee, referral) = {
+            let data = self.config.try_borrow()?;
+            let config = Config::load(&data)?;
+            let referral = match self.referrer {
+                Some(_) => config.referral_for(amount),
+                None => 0,
+            };
+            (config.fee_for(amount), referral)
+        };
+
+        // 原生 SOL 支付时向钱包转 lamports，否则向对应的 Token B 账户转账
+        let pay = |wallet: &AccountView, token_account: &AccountView, amount: u64| {
+            if amount == 0 {
+                return Ok(());
+            }
+            if self.native_b {
+                SystemTransfer {
+                    from: self.taker,
+                    to: wallet,
+                    lamports: amount,
+                }
+                .invoke()
+            } else {
+                TokenTransfer {
+                    from: self.taker_ata_b,
+                    mint: self.mint_b,
+                    to: token_account,
+                    authority: self.taker,
+                    amount,
+                    hook_accounts: self.hook_b,
+                }
+                .invoke()
+            }
+        };
+
+        // 推荐费从手续费中分出，不影响 maker 的所得
+        let split = PaymentSplit::new(amount, fee, referral)?;
+
+        // 版税：元数据中各创作者的分成之和不由本程序保证，累加时检查溢出
+        let mut royalty_paid = 0u64;
+        if let Some(metadata) = self.metadata {
+            let data = metadata.try_borrow()?;
+            let metadata = parse_metadata(&data, self.mint_a.address())?;
+            let royalty = split.royalty(metadata.seller_fee_basis_points);
+
+            for ((creator, share), account) in metadata.creators().zip(self.creators) {
+                // 创作者的收款账户必须属于该创作者
+                if self.native_b {
+                    if account.address().as_ref() != creator {
+                        return Err(EscrowError::InvalidRoyaltyRecipient.into());
+                    }
+                } else {
+                    let token_account = token_account(account)?;
+                    if token_account.owner().as_ref() != creator
+                        || token_account.mint() != self.mint_b.address()
+                    {
+                        return Err(EscrowError::InvalidRoyaltyRecipient.into());
+                    }
+                    drop(token_account);
+                }
+
+                let cut = royalty_cut(royalty, share);
+                pay(account, account, cut)?;
+                royalty_paid = royalty_paid.checked_add(cut).ok_or(EscrowError::MathOverflow)?;
+            }
+        }
+
+        let maker_proceeds = split.proceeds.checked_sub(royalty_paid).ok_or(EscrowError::MathOverflow)?;
+        pay(self.maker, self.maker_ata_b, maker_proceeds)?;
+        pay(self.config, self.fee_vault, split.protocol_fee)?;
+        record_fee(self.fee_ledger, split.protocol_fee)?;
+        if let Some(referrer) = self.referrer {
+            pay(referrer, referrer, split.referral)?;
+        }
+
+        Ok(())
+    }
+
+    /// taker 的 Token A ATA 当前余额
+    pub fn token_a_balance(&self) -> Result<u64, ProgramError> {
+        Ok(token_account(self.taker_ata_a)?.amount())
+    }
+
+    /// Token-2022 转账手续费会让 taker 实际收到的 Token A 少于转出的数量：
+    /// 按 taker ATA 余额的变化校验实际到账数量不少于 `min_token_a_out`
+    pub fn check_received(&self, balance_before: u64, min_token_a_out: u64) -> ProgramResult {
+        let received = self.token_a_balance()?.saturating_sub(balance_before);
+        if received < min_token_a_out {
+            return Err(EscrowError::SlippageExceeded.into());
+        }
+
+        Ok(())
+    }
+
+    /// Take / TakePartial 共用：转账前校验参与转账的 Token Account 都处于 Initialized 状态（未冻结）
+    /// pNFT 的 vault 始终冻结，由 Metaplex Transfer 负责解冻，不在此校验
+    pub fn check_token_states(&self) -> ProgramResult {
+        let (native_a, pnft) = {
+            let data = self.escrow.try_borrow()?;
+            let escrow = Escrow::load(&data)?;
+            (escrow.is_native_a(), escrow.is_pnft())
+        };
+
+        if !native_a && !pnft {
+            check_token_account_state(self.vault)?;
+            check_token_account_state(self.taker_ata_a)?;
+        }
+        if !self.native_b {
+            check_token_account_state(self.taker_ata_b)?;
+            check_token_account_state(self.maker_ata_b)?;
+            check_token_account_state(self.fee_vault)?;
+            for account in self.creators.iter().chain(self.referrer) {
+                check_token_account_state(account)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 指令数据要求创建回执时，拆出账户列表最后的回执 PDA
+    pub fn split_receipt(
+        instruction_data: &TakeInstructionData,
+        accounts: &'a [AccountView],
+    ) -> Result<(Option<&'a AccountView>, &'a [AccountView]), ProgramError> {
+        if !instruction_data.receipt {
+            return Ok((None, accounts));
+        }
+
+        let (receipt, rest) = accounts.split_last().ok_or(ProgramError::NotEnoughAccountKeys)?;
+        Ok((Some(receipt), rest))
+    }
+
+    /// 创建成交回执 PDA 并记录本次成交（由 payer 支付租金）
+    /// 对应 Anchor 的 init + seeds = [b"receipt", escrow.key().as_ref(), taker.key().as_ref()]
+    pub fn create_receipt(&self, amount_a: u64, amount_b: u64) -> ProgramResult {
+        let Some(receipt) = self.receipt else {
+            return Ok(());
+        };
+
+        let (receipt_key, bump) = Address::find_program_address(
+            &[
+                b"receipt",
+                self.escrow.address().as_ref(),
+                self.taker.address().as_ref(),
+            ],
+            &crate::ID,
+        );
+        if &receipt_key != receipt.address() {
+            return Err(EscrowError::InvalidReceiptPda.into());
+        }
+
+        let bump_binding = [bump];
+        let receipt_seeds = [
+            Seed::from(b"receipt"),
+            Seed::from(self.escrow.address().as_ref()),
+            Seed::from(self.taker.address().as_ref()),
+            Seed::from(&bump_binding),
+        ];
+        ProgramAccount::init::<TradeReceipt>(
+            self.payer,
+            receipt,
+            receipt_seeds.as_slice(),
+            TradeReceipt::LEN,
+        )?;
+
+        let mut data = receipt.try_borrow_mut()?;
+        let state = TradeReceipt::init(data.as_mut())?;
+        state.escrow = self.escrow.address().clone();
+        state.maker = self.maker.address().clone();
+        state.taker = self.taker.address().clone();
+        state.mint_a = self.mint_a.address().clone();
+        state.mint_b = self.mint_b.address().clone();
+        state.amount_a = amount_a;
+        state.amount_b = amount_b;
+        state.slot = Clock::get()?.slot;
+        state.bump = bump_binding;
+
+        Ok(())
+    }
+
+    /// Take / TakePartial 共用：创建 taker 与 maker 需要的 ATA 以及 fee vault（由 payer 支付）
+    pub fn init_atas(&self) -> ProgramResult {
+        let native_a = Escrow::load(&self.escrow.try_borrow()?)?.is_native_a();
+
+        // 原生 SOL 托管直接把 lamports 转给 taker，不需要 taker 的 Token A ATA
+        if !native_a {
+            AssociatedTokenAccount::init_if_needed(
+                self.taker_ata_a,
+                self.mint_a,
+                self.payer,
+                self.taker,
+                self.system_program,
+                self.token_program,
+            )?;
+        }
+
+        // 以原生 SOL 支付时 taker 直接向 maker 转 lamports，不需要 maker 的 Token B ATA 和 fee vault
+        if !self.native_b {
+            AssociatedTokenAccount::init_if_needed(
+                self.maker_ata_b,
+                self.mint_b,
+                self.payer,
+                self.maker,
+                self.system_program,
+                self.token_program_b,
+            )?;
+            AssociatedTokenAccount::init_if_needed(
+                self.fee_vault,
+                self.mint_b,
+                self.payer,
+                self.config,
+                self.system_program,
+                self.token_program_b,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// 校验推荐人：不能是 taker 本人（否则 taker 可以给自己返佣）
+/// 以原生 SOL 支付时推荐人是钱包地址，否则是推荐人持有的支付代币 Token Account
+fn check_referrer(referrer: &AccountView, taker: &AccountView, mint_b: &AccountView, native_b: bool) -> ProgramResult {
+    if native_b {
+        if referrer.address() == taker.address() {
+            return Err(EscrowError::InvalidReferrer.into());
+        }
+        return Ok(());
+    }
+
+    let token_account = token_account(referrer)?;
+    if token_account.owner() == taker.address() || token_account.mint() != mint_b.address() {
+        return Err(EscrowError::InvalidReferrer.into());
+    }
+    Ok(())
+}
+
+/// Take 时 taker 支付的 Token B 的拆分（不含转账，便于单独测试）
+#[derive(Debug, PartialEq, Eq)]
+pub struct PaymentSplit {
+    pub proceeds: u64,     // maker 的所得（扣除版税之前）
+    pub protocol_fee: u64, // 转入 fee vault 的协议手续费
+    pub referral: u64,     // 转给推荐人的推荐费
+}
+
+impl PaymentSplit {
+    /// 从 `amount` 中扣除手续费 `fee`，推荐费 `referral` 再从手续费中分出，不影响 maker 的所得
+    /// 手续费超过支付数量或推荐费超过手续费时返回 MathOverflow（Config 的费率校验保证不会发生）
+    pub fn new(amount: u64, fee: u64, referral: u64) -> Result<Self, ProgramError> {
+        Ok(Self {
+            proceeds: amount.checked_sub(fee).ok_or(EscrowError::MathOverflow)?,
+            protocol_fee: fee.checked_sub(referral).ok_or(EscrowError::MathOverflow)?,
+            referral,
+        })
+    }
+
+    /// 从 maker 所得中扣除的版税总额：seller_fee_basis_points 超过 100% 时按 100% 计算
+    pub fn royalty(&self, seller_fee_basis_points: u16) -> u64 {
+        (self.proceeds as u128 * seller_fee_basis_points.min(10_000) as u128 / 10_000) as u64
+    }
+}
+
+/// 单个创作者按分成比例 `share`（百分比，超过 100 按 100 计算）分得的版税，向下取整，余数归 maker
+pub fn royalty_cut(royalty: u64, share: u8) -> u64 {
+    (royalty as u128 * share.min(100) as u128 / 100) as u64
+}
+
+// ========== 指令数据结构 ==========
+pub struct TakeInstructionData {
+    pub max_receive: u64,     // 滑点保护：taker 最多愿意支付的 Token B 数量
+    pub min_token_a_out: u64, // 滑点保护：taker 至少要收到的 Token A 数量
+    pub exact_in: bool,       // exact-in 模式：max_receive 即为本次支付的 Token B 数量
+    pub unwrap: bool,         // Token A 为 WSOL 时，成交后关闭 taker 的 WSOL ATA，换回原生 SOL
+    pub receipt: bool,        // 成交时创建回执 PDA（账户列表最后传入）
+}
+
+impl TakeInstructionData {
+    /// 成交方式：买下整个 vault（默认）
+    pub const MODE_EXACT_OUT: u8 = 0;
+    /// 成交方式：指定支付的 Token B 数量，按比例获得 Token A
+    pub const MODE_EXACT_IN: u8 = 1;
+}
+
+impl<'a> TryFrom<&'a [u8]> for TakeInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        // - 0 字节：不做滑点保护
+        // - 16 字节：max_receive + min_token_a_out
+        // - 17 字节：max_receive + min_token_a_out + mode
+        // - 18 字节：max_receive + min_token_a_out + mode + unwrap（0 或 1）
+        // - 19 字节：max_receive + min_token_a_out + mode + unwrap + receipt（0 或 1）
+        if data.is_empty() {
+            return Ok(Self {
+                max_receive: u64::MAX,
+                min_token_a_out: 0,
+                exact_in: false,
+                unwrap: false,
+                receipt: false,
+            });
+        }
+
+        let (amounts, flags) = data
+            .split_at_checked(size_of::<u64>() * 2)
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        let (mode, unwrap, receipt) = match flags {
+            [] => (Self::MODE_EXACT_OUT, 0, 0),
+            [mode] => (*mode, 0, 0),
+            [mode, unwrap] => (*mode, *unwrap, 0),
+            [mode, unwrap, receipt] => (*mode, *unwrap, *receipt),
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+        let exact_in = match mode {
+            Self::MODE_EXACT_OUT => false,
+            Self::MODE_EXACT_IN => true,
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+        let unwrap = match unwrap {
+            0 => false,
+            1 => true,
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+        let receipt = match receipt {
+            0 => false,
+            1 => true,
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+
+        let max_receive = u64::from_le_bytes(amounts[0..8].try_into().unwrap());
+
+        // exact-in 模式下支付数量不能为 0，也不支持回执
+        if exact_in && (max_receive == 0 || receipt) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self {
+            max_receive,
+            min_token_a_out: u64::from_le_bytes(amounts[8..16].try_into().unwrap()),
+            exact_in,
+            unwrap,
+            receipt,
         })
     }
 }
+
 pub struct Take<'a> {
     pub accounts: TakeAccounts<'a>,
+    pub instruction_data: TakeInstructionData,
 }
 
-impl<'a> TryFrom<&'a [AccountView]> for Take<'a> {
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for Take<'a> {
     type Error = ProgramError;
 
-    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
-        let accounts = TakeAccounts::try_from(accounts)?;
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let instruction_data = TakeInstructionData::try_from(data)?;
+        let (receipt, accounts) = TakeAccounts::split_receipt(&instruction_data, accounts)?;
+        let mut accounts = TakeAccounts::try_from(accounts)?;
+        accounts.receipt = receipt;
 
         // Initialize necessary accounts
-        AssociatedTokenAccount::init_if_needed(
-            accounts.taker_ata_a,
-            accounts.mint_a,
-            accounts.taker,
-            accounts.taker,
-            accounts.system_program,
-            accounts.token_program,
-        )?;
-
-        AssociatedTokenAccount::init_if_needed(
-            accounts.maker_ata_b,
-            accounts.mint_b,
-            accounts.taker,
-            accounts.maker,
-            accounts.system_program,
-            accounts.token_program,
-        )?;
+        accounts.init_atas()?;
+        accounts.check_token_states()?;
 
-        Ok(Self { accounts })
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
     }
 }
 impl<'a> Take<'a> {
     pub const DISCRIMINATOR: &'a u8 = &1;
 
     pub fn process(&mut self) -> ProgramResult {
+        // exact-in 模式：按部分成交的规则计算可获得的 Token A
+        if self.instruction_data.exact_in {
+            return TakePartial::fill(
+                &self.accounts,
+                &TakePartialInstructionData {
+                    amount: self.instruction_data.max_receive,
+                    exact_in: true,
+                    min_token_a_out: self.instruction_data.min_token_a_out,
+                    unwrap: self.instruction_data.unwrap,
+                },
+            );
+        }
+
         let data = self.accounts.escrow.try_borrow()?;
         let escrow = Escrow::load(&data)?;
         // Check if the escrow is valid
-        let escrow_key = Address::create_program_address(
-            &[
-                b"escrow",
-                self.accounts.maker.address().as_ref(),
-                &escrow.seed.to_le_bytes(),
-                &escrow.bump[..], // 转换为字节切片
-            ],
-            &crate::ID,
-        )?; // 添加 ? 操作符处理 Result
-        if escrow_key != *self.accounts.escrow.address() {
-            return Err(ProgramError::InvalidAccountOwner);
+        escrow.check_address(self.accounts.maker.address(), self.accounts.escrow.address())?;
+
+        // 拍卖模式的 Escrow 只能通过出价成交
+        if escrow.is_auction() {
+            return Err(EscrowError::AuctionMode.into());
+        }
+
+        // 只有 Open 状态可以继续：线性释放成交后只能由 taker 领取，争议中只能由仲裁人裁决
+        escrow.check_open()?;
+
+        // 对手方已存入 Token B 的双向托管只能通过 Settle 结算
+        if escrow.is_deposited() {
+            return Err(EscrowError::DepositPending.into());
+        }
+
+        // 三方托管只能通过 Deposit 锁定 Token B，由 Resolve 结算
+        if escrow.has_arbiter() {
+            return Err(EscrowError::ArbiterMode.into());
+        }
+
+        // 已过期的 Escrow 不能再成交
+        let now = Clock::get()?.unix_timestamp;
+        if escrow.is_expired(now) {
+            return Err(EscrowError::EscrowExpired.into());
+        }
+
+        // 私有 Escrow 只能由指定的 taker 成交
+        if !escrow.is_taker_allowed(self.accounts.taker.address()) {
+            return Err(EscrowError::UnauthorizedTaker.into());
+        }
+
+        // 启用白名单的 Escrow：taker 必须在白名单中
+        if escrow.has_whitelist() {
+            check_whitelisted(
+                self.accounts.whitelist,
+                self.accounts.escrow,
+                self.accounts.taker.address(),
+            )?;
         }
 
         let seed_binding = escrow.seed.to_le_bytes();
-        let bump_binding = escrow.bump;
+        let bump_binding = [escrow.bump];
         let escrow_seeds = [
             Seed::from(b"escrow"),
             Seed::from(self.accounts.maker.address().as_ref()),
             Seed::from(&seed_binding),
             Seed::from(&bump_binding),
         ];
-        let signer = Signer::from(&escrow_seeds);
+        let signers = [Signer::from(&escrow_seeds)];
 
-        let amount = TokenAccount::from_account_view(self.accounts.vault)?.amount();
+        // 应付的 Token B：普通 Escrow 为 receive，荷兰拍按当前时间计算，备选支付代币为固定数量
+        let mut price = escrow
+            .price_in(self.accounts.mint_b.address(), now)
+            .ok_or(EscrowError::InvalidMint)?;
+        // UI 报价：按 mint_b 当前的利率把 UI 金额换算为需要转账的原始数量
+        if escrow.is_ui_receive() && self.accounts.mint_b.address() == &escrow.mint_b {
+            price = ui_receive_to_amount(self.accounts.mint_b, price, now)?;
+        }
 
-        // Transfer from the Vault to the Taker
-        Transfer {
-            from: self.accounts.vault,
-            to: self.accounts.taker_ata_a,
-            authority: self.accounts.escrow,
-            amount,
+        // 滑点保护：报价可能在 taker 询价之后被 maker 修改（UpdateEscrow / TopUp / WithdrawPartial）
+        let amount_a = if escrow.is_delegated() {
+            escrow.delegated_amount
+        } else if escrow.is_nft() {
+            1
+        } else if escrow.is_native_a() {
+            let rent_exempt = Rent::get()?.try_minimum_balance(Escrow::LEN)?;
+            self.accounts.escrow.lamports().saturating_sub(rent_exempt)
+        } else {
+            token_account(self.accounts.vault)?.amount()
+        };
+        if price > self.instruction_data.max_receive
+            || amount_a < self.instruction_data.min_token_a_out
+        {
+            return Err(EscrowError::SlippageExceeded.into());
         }
-        .invoke_signed(&[signer.clone()])?;
-        // Close the Vault
-        CloseAccount {
-            account: self.accounts.vault,
-            destination: self.accounts.maker,
-            authority: self.accounts.escrow,
+
+        record_maker_stats(self.accounts.stats, |stats| {
+            stats.record_filled(&escrow.mint_a, amount_a)
+        })?;
+        self.accounts.create_receipt(amount_a, price)?;
+
+        // 线性释放：Token A 留在 vault 中，taker 之后通过 Claim 按时间逐步领取
+        if escrow.is_vesting() {
+            self.accounts.pay_maker(price)?;
+
+            // Close the Whitelist
+            if escrow.has_whitelist() {
+                close_whitelist(self.accounts.whitelist, self.accounts.escrow, self.accounts.maker)?;
+            }
+
+            drop(data);
+            let mut data = self.accounts.escrow.try_borrow_mut()?;
+            let escrow = Escrow::load_mut(data.as_mut())?;
+            escrow.set_taker(Some(self.accounts.taker.address().clone()));
+            escrow.start_vesting(now);
+            escrow.set_status(Escrow::STATUS_FILLED);
+            escrow.touch(Clock::get()?.slot);
+
+            EscrowEvent::new(
+                EscrowEvent::TAKEN,
+                self.accounts.escrow.address(),
+                escrow,
+                self.accounts.taker.address(),
+                amount_a,
+                price,
+                Clock::get()?.slot,
+            )
+            .emit(Some(self.accounts.config))?;
+            return Ok(());
         }
-        .invoke_signed(&[signer.clone()])?;
+
+        // 委托模式：Escrow PDA 以 delegate 身份从 maker 的 ATA 拉取 Token A
+        // 原生 SOL 托管：存入的 lamports 在关闭 Escrow 时随租金一起转给 taker
+        let balance_before = if escrow.is_native_a() {
+            0
+        } else {
+            self.accounts.token_a_balance()?
+        };
+        if escrow.is_delegated() {
+            // Transfer from the Maker to the Taker with delegate authority
+            TokenTransfer {
+                from: self.accounts.vault,
+                mint: self.accounts.mint_a,
+                to: self.accounts.taker_ata_a,
+                authority: self.accounts.escrow,
+                amount: escrow.delegated_amount,
+                hook_accounts: self.accounts.hook_a,
+            }
+            .invoke_signed(&signers)?;
+        } else if !escrow.is_native_a() {
+            // NFT 托管按数量 1 转出
+            let amount = if escrow.is_nft() {
+                1
+            } else {
+                token_account(self.accounts.vault)?.amount()
+            };
+
+            // Transfer from the Vault to the Taker
+            // pNFT 的 vault 处于冻结状态，只能通过 Metaplex Transfer 转出
+            if escrow.is_pnft() {
+                let pnft = self.accounts.pnft.as_ref().ok_or(ProgramError::NotEnoughAccountKeys)?;
+                pnft.transfer(
+                    self.accounts.vault,
+                    self.accounts.escrow,
+                    self.accounts.taker_ata_a,
+                    self.accounts.taker,
+                    self.accounts.mint_a,
+                    self.accounts.escrow,
+                    self.accounts.payer,
+                    self.accounts.system_program,
+                    self.accounts.token_program,
+                    &signers,
+                )?;
+            } else {
+                TokenTransfer {
+                    from: self.accounts.vault,
+                    mint: self.accounts.mint_a,
+                    to: self.accounts.taker_ata_a,
+                    authority: self.accounts.escrow,
+                    amount,
+                    hook_accounts: self.accounts.hook_a,
+                }
+                .invoke_signed(&signers)?;
+            }
+            // Close the Vault
+            TokenCloseAccount {
+                account: self.accounts.vault,
+                destination: self.accounts.maker,
+                authority: self.accounts.escrow,
+            }
+            .invoke_signed(&signers)?;
+        }
+        if !escrow.is_native_a() {
+            self.accounts
+                .check_received(balance_before, self.instruction_data.min_token_a_out)?;
+            if self.instruction_data.unwrap {
+                unwrap_native(self.accounts.taker_ata_a, self.accounts.mint_a, self.accounts.taker)?;
+            }
+        }
+
+        // 捆绑挂单：额外代币全部转给 taker，vault 租金返还给 maker
+        if escrow.is_bundle() {
+            release_legs(
+                escrow,
+                self.accounts.legs,
+                self.accounts.escrow,
+                self.accounts.taker,
+                self.accounts.payer,
+                self.accounts.maker,
+                self.accounts.system_program,
+                self.accounts.token_program,
+                &signers,
+            )?;
+        }
+
         // Transfer from the Taker to the Maker
-        Transfer {
-            from: self.accounts.taker_ata_b,
-            to: self.accounts.maker_ata_b,
-            authority: self.accounts.taker,
-            amount: escrow.receive,
+        self.accounts.pay_maker(price)?;
+        // Close the Whitelist
+        if escrow.has_whitelist() {
+            close_whitelist(self.accounts.whitelist, self.accounts.escrow, self.accounts.maker)?;
         }
-        .invoke()?;
+
+        EscrowEvent::new(
+            EscrowEvent::TAKEN,
+            self.accounts.escrow.address(),
+            escrow,
+            self.accounts.taker.address(),
+            amount_a,
+            price,
+            Clock::get()?.slot,
+        )
+        .emit(Some(self.accounts.config))?;
+
         // Close the Escrow
         drop(data);
         ProgramAccount::close(self.accounts.escrow, self.accounts.taker)?;
         Ok(())
     }
 }
+
+// ========== TakeSponsored ==========
+/// 代付成交：账户列表为 relayer，之后与 Take 完全相同；指令数据与 Take 相同
+/// relayer 支付交易手续费以及 taker / maker 的 ATA 和 fee vault 的租金，taker 只需签名授权 Token B 转账
+pub struct TakeSponsored<'a> {
+    pub take: Take<'a>,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for TakeSponsored<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let [relayer, rest @ ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        SignerAccount::check(relayer)?;
+
+        let instruction_data = TakeInstructionData::try_from(data)?;
+        let (receipt, rest) = TakeAccounts::split_receipt(&instruction_data, rest)?;
+        let mut accounts = TakeAccounts::try_from(rest)?;
+        accounts.payer = relayer;
+        accounts.receipt = receipt;
+
+        // Initialize necessary accounts（由 relayer 支付）
+        accounts.init_atas()?;
+        accounts.check_token_states()?;
+
+        Ok(Self {
+            take: Take {
+                accounts,
+                instruction_data,
+            },
+        })
+    }
+}
+
+impl<'a> TakeSponsored<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &35;
+
+    pub fn process(&mut self) -> ProgramResult {
+        self.take.process()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 滑点保护数据：max_receive + min_token_a_out，之后追加 mode / unwrap / receipt 字节
+    fn data(max_receive: u64, min_token_a_out: u64, flags: &[u8]) -> [u8; 19] {
+        let mut data = [0u8; 19];
+        data[..8].copy_from_slice(&max_receive.to_le_bytes());
+        data[8..16].copy_from_slice(&min_token_a_out.to_le_bytes());
+        data[16..16 + flags.len()].copy_from_slice(flags);
+        data
+    }
+
+    #[test]
+    fn parses_optional_slippage_mode_unwrap_and_receipt() {
+        // 不传数据时不做滑点保护
+        let parsed = TakeInstructionData::try_from(&[][..]).unwrap();
+        assert_eq!((parsed.max_receive, parsed.min_token_a_out), (u64::MAX, 0));
+        assert!(!parsed.exact_in && !parsed.unwrap && !parsed.receipt);
+
+        let parsed = TakeInstructionData::try_from(&data(500, 7, &[])[..16]).unwrap();
+        assert_eq!((parsed.max_receive, parsed.min_token_a_out), (500, 7));
+        assert!(!parsed.exact_in && !parsed.unwrap && !parsed.receipt);
+
+        let parsed = TakeInstructionData::try_from(&data(500, 7, &[1])[..17]).unwrap();
+        assert!(parsed.exact_in && !parsed.unwrap);
+
+        let parsed = TakeInstructionData::try_from(&data(500, 7, &[0, 1])[..18]).unwrap();
+        assert!(!parsed.exact_in && parsed.unwrap && !parsed.receipt);
+
+        let parsed = TakeInstructionData::try_from(&data(500, 7, &[0, 0, 1])[..]).unwrap();
+        assert!(parsed.receipt);
+    }
+
+    #[test]
+    fn rejects_malformed_take_data() {
+        let invalid = Some(ProgramError::InvalidInstructionData);
+        // 长度不是 0、16..=19
+        assert_eq!(TakeInstructionData::try_from(&data(500, 7, &[])[..15]).err(), invalid);
+        assert_eq!(TakeInstructionData::try_from(&[0u8; 20][..]).err(), invalid);
+        // 未知的 mode，unwrap / receipt 不是 0 或 1
+        assert_eq!(TakeInstructionData::try_from(&data(500, 7, &[2])[..17]).err(), invalid);
+        assert_eq!(TakeInstructionData::try_from(&data(500, 7, &[0, 2])[..18]).err(), invalid);
+        assert_eq!(TakeInstructionData::try_from(&data(500, 7, &[0, 0, 2])[..]).err(), invalid);
+        // exact-in 模式支付数量不能为 0，也不支持回执
+        assert_eq!(TakeInstructionData::try_from(&data(0, 7, &[1])[..17]).err(), invalid);
+        assert_eq!(TakeInstructionData::try_from(&data(500, 7, &[1, 0, 1])[..]).err(), invalid);
+    }
+
+    #[test]
+    fn referral_comes_out_of_the_fee_not_the_maker() {
+        // 支付 10_000，手续费 30，其中推荐费 10
+        assert_eq!(
+            PaymentSplit::new(10_000, 30, 10),
+            Ok(PaymentSplit {
+                proceeds: 9_970,
+                protocol_fee: 20,
+                referral: 10,
+            })
+        );
+        assert_eq!(PaymentSplit::new(10_000, 30, 0).unwrap().protocol_fee, 30);
+
+        // 手续费超过支付数量或推荐费超过手续费时拒绝，而不是回绕
+        let overflow = Err(ProgramError::from(EscrowError::MathOverflow));
+        assert_eq!(PaymentSplit::new(10, 11, 0), overflow);
+        assert_eq!(PaymentSplit::new(10_000, 30, 31), overflow);
+    }
+
+    #[test]
+    fn royalties_come_out_of_maker_proceeds_and_round_down() {
+        let split = PaymentSplit::new(10_000, 100, 0).unwrap();
+        // 5% 版税按扣除手续费之后的所得计算
+        assert_eq!(split.royalty(500), 495);
+        // 超过 100% 的版税比例按 100% 计算
+        assert_eq!(split.royalty(u16::MAX), split.proceeds);
+
+        // 三个创作者各 33%：每份向下取整，余数留给 maker
+        let cuts = [33u8, 33, 34].map(|share| royalty_cut(495, share));
+        assert_eq!(cuts, [163, 163, 168]);
+        assert!(cuts.iter().sum::<u64>() <= 495);
+        // 分成超过 100% 按 100% 计算，不会超出版税总额
+        assert_eq!(royalty_cut(495, 200), 495);
+    }
+}
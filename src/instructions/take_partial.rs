@@ -0,0 +1,376 @@
+//! TakePartial 指令实现（部分成交 Escrow）
+//!
+//! 与 `Take` 一次性吃掉整个 vault 不同，`TakePartial` 允许 taker 只购买一部分 Token A：
+//! 1. taker 在指令数据中指定想要的 Token A 数量 `amount`
+//! 2. 按 vault 余额与 `receive` 的比例计算应支付的 Token B（向上取整，保护 maker）
+//! 3. 更新 Escrow 中剩余的 `receive`，vault 余额即为剩余的 Token A
+//! 4. 当 `amount` 等于 vault 全部余额时视为完全成交，关闭 vault 和 Escrow PDA
+//!
+//! Take 的 exact-in 模式也走这里：taker 指定支付的 Token B 数量 `amount_in`，
+//! 获得 floor(amount_in * vault / receive) 的 Token A，再按上面的规则向上取整计算实际支付，
+//! 因此实际支付不超过 `amount_in`。`amount_in` 不小于 `receive` 时按完全成交处理，只支付 `receive`。
+//!
+//! `amount` 之后可选的 `unwrap` 字节为 1 时，Token A 为 WSOL 的托管在成交后关闭 taker 的 WSOL ATA（见 take.rs）。
+//!
+use crate::errors::EscrowError;
+use crate::helper::{
+    token_account, transfer_lamports, unwrap_native, ProgramAccount, TokenCloseAccount,
+    TokenTransfer,
+};
+use crate::state::Escrow;
+use crate::whitelist::{check_whitelisted, close_whitelist};
+use crate::{AccountClose, TakeAccounts};
+use core::mem::size_of;
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
+    AccountView, Address, ProgramResult,
+};
+
+// ========== 指令数据结构 ==========
+pub struct TakePartialInstructionData {
+    pub amount: u64,          // 本次希望买入的 Token A 数量（exact-in 模式下为支付的 Token B 数量）
+    pub exact_in: bool,       // exact-in 模式（仅由 Take 使用）
+    pub min_token_a_out: u64, // 滑点保护：taker 至少要收到的 Token A 数量
+    pub unwrap: bool,         // Token A 为 WSOL 时，成交后关闭 taker 的 WSOL ATA，换回原生 SOL
+}
+
+impl<'a> TryFrom<&'a [u8]> for TakePartialInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        // 8 字节 amount，之后可选 1 字节 unwrap（0 或 1）
+        let (amount, unwrap) = data
+            .split_at_checked(size_of::<u64>())
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        let unwrap = match unwrap {
+            [] | [0] => false,
+            [1] => true,
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+
+        let amount = u64::from_le_bytes(amount.try_into().unwrap());
+
+        // 买入数量不能为 0
+        if amount == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self {
+            amount,
+            exact_in: false,
+            min_token_a_out: 0,
+            unwrap,
+        })
+    }
+}
+
+pub struct TakePartial<'a> {
+    pub accounts: TakeAccounts<'a>,
+    pub instruction_data: TakePartialInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for TakePartial<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        // 账户列表与 Take 完全一致，直接复用 TakeAccounts 的校验
+        let accounts = TakeAccounts::try_from(accounts)?;
+
+        let instruction_data = TakePartialInstructionData::try_from(data)?;
+
+        // Initialize necessary accounts
+        accounts.init_atas()?;
+        accounts.check_token_states()?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> TakePartial<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &3;
+
+    pub fn process(&mut self) -> ProgramResult {
+        Self::fill(&self.accounts, &self.instruction_data)
+    }
+
+    /// 部分成交的核心逻辑，TakePartial 与 Take 的 exact-in 模式共用
+    pub fn fill(
+        accounts: &TakeAccounts<'a>,
+        instruction_data: &TakePartialInstructionData,
+    ) -> ProgramResult {
+        let mut data = accounts.escrow.try_borrow_mut()?;
+        let escrow = Escrow::load_mut(data.as_mut())?;
+
+        // Check if the escrow is valid
+        let escrow_key = Address::create_program_address(
+            &[
+                b"escrow",
+                accounts.maker.address().as_ref(),
+                &escrow.seed.to_le_bytes(),
+                &[escrow.bump],
+            ],
+            &crate::ID,
+        )?;
+        if escrow_key != *accounts.escrow.address() {
+            return Err(EscrowError::InvalidEscrowPda.into());
+        }
+
+        // 拍卖模式的 Escrow 只能通过出价成交
+        if escrow.is_auction() {
+            return Err(EscrowError::AuctionMode.into());
+        }
+
+        escrow.check_open()?;
+
+        // 对手方已存入 Token B 的双向托管只能通过 Settle 结算
+        if escrow.is_deposited() {
+            return Err(EscrowError::DepositPending.into());
+        }
+
+        // 三方托管只能通过 Deposit 锁定 Token B，由 Resolve 结算
+        if escrow.has_arbiter() {
+            return Err(EscrowError::ArbiterMode.into());
+        }
+
+        // 捆绑挂单的各种代币只能通过 Take 整体成交；委托模式没有 vault，按委托数量整体成交；NFT 不可分割
+        // UI 报价只在 Take 整体成交时换算
+        if escrow.is_bundle() || escrow.is_delegated() || escrow.is_nft() || escrow.is_ui_receive() {
+            return Err(EscrowError::InvalidAccountData.into());
+        }
+
+        // 部分成交按 receive 计价，只能以 mint_b 支付
+        if accounts.mint_b.address() != &escrow.mint_b {
+            return Err(EscrowError::InvalidMint.into());
+        }
+
+        // 荷兰拍的价格随时间变化，只能通过 Take 一次性成交
+        if escrow.is_dutch() {
+            return Err(EscrowError::InvalidAccountData.into());
+        }
+
+        // 线性释放的 Escrow 只能通过 Take 整体成交，之后由 taker 通过 Claim 领取
+        if escrow.is_vesting() {
+            return Err(EscrowError::InvalidAccountData.into());
+        }
+
+        // 已过期的 Escrow 不能再成交
+        if escrow.is_expired(Clock::get()?.unix_timestamp) {
+            return Err(EscrowError::EscrowExpired.into());
+        }
+
+        // 私有 Escrow 只能由指定的 taker 成交
+        if !escrow.is_taker_allowed(accounts.taker.address()) {
+            return Err(EscrowError::UnauthorizedTaker.into());
+        }
+
+        // 启用白名单的 Escrow：taker 必须在白名单中
+        if escrow.has_whitelist() {
+            check_whitelisted(
+                accounts.whitelist,
+                accounts.escrow,
+                accounts.taker.address(),
+            )?;
+        }
+
+        // vault 当前余额就是剩余可卖的 Token A
+        // 原生 SOL 托管没有 vault：Escrow PDA 中超出租金豁免额的 lamports 即为剩余的 Token A
+        let native_a = escrow.is_native_a();
+        let vault_amount = if native_a {
+            let rent_exempt = Rent::get()?.try_minimum_balance(Escrow::LEN)?;
+            accounts
+                .escrow
+                .lamports()
+                .checked_sub(rent_exempt)
+                .ok_or(ProgramError::InsufficientFunds)?
+        } else {
+            token_account(accounts.vault)?.amount()
+        };
+        // exact-in：按支付的 Token B 折算 Token A，向下取整保护 maker
+        let amount = if instruction_data.exact_in {
+            exact_in_amount(instruction_data.amount, vault_amount, escrow.receive)?
+        } else {
+            instruction_data.amount
+        };
+        if amount == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        if amount > vault_amount {
+            return Err(ProgramError::InsufficientFunds);
+        }
+        if amount < instruction_data.min_token_a_out {
+            return Err(EscrowError::SlippageExceeded.into());
+        }
+
+        let fully_filled = amount == vault_amount;
+        let pay = partial_pay(amount, vault_amount, escrow.receive)?;
+
+        let seed_binding = escrow.seed.to_le_bytes();
+        let bump_binding = [escrow.bump];
+        let escrow_seeds = [
+            Seed::from(b"escrow"),
+            Seed::from(accounts.maker.address().as_ref()),
+            Seed::from(&seed_binding),
+            Seed::from(&bump_binding),
+        ];
+        let signers = [Signer::from(&escrow_seeds)];
+
+        // 记录剩余需要支付的 Token B 数量，备选支付代币按剩余的 Token A 同比例缩小
+        escrow.set_receive(escrow.receive.checked_sub(pay).ok_or(EscrowError::MathOverflow)?);
+        escrow.add_filled(amount)?;
+        escrow.touch(Clock::get()?.slot);
+        if !fully_filled {
+            let remaining = vault_amount.checked_sub(amount).ok_or(EscrowError::MathOverflow)?;
+            escrow.rescale_payment_options(vault_amount, remaining)?;
+        }
+
+        // Transfer from the Taker to the Maker
+        accounts.pay_maker(pay)?;
+
+        // Transfer from the Vault to the Taker
+        // 完全成交的原生 SOL 托管在关闭 Escrow 时把剩余 lamports 一并转给 taker
+        if !native_a {
+            let balance_before = accounts.token_a_balance()?;
+            TokenTransfer {
+                from: accounts.vault,
+                mint: accounts.mint_a,
+                to: accounts.taker_ata_a,
+                authority: accounts.escrow,
+                amount,
+                hook_accounts: accounts.hook_a,
+            }
+            .invoke_signed(&signers)?;
+            accounts.check_received(balance_before, instruction_data.min_token_a_out)?;
+            if instruction_data.unwrap {
+                unwrap_native(accounts.taker_ata_a, accounts.mint_a, accounts.taker)?;
+            }
+        } else if !fully_filled {
+            transfer_lamports(accounts.escrow, accounts.taker, amount)?;
+        }
+
+        if fully_filled {
+            // Close the Vault
+            if !native_a {
+                TokenCloseAccount {
+                    account: accounts.vault,
+                    destination: accounts.maker,
+                    authority: accounts.escrow,
+                }
+                .invoke_signed(&signers)?;
+            }
+
+            // Close the Whitelist
+            if escrow.has_whitelist() {
+                close_whitelist(accounts.whitelist, accounts.escrow, accounts.maker)?;
+            }
+
+            // Close the Escrow
+            drop(data);
+            ProgramAccount::close(accounts.escrow, accounts.taker)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// exact-in：支付 `amount_in` 个 Token B 可以获得的 Token A，floor(amount_in * vault / receive)
+///
+/// 向下取整保护 maker；`amount_in` 不小于 `receive` 时按完全成交处理，获得 vault 全部余额。
+pub fn exact_in_amount(amount_in: u64, vault_amount: u64, receive: u64) -> Result<u64, ProgramError> {
+    if amount_in >= receive {
+        return Ok(vault_amount);
+    }
+    let numerator = amount_in as u128 * vault_amount as u128;
+    u64::try_from(numerator / receive as u128).map_err(|_| EscrowError::MathOverflow.into())
+}
+
+/// 买入 `amount` 个 Token A 应支付的 Token B：ceil(amount * receive / vault_amount)
+///
+/// 使用 u128 避免乘法溢出；向上取整保证 maker 得到的价格不低于挂单价格。
+/// 买下 vault 全部余额时支付剩余的全部 `receive`，因此多次成交的支付总和恰好等于最初的 `receive`。
+pub fn partial_pay(amount: u64, vault_amount: u64, receive: u64) -> Result<u64, ProgramError> {
+    if amount == vault_amount {
+        return Ok(receive);
+    }
+    if amount > vault_amount {
+        return Err(ProgramError::InsufficientFunds);
+    }
+
+    let numerator = amount as u128 * receive as u128;
+    let pay = u64::try_from(numerator.div_ceil(vault_amount as u128)).map_err(|_| EscrowError::MathOverflow)?;
+
+    // 部分成交后必须还有剩余的 receive，否则剩下的 Token A 会被下一个 taker 白拿
+    if pay >= receive {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    Ok(pay)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partial_pay_rounds_against_taker() {
+        // 10 个 Token B 卖 3 个 Token A：买 1 个需要支付 ceil(10 / 3) = 4
+        assert_eq!(partial_pay(1, 3, 10), Ok(4));
+        // 整除时不多收
+        assert_eq!(partial_pay(25, 100, 1_000), Ok(250));
+
+        for (amount, vault, receive) in [(1, 7, 100), (3, 7, 100), (999, 1_000, 7_777), (1, u64::MAX, u64::MAX - 1)] {
+            let pay = partial_pay(amount, vault, receive).unwrap();
+            // 单价不低于挂单价：pay / amount >= receive / vault
+            assert!(pay as u128 * vault as u128 >= amount as u128 * receive as u128);
+        }
+    }
+
+    #[test]
+    fn partial_pay_rejects_dust_that_would_exhaust_receive() {
+        // 1 个 Token B 卖 1000 个 Token A：买 1 个就要支付全部 receive，剩下的 Token A 不能白送
+        assert_eq!(partial_pay(1, 1_000, 1), Err(ProgramError::InvalidInstructionData));
+        assert_eq!(partial_pay(1_001, 1_000, 1), Err(ProgramError::InsufficientFunds));
+        // 买下全部余额时支付剩余的全部 receive
+        assert_eq!(partial_pay(1_000, 1_000, 1), Ok(1));
+    }
+
+    #[test]
+    fn sequential_fills_never_exceed_receive() {
+        for (vault, receive, chunk) in [(3u64, 10u64, 1u64), (1_000, 7, 3), (7, 1_000, 2), (1_000_003, 999_999_937, 12_345)] {
+            let (mut vault_left, mut receive_left, mut paid) = (vault, receive, 0u64);
+            while vault_left > 0 {
+                let amount = chunk.min(vault_left);
+                let (amount, pay) = match partial_pay(amount, vault_left, receive_left) {
+                    Ok(pay) => (amount, pay),
+                    // 粉尘成交被拒绝时由下一个 taker 买下全部剩余
+                    Err(_) => (vault_left, partial_pay(vault_left, vault_left, receive_left).unwrap()),
+                };
+                paid += pay;
+                assert!(paid <= receive);
+                receive_left -= pay;
+                vault_left -= amount;
+            }
+            assert_eq!(paid, receive);
+            assert_eq!(receive_left, 0);
+        }
+    }
+
+    #[test]
+    fn exact_in_never_pays_more_than_amount_in() {
+        assert_eq!(exact_in_amount(10, 3, 10), Ok(3));
+        assert_eq!(exact_in_amount(u64::MAX, 3, 10), Ok(3));
+        // floor(5 * 3 / 10) = 1，实际支付 ceil(1 * 10 / 3) = 4 <= 5
+        assert_eq!(exact_in_amount(5, 3, 10), Ok(1));
+        assert_eq!(partial_pay(1, 3, 10), Ok(4));
+
+        for (amount_in, vault, receive) in [(5u64, 3u64, 10u64), (999, 1_000, 1_000), (123_456, 1_000_000, 9_999_999)] {
+            let amount = exact_in_amount(amount_in, vault, receive).unwrap();
+            if amount > 0 {
+                assert!(partial_pay(amount, vault, receive).unwrap() <= amount_in);
+            }
+        }
+    }
+}
@@ -0,0 +1,205 @@
+//! TopUp 指令实现（向挂单中追加 Token A）
+//!
+//! 本文件实现 `TopUp` 指令：
+//! - 验证调用者为 Escrow 的创建者（必须签名）
+//! - 将额外的 Token A 从 maker 的 ATA 转入 vault
+//! - 可选地按追加比例放大 `receive`（荷兰拍同时放大最低价），保持报价的单价不变
+//!
+//! 这样 maker 无需 Refund 再 Make 即可加大挂单数量。
+//!
+use crate::errors::EscrowError;
+use crate::helper::{
+    AssociatedTokenAccount, MintInterface, ProgramAccount, SignerAccount, TokenTransfer,
+};
+use crate::state::Escrow;
+use crate::update::split_revision;
+use crate::{AccountCheck, AssociatedTokenAccountCheck};
+use core::mem::size_of;
+use pinocchio::{
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    AccountView, Address, ProgramResult,
+};
+use pinocchio_token::state::TokenAccount;
+
+// ========== 账户结构 ==========
+pub struct TopUpAccounts<'a> {
+    pub maker: &'a AccountView,       // 托管创建者（必须是签名者）
+    pub escrow: &'a AccountView,      // Escrow PDA 账户
+    pub mint_a: &'a AccountView,      // Token A 的 Mint
+    pub maker_ata_a: &'a AccountView, // Maker 的 Token A ATA（追加代币的来源）
+    pub vault: &'a AccountView,       // Vault（存储 Token A 的 ATA）
+    pub token_program: &'a AccountView,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for TopUpAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [maker, escrow, mint_a, maker_ata_a, vault, token_program] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // 账户基础校验
+        SignerAccount::check(maker)?;
+        ProgramAccount::check(escrow)?;
+        MintInterface::check(mint_a)?;
+        AssociatedTokenAccount::check(maker_ata_a, maker, mint_a, token_program)?;
+        AssociatedTokenAccount::check(vault, escrow, mint_a, token_program)?;
+
+        Ok(Self {
+            maker,
+            escrow,
+            mint_a,
+            maker_ata_a,
+            vault,
+            token_program,
+        })
+    }
+}
+
+// ========== 指令数据结构 ==========
+pub struct TopUpInstructionData {
+    pub amount: u64,            // 追加的 Token A 数量
+    pub scale_receive: bool,    // 是否按追加比例放大 receive
+    pub expected_revision: u64, // 期望的 Escrow 修订号
+}
+
+impl<'a> TryFrom<&'a [u8]> for TopUpInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        // - 8 字节：amount
+        // - 9 字节：再加上是否放大 receive（0 / 1）
+        // 最后 8 字节为期望的 Escrow 修订号
+        let (data, expected_revision) = split_revision(data)?;
+        let (data, scale_receive) = match data.split_at_checked(size_of::<u64>()) {
+            Some((data, [])) => (data, false),
+            Some((data, [0])) => (data, false),
+            Some((data, [1])) => (data, true),
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+
+        let amount = u64::from_le_bytes(data.try_into().unwrap());
+        if amount == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self {
+            amount,
+            scale_receive,
+            expected_revision,
+        })
+    }
+}
+
+pub struct TopUp<'a> {
+    pub accounts: TopUpAccounts<'a>,
+    pub instruction_data: TopUpInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for TopUp<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = TopUpAccounts::try_from(accounts)?;
+        let instruction_data = TopUpInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> TopUp<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &24;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let mut data = self.accounts.escrow.try_borrow_mut()?;
+        let escrow = Escrow::load_mut(data.as_mut())?;
+
+        // 只有 Escrow 的创建者可以追加
+        if self.accounts.maker.address() != &escrow.maker {
+            return Err(EscrowError::MakerMismatch.into());
+        }
+
+        // 验证 Escrow PDA 是否有效
+        let escrow_key = Address::create_program_address(
+            &[
+                b"escrow",
+                self.accounts.maker.address().as_ref(),
+                &escrow.seed.to_le_bytes(),
+                &[escrow.bump],
+            ],
+            &crate::ID,
+        )?;
+        if &escrow_key != self.accounts.escrow.address() {
+            return Err(EscrowError::InvalidEscrowPda.into());
+        }
+
+        // 原生 SOL 托管没有 vault
+        if escrow.is_native_a() || self.accounts.mint_a.address() != &escrow.mint_a {
+            return Err(EscrowError::InvalidMint.into());
+        }
+
+        // 拍卖模式的 Escrow 只能通过出价成交
+        if escrow.is_auction() {
+            return Err(EscrowError::AuctionMode.into());
+        }
+
+        // 只有 Open 状态可以继续：线性释放成交后只能由 taker 领取，争议中只能由仲裁人裁决
+        escrow.check_open()?;
+        escrow.check_revision(self.instruction_data.expected_revision)?;
+
+        // 捆绑挂单按整体报价，不能只调整 Token A 的数量；委托模式没有 vault；NFT 数量恒为 1
+        if escrow.is_bundle() || escrow.is_delegated() || escrow.is_nft() {
+            return Err(EscrowError::InvalidAccountData.into());
+        }
+
+        // 对手方已存入 Token B 的双向托管只能通过 Settle 结算
+        if escrow.is_deposited() {
+            return Err(EscrowError::DepositPending.into());
+        }
+
+        // 已过期的 Escrow 只能退款
+        if escrow.is_expired(Clock::get()?.unix_timestamp) {
+            return Err(EscrowError::EscrowExpired.into());
+        }
+
+        // 按追加比例放大报价，保持单价不变
+        let current = TokenAccount::from_account_view(self.accounts.vault)?.amount();
+        if self.instruction_data.scale_receive {
+            let topped_up = current
+                .checked_add(self.instruction_data.amount)
+                .ok_or(EscrowError::MathOverflow)?;
+            escrow.rescale(current, topped_up)?;
+        }
+
+        // Transfer the additional Token A to the Vault
+        TokenTransfer {
+            from: self.accounts.maker_ata_a,
+            mint: self.accounts.mint_a,
+            to: self.accounts.vault,
+            authority: self.accounts.maker,
+            amount: self.instruction_data.amount,
+            hook_accounts: &[],
+        }
+        .invoke()?;
+
+        // 按 vault 实际收到的数量（扣除 Token-2022 转账手续费后）累计托管数量
+        let received = TokenAccount::from_account_view(self.accounts.vault)?
+            .amount()
+            .checked_sub(current)
+            .ok_or(EscrowError::MathOverflow)?;
+        escrow.set_amount(
+            escrow
+                .amount
+                .checked_add(received)
+                .ok_or(EscrowError::MathOverflow)?,
+        );
+        escrow.touch(Clock::get()?.slot);
+
+        Ok(())
+    }
+}
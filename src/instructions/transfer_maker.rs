@@ -0,0 +1,293 @@
+//! TransferMaker 指令实现（把挂单移交给新的 maker 地址）
+//!
+//! Escrow PDA 的种子包含 maker 地址（`["escrow", maker, seed]`），所有指令都用传入的 maker
+//! 重新派生 PDA 并以它签名，因此不能只修改状态中的 `maker` 字段。
+//! `TransferMaker` 把整个 Escrow 迁移到新 maker 派生的 PDA（seed 不变）：
+//! - 以新 maker 创建新的 Escrow PDA，复制全部状态，只替换 maker 与 bump
+//! - 创建新 Escrow 拥有的 vault，把 Token A 全部转入（原生 SOL 托管直接划转 lamports）
+//! - 启用白名单时一并迁移白名单 PDA（种子 `["whitelist", 新 escrow]`）
+//! - 关闭旧的 vault、白名单与 Escrow PDA，租金返还给当前 maker（新账户的租金也由当前 maker 支付）
+//!
+//! 注意：Escrow 地址会改变，客户端需要改用新的地址；
+//! 针对旧地址的还价无法再被接受，还价者可以通过 WithdrawCounterOffer 取回。
+//! 拍卖模式（出价 PDA 与 vault_b 绑定旧地址）、捆绑挂单与已 Deposit 的双向托管不支持迁移。
+//!
+use crate::errors::EscrowError;
+use crate::helper::{
+    transfer_lamports, AssociatedTokenAccount, MintInterface, ProgramAccount, SignerAccount, SystemProgramCheck,
+    TokenTransfer,
+};
+use crate::state::{derive_escrow_address, Escrow, Whitelist, ESCROW_SEED};
+use crate::whitelist::verify_whitelist;
+use crate::{
+    AccountCheck, AccountClose, AssociatedTokenAccountCheck, AssociatedTokenAccountInit,
+    ProgramAccountInit,
+};
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
+    AccountView, Address, ProgramResult,
+};
+use pinocchio_token::{
+    instructions::CloseAccount,
+    state::TokenAccount,
+};
+
+// ========== 账户结构 ==========
+pub struct TransferMakerAccounts<'a> {
+    pub maker: &'a AccountView,      // 当前 maker（必须是签名者，支付新账户的租金）
+    pub new_maker: &'a AccountView,  // 新的 maker 地址（无需签名）
+    pub escrow: &'a AccountView,     // 旧的 Escrow PDA
+    pub new_escrow: &'a AccountView, // 新的 Escrow PDA（`["escrow", new_maker, seed]`）
+    pub mint_a: &'a AccountView,     // Token A 的 Mint
+    pub vault: &'a AccountView,      // 旧的 vault（原生 SOL 托管时不使用）
+    pub new_vault: &'a AccountView,  // 新 Escrow 拥有的 vault（原生 SOL 托管时不使用）
+    pub system_program: &'a AccountView,
+    pub token_program: &'a AccountView,
+    pub whitelist: Option<&'a AccountView>,     // 可选：旧的白名单 PDA（Escrow 启用白名单时必须传入）
+    pub new_whitelist: Option<&'a AccountView>, // 可选：新的白名单 PDA
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for TransferMakerAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [maker, new_maker, escrow, new_escrow, mint_a, vault, new_vault, system_program, token_program, _, rest @ ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        SystemProgramCheck::check(system_program)?;
+
+        // 账户基础校验
+        SignerAccount::check(maker)?;
+        ProgramAccount::check(escrow)?;
+
+        // 原生 SOL 托管没有 vault
+        if !Escrow::load(&escrow.try_borrow()?)?.is_native_a() {
+            MintInterface::check(mint_a)?;
+            AssociatedTokenAccount::check(vault, escrow, mint_a, token_program)?;
+        }
+
+        Ok(Self {
+            maker,
+            new_maker,
+            escrow,
+            new_escrow,
+            mint_a,
+            vault,
+            new_vault,
+            system_program,
+            token_program,
+            whitelist: rest.first(),
+            new_whitelist: rest.get(1),
+        })
+    }
+}
+
+// ========== 指令数据结构（TransferMaker 无需额外数据）==========
+pub struct TransferMaker<'a> {
+    pub accounts: TransferMakerAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for TransferMaker<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = TransferMakerAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> TransferMaker<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &26;
+
+    /// 把旧白名单的条目复制到新 Escrow 的白名单 PDA，然后关闭旧白名单
+    fn migrate_whitelist(&self) -> ProgramResult {
+        let whitelist = self.accounts.whitelist.ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let new_whitelist = self
+            .accounts
+            .new_whitelist
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        verify_whitelist(whitelist, self.accounts.escrow)?;
+
+        let (whitelist_key, bump) = Address::find_program_address(
+            &[b"whitelist", self.accounts.new_escrow.address().as_ref()],
+            &crate::ID,
+        );
+        if &whitelist_key != new_whitelist.address() {
+            return Err(EscrowError::InvalidWhitelistPda.into());
+        }
+
+        let bump_binding = [bump];
+        let whitelist_seeds = [
+            Seed::from(b"whitelist"),
+            Seed::from(self.accounts.new_escrow.address().as_ref()),
+            Seed::from(&bump_binding),
+        ];
+        ProgramAccount::init::<Whitelist>(
+            self.accounts.maker,
+            new_whitelist,
+            whitelist_seeds.as_slice(),
+            Whitelist::LEN,
+        )?;
+
+        {
+            let data = whitelist.try_borrow()?;
+            let state = Whitelist::load(&data)?;
+            let mut new_data = new_whitelist.try_borrow_mut()?;
+            let new_state = Whitelist::init(new_data.as_mut())?;
+
+            new_state.set_inner(self.accounts.new_escrow.address().clone(), bump_binding);
+            for entry in state.entries() {
+                new_state.add(entry.clone())?;
+            }
+        }
+
+        ProgramAccount::close(whitelist, self.accounts.maker)
+    }
+
+    pub fn process(&mut self) -> ProgramResult {
+        // 1. 验证旧 Escrow 并保存一份状态快照
+        let mut snapshot = [0u8; Escrow::LEN];
+        let (seed, bump, is_native_a, has_whitelist) = {
+            let data = self.accounts.escrow.try_borrow()?;
+            let escrow = Escrow::load(&data)?;
+
+            // 只有 Escrow 的创建者可以移交
+            if self.accounts.maker.address() != &escrow.maker {
+                return Err(EscrowError::MakerMismatch.into());
+            }
+
+            // 验证 Escrow PDA 是否有效
+            let escrow_key = Address::create_program_address(
+                &[
+                    b"escrow",
+                    self.accounts.maker.address().as_ref(),
+                    &escrow.seed.to_le_bytes(),
+                    &[escrow.bump],
+                ],
+                &crate::ID,
+            )?;
+            if &escrow_key != self.accounts.escrow.address() {
+                return Err(EscrowError::InvalidEscrowPda.into());
+            }
+
+            if self.accounts.new_maker.address() == self.accounts.maker.address() {
+                return Err(EscrowError::InvalidNewMaker.into());
+            }
+
+            // 出价 PDA 与 vault_b 绑定旧的 Escrow 地址，拍卖模式不支持迁移
+            if escrow.is_auction() {
+                return Err(EscrowError::AuctionMode.into());
+            }
+
+            // 捆绑挂单的额外代币 vault 绑定旧的 Escrow 地址，委托授权绑定旧 maker 的 ATA，都不支持迁移
+            if escrow.is_bundle() || escrow.is_delegated() {
+                return Err(EscrowError::InvalidAccountData.into());
+            }
+
+            // 对手方已存入 Token B 的双向托管只能通过 Settle / Resolve 结算
+            if escrow.is_deposited() {
+                return Err(EscrowError::DepositPending.into());
+            }
+
+            if !escrow.is_native_a() && self.accounts.mint_a.address() != &escrow.mint_a {
+                return Err(EscrowError::InvalidMint.into());
+            }
+
+            snapshot.copy_from_slice(&data);
+            (escrow.seed, [escrow.bump], escrow.is_native_a(), escrow.has_whitelist())
+        };
+
+        // 2. 以新 maker 创建新的 Escrow PDA，复制状态并替换 maker 与 bump
+        let seed_binding = seed.to_le_bytes();
+        let (new_escrow_key, new_bump) = derive_escrow_address(self.accounts.new_maker.address(), seed);
+        if &new_escrow_key != self.accounts.new_escrow.address() {
+            return Err(EscrowError::InvalidEscrowPda.into());
+        }
+
+        let new_bump_binding = [new_bump];
+        let new_escrow_seeds = [
+            Seed::from(ESCROW_SEED),
+            Seed::from(self.accounts.new_maker.address().as_ref()),
+            Seed::from(&seed_binding),
+            Seed::from(&new_bump_binding),
+        ];
+        ProgramAccount::init::<Escrow>(
+            self.accounts.maker,
+            self.accounts.new_escrow,
+            new_escrow_seeds.as_slice(),
+            Escrow::LEN,
+        )?;
+
+        {
+            let mut data = self.accounts.new_escrow.try_borrow_mut()?;
+            data.copy_from_slice(&snapshot);
+            let escrow = Escrow::load_mut(data.as_mut())?;
+            escrow.set_maker(self.accounts.new_maker.address().clone());
+            escrow.set_bump(new_bump);
+            escrow.touch(Clock::get()?.slot);
+            if !is_native_a {
+                escrow.set_vault(self.accounts.new_vault.address().clone());
+            }
+        }
+
+        // 3. 迁移托管的 Token A
+        if is_native_a {
+            // 原生 SOL 托管：超出租金豁免额的 lamports 就是托管的 SOL
+            let rent_exempt = Rent::get()?.try_minimum_balance(Escrow::LEN)?;
+            let amount = self.accounts.escrow.lamports().saturating_sub(rent_exempt);
+            transfer_lamports(self.accounts.escrow, self.accounts.new_escrow, amount)?;
+        } else {
+            AssociatedTokenAccount::init(
+                self.accounts.new_vault,
+                self.accounts.mint_a,
+                self.accounts.maker,
+                self.accounts.new_escrow,
+                self.accounts.system_program,
+                self.accounts.token_program,
+            )?;
+
+            let escrow_seeds = [
+                Seed::from(ESCROW_SEED),
+                Seed::from(self.accounts.maker.address().as_ref()),
+                Seed::from(&seed_binding),
+                Seed::from(&bump),
+            ];
+            let signers = [Signer::from(&escrow_seeds)];
+
+            // Transfer Token A from the old Vault to the new Vault
+            TokenTransfer {
+                from: self.accounts.vault,
+                mint: self.accounts.mint_a,
+                to: self.accounts.new_vault,
+                authority: self.accounts.escrow,
+                amount: TokenAccount::from_account_view(self.accounts.vault)?.amount(),
+                hook_accounts: &[],
+            }
+            .invoke_signed(&signers)?;
+
+            // Close the old Vault
+            CloseAccount {
+                account: self.accounts.vault,
+                destination: self.accounts.maker,
+                authority: self.accounts.escrow,
+            }
+            .invoke_signed(&signers)?;
+        }
+
+        // 4. 迁移白名单
+        if has_whitelist {
+            self.migrate_whitelist()?;
+        }
+
+        // 5. 关闭旧的 Escrow（将租金返还给当前 maker）
+        ProgramAccount::close(self.accounts.escrow, self.accounts.maker)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,168 @@
+//! UpdateEscrow 指令实现（修改挂单报价）
+//!
+//! 本文件实现 `UpdateEscrow` 指令：
+//! - 验证调用者为 Escrow 的创建者（必须签名）
+//! - 修改 Escrow 中记录的 `receive`（期望收到的 Token B 数量）
+//! - 如果额外传入了新的 mint_b 账户，同时更换期望收到的代币种类
+//!
+//! 这样 maker 无需 Refund 再 Make（两笔交易 + 租金往返）即可调整报价。
+//!
+//! 指令数据最后 8 字节为期望的 `revision`（客户端读取 Escrow 时的修订号），与当前修订号不同时以
+//! `StaleRevision` 失败；TopUp、WithdrawPartial 与 Renew 同样如此（见 `split_revision`）。
+//!
+use crate::errors::EscrowError;
+use crate::helper::{MintInterface, ProgramAccount, SignerAccount, NATIVE_MINT};
+use crate::state::Escrow;
+use crate::AccountCheck;
+use core::mem::size_of;
+use pinocchio::{
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    AccountView, Address, ProgramResult,
+};
+
+// ========== 账户结构 ==========
+pub struct UpdateEscrowAccounts<'a> {
+    pub maker: &'a AccountView,          // 托管创建者（必须是签名者）
+    pub escrow: &'a AccountView,         // Escrow PDA 账户
+    pub mint_b: Option<&'a AccountView>, // 可选：新的 Token B Mint
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for UpdateEscrowAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [maker, escrow, rest @ ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        let mint_b = rest.first();
+
+        // 账户基础校验
+        SignerAccount::check(maker)?;
+        ProgramAccount::check(escrow)?;
+        if let Some(mint_b) = mint_b {
+            MintInterface::check(mint_b)?;
+        }
+
+        Ok(Self {
+            maker,
+            escrow,
+            mint_b,
+        })
+    }
+}
+
+// ========== 指令数据结构 ==========
+/// 拆出指令数据最后 8 字节的期望修订号（UpdateEscrow、TopUp、WithdrawPartial 与 Renew 共用）
+pub fn split_revision(data: &[u8]) -> Result<(&[u8], u64), ProgramError> {
+    let (data, revision) = data
+        .split_last_chunk::<{ size_of::<u64>() }>()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    Ok((data, u64::from_le_bytes(*revision)))
+}
+
+pub struct UpdateEscrowInstructionData {
+    pub receive: u64,           // 新的期望接收 Token B 数量
+    pub expected_revision: u64, // 期望的 Escrow 修订号
+}
+
+impl<'a> TryFrom<&'a [u8]> for UpdateEscrowInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        let (data, expected_revision) = split_revision(data)?;
+        if data.len() != size_of::<u64>() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let receive = u64::from_le_bytes(data[0..8].try_into().unwrap());
+
+        Ok(Self {
+            receive,
+            expected_revision,
+        })
+    }
+}
+
+pub struct UpdateEscrow<'a> {
+    pub accounts: UpdateEscrowAccounts<'a>,
+    pub instruction_data: UpdateEscrowInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for UpdateEscrow<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = UpdateEscrowAccounts::try_from(accounts)?;
+        let instruction_data = UpdateEscrowInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> UpdateEscrow<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &5;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let mut data = self.accounts.escrow.try_borrow_mut()?;
+        let escrow = Escrow::load_mut(data.as_mut())?;
+
+        // 只有 Escrow 的创建者可以修改报价
+        if self.accounts.maker.address() != &escrow.maker {
+            return Err(EscrowError::MakerMismatch.into());
+        }
+
+        // 验证 Escrow PDA 是否有效
+        let escrow_key = Address::create_program_address(
+            &[
+                b"escrow",
+                self.accounts.maker.address().as_ref(),
+                &escrow.seed.to_le_bytes(),
+                &[escrow.bump],
+            ],
+            &crate::ID,
+        )?;
+        if &escrow_key != self.accounts.escrow.address() {
+            return Err(EscrowError::InvalidEscrowPda.into());
+        }
+
+        // 拍卖模式的 Escrow 只能通过出价成交
+        if escrow.is_auction() {
+            return Err(EscrowError::AuctionMode.into());
+        }
+
+        // 只有 Open 状态可以继续：线性释放成交后只能由 taker 领取，争议中只能由仲裁人裁决
+        escrow.check_open()?;
+        escrow.check_revision(self.instruction_data.expected_revision)?;
+
+        // 对手方已存入 Token B 的双向托管只能通过 Settle 结算
+        if escrow.is_deposited() {
+            return Err(EscrowError::DepositPending.into());
+        }
+
+        // 荷兰拍：receive 为起拍价，不能低于最低价
+        if escrow.is_dutch() && self.instruction_data.receive < escrow.floor_price {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        escrow.set_receive(self.instruction_data.receive);
+        if let Some(mint_b) = self.accounts.mint_b {
+            escrow.set_mint_b(mint_b.address().clone());
+
+            // 更换 mint_b 时同步更新原生 SOL 支付标志位
+            let flags = if mint_b.address() == &NATIVE_MINT {
+                escrow.flags | Escrow::FLAG_NATIVE_B
+            } else {
+                escrow.flags & !Escrow::FLAG_NATIVE_B
+            };
+            escrow.set_flags(flags);
+        }
+        escrow.touch(Clock::get()?.slot);
+
+        Ok(())
+    }
+}
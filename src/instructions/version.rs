@@ -0,0 +1,80 @@
+//! GetVersion 指令实现（查询程序版本与支持的功能）
+//!
+//! 本文件实现 `GetVersion` 指令：
+//! - 不需要任何账户，也没有指令数据
+//! - 通过 `set_return_data` 返回程序的 semver 与功能位图，客户端与 CPI 调用方可以按各集群实际部署的版本选择行为
+//!
+//! 返回数据布局（共 `GetVersion::RETURN_LEN` 字节，整数均为小端序）：
+//! `major(u16) | minor(u16) | patch(u16) | features(u64) | payload_version(u8) | escrow_version(u8)`
+//!
+//! semver 取自 Cargo.toml 的 `version`；`payload_version` 为支持的最高指令数据版本（见 instruction.rs），
+//! `escrow_version` 为当前的 Escrow 布局版本（见 state.rs）。功能位图见 `GetVersion::FEATURE_*`。
+//!
+use crate::instruction::PAYLOAD_VERSION;
+use crate::state::Escrow;
+use core::mem::size_of;
+use pinocchio::{cpi::set_return_data, ProgramResult};
+
+/// 在编译期把 Cargo 传入的版本号分量解析为整数
+const fn parse_version(part: &str) -> u16 {
+    let bytes = part.as_bytes();
+    let mut value = 0u16;
+    let mut i = 0;
+    while i < bytes.len() {
+        value = value * 10 + (bytes[i] - b'0') as u16;
+        i += 1;
+    }
+    value
+}
+
+// ========== 指令结构（GetVersion 不需要账户与指令数据）==========
+pub struct GetVersion;
+
+impl GetVersion {
+    pub const DISCRIMINATOR: &'static u8 = &51;
+
+    /// 返回数据长度
+    pub const RETURN_LEN: usize = size_of::<u16>() * 3 + size_of::<u64>() + size_of::<u8>() * 2;
+
+    pub const MAJOR: u16 = parse_version(env!("CARGO_PKG_VERSION_MAJOR"));
+    pub const MINOR: u16 = parse_version(env!("CARGO_PKG_VERSION_MINOR"));
+    pub const PATCH: u16 = parse_version(env!("CARGO_PKG_VERSION_PATCH"));
+
+    /// 功能：Make / Take / Refund 写入结构化事件（见 events.rs）
+    pub const FEATURE_EVENTS: u64 = 1 << 0;
+    /// 功能：事件带有全局配置分配的序号
+    pub const FEATURE_EVENT_SEQ: u64 = 1 << 1;
+    /// 功能：事件使用 Anchor 格式（`anchor-events` feature）
+    pub const FEATURE_ANCHOR_EVENTS: u64 = 1 << 2;
+    /// 功能：输出可读的调试日志（`verbose-logs` feature）
+    pub const FEATURE_VERBOSE_LOGS: u64 = 1 << 3;
+    /// 功能：接受 Anchor 的 8 字节指令判别器（见 sighash.rs）
+    pub const FEATURE_SIGHASH: u64 = 1 << 4;
+    /// 功能：接受带版本前缀的指令数据（见 instruction.rs）
+    pub const FEATURE_VERSIONED_PAYLOADS: u64 = 1 << 5;
+    /// 功能：maker 的修改类指令校验 Escrow 修订号
+    pub const FEATURE_REVISION: u64 = 1 << 6;
+
+    /// 本次构建支持的功能
+    pub const FEATURES: u64 = Self::FEATURE_EVENTS
+        | Self::FEATURE_EVENT_SEQ
+        | if cfg!(feature = "anchor-events") { Self::FEATURE_ANCHOR_EVENTS } else { 0 }
+        | if cfg!(feature = "verbose-logs") { Self::FEATURE_VERBOSE_LOGS } else { 0 }
+        | Self::FEATURE_SIGHASH
+        | Self::FEATURE_VERSIONED_PAYLOADS
+        | Self::FEATURE_REVISION;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let mut out = [0u8; Self::RETURN_LEN];
+        out[0..2].copy_from_slice(&Self::MAJOR.to_le_bytes());
+        out[2..4].copy_from_slice(&Self::MINOR.to_le_bytes());
+        out[4..6].copy_from_slice(&Self::PATCH.to_le_bytes());
+        out[6..14].copy_from_slice(&Self::FEATURES.to_le_bytes());
+        out[14] = PAYLOAD_VERSION;
+        out[15] = Escrow::CURRENT_VERSION;
+
+        set_return_data(&out);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,129 @@
+//! ViewEscrow 指令实现（只读查询 Escrow 状态）
+//!
+//! 本文件实现 `ViewEscrow` 指令：
+//! - 不修改任何账户，也不需要签名
+//! - 通过 `set_return_data` 返回打包后的 Escrow 摘要，供 CPI 调用方与模拟交易的客户端读取，无需自行解析账户原始字节
+//!
+//! 返回数据布局（共 `ViewEscrow::RETURN_LEN` 字节，整数均为小端序）：
+//! `maker(32) | mint_a(32) | mint_b(32) | receive(u64) | vault_amount(u64) | status(u8)`
+//!
+//! `vault_amount` 为托管中的 Token A 数量：原生 SOL 托管为超出租金豁免额的 lamports，
+//! 委托模式为委托额度，其余为 vault 余额（此时必须传入 vault 账户）。
+//!
+use crate::errors::EscrowError;
+use crate::helper::ProgramAccount;
+use crate::state::Escrow;
+use crate::AccountCheck;
+use core::mem::size_of;
+use pinocchio::{
+    cpi::set_return_data,
+    error::ProgramError,
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
+    AccountView, Address, ProgramResult,
+};
+use pinocchio_token::state::TokenAccount;
+
+// ========== 账户结构 ==========
+pub struct ViewEscrowAccounts<'a> {
+    pub escrow: &'a AccountView,        // Escrow PDA 账户
+    pub vault: Option<&'a AccountView>, // 可选：Token A vault（原生 SOL 托管与委托模式不需要）
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for ViewEscrowAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [escrow, rest @ ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // 账户基础校验
+        ProgramAccount::check(escrow)?;
+
+        Ok(Self {
+            escrow,
+            vault: rest.first(),
+        })
+    }
+}
+
+// ========== 指令数据结构（ViewEscrow 无需额外数据）==========
+pub struct ViewEscrow<'a> {
+    pub accounts: ViewEscrowAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for ViewEscrow<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = ViewEscrowAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> ViewEscrow<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &39;
+
+    /// 返回数据长度
+    pub const RETURN_LEN: usize = size_of::<Address>() * 3 + size_of::<u64>() * 2 + size_of::<u8>();
+
+    /// 状态：可以成交
+    pub const STATUS_OPEN: u8 = 0;
+    /// 状态：已过期，等待 Refund / CloseExpired
+    pub const STATUS_EXPIRED: u8 = 1;
+    /// 状态：对手方已存入 Token B，等待 Settle
+    pub const STATUS_DEPOSITED: u8 = 2;
+    /// 状态：线性释放已成交，taker 正在领取
+    pub const STATUS_TAKEN: u8 = 3;
+    /// 状态：争议中，等待仲裁人裁决
+    pub const STATUS_DISPUTED: u8 = 4;
+
+    fn status(escrow: &Escrow, now: i64) -> u8 {
+        if escrow.is_disputed() {
+            Self::STATUS_DISPUTED
+        } else if escrow.is_taken() {
+            Self::STATUS_TAKEN
+        } else if escrow.is_deposited() {
+            Self::STATUS_DEPOSITED
+        } else if escrow.is_expired(now) {
+            Self::STATUS_EXPIRED
+        } else {
+            Self::STATUS_OPEN
+        }
+    }
+
+    pub fn process(&mut self) -> ProgramResult {
+        let data = self.accounts.escrow.try_borrow()?;
+        let escrow = Escrow::load(&data)?;
+
+        // 托管中的 Token A 数量
+        let vault_amount = if escrow.is_delegated() {
+            escrow.delegated_amount
+        } else if escrow.is_native_a() {
+            let rent_exempt = Rent::get()?.try_minimum_balance(Escrow::LEN)?;
+            self.accounts.escrow.lamports().saturating_sub(rent_exempt)
+        } else {
+            let vault = self.accounts.vault.ok_or(ProgramError::NotEnoughAccountKeys)?;
+            let vault = TokenAccount::from_account_view(vault)?;
+            if vault.owner() != self.accounts.escrow.address() || vault.mint() != &escrow.mint_a {
+                return Err(EscrowError::InvalidVault.into());
+            }
+            vault.amount()
+        };
+
+        let status = Self::status(escrow, Clock::get()?.unix_timestamp);
+
+        let mut out = [0u8; Self::RETURN_LEN];
+        out[0..32].copy_from_slice(escrow.maker.as_ref());
+        out[32..64].copy_from_slice(escrow.mint_a.as_ref());
+        out[64..96].copy_from_slice(escrow.mint_b.as_ref());
+        out[96..104].copy_from_slice(&escrow.receive.to_le_bytes());
+        out[104..112].copy_from_slice(&vault_amount.to_le_bytes());
+        out[112] = status;
+
+        set_return_data(&out);
+
+        Ok(())
+    }
+}
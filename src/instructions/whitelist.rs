@@ -0,0 +1,256 @@
+//! AddToWhitelist / RemoveFromWhitelist 指令实现（许可制 Escrow）
+//!
+//! 本文件实现 Escrow 白名单相关的指令：
+//! - `AddToWhitelist`：maker 向白名单添加 taker 地址，首次调用时创建白名单 PDA
+//!   （种子 `["whitelist", escrow]`）并在 Escrow 中记录 `FLAG_WHITELIST`
+//! - `RemoveFromWhitelist`：maker 从白名单中移除 taker 地址
+//!
+//! 启用白名单后，Take / TakePartial 必须额外传入白名单账户且 taker 在名单中；
+//! Escrow 被关闭（成交、退款、过期）时白名单 PDA 会一并关闭，租金返还给 maker。
+//!
+use crate::errors::EscrowError;
+use crate::helper::{ProgramAccount, SignerAccount, SystemProgramCheck, WhitelistAccount};
+use crate::state::{Escrow, Whitelist};
+use crate::{AccountCheck, AccountClose, ProgramAccountInit};
+use core::mem::size_of;
+use pinocchio::{
+    cpi::Seed,
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    AccountView, Address, ProgramResult,
+};
+
+/// 验证白名单账户属于 `escrow`（owner、长度、PDA 种子以及记录的 Escrow 地址）
+pub fn verify_whitelist(whitelist: &AccountView, escrow: &AccountView) -> ProgramResult {
+    WhitelistAccount::check(whitelist)?;
+
+    let data = whitelist.try_borrow()?;
+    let state = Whitelist::load(&data)?;
+    if &state.escrow != escrow.address() {
+        return Err(EscrowError::InvalidWhitelistPda.into());
+    }
+
+    let whitelist_key = Address::create_program_address(
+        &[b"whitelist", escrow.address().as_ref(), &state.bump],
+        &crate::ID,
+    )?;
+    if &whitelist_key != whitelist.address() {
+        return Err(EscrowError::InvalidWhitelistPda.into());
+    }
+
+    Ok(())
+}
+
+/// 启用白名单的 Escrow：验证白名单账户并确认 taker 在名单中
+pub fn check_whitelisted(
+    whitelist: Option<&AccountView>,
+    escrow: &AccountView,
+    taker: &Address,
+) -> ProgramResult {
+    let whitelist = whitelist.ok_or(ProgramError::NotEnoughAccountKeys)?;
+    verify_whitelist(whitelist, escrow)?;
+
+    if !Whitelist::load(&whitelist.try_borrow()?)?.contains(taker) {
+        return Err(EscrowError::UnauthorizedTaker.into());
+    }
+
+    Ok(())
+}
+
+/// 关闭 Escrow 时一并关闭白名单 PDA，租金返还给 `destination`
+pub fn close_whitelist(
+    whitelist: Option<&AccountView>,
+    escrow: &AccountView,
+    destination: &AccountView,
+) -> ProgramResult {
+    let whitelist = whitelist.ok_or(ProgramError::NotEnoughAccountKeys)?;
+    verify_whitelist(whitelist, escrow)?;
+
+    ProgramAccount::close(whitelist, destination)
+}
+
+// ========== 账户结构 ==========
+pub struct WhitelistAccounts<'a> {
+    pub maker: &'a AccountView,     // 托管创建者（必须是签名者）
+    pub escrow: &'a AccountView,    // Escrow PDA 账户
+    pub whitelist: &'a AccountView, // 白名单 PDA 账户
+    pub system_program: Option<&'a AccountView>, // 首次创建白名单时需要
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for WhitelistAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [maker, escrow, whitelist, rest @ ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // 账户基础校验
+        SignerAccount::check(maker)?;
+        ProgramAccount::check(escrow)?;
+
+        Ok(Self {
+            maker,
+            escrow,
+            whitelist,
+            system_program: rest.first(),
+        })
+    }
+}
+
+impl<'a> WhitelistAccounts<'a> {
+    /// 验证 maker 是 Escrow 的创建者，并校验 Escrow PDA
+    fn check_maker(&self, escrow: &Escrow) -> ProgramResult {
+        // 只有 Escrow 的创建者可以维护白名单
+        if self.maker.address() != &escrow.maker {
+            return Err(EscrowError::MakerMismatch.into());
+        }
+
+        let escrow_key = Address::create_program_address(
+            &[
+                b"escrow",
+                self.maker.address().as_ref(),
+                &escrow.seed.to_le_bytes(),
+                &[escrow.bump],
+            ],
+            &crate::ID,
+        )?;
+        if &escrow_key != self.escrow.address() {
+            return Err(EscrowError::InvalidEscrowPda.into());
+        }
+
+        Ok(())
+    }
+}
+
+// ========== 指令数据结构 ==========
+pub struct WhitelistInstructionData {
+    pub taker: Address, // 要添加 / 移除的 taker 地址
+}
+
+impl<'a> TryFrom<&'a [u8]> for WhitelistInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len() != size_of::<Address>() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let taker = Address::new_from_array(data.try_into().unwrap());
+
+        Ok(Self { taker })
+    }
+}
+
+// ========== AddToWhitelist ==========
+pub struct AddToWhitelist<'a> {
+    pub accounts: WhitelistAccounts<'a>,
+    pub instruction_data: WhitelistInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for AddToWhitelist<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = WhitelistAccounts::try_from(accounts)?;
+        let instruction_data = WhitelistInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> AddToWhitelist<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &7;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let mut data = self.accounts.escrow.try_borrow_mut()?;
+        let escrow = Escrow::load_mut(data.as_mut())?;
+        self.accounts.check_maker(escrow)?;
+
+        if escrow.has_whitelist() {
+            verify_whitelist(self.accounts.whitelist, self.accounts.escrow)?;
+        } else {
+            // 首次添加：创建白名单 PDA
+            // 对应 Anchor 的 init + seeds = [b"whitelist", escrow.key().as_ref()]
+            let system_program = self
+                .accounts
+                .system_program
+                .ok_or(ProgramError::NotEnoughAccountKeys)?;
+            SystemProgramCheck::check(system_program)?;
+
+            let (whitelist_key, bump) = Address::find_program_address(
+                &[b"whitelist", self.accounts.escrow.address().as_ref()],
+                &crate::ID,
+            );
+            if &whitelist_key != self.accounts.whitelist.address() {
+                return Err(EscrowError::InvalidWhitelistPda.into());
+            }
+
+            let bump_binding = [bump];
+            let whitelist_seeds = [
+                Seed::from(b"whitelist"),
+                Seed::from(self.accounts.escrow.address().as_ref()),
+                Seed::from(&bump_binding),
+            ];
+
+            ProgramAccount::init::<Whitelist>(
+                self.accounts.maker,
+                self.accounts.whitelist,
+                whitelist_seeds.as_slice(),
+                Whitelist::LEN,
+            )?;
+
+            let mut whitelist_data = self.accounts.whitelist.try_borrow_mut()?;
+            Whitelist::init(whitelist_data.as_mut())?
+                .set_inner(self.accounts.escrow.address().clone(), bump_binding);
+
+            escrow.set_flags(escrow.flags | Escrow::FLAG_WHITELIST);
+            escrow.touch(Clock::get()?.slot);
+        }
+
+        let mut whitelist_data = self.accounts.whitelist.try_borrow_mut()?;
+        Whitelist::load_mut(whitelist_data.as_mut())?.add(self.instruction_data.taker.clone())
+    }
+}
+
+// ========== RemoveFromWhitelist ==========
+pub struct RemoveFromWhitelist<'a> {
+    pub accounts: WhitelistAccounts<'a>,
+    pub instruction_data: WhitelistInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for RemoveFromWhitelist<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = WhitelistAccounts::try_from(accounts)?;
+        let instruction_data = WhitelistInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> RemoveFromWhitelist<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &8;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let data = self.accounts.escrow.try_borrow()?;
+        let escrow = Escrow::load(&data)?;
+        self.accounts.check_maker(escrow)?;
+
+        // 没有白名单的 Escrow 无可移除
+        if !escrow.has_whitelist() {
+            return Err(EscrowError::InvalidAccountData.into());
+        }
+        verify_whitelist(self.accounts.whitelist, self.accounts.escrow)?;
+
+        let mut whitelist_data = self.accounts.whitelist.try_borrow_mut()?;
+        Whitelist::load_mut(whitelist_data.as_mut())?.remove(&self.instruction_data.taker)
+    }
+}
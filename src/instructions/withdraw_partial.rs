@@ -0,0 +1,208 @@
+//! WithdrawPartial 指令实现（从挂单中取回部分 Token A）
+//!
+//! 本文件实现 `WithdrawPartial` 指令：
+//! - 验证调用者为 Escrow 的创建者（必须签名）
+//! - 将部分 Token A 从 vault 转回 maker 的 ATA，Escrow 保持挂单
+//! - 按取回比例缩小 `receive`（荷兰拍同时缩小最低价），保持报价的单价不变
+//!
+//! 全部取回请使用 Refund。
+//!
+use crate::errors::EscrowError;
+use crate::helper::{
+    AssociatedTokenAccount, MintInterface, ProgramAccount, SignerAccount, TokenTransfer,
+};
+use crate::state::Escrow;
+use crate::update::split_revision;
+use crate::{AccountCheck, AssociatedTokenAccountCheck};
+use core::mem::size_of;
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    AccountView, Address, ProgramResult,
+};
+use pinocchio_token::state::TokenAccount;
+
+// ========== 账户结构 ==========
+pub struct WithdrawPartialAccounts<'a> {
+    pub maker: &'a AccountView,       // 托管创建者（必须是签名者）
+    pub escrow: &'a AccountView,      // Escrow PDA 账户
+    pub mint_a: &'a AccountView,      // Token A 的 Mint
+    pub maker_ata_a: &'a AccountView, // Maker 的 Token A ATA（接收取回的代币）
+    pub vault: &'a AccountView,       // Vault（存储 Token A 的 ATA）
+    pub token_program: &'a AccountView,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for WithdrawPartialAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [maker, escrow, mint_a, maker_ata_a, vault, token_program] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // 账户基础校验
+        SignerAccount::check(maker)?;
+        ProgramAccount::check(escrow)?;
+        MintInterface::check(mint_a)?;
+        AssociatedTokenAccount::check(maker_ata_a, maker, mint_a, token_program)?;
+        AssociatedTokenAccount::check(vault, escrow, mint_a, token_program)?;
+
+        Ok(Self {
+            maker,
+            escrow,
+            mint_a,
+            maker_ata_a,
+            vault,
+            token_program,
+        })
+    }
+}
+
+// ========== 指令数据结构 ==========
+pub struct WithdrawPartialInstructionData {
+    pub amount: u64,            // 取回的 Token A 数量
+    pub expected_revision: u64, // 期望的 Escrow 修订号
+}
+
+impl<'a> TryFrom<&'a [u8]> for WithdrawPartialInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        let (data, expected_revision) = split_revision(data)?;
+        if data.len() != size_of::<u64>() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let amount = u64::from_le_bytes(data[0..8].try_into().unwrap());
+        if amount == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self {
+            amount,
+            expected_revision,
+        })
+    }
+}
+
+pub struct WithdrawPartial<'a> {
+    pub accounts: WithdrawPartialAccounts<'a>,
+    pub instruction_data: WithdrawPartialInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for WithdrawPartial<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = WithdrawPartialAccounts::try_from(accounts)?;
+        let instruction_data = WithdrawPartialInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> WithdrawPartial<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &25;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let (seed, bump) = {
+            let mut data = self.accounts.escrow.try_borrow_mut()?;
+            let escrow = Escrow::load_mut(data.as_mut())?;
+
+            // 只有 Escrow 的创建者可以取回
+            if self.accounts.maker.address() != &escrow.maker {
+                return Err(EscrowError::MakerMismatch.into());
+            }
+
+            // 验证 Escrow PDA 是否有效
+            let escrow_key = Address::create_program_address(
+                &[
+                    b"escrow",
+                    self.accounts.maker.address().as_ref(),
+                    &escrow.seed.to_le_bytes(),
+                    &[escrow.bump],
+                ],
+                &crate::ID,
+            )?;
+            if &escrow_key != self.accounts.escrow.address() {
+                return Err(EscrowError::InvalidEscrowPda.into());
+            }
+
+            // 原生 SOL 托管没有 vault
+            if escrow.is_native_a() || self.accounts.mint_a.address() != &escrow.mint_a {
+                return Err(EscrowError::InvalidMint.into());
+            }
+
+            // 拍卖模式的 Escrow 只能通过出价成交
+            if escrow.is_auction() {
+                return Err(EscrowError::AuctionMode.into());
+            }
+
+            // 只有 Open 状态可以继续：线性释放成交后只能由 taker 领取，争议中只能由仲裁人裁决
+            escrow.check_open()?;
+            escrow.check_revision(self.instruction_data.expected_revision)?;
+
+            // 捆绑挂单按整体报价，不能只调整 Token A 的数量；委托模式没有 vault；NFT 数量恒为 1
+            if escrow.is_bundle() || escrow.is_delegated() || escrow.is_nft() {
+                return Err(EscrowError::InvalidAccountData.into());
+            }
+
+            // 对手方已存入 Token B 的双向托管只能通过 Settle 结算
+            if escrow.is_deposited() {
+                return Err(EscrowError::DepositPending.into());
+            }
+
+            // 设置了取消罚金的 Escrow 只能通过 Refund 撤回，避免分批取回绕过罚金
+            if escrow.has_penalty() {
+                return Err(EscrowError::InvalidAccountData.into());
+            }
+
+            // maker 承诺在锁定期内保持报价有效
+            if escrow.is_refund_locked(Clock::get()?.unix_timestamp) {
+                return Err(EscrowError::RefundLocked.into());
+            }
+
+            // 取回后 vault 中必须还有剩余，全部取回请使用 Refund
+            let current = TokenAccount::from_account_view(self.accounts.vault)?.amount();
+            if self.instruction_data.amount >= current {
+                return Err(ProgramError::InsufficientFunds);
+            }
+
+            // 按取回比例缩小报价，保持单价不变
+            let remaining = current
+                .checked_sub(self.instruction_data.amount)
+                .ok_or(EscrowError::MathOverflow)?;
+            escrow.rescale(current, remaining)?;
+            escrow.set_amount(escrow.amount.saturating_sub(self.instruction_data.amount));
+            escrow.touch(Clock::get()?.slot);
+
+            (escrow.seed, [escrow.bump])
+        };
+
+        let seed_binding = seed.to_le_bytes();
+        let escrow_seeds = [
+            Seed::from(b"escrow"),
+            Seed::from(self.accounts.maker.address().as_ref()),
+            Seed::from(&seed_binding),
+            Seed::from(&bump),
+        ];
+        let signers = [Signer::from(&escrow_seeds)];
+
+        // Transfer Token A from the Vault back to the Maker
+        TokenTransfer {
+            from: self.accounts.vault,
+            mint: self.accounts.mint_a,
+            to: self.accounts.maker_ata_a,
+            authority: self.accounts.escrow,
+            amount: self.instruction_data.amount,
+            hook_accounts: &[],
+        }
+        .invoke_signed(&signers)?;
+
+        Ok(())
+    }
+}
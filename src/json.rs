@@ -0,0 +1,193 @@
+//! 账户状态的 JSON 表示（`json` feature）
+//!
+//! 可用依赖中没有 serde，因此这里不实现 `Serialize` / `Deserialize`，而是用 `Json` 包装类型为 `Escrow` 与 `Config`
+//! 实现 `Display`：链下 Rust 索引器用 `Escrow::load` / `Config::load` 解码账户数据后，`Json(escrow).to_string()`
+//! （或 `write!`）即可得到一个 JSON 对象：
+//! - 地址字段为 base58 字符串（`Address` 的 `Display`，来自 solana-address 的 `decode` feature）
+//! - 整数字段为 JSON 数字，账户判别器为十六进制字符串，预留字节不输出
+//! - `taker` 在公开 Escrow 中为 `null`，`legs` / `payment_options` 只输出已使用的条目
+//!
+//! 不分配内存、不依赖 std，可以写入任何 `core::fmt::Write`。
+//!
+use crate::state::{Config, Escrow};
+use core::fmt::{self, Display, Formatter};
+use pinocchio::Address;
+
+/// 把账户状态格式化为 JSON 对象
+pub struct Json<'a, T>(pub &'a T);
+
+/// 十六进制字符串（不带 0x 前缀）
+struct Hex<'a>(&'a [u8]);
+
+impl Display for Hex<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+/// 逐个写入 JSON 对象的字段，负责花括号与逗号
+struct Object<'a, 'b> {
+    f: &'a mut Formatter<'b>,
+    empty: bool,
+}
+
+impl<'a, 'b> Object<'a, 'b> {
+    fn new(f: &'a mut Formatter<'b>) -> Result<Self, fmt::Error> {
+        f.write_str("{")?;
+        Ok(Self { f, empty: true })
+    }
+
+    /// 写入字段名，返回 Formatter 供调用方写入字段值
+    fn key(&mut self, name: &str) -> Result<&mut Formatter<'b>, fmt::Error> {
+        if !self.empty {
+            self.f.write_str(",")?;
+        }
+        self.empty = false;
+        write!(self.f, "\"{name}\":")?;
+        Ok(self.f)
+    }
+
+    /// 数字字段
+    fn number(&mut self, name: &str, value: impl Display) -> fmt::Result {
+        write!(self.key(name)?, "{value}")
+    }
+
+    /// 字符串字段（值中不会出现需要转义的字符：地址为 base58，其余为十六进制）
+    fn string(&mut self, name: &str, value: impl Display) -> fmt::Result {
+        write!(self.key(name)?, "\"{value}\"")
+    }
+
+    /// 地址字段，None 输出为 null
+    fn address(&mut self, name: &str, value: Option<&Address>) -> fmt::Result {
+        match value {
+            Some(address) => self.string(name, address),
+            None => self.key(name)?.write_str("null"),
+        }
+    }
+
+    fn finish(self) -> fmt::Result {
+        self.f.write_str("}")
+    }
+}
+
+impl Display for Json<'_, Escrow> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let escrow = self.0;
+        let mut object = Object::new(f)?;
+        object.string("discriminator", Hex(&escrow.discriminator))?;
+        object.number("version", escrow.version)?;
+        object.number("status", escrow.status)?;
+        object.number("seed", escrow.seed)?;
+        object.address("maker", Some(&escrow.maker))?;
+        object.address("mint_a", Some(&escrow.mint_a))?;
+        object.address("mint_b", Some(&escrow.mint_b))?;
+        object.number("receive", escrow.receive)?;
+        object.number("expiry_timestamp", escrow.expiry_timestamp)?;
+        object.address("taker", escrow.designated_taker())?;
+        object.number("floor_price", escrow.floor_price)?;
+        object.number("decay_start", escrow.decay_start)?;
+        object.number("decay_end", escrow.decay_end)?;
+        object.number("vesting_duration", escrow.vesting_duration)?;
+        object.number("vesting_start", escrow.vesting_start)?;
+        object.number("vesting_end", escrow.vesting_end)?;
+        object.number("claimed", escrow.claimed)?;
+        object.address("arbiter", Some(&escrow.arbiter))?;
+        object.number("delegated_amount", escrow.delegated_amount)?;
+        object.number("refund_locked_until", escrow.refund_locked_until)?;
+        object.address("penalty_recipient", Some(&escrow.penalty_recipient))?;
+
+        let legs = object.key("legs")?;
+        legs.write_str("[")?;
+        for (i, leg) in escrow.legs().iter().enumerate() {
+            if i != 0 {
+                legs.write_str(",")?;
+            }
+            let mut entry = Object::new(legs)?;
+            entry.address("mint", Some(&leg.mint))?;
+            entry.number("amount", leg.amount)?;
+            entry.finish()?;
+        }
+        legs.write_str("]")?;
+
+        let options = object.key("payment_options")?;
+        options.write_str("[")?;
+        for (i, option) in escrow.payment_options().iter().enumerate() {
+            if i != 0 {
+                options.write_str(",")?;
+            }
+            let mut entry = Object::new(options)?;
+            entry.address("mint", Some(&option.mint))?;
+            entry.number("receive", option.receive)?;
+            entry.finish()?;
+        }
+        options.write_str("]")?;
+
+        object.number("penalty_bps", escrow.penalty_bps)?;
+        object.number("flags", escrow.flags)?;
+        object.number("bump", escrow.bump)?;
+        object.address("vault", Some(&escrow.vault))?;
+        object.number("amount", escrow.amount)?;
+        object.number("filled", escrow.filled)?;
+        object.number("created_at", escrow.created_at)?;
+        object.number("updated_slot", escrow.updated_slot)?;
+        object.number("revision", escrow.revision)?;
+        object.finish()
+    }
+}
+
+impl Display for Json<'_, Config> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let config = self.0;
+        let mut object = Object::new(f)?;
+        object.address("fee_authority", Some(&config.fee_authority))?;
+        object.number("min_amount", config.min_amount)?;
+        object.number("max_amount", config.max_amount)?;
+        object.number("fee_bps", config.fee_bps)?;
+        object.number("referral_bps", config.referral_bps)?;
+        object.number("paused", config.paused)?;
+        object.number("bump", config.bump[0])?;
+        object.number("event_seq", config.event_seq)?;
+        object.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::string::ToString;
+
+    #[test]
+    fn renders_escrow_and_config_as_json() {
+        let mut escrow: Escrow = unsafe { core::mem::zeroed() };
+        escrow.set_receive(1_000);
+        escrow.add_leg(Address::new_from_array([1; 32]), 7).unwrap();
+        let json = Json(&escrow).to_string();
+        assert!(json.starts_with("{\"discriminator\":\"0000000000000000\",\"version\":0,"));
+        assert!(json.contains("\"maker\":\"11111111111111111111111111111111\""));
+        assert!(json.contains("\"receive\":1000,"));
+        assert!(json.contains("\"taker\":null,"));
+        assert!(json.contains("\"legs\":[{\"mint\":\"4vJ9JU1bJJE96FWSJKvHsmmFADCg4gpZQff4P3bkLKi\",\"amount\":7}],"));
+        assert!(json.contains("\"payment_options\":[],"));
+        assert!(json.ends_with("\"revision\":0}"));
+
+        let config = Config {
+            discriminator: Config::ACCOUNT_DISCRIMINATOR,
+            fee_authority: Address::default(),
+            min_amount: 0,
+            max_amount: 0,
+            fee_bps: 30,
+            referral_bps: 10,
+            paused: 0,
+            bump: [255],
+            reserved: [0; 2],
+            event_seq: 3,
+        };
+        assert_eq!(
+            Json(&config).to_string(),
+            "{\"fee_authority\":\"11111111111111111111111111111111\",\"min_amount\":0,\"max_amount\":0,\
+             \"fee_bps\":30,\"referral_bps\":10,\"paused\":0,\"bump\":255,\"event_seq\":3}"
+        );
+    }
+}
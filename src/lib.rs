@@ -1,23 +1,33 @@
 //! Blueshift Escrow 程序入口与模块导出
 //!
 //! 这是一个简化的 Solana 智能合约（使用 pinocchio 框架）的顶层库文件。
-//! - 定义程序 ID
-//! - 导出子模块 `instructions`、`state`、`errors`
-//! - 实现简单的指令分发（根据第一字节的 discriminator 路由到具体指令处理器）
+//! - 定义程序 ID（构建时可用环境变量 `ESCROW_PROGRAM_ID` 覆盖）
+//! - 导出子模块 `instructions`、`state`、`errors`、`instruction`（启用 `idl-build` feature 时还有 `idl`）
+//! - 启用 `no-entrypoint` feature 时不导出 entrypoint 与 panic handler，可以作为依赖链接进其他程序
+//! - 启用 `client` feature 时改为链下客户端库：链接 std、不导出 entrypoint，并提供 `client` 模块（见 client.rs）
+//! - 启用 `cpi` feature 时不导出 entrypoint，并提供 `cpi` 模块供其他链上程序调用（见 cpi.rs）
+//! - 启用 `json` feature 时提供 `json` 模块，把 `Escrow` / `Config` 格式化为 JSON（见 json.rs）
+//! - 导出 `decode` 模块，供索引器识别本程序拥有的任意账户（见 decode.rs）
+//! - 实现简单的指令分发（由 `EscrowInstruction::unpack` 解析判别器，也接受 Anchor 的 8 字节判别器，见 instruction.rs）
+//! - 指令失败时把错误名写入程序日志（见 errors.rs 的 `log_error`）
 //!
 //! 使用说明（快速）：
 //! 1. 构建：`cargo build --target wasm32-unknown-unknown`
 //! 2. 测试：`cargo test`
 //!
-#![no_std]
-use pinocchio::{
-    address::address, entrypoint, error::ProgramError, nostd_panic_handler, AccountView, Address,
-    ProgramResult,
-};
-use state::Escrow;
+#![cfg_attr(not(feature = "client"), no_std)]
+use pinocchio::{address::address, Address};
+#[cfg(not(feature = "no-entrypoint"))]
+use pinocchio::{entrypoint, nostd_panic_handler, AccountView, ProgramResult};
 
+// 作为依赖链接进其他程序（客户端、CPI 调用方）时不能再导出 entrypoint 与 panic handler
+#[cfg(not(feature = "no-entrypoint"))]
 nostd_panic_handler!();
+#[cfg(not(feature = "no-entrypoint"))]
 entrypoint!(process_instruction);
+// 作为依赖时 panic handler 由调用方提供；本地构建（非 Solana 目标）与 nostd_panic_handler! 一样链接 std 的默认实现
+#[cfg(all(feature = "no-entrypoint", not(target_os = "solana")))]
+extern crate std;
 
 pub mod instructions;
 pub use instructions::*;
@@ -26,18 +36,41 @@ pub mod state;
 pub use state::*;
 pub mod errors;
 pub use errors::*;
-// 程序 ID（示例占位地址）
-pub const ID: Address = address!("22222222222222222222222222222222222222222222");
+pub mod instruction;
+pub use instruction::*;
+pub mod decode;
+#[cfg(feature = "idl-build")]
+pub mod idl;
+#[cfg(feature = "client")]
+pub mod client;
+#[cfg(feature = "cpi")]
+pub mod cpi;
+#[cfg(feature = "json")]
+pub mod json;
+// 程序 ID：默认为示例占位地址，构建时可以用环境变量 `ESCROW_PROGRAM_ID`（base58）覆盖，
+// 如 `ESCROW_PROGRAM_ID=<devnet 地址> cargo build-sbf`，各集群的部署地址不需要修改源码。
+// 所有 PDA 派生、owner 校验与 CPI 目标都读取这个常量；地址不是合法的 base58 时编译失败。
+// Cargo 会跟踪 `option_env!` 读取的变量，修改后自动重新编译。
+pub const ID: Address = match option_env!("ESCROW_PROGRAM_ID") {
+    Some(id) => Address::from_str_const(id),
+    None => address!("22222222222222222222222222222222222222222222"),
+};
 
+#[cfg(not(feature = "no-entrypoint"))]
 fn process_instruction(
+    program_id: &Address,
+    accounts: &[AccountView],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    // 失败时先把错误名写入日志，再把错误原样返回给运行时
+    dispatch(program_id, accounts, instruction_data).inspect_err(log_error)
+}
+
+#[cfg(not(feature = "no-entrypoint"))]
+fn dispatch(
     _program_id: &Address,
     accounts: &[AccountView],
     instruction_data: &[u8],
 ) -> ProgramResult {
-    match instruction_data.split_first() {
-        Some((Make::DISCRIMINATOR, data)) => Make::try_from((data, accounts))?.process(),
-        Some((Take::DISCRIMINATOR, _)) => Take::try_from(accounts)?.process(),
-        Some((Refund::DISCRIMINATOR, _)) => Refund::try_from(accounts)?.process(),
-        _ => Err(ProgramError::InvalidInstructionData)
-    }
+    EscrowInstruction::unpack(instruction_data)?.process(accounts)
 }
\ No newline at end of file
@@ -1,43 +1,296 @@
 //! 状态定义：Escrow 结构体
 //!
 //! 本文件定义了存储在 Escrow PDA 中的固定长度结构体布局。
-//! Escrow 包含：种子、创建者、两个 mint 地址、期望接收的数量和 PDA bump。
-//! 使用固定长度并通过 unsafe transmute 快速在字节数组和结构体之间转换，务必保证长度匹配。
+//! Escrow 以 8 字节账户判别器、布局版本号和生命周期状态开头，之后包含：种子、创建者、两个 mint 地址、期望接收的数量、过期时间、指定 taker（`has_taker` 标志位于头部固定偏移）、荷兰拍价格曲线、线性释放进度、委托数量、撤回锁定期、取消罚金、PDA bump、标志位、vault 地址、托管数量与已成交数量，以及创建时间与最后更新的 slot。
+//! 使用固定长度的 `#[repr(C)]` 布局，通过 `from_bytes` / `from_bytes_mut` 在字节数组和结构体之间零拷贝转换。
+//! Escrow 实现了 `Pod`：布局没有填充字节（编译期断言 `size_of::<Escrow>() == Escrow::LEN`），
+//! `load` / `load_mut` 零拷贝读取前同时校验长度与对齐，无法保证对齐的数据使用 `Escrow::read` 复制读取。
+//! 其他账户类型（白名单、出价、还价、全局配置等）同样以 8 字节判别器开头（各自的 `ACCOUNT_DISCRIMINATOR`）并实现 `Pod`
+//! （需要时以 `reserved` 补齐到 8 字节的整数倍），`load` / `load_mut` 校验判别器、长度与对齐，新创建的账户通过 `init` 写入判别器，索引器按判别器识别账户类型（见 decode.rs）。
 //!
-use pinocchio::{address::address, error::ProgramError, AccountView, Address,};
-use core::mem::size_of;
+//! 判别器、版本号、状态、是否指定 taker、seed、maker、mint_a、mint_b 位于固定偏移（见 `Escrow::*_OFFSET`），
+//! 各版本间保持不变，索引器可以直接用于 getProgramAccounts 的 memcmp 过滤：
+//!
+//! | 字段 | 偏移 | 长度 |
+//! |------|------|------|
+//! | discriminator | 0 | 8 |
+//! | version | 8 | 1 |
+//! | status | 9 | 1 |
+//! | has_taker | 10 | 1 |
+//! | seed | 16 | 8 |
+//! | maker | 24 | 32 |
+//! | mint_a | 56 | 32 |
+//! | mint_b | 88 | 32 |
+//!
+use pinocchio::{error::ProgramError, sysvars::clock::Clock, Address};
+use core::mem::{align_of, offset_of, size_of};
+use crate::errors::EscrowError;
+
+/// 可以与字节数组零拷贝互转的账户布局
+///
+/// # Safety
+///
+/// 实现者必须是 `#[repr(C)]`、没有填充字节（`size_of::<Self>()` 等于各字段长度之和），
+/// 且每个字段对任意位模式都合法（整数、字节数组、`Address` 以及同样满足这些条件的结构体）。
+pub unsafe trait Pod: Sized {}
+
+/// 零拷贝读取：校验长度与对齐后把字节切片视为 `T`
+#[inline(always)]
+pub fn from_bytes<T: Pod>(bytes: &[u8]) -> Result<&T, ProgramError> {
+    if bytes.len() != size_of::<T>() || bytes.as_ptr().align_offset(align_of::<T>()) != 0 {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(unsafe { &*bytes.as_ptr().cast::<T>() })
+}
+
+/// 零拷贝可变读取：校验长度与对齐后把字节切片视为 `T`
+#[inline(always)]
+pub fn from_bytes_mut<T: Pod>(bytes: &mut [u8]) -> Result<&mut T, ProgramError> {
+    if bytes.len() != size_of::<T>() || bytes.as_ptr().align_offset(align_of::<T>()) != 0 {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(unsafe { &mut *bytes.as_mut_ptr().cast::<T>() })
+}
+
+/// 从任意对齐的字节切片复制出 `T`（例如客户端解析 RPC 返回的账户数据）
+#[inline(always)]
+pub fn read_unaligned<T: Pod>(bytes: &[u8]) -> Result<T, ProgramError> {
+    if bytes.len() != size_of::<T>() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(unsafe { bytes.as_ptr().cast::<T>().read_unaligned() })
+}
+
+/// 校验账户长度与 8 字节判别器（Escrow 之外的账户类型共用，Escrow 另外校验布局版本）
+#[inline(always)]
+pub fn check_discriminator(bytes: &[u8], len: usize, discriminator: &[u8; 8]) -> Result<(), ProgramError> {
+    if bytes.len() != len || bytes[..8] != *discriminator {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(())
+}
+
+/// 为新创建的账户写入判别器：判别器必须全 0（刚创建的账户），防止覆盖已初始化的账户
+#[inline(always)]
+pub fn write_discriminator(bytes: &mut [u8], len: usize, discriminator: &[u8; 8]) -> Result<(), ProgramError> {
+    if bytes.len() != len || bytes[..8] != [0u8; 8] {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+    bytes[..8].copy_from_slice(discriminator);
+    Ok(())
+}
 
 #[repr(C)]
 pub struct Escrow {
+    pub discriminator: [u8; 8], // 账户判别器（见 Escrow::ACCOUNT_DISCRIMINATOR）
+    pub version: u8,            // 布局版本号（见 Escrow::CURRENT_VERSION），位置在各版本间保持不变
+    pub status: u8,             // 生命周期状态（见 Escrow::STATUS_*），版本 5 起位于固定偏移
+    pub has_taker: u8,          // 是否指定了 taker（0 或 1，版本 6 新增，占用原预留字节）
+    pub reserved: [u8; 5],      // 预留，保持后续字段 8 字节对齐
     pub seed: u64,        // 用于 PDA 派生的种子
     pub maker: Address,    // 托管创建者地址
     pub mint_a: Address,   // 存入的代币 Mint
     pub mint_b: Address,   // 期望获得的代币 Mint
     pub receive: u64,     // 期望接收的代币 B 数量
-    pub bump: [u8;1]      // PDA bump 字节
+    pub expiry_timestamp: i64, // 过期的 Unix 时间戳（0 表示永不过期）
+    pub taker: Address,    // 指定的 taker（has_taker 为 0 时为全 0，任何人都可以成交）
+    pub floor_price: u64,  // 荷兰拍：最低价（Token B 数量）
+    pub decay_start: i64,  // 荷兰拍：开始降价的 Unix 时间戳（价格为 receive）
+    pub decay_end: i64,    // 荷兰拍：降到最低价的 Unix 时间戳
+    pub vesting_duration: i64, // 线性释放：从 Take 开始到全部解锁的时长（秒）
+    pub vesting_start: i64,    // 线性释放：开始释放的 Unix 时间戳（Take 时设置）
+    pub vesting_end: i64,      // 线性释放：全部解锁的 Unix 时间戳（Take 时设置）
+    pub claimed: u64,          // 线性释放：taker 已领取的 Token A 数量
+    pub arbiter: Address,      // 仲裁人（全 0 表示没有仲裁人）
+    pub delegated_amount: u64, // 委托模式：Take 时从 maker 的 Token A ATA 拉取的数量
+    pub refund_locked_until: i64, // 在该 Unix 时间戳之前 maker 不能撤回报价（0 表示不锁定）
+    pub penalty_recipient: Address, // 取消罚金的接收者（penalty_bps 为 0 时无意义）
+    pub legs: [BundleLeg; Escrow::MAX_LEGS], // 捆绑挂单：Token A 之外额外提供的代币
+    pub payment_options: [PaymentOption; Escrow::MAX_PAYMENT_OPTIONS], // mint_b 之外可接受的支付代币
+    pub penalty_bps: u16, // 过期前 Refund 时付给 penalty_recipient 的 Token A 比例（基点，0 表示没有罚金）
+    pub flags: u16,       // 标志位（见 Escrow::FLAG_*）
+    pub leg_count: u8,    // 捆绑挂单：已使用的额外代币条目数（0 表示普通 Escrow）
+    pub payment_option_count: u8, // 已使用的备选支付代币条目数
+    pub legacy_status: u8, // 版本 4 及之前的 status 位置，版本 5 起不再使用
+    pub bump: u8,         // PDA bump（Make 时由 find_program_address 得到的 canonical bump）
+    // 以下为版本 2 新增的字段（新字段追加在末尾，Migrate 扩容后即可原地升级）
+    pub vault: Address,   // 托管 Token A 的账户：vault ATA，委托模式为 maker 的 Token A ATA，原生 SOL 托管为全 0
+    // 以下为版本 3 新增的字段
+    pub amount: u64,      // 累计托管的 Token A 数量（Make 与 TopUp 实际存入的数量，减去 WithdrawPartial 取回的数量）
+    pub filled: u64,      // 已转给 taker 的 Token A 数量（TakePartial 与 Claim 累加；整体成交后 Escrow 直接关闭）
+    // 以下为版本 4 新增的字段
+    pub created_at: i64,  // 创建时的 Unix 时间戳（从旧版本升级而来的 Escrow 为 0）
+    pub updated_slot: u64, // 最后一次修改 Escrow 的 slot（创建时为创建所在的 slot）
+    // 以下为版本 7 新增的字段
+    pub revision: u64,    // 修订号：每次修改后递增（见 `touch`），修改报价类指令必须传入期望的修订号
 }
 impl Escrow {
-    pub const LEN: usize = size_of::<u64>() 
+    pub const LEN: usize = size_of::<[u8; 8]>()
+    + size_of::<u8>()
+    + size_of::<u8>()
+    + size_of::<u8>()
+    + size_of::<[u8; 5]>()
+    + size_of::<u64>() 
     + size_of::<Address>() 
     + size_of::<Address>() 
     + size_of::<Address>() 
     + size_of::<u64>()
-    + size_of::<[u8;1]>();
+    + size_of::<i64>()
+    + size_of::<Address>()
+    + size_of::<u64>()
+    + size_of::<i64>()
+    + size_of::<i64>()
+    + size_of::<i64>()
+    + size_of::<i64>()
+    + size_of::<i64>()
+    + size_of::<u64>()
+    + size_of::<Address>()
+    + size_of::<u64>()
+    + size_of::<i64>()
+    + size_of::<Address>()
+    + size_of::<BundleLeg>() * Self::MAX_LEGS
+    + size_of::<PaymentOption>() * Self::MAX_PAYMENT_OPTIONS
+    + size_of::<u16>()
+    + size_of::<u16>()
+    + size_of::<u8>()
+    + size_of::<u8>()
+    + size_of::<u8>()
+    + size_of::<[u8;1]>()
+    + size_of::<Address>()
+    + size_of::<u64>()
+    + size_of::<u64>()
+    + size_of::<i64>()
+    + size_of::<u64>()
+    + size_of::<u64>();
+
+    /// 捆绑挂单最多额外提供的代币种类数（不含 Token A）
+    pub const MAX_LEGS: usize = 3;
+
+    /// mint_b 之外最多可接受的备选支付代币种类数
+    pub const MAX_PAYMENT_OPTIONS: usize = 2;
+
+    /// 取消罚金比例的上限（基点，10000 = 100%）
+    pub const MAX_PENALTY_BPS: u16 = 10_000;
+
+    /// Token A 为原生 SOL：lamports 直接存放在 Escrow PDA 中，没有 vault
+    pub const FLAG_NATIVE_A: u16 = 1 << 0;
+    /// Token B 为原生 SOL：taker 通过 System Program 直接向 maker 支付 lamports
+    pub const FLAG_NATIVE_B: u16 = 1 << 1;
+    /// 已创建白名单 PDA：只有白名单中的 taker 可以成交
+    pub const FLAG_WHITELIST: u16 = 1 << 2;
+    /// 对手方已通过 Deposit 锁定 Token B：只能通过 Settle 原子结算
+    pub const FLAG_DEPOSITED: u16 = 1 << 3;
+    /// 英式拍卖：taker 通过 PlaceBid 出价，maker 通过 AcceptBid 成交
+    pub const FLAG_AUCTION: u16 = 1 << 4;
+    /// 荷兰拍：Take 时的价格从 receive 随时间线性降到 floor_price
+    pub const FLAG_DUTCH: u16 = 1 << 5;
+    /// 线性释放：Take 后 Token A 留在 vault 中，taker 通过 Claim 按时间逐步领取
+    pub const FLAG_VESTING: u16 = 1 << 6;
+    // 1 << 7、1 << 8 曾用于已成交与争议中，已由 `status` 取代
+    /// 委托模式：Token A 留在 maker 的 ATA 中，Escrow PDA 作为 delegate 在 Take 时拉取
+    pub const FLAG_DELEGATED: u16 = 1 << 9;
+    /// NFT 托管：Token A 为 supply 为 1、decimals 为 0 的 NFT，由 MakeNft 创建
+    pub const FLAG_NFT: u16 = 1 << 10;
+    /// pNFT 托管：Token A 为 Programmable NFT，vault 的转入转出必须通过 Metaplex Transfer（由 MakePnft 创建）
+    pub const FLAG_PNFT: u16 = 1 << 11;
+    /// 版税：NFT 成交时按 Metaplex 元数据向创作者支付版税（由 MakeNft 启用）
+    pub const FLAG_ROYALTIES: u16 = 1 << 12;
+    /// UI 报价：receive 为 mint_b（利率代币）的 UI 金额，Take 时按当前利率换算为原始数量（由 Make 启用）
+    pub const FLAG_UI_RECEIVE: u16 = 1 << 13;
+
+    /// 账户判别器：Make 时写入，load 时校验，关闭时被清除
+    pub const ACCOUNT_DISCRIMINATOR: [u8; 8] = *b"escrowv1";
+    /// 版本号在账户数据中的偏移（紧跟判别器，各版本保持不变）
+    pub const VERSION_OFFSET: usize = 8;
+    /// 当前布局版本：新增或调整字段时递增，并在 Migrate 中添加对应的升级步骤
+    pub const CURRENT_VERSION: u8 = 7;
+
+    /// getProgramAccounts memcmp 过滤使用的字段偏移（各版本间保持不变）
+    pub const STATUS_OFFSET: usize = offset_of!(Escrow, status);
+    pub const HAS_TAKER_OFFSET: usize = offset_of!(Escrow, has_taker);
+    pub const SEED_OFFSET: usize = offset_of!(Escrow, seed);
+    pub const MAKER_OFFSET: usize = offset_of!(Escrow, maker);
+    pub const MINT_A_OFFSET: usize = offset_of!(Escrow, mint_a);
+    pub const MINT_B_OFFSET: usize = offset_of!(Escrow, mint_b);
+
+    /// 状态：可以成交（Make 创建时的初始状态）
+    pub const STATUS_OPEN: u8 = 0;
+    /// 状态：已成交。线性释放的 Escrow 在 Take 之后进入释放阶段，不能再成交或退款；
+    /// 其余 Escrow 成交后直接关闭
+    pub const STATUS_FILLED: u8 = 1;
+    /// 状态：已取消（Refund 会立即关闭 Escrow，为保留账户的取消流程预留）
+    pub const STATUS_CANCELLED: u8 = 2;
+    /// 状态：已过期（CloseExpired 会立即关闭 Escrow，为保留账户的过期流程预留）
+    pub const STATUS_EXPIRED: u8 = 3;
+    /// 状态：争议中。maker 或对手方发起了争议，只有仲裁人可以通过 Resolve 裁决
+    pub const STATUS_DISPUTED: u8 = 4;
 
+    /// 修改已创建 Escrow 的入口：校验判别器、版本、长度与对齐后返回可变引用，
+    /// 字段通过 `set_*` 方法（如 `set_receive`、`set_status`、`set_filled`）原地修改
     #[inline(always)]
     pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
-        if bytes.len() != Escrow::LEN {
-            return Err(ProgramError::InvalidAccountData);
-        }
-        Ok(unsafe { &mut *core::mem::transmute::<*mut u8, *mut Self>(bytes.as_mut_ptr()) })
+        Self::check_header(bytes)?;
+        from_bytes_mut(bytes)
     }
 
+    /// 只读入口：数据长度必须恰好等于 `Escrow::LEN`（不接受更长的账户），
+    /// 与判别器一起防止把本程序拥有的其他类型账户当作 Escrow 读取
     #[inline(always)]
     pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
-        if bytes.len() != Escrow::LEN {
-            return Err(ProgramError::InvalidAccountData);
+        Self::check_header(bytes)?;
+        from_bytes(bytes)
+    }
+
+    /// 从任意对齐的字节切片复制出 Escrow，判别器与版本的校验与 `load` 相同
+    #[inline(always)]
+    pub fn read(bytes: &[u8]) -> Result<Self, ProgramError> {
+        Self::check_header(bytes)?;
+        read_unaligned(bytes)
+    }
+
+    /// 校验账户判别器与布局版本：旧版本的 Escrow 需要先通过 Migrate 升级
+    /// 当前版本要求长度严格等于 `Escrow::LEN`，同时校验 `has_taker` 只能为 0 或 1
+    #[inline(always)]
+    fn check_header(bytes: &[u8]) -> Result<(), ProgramError> {
+        match Self::stored_version(bytes) {
+            Some(Self::CURRENT_VERSION)
+                if bytes.len() == Escrow::LEN && bytes[Self::HAS_TAKER_OFFSET] <= 1 =>
+            {
+                Ok(())
+            }
+            Some(version) if version < Self::CURRENT_VERSION => Err(EscrowError::OutdatedVersion.into()),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+
+    /// 读取账户数据中记录的布局版本号（判别器不匹配时返回 None），不要求长度与当前布局一致
+    #[inline(always)]
+    pub fn stored_version(bytes: &[u8]) -> Option<u8> {
+        match bytes.get(..=Self::VERSION_OFFSET) {
+            Some([discriminator @ .., version]) if discriminator == Self::ACCOUNT_DISCRIMINATOR => Some(*version),
+            _ => None,
+        }
+    }
+
+    /// 跳过版本校验的可变引用，仅供 Migrate 在扩容到当前长度后升级旧版本数据
+    ///
+    /// 新字段只追加在末尾，旧版本的字段在当前布局中的偏移不变。
+    #[inline(always)]
+    pub fn load_mut_unchecked(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        from_bytes_mut(bytes)
+    }
+
+    /// 初始化新创建的 Escrow 账户：写入账户判别器与当前版本号后返回可变引用
+    ///
+    /// 判别器必须全 0（刚创建的账户），防止覆盖已有的 Escrow。
+    #[inline(always)]
+    pub fn init(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if bytes.len() != Escrow::LEN || bytes[..8] != [0u8; 8] {
+            return Err(ProgramError::AccountAlreadyInitialized);
         }
-        Ok(unsafe { &*core::mem::transmute::<*const u8, *const Self>(bytes.as_ptr()) })
+        bytes[..8].copy_from_slice(&Self::ACCOUNT_DISCRIMINATOR);
+        bytes[Self::VERSION_OFFSET] = Self::CURRENT_VERSION;
+        Self::load_mut(bytes)
     }
 
     #[inline(always)]
@@ -66,17 +319,1511 @@ impl Escrow {
     }
 
     #[inline(always)]
-    pub fn set_bump(&mut self, bump: [u8;1]) {
+    pub fn set_expiry_timestamp(&mut self, expiry_timestamp: i64) {
+        self.expiry_timestamp = expiry_timestamp;
+    }
+
+    /// 设置指定的 taker，None 表示任何人都可以成交
+    #[inline(always)]
+    pub fn set_taker(&mut self, taker: Option<Address>) {
+        self.has_taker = taker.is_some() as u8;
+        self.taker = taker.unwrap_or_default();
+    }
+
+    /// 设置荷兰拍价格曲线：`decay_start` 时为 receive，`decay_end` 及之后为 `floor_price`
+    #[inline(always)]
+    pub fn set_dutch(&mut self, floor_price: u64, decay_start: i64, decay_end: i64) {
+        self.floor_price = floor_price;
+        self.decay_start = decay_start;
+        self.decay_end = decay_end;
+    }
+
+    #[inline(always)]
+    pub fn set_vesting_duration(&mut self, vesting_duration: i64) {
+        self.vesting_duration = vesting_duration;
+    }
+
+    /// 开始线性释放：从 `now` 起经过 vesting_duration 全部解锁
+    #[inline(always)]
+    pub fn start_vesting(&mut self, now: i64) {
+        self.vesting_start = now;
+        self.vesting_end = now.saturating_add(self.vesting_duration);
+    }
+
+    #[inline(always)]
+    pub fn set_claimed(&mut self, claimed: u64) {
+        self.claimed = claimed;
+    }
+
+    #[inline(always)]
+    pub fn set_arbiter(&mut self, arbiter: Address) {
+        self.arbiter = arbiter;
+    }
+
+    #[inline(always)]
+    pub fn set_delegated_amount(&mut self, delegated_amount: u64) {
+        self.delegated_amount = delegated_amount;
+    }
+
+    #[inline(always)]
+    pub fn set_refund_locked_until(&mut self, refund_locked_until: i64) {
+        self.refund_locked_until = refund_locked_until;
+    }
+
+    #[inline(always)]
+    pub fn set_penalty(&mut self, penalty_bps: u16, penalty_recipient: Address) {
+        self.penalty_bps = penalty_bps;
+        self.penalty_recipient = penalty_recipient;
+    }
+
+    #[inline(always)]
+    pub fn set_bump(&mut self, bump: u8) {
         self.bump = bump;
     }
 
     #[inline(always)]
-    pub fn set_inner(&mut self, seed: u64, maker: Address, mint_a: Address, mint_b: Address, receive: u64, bump: [u8;1]) {
+    pub fn set_vault(&mut self, vault: Address) {
+        self.vault = vault;
+    }
+
+    #[inline(always)]
+    pub fn set_amount(&mut self, amount: u64) {
+        self.amount = amount;
+    }
+
+    #[inline(always)]
+    pub fn set_filled(&mut self, filled: u64) {
+        self.filled = filled;
+    }
+
+    /// 记录创建时间与创建所在的 slot
+    #[inline(always)]
+    pub fn set_created(&mut self, clock: &Clock) {
+        self.created_at = clock.unix_timestamp;
+        self.updated_slot = clock.slot;
+    }
+
+    /// 修改 Escrow 的指令在结束前调用，记录最后更新的 slot 并递增修订号
+    #[inline(always)]
+    pub fn touch(&mut self, slot: u64) {
+        self.updated_slot = slot;
+        self.revision = self.revision.wrapping_add(1);
+    }
+
+    /// 修改报价类指令的重放保护：传入的期望修订号与当前修订号不同时返回 StaleRevision，
+    /// 防止 maker 基于旧状态构造的修改与 taker 的成交（或其他修改）交错执行
+    #[inline(always)]
+    pub fn check_revision(&self, expected: u64) -> Result<(), ProgramError> {
+        if self.revision != expected {
+            return Err(EscrowError::StaleRevision.into());
+        }
+        Ok(())
+    }
+
+    /// 记录转给 taker 的 Token A 数量
+    #[inline(always)]
+    pub fn add_filled(&mut self, filled: u64) -> Result<(), ProgramError> {
+        self.filled = self
+            .filled
+            .checked_add(filled)
+            .ok_or(EscrowError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// 仍在托管中的 Token A 数量（vault 余额应当与之相等）
+    #[inline(always)]
+    pub fn remaining(&self) -> u64 {
+        self.amount.saturating_sub(self.filled)
+    }
+
+    #[inline(always)]
+    pub fn set_flags(&mut self, flags: u16) {
+        self.flags = flags;
+    }
+
+    #[inline(always)]
+    #[allow(clippy::too_many_arguments)]
+    pub fn set_inner(&mut self, seed: u64, maker: Address, mint_a: Address, mint_b: Address, receive: u64, expiry_timestamp: i64, bump: u8) {
         self.seed = seed;
         self.maker = maker;
         self.mint_a = mint_a;
         self.mint_b = mint_b;
         self.receive = receive;
+        self.expiry_timestamp = expiry_timestamp;
+        self.status = Self::STATUS_OPEN;
         self.bump = bump;
     }
-}
\ No newline at end of file
+
+    #[inline(always)]
+    pub fn set_status(&mut self, status: u8) {
+        self.status = status;
+    }
+
+    /// 校验 Escrow 处于 Open 状态：已成交返回 EscrowTaken，争议中返回 ArbiterMode
+    #[inline(always)]
+    pub fn check_open(&self) -> Result<(), ProgramError> {
+        match self.status {
+            Self::STATUS_OPEN => Ok(()),
+            Self::STATUS_FILLED => Err(EscrowError::EscrowTaken.into()),
+            Self::STATUS_DISPUTED => Err(EscrowError::ArbiterMode.into()),
+            _ => Err(EscrowError::InvalidAccountData.into()),
+        }
+    }
+
+    /// 校验 `address` 是 `maker` 以记录的 seed 与 bump 派生的 Escrow PDA（Take / Refund 使用）
+    ///
+    /// 直接用记录的 bump 调用 `create_program_address`，不重新搜索 canonical bump。
+    pub fn check_address(&self, maker: &Address, address: &Address) -> Result<(), ProgramError> {
+        let escrow_key = Address::create_program_address(
+            &[ESCROW_SEED, maker.as_ref(), &self.seed.to_le_bytes(), &[self.bump]],
+            &crate::ID,
+        )?;
+        if &escrow_key != address {
+            return Err(EscrowError::InvalidEscrowPda.into());
+        }
+        Ok(())
+    }
+
+    /// 在给定时间 `now` 时 Escrow 是否已经过期（未设置过期时间的 Escrow 永不过期）
+    #[inline(always)]
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expiry_timestamp != 0 && now >= self.expiry_timestamp
+    }
+
+    /// 在给定时间 `now` 时 maker 是否仍不能撤回报价（Refund / WithdrawPartial / CancelAuction）
+    #[inline(always)]
+    pub fn is_refund_locked(&self, now: i64) -> bool {
+        now < self.refund_locked_until
+    }
+
+    /// 是否设置了取消罚金
+    #[inline(always)]
+    pub fn has_penalty(&self) -> bool {
+        self.penalty_bps != 0
+    }
+
+    /// 在给定时间 `now` 撤回 `amount` 数量的 Token A 时需要支付的罚金（向下取整，过期后不收取）
+    #[inline(always)]
+    pub fn penalty_for(&self, amount: u64, now: i64) -> u64 {
+        if self.is_expired(now) {
+            return 0;
+        }
+        (amount as u128 * self.penalty_bps as u128 / Self::MAX_PENALTY_BPS as u128) as u64
+    }
+
+    /// 是否为私有 Escrow（只有指定的 taker 可以成交）
+    #[inline(always)]
+    pub fn has_designated_taker(&self) -> bool {
+        self.has_taker != 0
+    }
+
+    /// 指定的 taker（公开 Escrow 为 None）
+    #[inline(always)]
+    pub fn designated_taker(&self) -> Option<&Address> {
+        self.has_designated_taker().then_some(&self.taker)
+    }
+
+    /// `taker` 是否有权成交该 Escrow
+    #[inline(always)]
+    pub fn is_taker_allowed(&self, taker: &Address) -> bool {
+        self.designated_taker().is_none_or(|designated| designated == taker)
+    }
+
+    /// 是否为三方托管（Make 时指定了仲裁人）
+    #[inline(always)]
+    pub fn has_arbiter(&self) -> bool {
+        self.arbiter != Address::default()
+    }
+
+    /// 是否为捆绑挂单（Token A 之外还提供额外的代币）
+    #[inline(always)]
+    pub fn is_bundle(&self) -> bool {
+        self.leg_count != 0
+    }
+
+    /// 捆绑挂单中已使用的额外代币条目
+    #[inline(always)]
+    pub fn legs(&self) -> &[BundleLeg] {
+        &self.legs[..self.leg_count as usize]
+    }
+
+    /// 添加一条额外代币，已满时返回错误
+    pub fn add_leg(&mut self, mint: Address, amount: u64) -> Result<(), ProgramError> {
+        if self.leg_count as usize >= Self::MAX_LEGS {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        self.legs[self.leg_count as usize] = BundleLeg { mint, amount };
+        self.leg_count += 1;
+        Ok(())
+    }
+
+    /// mint_b 之外可接受的备选支付代币
+    #[inline(always)]
+    pub fn payment_options(&self) -> &[PaymentOption] {
+        &self.payment_options[..self.payment_option_count as usize]
+    }
+
+    /// 添加一种备选支付代币，已满时返回错误
+    pub fn add_payment_option(&mut self, mint: Address, receive: u64) -> Result<(), ProgramError> {
+        if self.payment_option_count as usize >= Self::MAX_PAYMENT_OPTIONS {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        self.payment_options[self.payment_option_count as usize] = PaymentOption { mint, receive };
+        self.payment_option_count += 1;
+        Ok(())
+    }
+
+    /// 是否接受以 `mint` 支付（mint_b 或备选支付代币之一）
+    #[inline(always)]
+    pub fn accepts_payment(&self, mint: &Address) -> bool {
+        mint == &self.mint_b || self.payment_options().iter().any(|option| &option.mint == mint)
+    }
+
+    /// 在给定时间 `now` 以 `mint` 支付需要的数量，`mint` 不被接受时返回 None
+    /// mint_b 按 `price_at` 计算，备选支付代币为固定数量
+    pub fn price_in(&self, mint: &Address, now: i64) -> Option<u64> {
+        if mint == &self.mint_b {
+            return Some(self.price_at(now));
+        }
+        self.payment_options()
+            .iter()
+            .find(|option| &option.mint == mint)
+            .map(|option| option.receive)
+    }
+
+    /// 是否处于争议中（只能由仲裁人裁决）
+    #[inline(always)]
+    pub fn is_disputed(&self) -> bool {
+        self.status == Self::STATUS_DISPUTED
+    }
+
+    /// 是否启用了白名单（由 AddToWhitelist 创建白名单 PDA 时设置）
+    #[inline(always)]
+    pub fn has_whitelist(&self) -> bool {
+        self.flags & Self::FLAG_WHITELIST != 0
+    }
+
+    /// 对手方是否已存入 Token B（双向托管）
+    #[inline(always)]
+    pub fn is_deposited(&self) -> bool {
+        self.flags & Self::FLAG_DEPOSITED != 0
+    }
+
+    /// 是否为拍卖模式的 Escrow（Make 时设置）
+    #[inline(always)]
+    pub fn is_auction(&self) -> bool {
+        self.flags & Self::FLAG_AUCTION != 0
+    }
+
+    /// 是否为荷兰拍（价格随时间下降）
+    #[inline(always)]
+    pub fn is_dutch(&self) -> bool {
+        self.flags & Self::FLAG_DUTCH != 0
+    }
+
+    /// 在给定时间 `now` 成交需要支付的 Token B 数量
+    /// 普通 Escrow 固定为 receive；荷兰拍在 [decay_start, decay_end] 区间内从 receive 线性降到 floor_price（向上取整，保护 maker）
+    pub fn price_at(&self, now: i64) -> u64 {
+        if !self.is_dutch() || now <= self.decay_start {
+            return self.receive;
+        }
+        if now >= self.decay_end {
+            return self.floor_price;
+        }
+
+        let range = self.receive.saturating_sub(self.floor_price) as u128;
+        let remaining = (self.decay_end - now) as u128;
+        let duration = (self.decay_end - self.decay_start) as u128;
+        self.floor_price + (range * remaining).div_ceil(duration) as u64
+    }
+
+    /// vault 中的 Token A 从 `from` 变为 `to` 时按比例调整报价（receive、荷兰拍最低价与备选支付代币），保持单价不变
+    /// 向上取整，保护 maker
+    pub fn rescale(&mut self, from: u64, to: u64) -> Result<(), ProgramError> {
+        if from == 0 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let scale = |value: u64| {
+            u64::try_from((value as u128 * to as u128).div_ceil(from as u128))
+                .map_err(|_| EscrowError::MathOverflow)
+        };
+
+        self.receive = scale(self.receive)?;
+        if self.is_dutch() {
+            self.floor_price = scale(self.floor_price)?;
+        }
+        self.rescale_payment_options(from, to)
+    }
+
+    /// vault 中的 Token A 从 `from` 变为 `to` 时按比例调整备选支付代币的数量（向上取整，保护 maker）
+    pub fn rescale_payment_options(&mut self, from: u64, to: u64) -> Result<(), ProgramError> {
+        if from == 0 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let count = self.payment_option_count as usize;
+        for option in self.payment_options[..count].iter_mut() {
+            option.receive = u64::try_from((option.receive as u128 * to as u128).div_ceil(from as u128))
+                .map_err(|_| EscrowError::MathOverflow)?;
+        }
+        Ok(())
+    }
+
+    /// 是否为线性释放的 Escrow
+    #[inline(always)]
+    pub fn is_vesting(&self) -> bool {
+        self.flags & Self::FLAG_VESTING != 0
+    }
+
+    /// 是否已经成交（线性释放进行中）
+    #[inline(always)]
+    pub fn is_taken(&self) -> bool {
+        self.status == Self::STATUS_FILLED
+    }
+
+    /// 在给定时间 `now` 累计已解锁的 Token A 数量，`total` 为释放总量（向下取整）
+    pub fn vested_at(&self, total: u64, now: i64) -> u64 {
+        if now >= self.vesting_end {
+            return total;
+        }
+        if now <= self.vesting_start {
+            return 0;
+        }
+
+        let elapsed = (now - self.vesting_start) as u128;
+        let duration = (self.vesting_end - self.vesting_start) as u128;
+        (total as u128 * elapsed / duration) as u64
+    }
+
+    /// 在给定时间 `now` 可以领取的 Token A 数量：释放总量为 vault 余额加上已领取的数量，扣除已领取的部分
+    pub fn claimable_at(&self, vault_amount: u64, now: i64) -> Result<u64, ProgramError> {
+        let total = vault_amount
+            .checked_add(self.claimed)
+            .ok_or(EscrowError::MathOverflow)?;
+        Ok(self.vested_at(total, now).saturating_sub(self.claimed))
+    }
+
+    /// 是否为委托模式（由 MakeWithDelegate 创建，没有 vault）
+    #[inline(always)]
+    pub fn is_delegated(&self) -> bool {
+        self.flags & Self::FLAG_DELEGATED != 0
+    }
+
+    /// 是否为 NFT 托管（由 MakeNft 创建，Token A 数量恒为 1）
+    #[inline(always)]
+    pub fn is_nft(&self) -> bool {
+        self.flags & Self::FLAG_NFT != 0
+    }
+
+    /// 成交时是否需要支付版税
+    #[inline(always)]
+    pub fn has_royalties(&self) -> bool {
+        self.flags & Self::FLAG_ROYALTIES != 0
+    }
+
+    /// receive 是否为利率代币的 UI 金额
+    #[inline(always)]
+    pub fn is_ui_receive(&self) -> bool {
+        self.flags & Self::FLAG_UI_RECEIVE != 0
+    }
+
+    /// 是否为 pNFT 托管（由 MakePnft 创建）
+    #[inline(always)]
+    pub fn is_pnft(&self) -> bool {
+        self.flags & Self::FLAG_PNFT != 0
+    }
+
+    /// Token A 是否为原生 SOL（由 MakeSol 创建）
+    #[inline(always)]
+    pub fn is_native_a(&self) -> bool {
+        self.flags & Self::FLAG_NATIVE_A != 0
+    }
+
+    /// Token B 是否为原生 SOL（mint_b 为 NATIVE_MINT）
+    #[inline(always)]
+    pub fn is_native_b(&self) -> bool {
+        self.flags & Self::FLAG_NATIVE_B != 0
+    }
+}
+
+// Escrow 的所有字段都是整数、字节数组、Address 或同样满足 Pod 条件的结构体
+unsafe impl Pod for Escrow {}
+const _: () = assert!(size_of::<Escrow>() == Escrow::LEN);
+
+// 固定偏移是对索引器的承诺，调整布局时不能移动这些字段
+const _: () = assert!(
+    offset_of!(Escrow, version) == Escrow::VERSION_OFFSET
+        && Escrow::STATUS_OFFSET == 9
+        && Escrow::HAS_TAKER_OFFSET == 10
+        && Escrow::SEED_OFFSET == 16
+        && Escrow::MAKER_OFFSET == 24
+        && Escrow::MINT_A_OFFSET == 56
+        && Escrow::MINT_B_OFFSET == 88
+);
+
+/// Escrow PDA 的种子前缀，完整种子为 `["escrow", maker, seed.to_le_bytes(), bump]`
+pub const ESCROW_SEED: &[u8] = b"escrow";
+
+/// 派生 maker 以 `seed` 创建的 Escrow PDA 地址与 canonical bump
+///
+/// Make 只接受这里派生的地址，链上 CPI 调用方与链下客户端应使用同一实现，而不是各自拼接种子。
+pub fn derive_escrow_address(maker: &Address, seed: u64) -> (Address, u8) {
+    Address::find_program_address(&[ESCROW_SEED, maker.as_ref(), &seed.to_le_bytes()], &crate::ID)
+}
+
+/// 派生 Escrow 托管 Token A 的 vault：`escrow` 拥有的 mint_a ATA
+///
+/// `token_program` 为 mint_a 所属的 Token Program（原版或 Token-2022）。
+/// 委托模式没有 vault，传入 maker 得到的是 maker 的 Token A ATA。
+pub fn derive_vault_address(escrow: &Address, mint_a: &Address, token_program: &Address) -> Address {
+    Address::find_program_address(
+        &[escrow.as_ref(), token_program.as_ref(), mint_a.as_ref()],
+        &pinocchio_associated_token_account::ID,
+    )
+    .0
+}
+
+/// 捆绑挂单中的一条额外代币：存放在 Escrow 拥有的该 Mint 的 ATA 中
+#[repr(C)]
+pub struct BundleLeg {
+    pub mint: Address, // 额外代币的 Mint
+    pub amount: u64,   // Make 时 vault 实际收到的数量（Token-2022 转账手续费会少于转出数量）
+}
+
+/// mint_b 之外可接受的一种备选支付代币：taker 可以改用该 Mint 支付 `receive` 数量
+#[repr(C)]
+pub struct PaymentOption {
+    pub mint: Address, // 备选支付代币的 Mint
+    pub receive: u64,  // 以该代币支付时需要的数量
+}
+
+unsafe impl Pod for BundleLeg {}
+const _: () = assert!(size_of::<BundleLeg>() == size_of::<Address>() + size_of::<u64>());
+unsafe impl Pod for PaymentOption {}
+const _: () = assert!(size_of::<PaymentOption>() == size_of::<Address>() + size_of::<u64>());
+
+/// 白名单：每个 Escrow 最多一个，PDA 种子为 `["whitelist", escrow, bump]`
+/// 只有列表中的 taker 可以成交该 Escrow，比单个指定 taker 更灵活
+#[repr(C)]
+pub struct Whitelist {
+    pub discriminator: [u8; 8],                       // 账户判别器（见 Whitelist::ACCOUNT_DISCRIMINATOR）
+    pub escrow: Address,                              // 所属的 Escrow 地址
+    pub entries: [Address; Whitelist::MAX_ENTRIES],   // 允许成交的 taker 列表
+    pub count: u8,                                    // 已使用的条目数
+    pub bump: [u8;1]                                  // PDA bump 字节
+}
+
+unsafe impl Pod for Whitelist {}
+const _: () = assert!(size_of::<Whitelist>() == Whitelist::LEN);
+
+impl Whitelist {
+    /// 单个白名单最多容纳的地址数量
+    pub const MAX_ENTRIES: usize = 16;
+
+    /// 账户判别器：位于数据开头，`load` / `load_mut` 时校验
+    pub const ACCOUNT_DISCRIMINATOR: [u8; 8] = *b"wlist_v1";
+
+    pub const LEN: usize = size_of::<[u8; 8]>()
+    + size_of::<Address>()
+    + size_of::<Address>() * Self::MAX_ENTRIES
+    + size_of::<u8>()
+    + size_of::<[u8;1]>();
+
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        check_discriminator(bytes, Self::LEN, &Self::ACCOUNT_DISCRIMINATOR)?;
+        from_bytes_mut(bytes)
+    }
+
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        check_discriminator(bytes, Self::LEN, &Self::ACCOUNT_DISCRIMINATOR)?;
+        from_bytes(bytes)
+    }
+
+    /// 初始化新创建的账户：写入账户判别器后返回可变引用
+    #[inline(always)]
+    pub fn init(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        write_discriminator(bytes, Self::LEN, &Self::ACCOUNT_DISCRIMINATOR)?;
+        Self::load_mut(bytes)
+    }
+
+    #[inline(always)]
+    pub fn set_inner(&mut self, escrow: Address, bump: [u8;1]) {
+        self.escrow = escrow;
+        self.count = 0;
+        self.bump = bump;
+    }
+
+    /// 当前有效的白名单条目
+    #[inline(always)]
+    pub fn entries(&self) -> &[Address] {
+        &self.entries[..self.count as usize]
+    }
+
+    #[inline(always)]
+    pub fn contains(&self, taker: &Address) -> bool {
+        self.entries().iter().any(|entry| entry == taker)
+    }
+
+    /// 添加地址（已存在时直接返回成功），列表已满时返回错误
+    pub fn add(&mut self, taker: Address) -> Result<(), ProgramError> {
+        if self.contains(&taker) {
+            return Ok(());
+        }
+        if self.count as usize >= Self::MAX_ENTRIES {
+            return Err(EscrowError::WhitelistFull.into());
+        }
+        self.entries[self.count as usize] = taker;
+        self.count += 1;
+        Ok(())
+    }
+
+    /// 移除地址（用最后一个条目填补空位），地址不存在时返回错误
+    pub fn remove(&mut self, taker: &Address) -> Result<(), ProgramError> {
+        let index = self
+            .entries()
+            .iter()
+            .position(|entry| entry == taker)
+            .ok_or(ProgramError::InvalidArgument)?;
+        let last = self.count as usize - 1;
+        self.entries.swap(index, last);
+        self.entries[last] = Address::default();
+        self.count -= 1;
+        Ok(())
+    }
+}
+
+/// 拍卖出价：每个拍卖 Escrow 一个，PDA 种子为 `["bid", escrow, bump]`
+/// 记录当前最高出价，出价的 Token B 锁定在 Escrow 拥有的 vault_b 中
+#[repr(C)]
+pub struct Bid {
+    pub discriminator: [u8; 8], // 账户判别器（见 Bid::ACCOUNT_DISCRIMINATOR）
+    pub amount: u64,        // 当前最高出价（Token B 数量）
+    pub escrow: Address,    // 所属的 Escrow 地址
+    pub bidder: Address,    // 当前最高出价者（全 0 表示尚无出价）
+    pub bump: [u8;1],       // PDA bump 字节
+    pub reserved: [u8; 7],  // 预留，保持 8 字节对齐
+}
+
+unsafe impl Pod for Bid {}
+const _: () = assert!(size_of::<Bid>() == Bid::LEN);
+
+impl Bid {
+    /// 账户判别器：位于数据开头，`load` / `load_mut` 时校验
+    pub const ACCOUNT_DISCRIMINATOR: [u8; 8] = *b"bid___v1";
+
+    pub const LEN: usize = size_of::<[u8; 8]>()
+    + size_of::<u64>()
+    + size_of::<Address>()
+    + size_of::<Address>()
+    + size_of::<[u8;1]>()
+    + size_of::<[u8; 7]>();
+
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        check_discriminator(bytes, Self::LEN, &Self::ACCOUNT_DISCRIMINATOR)?;
+        from_bytes_mut(bytes)
+    }
+
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        check_discriminator(bytes, Self::LEN, &Self::ACCOUNT_DISCRIMINATOR)?;
+        from_bytes(bytes)
+    }
+
+    /// 初始化新创建的账户：写入账户判别器后返回可变引用
+    #[inline(always)]
+    pub fn init(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        write_discriminator(bytes, Self::LEN, &Self::ACCOUNT_DISCRIMINATOR)?;
+        Self::load_mut(bytes)
+    }
+
+    #[inline(always)]
+    pub fn set_inner(&mut self, escrow: Address, bump: [u8;1]) {
+        self.amount = 0;
+        self.escrow = escrow;
+        self.bidder = Address::default();
+        self.bump = bump;
+    }
+
+    /// 记录新的最高出价
+    #[inline(always)]
+    pub fn set_bid(&mut self, bidder: Address, amount: u64) {
+        self.bidder = bidder;
+        self.amount = amount;
+    }
+
+    /// 是否已经有人出价
+    #[inline(always)]
+    pub fn has_bid(&self) -> bool {
+        self.bidder != Address::default()
+    }
+}
+
+/// 还价：taker 对某个 Escrow 提出的替代报价，PDA 种子为 `["offer", escrow, proposer, bump]`
+/// 报价的 Token B 锁定在该 PDA 拥有的 Token B ATA 中，maker 接受或 proposer 撤回时关闭
+#[repr(C)]
+pub struct Offer {
+    pub discriminator: [u8; 8], // 账户判别器（见 Offer::ACCOUNT_DISCRIMINATOR）
+    pub amount: u64,        // 提出的 Token B 数量
+    pub escrow: Address,    // 所属的 Escrow 地址
+    pub proposer: Address,  // 还价者
+    pub bump: [u8;1],       // PDA bump 字节
+    pub reserved: [u8; 7],  // 预留，保持 8 字节对齐
+}
+
+unsafe impl Pod for Offer {}
+const _: () = assert!(size_of::<Offer>() == Offer::LEN);
+
+impl Offer {
+    /// 账户判别器：位于数据开头，`load` / `load_mut` 时校验
+    pub const ACCOUNT_DISCRIMINATOR: [u8; 8] = *b"offer_v1";
+
+    pub const LEN: usize = size_of::<[u8; 8]>()
+    + size_of::<u64>()
+    + size_of::<Address>()
+    + size_of::<Address>()
+    + size_of::<[u8;1]>()
+    + size_of::<[u8; 7]>();
+
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        check_discriminator(bytes, Self::LEN, &Self::ACCOUNT_DISCRIMINATOR)?;
+        from_bytes_mut(bytes)
+    }
+
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        check_discriminator(bytes, Self::LEN, &Self::ACCOUNT_DISCRIMINATOR)?;
+        from_bytes(bytes)
+    }
+
+    /// 初始化新创建的账户：写入账户判别器后返回可变引用
+    #[inline(always)]
+    pub fn init(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        write_discriminator(bytes, Self::LEN, &Self::ACCOUNT_DISCRIMINATOR)?;
+        Self::load_mut(bytes)
+    }
+
+    #[inline(always)]
+    pub fn set_inner(&mut self, amount: u64, escrow: Address, proposer: Address, bump: [u8;1]) {
+        self.amount = amount;
+        self.escrow = escrow;
+        self.proposer = proposer;
+        self.bump = bump;
+    }
+}
+
+/// 全局配置：整个程序只有一个，PDA 种子为 `["config", bump]`
+/// 记录协议手续费与推荐费（均按 Token B 成交额的万分比收取）、有权修改配置与提取手续费的管理员、
+/// 暂停开关、Make 允许托管的 Token A 数量范围，以及事件序号计数器。
+/// 手续费按 Token B 的 Mint 分别存放在配置 PDA 拥有的 ATA（fee vault）中，因此不记录单一的 fee vault。
+#[repr(C)]
+pub struct Config {
+    pub discriminator: [u8; 8], // 账户判别器（见 Config::ACCOUNT_DISCRIMINATOR）
+    pub fee_authority: Address, // 手续费管理员（全局配置的管理员）
+    pub min_amount: u64,        // Make 托管的最小 Token A 数量（原始数量，0 表示不限制）
+    pub max_amount: u64,        // Make 托管的最大 Token A 数量（原始数量，0 表示不限制）
+    pub fee_bps: u16,           // 手续费费率（万分比）
+    pub referral_bps: u16,      // 推荐费费率（万分比，从手续费中分出，Take 时传入推荐人才收取）
+    pub paused: u8,             // 暂停开关（非 0 时 Make 与 Take 被拒绝）
+    pub bump: [u8;1],           // PDA bump 字节
+    pub reserved: [u8; 2],      // 预留，保持 8 字节对齐
+    pub event_seq: u64,         // 最近一条事件的序号（每写入一条事件加 1，见 events.rs）
+}
+
+unsafe impl Pod for Config {}
+const _: () = assert!(size_of::<Config>() == Config::LEN);
+
+impl Config {
+    /// 费率上限：100%
+    pub const MAX_FEE_BPS: u16 = 10_000;
+
+    /// 账户判别器：位于数据开头，`load` / `load_mut` 时校验
+    pub const ACCOUNT_DISCRIMINATOR: [u8; 8] = *b"configv1";
+
+    pub const LEN: usize = size_of::<[u8; 8]>()
+    + size_of::<Address>()
+    + size_of::<u64>()
+    + size_of::<u64>()
+    + size_of::<u16>()
+    + size_of::<u16>()
+    + size_of::<u8>()
+    + size_of::<[u8;1]>()
+    + size_of::<[u8; 2]>()
+    + size_of::<u64>();
+
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        check_discriminator(bytes, Self::LEN, &Self::ACCOUNT_DISCRIMINATOR)?;
+        from_bytes_mut(bytes)
+    }
+
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        check_discriminator(bytes, Self::LEN, &Self::ACCOUNT_DISCRIMINATOR)?;
+        from_bytes(bytes)
+    }
+
+    /// 初始化新创建的账户：写入账户判别器后返回可变引用
+    #[inline(always)]
+    pub fn init(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        write_discriminator(bytes, Self::LEN, &Self::ACCOUNT_DISCRIMINATOR)?;
+        Self::load_mut(bytes)
+    }
+
+    #[inline(always)]
+    pub fn set_fee_authority(&mut self, fee_authority: Address) {
+        self.fee_authority = fee_authority;
+    }
+
+    #[inline(always)]
+    pub fn set_fee_bps(&mut self, fee_bps: u16) {
+        self.fee_bps = fee_bps;
+    }
+
+    #[inline(always)]
+    pub fn set_referral_bps(&mut self, referral_bps: u16) {
+        self.referral_bps = referral_bps;
+    }
+
+    #[inline(always)]
+    pub fn set_inner(&mut self, fee_authority: Address, fee_bps: u16, referral_bps: u16, bump: [u8;1]) {
+        self.fee_authority = fee_authority;
+        self.fee_bps = fee_bps;
+        self.referral_bps = referral_bps;
+        self.paused = 0;
+        self.bump = bump;
+        self.event_seq = 0;
+    }
+
+    #[inline(always)]
+    pub fn set_limits(&mut self, min_amount: u64, max_amount: u64) {
+        self.min_amount = min_amount;
+        self.max_amount = max_amount;
+    }
+
+    #[inline(always)]
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused as u8;
+    }
+
+    /// 分配下一条事件的序号（从 1 开始，0 表示未编号）
+    #[inline(always)]
+    pub fn next_event_seq(&mut self) -> u64 {
+        self.event_seq = self.event_seq.wrapping_add(1);
+        self.event_seq
+    }
+
+    /// 是否处于暂停状态
+    #[inline(always)]
+    pub fn is_paused(&self) -> bool {
+        self.paused != 0
+    }
+
+    /// 暂停时返回 ProgramPaused
+    #[inline(always)]
+    pub fn check_not_paused(&self) -> Result<(), ProgramError> {
+        if self.is_paused() {
+            return Err(EscrowError::ProgramPaused.into());
+        }
+        Ok(())
+    }
+
+    /// Make 前的校验：未暂停，且托管数量在允许范围内
+    #[inline(always)]
+    pub fn check_make(&self, amount: u64) -> Result<(), ProgramError> {
+        self.check_not_paused()?;
+        if amount < self.min_amount || (self.max_amount != 0 && amount > self.max_amount) {
+            return Err(EscrowError::AmountOutOfRange.into());
+        }
+        Ok(())
+    }
+
+    /// 成交额 `amount` 对应的手续费（向下取整）
+    #[inline(always)]
+    pub fn fee_for(&self, amount: u64) -> u64 {
+        (amount as u128 * self.fee_bps as u128 / Self::MAX_FEE_BPS as u128) as u64
+    }
+
+    /// 成交额 `amount` 对应的推荐费（向下取整），从手续费中分出，不超过 `fee_for(amount)`
+    /// （SetFee 保证 referral_bps 不高于 fee_bps，这里再兜底旧配置）
+    #[inline(always)]
+    pub fn referral_for(&self, amount: u64) -> u64 {
+        let referral = (amount as u128 * self.referral_bps as u128 / Self::MAX_FEE_BPS as u128) as u64;
+        referral.min(self.fee_for(amount))
+    }
+}
+
+/// 众筹池：多个 taker 共同凑齐一个 Escrow 的 receive，PDA 种子为 `["pool", escrow, bump]`
+/// 凑集的 Token B 锁定在该 PDA 拥有的 Token B ATA（pool vault）中
+#[repr(C)]
+pub struct Pool {
+    pub discriminator: [u8; 8], // 账户判别器（见 Pool::ACCOUNT_DISCRIMINATOR）
+    pub total: u64,         // 已凑集的 Token B 数量
+    pub escrow: Address,    // 所属的 Escrow 地址
+    pub creator: Address,   // 创建者（第一个出资者，支付池子与 pool vault 的租金）
+    pub contributors: u8,   // 当前出资人数
+    pub bump: [u8;1],       // PDA bump 字节
+    pub reserved: [u8; 6],  // 预留，保持 8 字节对齐
+}
+
+unsafe impl Pod for Pool {}
+const _: () = assert!(size_of::<Pool>() == Pool::LEN);
+
+impl Pool {
+    /// 单个众筹池最多容纳的出资人数（Finalize 需要在一笔交易中传入所有出资者的账户）
+    pub const MAX_CONTRIBUTORS: u8 = 8;
+
+    /// 账户判别器：位于数据开头，`load` / `load_mut` 时校验
+    pub const ACCOUNT_DISCRIMINATOR: [u8; 8] = *b"pool__v1";
+
+    pub const LEN: usize = size_of::<[u8; 8]>()
+    + size_of::<u64>()
+    + size_of::<Address>()
+    + size_of::<Address>()
+    + size_of::<u8>()
+    + size_of::<[u8;1]>()
+    + size_of::<[u8; 6]>();
+
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        check_discriminator(bytes, Self::LEN, &Self::ACCOUNT_DISCRIMINATOR)?;
+        from_bytes_mut(bytes)
+    }
+
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        check_discriminator(bytes, Self::LEN, &Self::ACCOUNT_DISCRIMINATOR)?;
+        from_bytes(bytes)
+    }
+
+    /// 初始化新创建的账户：写入账户判别器后返回可变引用
+    #[inline(always)]
+    pub fn init(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        write_discriminator(bytes, Self::LEN, &Self::ACCOUNT_DISCRIMINATOR)?;
+        Self::load_mut(bytes)
+    }
+
+    #[inline(always)]
+    pub fn set_inner(&mut self, escrow: Address, creator: Address, bump: [u8;1]) {
+        self.total = 0;
+        self.escrow = escrow;
+        self.creator = creator;
+        self.contributors = 0;
+        self.bump = bump;
+    }
+
+    #[inline(always)]
+    pub fn set_total(&mut self, total: u64) {
+        self.total = total;
+    }
+
+    #[inline(always)]
+    pub fn set_contributors(&mut self, contributors: u8) {
+        self.contributors = contributors;
+    }
+}
+
+/// 出资记录：某个出资者在某个众筹池中的出资，PDA 种子为 `["contribution", pool, contributor, bump]`
+#[repr(C)]
+pub struct Contribution {
+    pub discriminator: [u8; 8], // 账户判别器（见 Contribution::ACCOUNT_DISCRIMINATOR）
+    pub amount: u64,          // 出资的 Token B 数量
+    pub pool: Address,        // 所属的众筹池地址
+    pub contributor: Address, // 出资者
+    pub bump: [u8;1],         // PDA bump 字节
+    pub reserved: [u8; 7],    // 预留，保持 8 字节对齐
+}
+
+unsafe impl Pod for Contribution {}
+const _: () = assert!(size_of::<Contribution>() == Contribution::LEN);
+
+impl Contribution {
+    /// 账户判别器：位于数据开头，`load` / `load_mut` 时校验
+    pub const ACCOUNT_DISCRIMINATOR: [u8; 8] = *b"contrbv1";
+
+    pub const LEN: usize = size_of::<[u8; 8]>()
+    + size_of::<u64>()
+    + size_of::<Address>()
+    + size_of::<Address>()
+    + size_of::<[u8;1]>()
+    + size_of::<[u8; 7]>();
+
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        check_discriminator(bytes, Self::LEN, &Self::ACCOUNT_DISCRIMINATOR)?;
+        from_bytes_mut(bytes)
+    }
+
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        check_discriminator(bytes, Self::LEN, &Self::ACCOUNT_DISCRIMINATOR)?;
+        from_bytes(bytes)
+    }
+
+    /// 初始化新创建的账户：写入账户判别器后返回可变引用
+    #[inline(always)]
+    pub fn init(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        write_discriminator(bytes, Self::LEN, &Self::ACCOUNT_DISCRIMINATOR)?;
+        Self::load_mut(bytes)
+    }
+
+    #[inline(always)]
+    pub fn set_inner(&mut self, pool: Address, contributor: Address, bump: [u8;1]) {
+        self.amount = 0;
+        self.pool = pool;
+        self.contributor = contributor;
+        self.bump = bump;
+    }
+
+    #[inline(always)]
+    pub fn set_amount(&mut self, amount: u64) {
+        self.amount = amount;
+    }
+}
+
+/// maker 的种子计数器：每个 maker 一个，PDA 种子为 `["counter", maker, bump]`
+/// MakeAuto 用 `next_seed` 作为新 Escrow 的种子并递增，maker 不需要自己挑选不冲突的种子
+#[repr(C)]
+pub struct MakerCounter {
+    pub discriminator: [u8; 8], // 账户判别器（见 MakerCounter::ACCOUNT_DISCRIMINATOR）
+    pub maker: Address,    // 计数器所属的 maker
+    pub next_seed: u64,    // 下一个 MakeAuto 使用的种子
+    pub bump: [u8;1],      // PDA bump 字节
+    pub reserved: [u8; 7], // 预留，保持 8 字节对齐
+}
+
+unsafe impl Pod for MakerCounter {}
+const _: () = assert!(size_of::<MakerCounter>() == MakerCounter::LEN);
+impl MakerCounter {
+    /// 账户判别器：位于数据开头，`load` / `load_mut` 时校验
+    pub const ACCOUNT_DISCRIMINATOR: [u8; 8] = *b"countrv1";
+
+    pub const LEN: usize = size_of::<[u8; 8]>()
+    + size_of::<Address>()
+    + size_of::<u64>()
+    + size_of::<[u8;1]>()
+    + size_of::<[u8; 7]>();
+
+    /// 计数器的起始种子：取 u64 的高半区间，不会与 maker 手动挑选的小种子冲突
+    pub const SEED_BASE: u64 = 1 << 63;
+
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        check_discriminator(bytes, Self::LEN, &Self::ACCOUNT_DISCRIMINATOR)?;
+        from_bytes_mut(bytes)
+    }
+
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        check_discriminator(bytes, Self::LEN, &Self::ACCOUNT_DISCRIMINATOR)?;
+        from_bytes(bytes)
+    }
+
+    /// 初始化新创建的账户：写入账户判别器后返回可变引用
+    #[inline(always)]
+    pub fn init(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        write_discriminator(bytes, Self::LEN, &Self::ACCOUNT_DISCRIMINATOR)?;
+        Self::load_mut(bytes)
+    }
+
+    #[inline(always)]
+    pub fn set_inner(&mut self, maker: Address, bump: [u8;1]) {
+        self.maker = maker;
+        self.next_seed = Self::SEED_BASE;
+        self.bump = bump;
+    }
+
+    /// 取出下一个种子并递增计数器
+    #[inline(always)]
+    pub fn take_seed(&mut self) -> Result<u64, ProgramError> {
+        let seed = self.next_seed;
+        self.next_seed = seed.checked_add(1).ok_or(EscrowError::MathOverflow)?;
+        Ok(seed)
+    }
+}
+
+/// 成交承诺：commit–reveal 两步成交的第一步，PDA 种子为 `["commit", taker, hash, bump]`
+/// `hash` 为 sha256(escrow || taker || salt)，在 RevealTake 之前不暴露目标 Escrow
+#[repr(C)]
+pub struct Commitment {
+    pub discriminator: [u8; 8], // 账户判别器（见 Commitment::ACCOUNT_DISCRIMINATOR）
+    pub slot: u64,      // 提交承诺时的 slot
+    pub taker: Address, // 提交承诺的 taker（支付租金，RevealTake 时必须由它成交）
+    pub hash: [u8; 32], // sha256(escrow || taker || salt)
+    pub bump: [u8;1],   // PDA bump 字节
+    pub reserved: [u8; 7], // 预留，保持 8 字节对齐
+}
+
+unsafe impl Pod for Commitment {}
+const _: () = assert!(size_of::<Commitment>() == Commitment::LEN);
+
+impl Commitment {
+    /// 提交承诺之后至少经过的 slot 数，才能 RevealTake
+    pub const MIN_REVEAL_SLOTS: u64 = 1;
+
+    /// 账户判别器：位于数据开头，`load` / `load_mut` 时校验
+    pub const ACCOUNT_DISCRIMINATOR: [u8; 8] = *b"commitv1";
+
+    pub const LEN: usize = size_of::<[u8; 8]>()
+    + size_of::<u64>()
+    + size_of::<Address>()
+    + size_of::<[u8; 32]>()
+    + size_of::<[u8;1]>()
+    + size_of::<[u8; 7]>();
+
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        check_discriminator(bytes, Self::LEN, &Self::ACCOUNT_DISCRIMINATOR)?;
+        from_bytes_mut(bytes)
+    }
+
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        check_discriminator(bytes, Self::LEN, &Self::ACCOUNT_DISCRIMINATOR)?;
+        from_bytes(bytes)
+    }
+
+    /// 初始化新创建的账户：写入账户判别器后返回可变引用
+    #[inline(always)]
+    pub fn init(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        write_discriminator(bytes, Self::LEN, &Self::ACCOUNT_DISCRIMINATOR)?;
+        Self::load_mut(bytes)
+    }
+
+    #[inline(always)]
+    pub fn set_inner(&mut self, slot: u64, taker: Address, hash: [u8; 32], bump: [u8;1]) {
+        self.slot = slot;
+        self.taker = taker;
+        self.hash = hash;
+        self.bump = bump;
+    }
+}
+
+/// 某个 Mint 的累计成交量
+#[repr(C)]
+pub struct MintVolume {
+    pub mint: Address, // Token A 的 Mint
+    pub amount: u64,   // 累计成交的 Token A 数量
+}
+
+unsafe impl Pod for MintVolume {}
+
+/// maker 统计：每个 maker 一个，PDA 种子为 `["stats", maker, bump]`
+/// 由 InitMakerStats 创建；Make / Take / Refund 传入时更新，供市场展示 maker 的链上信誉
+#[repr(C)]
+pub struct MakerStats {
+    pub discriminator: [u8; 8],                           // 账户判别器（见 MakerStats::ACCOUNT_DISCRIMINATOR）
+    pub maker: Address,                                   // 所属的 maker
+    pub created: u64,                                     // 创建的 Escrow 数量
+    pub filled: u64,                                      // 被成交的 Escrow 数量
+    pub cancelled: u64,                                   // 退款取消的 Escrow 数量
+    pub volumes: [MintVolume; MakerStats::MAX_MINTS],     // 按 Token A 的 Mint 统计的成交量
+    pub volume_count: u8,                                 // 已使用的成交量条目数
+    pub bump: [u8;1],                                     // PDA bump 字节
+    pub reserved: [u8; 6],                                // 预留，保持 8 字节对齐
+}
+
+unsafe impl Pod for MakerStats {}
+const _: () = assert!(size_of::<MakerStats>() == MakerStats::LEN);
+
+impl MakerStats {
+    /// 最多分别统计的 Mint 数量，超出后新的 Mint 不再记录成交量
+    pub const MAX_MINTS: usize = 8;
+
+    /// 账户判别器：位于数据开头，`load` / `load_mut` 时校验
+    pub const ACCOUNT_DISCRIMINATOR: [u8; 8] = *b"mstatsv1";
+
+    pub const LEN: usize = size_of::<[u8; 8]>()
+    + size_of::<Address>()
+    + size_of::<u64>() * 3
+    + size_of::<MintVolume>() * Self::MAX_MINTS
+    + size_of::<u8>()
+    + size_of::<[u8;1]>()
+    + size_of::<[u8; 6]>();
+
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        check_discriminator(bytes, Self::LEN, &Self::ACCOUNT_DISCRIMINATOR)?;
+        from_bytes_mut(bytes)
+    }
+
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        check_discriminator(bytes, Self::LEN, &Self::ACCOUNT_DISCRIMINATOR)?;
+        from_bytes(bytes)
+    }
+
+    /// 初始化新创建的账户：写入账户判别器后返回可变引用
+    #[inline(always)]
+    pub fn init(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        write_discriminator(bytes, Self::LEN, &Self::ACCOUNT_DISCRIMINATOR)?;
+        Self::load_mut(bytes)
+    }
+
+    #[inline(always)]
+    pub fn set_inner(&mut self, maker: Address, bump: [u8;1]) {
+        self.maker = maker;
+        self.bump = bump;
+    }
+
+    #[inline(always)]
+    pub fn record_created(&mut self) {
+        self.created = self.created.saturating_add(1);
+    }
+
+    #[inline(always)]
+    pub fn record_cancelled(&mut self) {
+        self.cancelled = self.cancelled.saturating_add(1);
+    }
+
+    /// 记录一次成交以及该 Mint 的成交量（条目已满时只计数）
+    pub fn record_filled(&mut self, mint: &Address, amount: u64) {
+        self.filled = self.filled.saturating_add(1);
+
+        let count = self.volume_count as usize;
+        if let Some(entry) = self.volumes[..count].iter_mut().find(|entry| &entry.mint == mint) {
+            entry.amount = entry.amount.saturating_add(amount);
+        } else if count < Self::MAX_MINTS {
+            self.volumes[count] = MintVolume {
+                mint: mint.clone(),
+                amount,
+            };
+            self.volume_count += 1;
+        }
+    }
+}
+
+/// 成交回执：Take 时可选创建，PDA 种子为 `["receipt", escrow, taker, bump]`
+/// Escrow 成交后即被关闭，回执为钱包与税务工具保留成交记录；创建后程序不再修改
+#[repr(C)]
+pub struct TradeReceipt {
+    pub discriminator: [u8; 8], // 账户判别器（见 TradeReceipt::ACCOUNT_DISCRIMINATOR）
+    pub escrow: Address,   // 成交的 Escrow 地址
+    pub maker: Address,    // 托管创建者
+    pub taker: Address,    // 成交的 taker
+    pub mint_a: Address,   // taker 获得的代币
+    pub mint_b: Address,   // taker 支付的代币（备选支付代币成交时为对应的 Mint）
+    pub amount_a: u64,     // taker 获得的 Token A 数量
+    pub amount_b: u64,     // taker 支付的 Token B 数量（含协议手续费）
+    pub slot: u64,         // 成交时的 slot
+    pub bump: [u8;1],      // PDA bump 字节
+    pub reserved: [u8; 7], // 预留，保持 8 字节对齐
+}
+
+unsafe impl Pod for TradeReceipt {}
+const _: () = assert!(size_of::<TradeReceipt>() == TradeReceipt::LEN);
+
+impl TradeReceipt {
+    /// 账户判别器：位于数据开头，`load` / `load_mut` 时校验
+    pub const ACCOUNT_DISCRIMINATOR: [u8; 8] = *b"rcpt__v1";
+
+    pub const LEN: usize = size_of::<[u8; 8]>()
+    + size_of::<Address>() * 5
+    + size_of::<u64>() * 3
+    + size_of::<[u8;1]>()
+    + size_of::<[u8; 7]>();
+
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        check_discriminator(bytes, Self::LEN, &Self::ACCOUNT_DISCRIMINATOR)?;
+        from_bytes_mut(bytes)
+    }
+
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        check_discriminator(bytes, Self::LEN, &Self::ACCOUNT_DISCRIMINATOR)?;
+        from_bytes(bytes)
+    }
+
+    /// 初始化新创建的账户：写入账户判别器后返回可变引用
+    #[inline(always)]
+    pub fn init(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        write_discriminator(bytes, Self::LEN, &Self::ACCOUNT_DISCRIMINATOR)?;
+        Self::load_mut(bytes)
+    }
+}
+
+/// 手续费账本：每种手续费代币一个，PDA 种子为 `["fee_vault", mint, bump]`
+/// 记录 Take / TakePartial 收取的累计手续费与 WithdrawFees 提取的数量；代币本身仍存放在 fee vault 中
+#[repr(C)]
+pub struct FeeVault {
+    pub discriminator: [u8; 8], // 账户判别器（见 FeeVault::ACCOUNT_DISCRIMINATOR）
+    pub mint: Address,     // 手续费代币的 Mint（原生 SOL 为 NATIVE_MINT）
+    pub accumulated: u64,  // 累计收取的手续费
+    pub withdrawn: u64,    // 累计提取的手续费
+    pub bump: [u8;1],      // PDA bump 字节
+    pub reserved: [u8; 7], // 预留，保持 8 字节对齐
+}
+
+unsafe impl Pod for FeeVault {}
+const _: () = assert!(size_of::<FeeVault>() == FeeVault::LEN);
+
+impl FeeVault {
+    /// 账户判别器：位于数据开头，`load` / `load_mut` 时校验
+    pub const ACCOUNT_DISCRIMINATOR: [u8; 8] = *b"feevltv1";
+
+    pub const LEN: usize = size_of::<[u8; 8]>()
+    + size_of::<Address>()
+    + size_of::<u64>() * 2
+    + size_of::<[u8;1]>()
+    + size_of::<[u8; 7]>();
+
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        check_discriminator(bytes, Self::LEN, &Self::ACCOUNT_DISCRIMINATOR)?;
+        from_bytes_mut(bytes)
+    }
+
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        check_discriminator(bytes, Self::LEN, &Self::ACCOUNT_DISCRIMINATOR)?;
+        from_bytes(bytes)
+    }
+
+    /// 初始化新创建的账户：写入账户判别器后返回可变引用
+    #[inline(always)]
+    pub fn init(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        write_discriminator(bytes, Self::LEN, &Self::ACCOUNT_DISCRIMINATOR)?;
+        Self::load_mut(bytes)
+    }
+
+    #[inline(always)]
+    pub fn set_inner(&mut self, mint: Address, bump: [u8;1]) {
+        self.mint = mint;
+        self.bump = bump;
+    }
+
+    /// 记录收取的手续费
+    #[inline(always)]
+    pub fn record_fee(&mut self, amount: u64) -> Result<(), ProgramError> {
+        self.accumulated = self
+            .accumulated
+            .checked_add(amount)
+            .ok_or(EscrowError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// 记录提取的手续费
+    #[inline(always)]
+    pub fn record_withdrawal(&mut self, amount: u64) -> Result<(), ProgramError> {
+        self.withdrawn = self
+            .withdrawn
+            .checked_add(amount)
+            .ok_or(EscrowError::MathOverflow)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 字段全 0 的 Escrow（只包含整数与字节数组字段，全 0 是合法的值）
+    fn new_escrow() -> Escrow {
+        unsafe { core::mem::zeroed() }
+    }
+
+    /// 起拍价 1000、最低价 100，在 [0, 100] 内线性降价的荷兰拍
+    fn dutch_escrow() -> Escrow {
+        let mut escrow = new_escrow();
+        escrow.set_receive(1_000);
+        escrow.set_flags(Escrow::FLAG_DUTCH);
+        escrow.set_dutch(100, 0, 100);
+        escrow
+    }
+
+    #[test]
+    fn price_at_start_mid_and_end_of_decay() {
+        let escrow = dutch_escrow();
+        assert_eq!(escrow.price_at(-5), 1_000);
+        assert_eq!(escrow.price_at(0), 1_000);
+        assert_eq!(escrow.price_at(50), 550);
+        assert_eq!(escrow.price_at(100), 100);
+        assert_eq!(escrow.price_at(10_000), 100);
+        assert_eq!(escrow.price_at(i64::MAX), 100);
+    }
+
+    #[test]
+    fn price_at_rounds_up_and_never_drops_below_floor() {
+        // 起拍价 10、最低价 2、3 秒降完：2 + ceil(8 * 2 / 3) = 8，2 + ceil(8 / 3) = 5
+        let mut escrow = dutch_escrow();
+        escrow.set_receive(10);
+        escrow.set_dutch(2, 0, 3);
+        assert_eq!(escrow.price_at(1), 8);
+        assert_eq!(escrow.price_at(2), 5);
+
+        let escrow = dutch_escrow();
+        let mut last = escrow.price_at(0);
+        for now in 1..=100 {
+            let price = escrow.price_at(now);
+            assert!(price <= last && price >= escrow.floor_price);
+            last = price;
+        }
+    }
+
+    /// 在 [1_000, 2_000] 内线性释放、尚未领取的 Escrow
+    fn vesting_escrow() -> Escrow {
+        let mut escrow = new_escrow();
+        escrow.set_flags(Escrow::FLAG_VESTING);
+        escrow.set_vesting_duration(1_000);
+        escrow.start_vesting(1_000);
+        escrow
+    }
+
+    #[test]
+    fn vested_at_before_during_and_after_vesting() {
+        let escrow = vesting_escrow();
+        assert_eq!(escrow.vested_at(1_000, 0), 0);
+        assert_eq!(escrow.vested_at(1_000, 1_000), 0);
+        assert_eq!(escrow.vested_at(1_000, 1_250), 250);
+        assert_eq!(escrow.vested_at(1_000, 2_000), 1_000);
+        assert_eq!(escrow.vested_at(1_000, i64::MAX), 1_000);
+
+        // 向下取整：1/3 的时长释放 floor(100 / 3)
+        let mut short = new_escrow();
+        short.set_vesting_duration(3);
+        short.start_vesting(0);
+        assert_eq!(short.vested_at(100, 1), 33);
+    }
+
+    #[test]
+    fn vested_at_large_amounts_do_not_overflow() {
+        // total * elapsed 超出 u64，u128 中间值仍然精确
+        let escrow = vesting_escrow();
+        assert_eq!(escrow.vested_at(u64::MAX, 1_500), u64::MAX / 2);
+        assert_eq!(escrow.vested_at(u64::MAX, 1_999), (u64::MAX as u128 * 999 / 1_000) as u64);
+        assert_eq!(escrow.vested_at(u64::MAX, 2_000), u64::MAX);
+
+        let mut long = new_escrow();
+        long.set_vesting_duration(i64::MAX);
+        long.start_vesting(0);
+        assert_eq!(long.vested_at(u64::MAX, i64::MAX / 2), (u64::MAX as u128 * (i64::MAX / 2) as u128 / i64::MAX as u128) as u64);
+    }
+
+    #[test]
+    fn claims_add_up_to_the_vested_total() {
+        // 多次领取：每次按 vault 余额 + 已领取重新计算总量，最后一次领取后 vault 恰好清空
+        let mut escrow = vesting_escrow();
+        let mut vault = 1_001u64;
+        for now in [999, 1_001, 1_333, 1_333, 1_777, 2_000, 2_500] {
+            let claimable = escrow.claimable_at(vault, now).unwrap();
+            assert!(claimable <= vault);
+            vault -= claimable;
+            escrow.set_claimed(escrow.claimed + claimable);
+            assert_eq!(escrow.claimed, escrow.vested_at(1_001, now));
+        }
+        assert_eq!((vault, escrow.claimed), (0, 1_001));
+        assert_eq!(escrow.claimable_at(u64::MAX, 2_000).err(), Some(EscrowError::MathOverflow.into()));
+    }
+
+    fn new_config(fee_bps: u16, referral_bps: u16) -> Config {
+        Config {
+            discriminator: Config::ACCOUNT_DISCRIMINATOR,
+            fee_authority: Address::default(),
+            min_amount: 0,
+            max_amount: 0,
+            fee_bps,
+            referral_bps,
+            paused: 0,
+            bump: [0],
+            reserved: [0; 2],
+            event_seq: 0,
+        }
+    }
+
+    #[test]
+    fn referral_is_carved_out_of_the_fee() {
+        // 手续费 1%，其中 0.25% 给推荐人：maker 所得只扣除 fee_for，与是否传入推荐人无关
+        let config = new_config(100, 25);
+        assert_eq!(config.fee_for(10_000), 100);
+        assert_eq!(config.referral_for(10_000), 25);
+
+        // 旧配置中推荐费率高于手续费率时，推荐费不超过手续费
+        let config = new_config(50, 300);
+        assert_eq!(config.referral_for(10_000), config.fee_for(10_000));
+        assert_eq!(new_config(0, 0).referral_for(u64::MAX), 0);
+    }
+
+    #[test]
+    fn price_at_is_fixed_without_dutch_flag() {
+        let mut escrow = dutch_escrow();
+        escrow.set_flags(0);
+        assert_eq!(escrow.price_at(50), 1_000);
+        assert_eq!(escrow.price_at(1_000), 1_000);
+    }
+
+    #[test]
+    fn check_address_uses_recorded_seed_and_bump() {
+        let maker = Address::new_from_array([7; 32]);
+        let (address, bump) = derive_escrow_address(&maker, 42);
+        let mut escrow = new_escrow();
+        escrow.seed = 42;
+        escrow.bump = bump;
+        assert_eq!(escrow.check_address(&maker, &address), Ok(()));
+
+        // 传入的 Escrow 账户不是该 PDA
+        let mismatch = Err(ProgramError::from(EscrowError::InvalidEscrowPda));
+        assert_eq!(escrow.check_address(&maker, &maker), mismatch);
+        // 其他 maker 或 seed 派生不出该地址（得到其他地址，或种子落在曲线上时 create_program_address 失败）
+        assert!(escrow.check_address(&Address::new_from_array([8; 32]), &address).is_err());
+        escrow.seed = 43;
+        assert!(escrow.check_address(&maker, &address).is_err());
+
+        // 非 canonical bump 派生的是另一个地址
+        escrow.seed = 42;
+        escrow.bump = (0..bump)
+            .rev()
+            .find(|bump| {
+                Address::create_program_address(&[ESCROW_SEED, maker.as_ref(), &42u64.to_le_bytes(), &[*bump]], &crate::ID)
+                    .is_ok()
+            })
+            .unwrap();
+        assert_eq!(escrow.check_address(&maker, &address), mismatch);
+    }
+
+    #[test]
+    fn maker_counter_hands_out_increasing_seeds() {
+        let mut counter = MakerCounter {
+            discriminator: MakerCounter::ACCOUNT_DISCRIMINATOR,
+            maker: Address::default(),
+            next_seed: 0,
+            bump: [0],
+            reserved: [0; 7],
+        };
+        counter.set_inner(Address::new_from_array([1; 32]), [255]);
+        assert_eq!(counter.take_seed(), Ok(MakerCounter::SEED_BASE));
+        assert_eq!(counter.take_seed(), Ok(MakerCounter::SEED_BASE + 1));
+        assert_eq!(counter.next_seed, MakerCounter::SEED_BASE + 2);
+
+        // 计数器用尽时失败，而不是回绕到已使用的种子
+        counter.next_seed = u64::MAX;
+        assert_eq!(counter.take_seed(), Err(EscrowError::MathOverflow.into()));
+        assert_eq!(counter.next_seed, u64::MAX);
+    }
+
+    #[test]
+    fn accounts_are_told_apart_by_discriminator() {
+        // Bid 与 Offer 长度相同，只能靠判别器区分
+        assert_eq!(Bid::LEN, Offer::LEN);
+        let mut bytes = [0u64; Bid::LEN.div_ceil(8)];
+        let data = &mut aligned_bytes(&mut bytes)[..Bid::LEN];
+
+        // 未初始化（判别器全 0）的账户不能读取
+        assert!(Bid::load(data).is_err());
+        Bid::init(data).unwrap().set_inner(Address::new_from_array([1; 32]), [254]);
+        assert_eq!(data[..8], Bid::ACCOUNT_DISCRIMINATOR);
+        assert_eq!(Bid::load(data).unwrap().bump, [254]);
+
+        // 同样长度的其他类型读取失败，已初始化的账户不能再次 init
+        assert!(Offer::load(data).is_err());
+        assert!(Offer::init(data).is_err());
+        assert!(Bid::init(data).is_err());
+    }
+
+    /// 把 u64 数组视为字节切片，保证零拷贝读取所需的 8 字节对齐
+    fn aligned_bytes(words: &mut [u64]) -> &mut [u8] {
+        unsafe { core::slice::from_raw_parts_mut(words.as_mut_ptr().cast::<u8>(), words.len() * 8) }
+    }
+}